@@ -1,17 +1,18 @@
 use geo::Coordinate;
-use rust_macro::editor_plugin;
 use rust_editor::input::mouse;
 use rust_editor::plugin::Plugin;
+use rust_macro::editor_plugin;
+
+/// Factor the zoom changes by for one step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.1;
 
+/// Scroll amount of one step of the mouse wheel in pixels.
+const WHEEL_STEP: f64 = 100.;
+
+/// Moves the viewport of the editor while the middle mouse button is held down and zooms around the cursor with the
+/// mouse wheel.
 #[editor_plugin]
 pub struct Camera {
-    #[option(
-        skip,
-        label = "Camera Position",
-        description = "Current position of the camera"
-    )]
-    position: Coordinate<f64>,
-
     #[option(
         skip,
         default = false,
@@ -19,29 +20,13 @@ pub struct Camera {
         description = "Enables / Disables the camera"
     )]
     active: bool,
+
+    /// Position of the cursor on the screen when the view was moved last.
+    #[option(skip)]
+    last_screen_pos: Coordinate<f64>,
 }
 
 impl Camera {
-    pub fn x(&self) -> f64 {
-        self.position.x
-    }
-
-    pub fn set_x(&mut self, x: f64) {
-        self.position.x = x;
-    }
-
-    pub fn y(&self) -> f64 {
-        self.position.y
-    }
-
-    pub fn set_y(&mut self, y: f64) {
-        self.position.y = y;
-    }
-
-    pub fn position(&self) -> Coordinate<f64> {
-        self.position
-    }
-
     pub fn active(&self) -> bool {
         self.active
     }
@@ -57,26 +42,30 @@ where
 {
     fn mouse_down(
         &mut self,
-        _mouse_pos: Coordinate<f64>,
+        mouse_pos: Coordinate<f64>,
         button: mouse::Button,
-        _: &App<Data>,
+        editor: &App<Data>,
     ) -> bool {
         self.active = button == mouse::Button::Middle;
+        self.last_screen_pos = editor.viewport().world_to_screen(mouse_pos);
         false
     }
 
     fn mouse_move(
         &mut self,
+        mouse_pos: Coordinate<f64>,
         _: Coordinate<f64>,
-        mouse_movement: Coordinate<f64>,
         _: mouse::Button,
-        _: &mut App<Data>,
+        editor: &mut App<Data>,
     ) -> bool {
         if !self.active {
             return false;
         }
 
-        self.position = self.position + mouse_movement;
+        // The world position under the cursor changes while the view moves, so the movement is tracked on the screen
+        let screen_pos = editor.viewport().world_to_screen(mouse_pos);
+        editor.viewport_mut().pan(screen_pos - self.last_screen_pos);
+        self.last_screen_pos = screen_pos;
         false
     }
 
@@ -92,91 +81,82 @@ where
 
         false
     }
+
+    fn mouse_wheel(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        delta: f64,
+        editor: &mut App<Data>,
+    ) -> bool {
+        let screen_pos = editor.viewport().world_to_screen(mouse_pos);
+        editor
+            .viewport_mut()
+            .zoom_at(screen_pos, ZOOM_STEP.powf(-delta / WHEEL_STEP));
+        false
+    }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-
     use geo::Coordinate;
-
-    use crate::{input::mouse, plugins::plugin::Plugin, ui::app::App};
+    use rust_editor::{input::mouse, ui::headless::HeadlessApp};
 
     use super::Camera;
 
-    #[test]
-    fn left_mouse_button_activates_camera() {
-        let app = App::<bool>::default();
-        let mut camera = Camera::default();
+    fn app() -> HeadlessApp<bool> {
+        let mut app = HeadlessApp::<bool>::new(800, 600);
+        app.add_plugin(Camera::default()).unwrap();
 
-        camera.mouse_down(Coordinate { x: 0., y: 0. }, mouse::Button::Left, &app);
-
-        assert!(camera.active);
+        app
     }
 
     #[test]
-    fn not_left_mouse_button_does_not_activates_camera() {
-        let app = App::<bool>::default();
-        let mut camera = Camera::default();
-
-        camera.mouse_down(Coordinate { x: 0., y: 0. }, mouse::Button::Right, &app);
+    fn integration_middle_mouse_button_pans_view() {
+        let mut app = app();
 
-        assert_eq!(camera.active, false);
-    }
+        app.mouse_down(Coordinate { x: 100., y: 100. }, mouse::Button::Middle);
+        app.mouse_move(Coordinate { x: 150., y: 120. }, mouse::Button::Middle);
+        app.mouse_move(Coordinate { x: 200., y: 140. }, mouse::Button::Middle);
+        app.mouse_up(Coordinate { x: 200., y: 140. }, mouse::Button::Middle);
 
-    #[test]
-    fn move_camera_if_button_1_pressed() {
-        let mut app = App::<bool>::default();
-        let mut camera = Camera::default();
-
-        camera.set_active(true);
-        camera.set_x(128.);
-        camera.set_y(128.);
-        camera.mouse_move(
-            Coordinate { x: 0., y: 0. },
-            Coordinate { x: 256., y: 256. },
-            mouse::Button::Left,
-            &mut app,
+        assert_eq!(
+            app.app().viewport().offset(),
+            Coordinate { x: 100., y: 40. }
         );
 
-        assert_eq!(camera.x(), 256. + 128.);
-        assert_eq!(camera.y(), 256. + 128.);
+        // Moving without a pressed button does not pan
+        app.mouse_move(Coordinate { x: 300., y: 300. }, mouse::Button::Middle);
+        assert_eq!(
+            app.app().viewport().offset(),
+            Coordinate { x: 100., y: 40. }
+        );
     }
 
     #[test]
-    fn not_move_camera_if_not_button_1_pressed() {
-        let mut app = App::<bool>::default();
-        let mut camera = Camera::default();
-
-        camera.set_active(false);
-        camera.mouse_move(
-            Coordinate { x: 0., y: 0. },
-            Coordinate { x: 256., y: 256. },
+    fn integration_left_mouse_button_does_not_pan_view() {
+        let mut app = app();
+
+        app.drag(
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 200., y: 200. },
             mouse::Button::Left,
-            &mut app,
         );
 
-        assert_eq!(camera.position(), Coordinate { x: 0., y: 0. });
+        assert_eq!(app.app().viewport().offset(), Coordinate { x: 0., y: 0. });
     }
 
     #[test]
-    fn left_mouse_button_deactivates_camera() {
-        let mut app = App::<bool>::default();
-        let mut camera = Camera::default();
+    fn integration_wheel_zooms_around_cursor() {
+        let mut app = app();
+        let cursor = Coordinate { x: 400., y: 300. };
 
-        camera.set_active(true);
-        camera.mouse_up(Coordinate { x: 0., y: 0. }, mouse::Button::Left, &mut app);
-        assert_eq!(camera.active(), false);
-    }
+        app.wheel(cursor, -100.);
+        assert!((app.app().viewport().zoom() - 1.1).abs() < 1e-9);
 
-    #[test]
-    fn not_left_mouse_button_does_not_deactivates_camera() {
-        let mut app = App::<bool>::default();
-        let mut camera = Camera::default();
+        app.wheel(cursor, 200.);
+        assert!(app.app().viewport().zoom() < 1.);
 
-        camera.set_active(true);
-        camera.mouse_up(Coordinate { x: 0., y: 0. }, mouse::Button::Right, &mut app);
-        assert!(camera.active());
+        let world = app.app().viewport().screen_to_world(cursor);
+        assert!((world.x - 400.).abs() < 1e-9 && (world.y - 300.).abs() < 1e-9);
     }
 }
-*/
\ No newline at end of file
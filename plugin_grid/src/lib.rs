@@ -1,6 +1,5 @@
-use rust_editor::plugin::Plugin;
+use rust_editor::{plugin::Plugin, renderer::Canvas};
 use rust_macro::editor_plugin;
-use web_sys::CanvasRenderingContext2d;
 
 #[editor_plugin]
 pub struct Grid {
@@ -27,7 +26,7 @@ impl<Data> Plugin<Data> for Grid
 where
     Data: Default + 'static,
 {
-    fn render(&self, context: &CanvasRenderingContext2d, _: &App<Data>) {
+    fn render(&self, context: &mut dyn Canvas, _: &App<Data>) {
         if self.offset == 0 {
             return;
         }
@@ -38,7 +37,7 @@ where
 
         context.save();
         context.set_line_width(2.0);
-        context.set_stroke_style(&"rgb(40, 40, 40)".into());
+        context.set_stroke_style("rgb(40, 40, 40)");
 
         let steps_x = (width as f64 / self.offset as f64).ceil() as u32;
         let steps_y = (height as f64 / self.offset as f64).ceil() as u32;
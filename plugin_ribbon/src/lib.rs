@@ -158,7 +158,7 @@ where
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, _: &Shortkey, _: &Context<App<Data>>, _: &mut App<Data>) {}
+    fn shortkey_pressed(&mut self, _: &Shortkey, _: &mut App<Data>) {}
 
     fn editor_elements(&mut self, ctx: &Context<App<Data>>, _: &App<Data>) -> Vec<Html> {
 
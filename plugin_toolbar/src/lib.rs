@@ -1,4 +1,3 @@
-use rust_editor::log;
 use rust_editor::plugin::Plugin;
 use rust_editor::ui::app::{EditorError, Shortkey};
 use rust_macro::editor_plugin;
@@ -96,6 +95,14 @@ where
         }
     }
 
+    /// Returns true if the toolbar with the id `toolbar_id` has a button with the given `identifier`.
+    pub fn has_button(&self, toolbar_id: &str, identifier: &'static str) -> bool {
+        self.toolbars
+            .values()
+            .flatten()
+            .any(|toolbar| toolbar.id == toolbar_id && toolbar.has_button(identifier))
+    }
+
     // TODO refactoring into separate yew component
     fn view_button(&self, button: &ToolbarButton<Data>, ctx: &Context<App<Data>>) -> Html {
         let mut classes = classes!();
@@ -126,12 +133,10 @@ where
     Data: Default + 'static,
 {
     fn startup(&mut self, _: &mut App<Data>) -> Result<(), EditorError> {
-        log!("Toolbar plugin STARTUP");
-
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, _: &Shortkey, _: &Context<App<Data>>, _: &mut App<Data>) {}
+    fn shortkey_pressed(&mut self, _: &Shortkey, _: &mut App<Data>) {}
 
     fn editor_elements(&mut self, ctx: &Context<App<Data>>, _: &App<Data>) -> Vec<Html> {
         use view::Toolbar as UiToolbar;
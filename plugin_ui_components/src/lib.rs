@@ -57,13 +57,13 @@ where
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, _: &Shortkey, _: &Context<App<Data>>, _: &mut App<Data>) {}
+    fn shortkey_pressed(&mut self, _: &Shortkey, _: &mut App<Data>) {}
 
     fn editor_elements(&mut self, _: &Context<App<Data>>, _: &App<Data>) -> Vec<Html> {
         self.elements.clone()
     }
 
-    fn on_message(&mut self, message: Box<dyn Any>) {
+    fn on_message(&mut self, message: Box<dyn Any>, _: &mut App<Data>) {
         let message = message.as_ref().downcast_ref::<ComponentMessage>().unwrap();
 
         // TODO enable option
@@ -61,7 +61,7 @@ where
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, _: &Context<App<Data>>, editor: &mut App<Data>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Data>) {
         if *key == vec![Key::Ctrl, Key::Z] {
             if let Some(action) = self.stack.pop() {
                 action.borrow_mut().undo(editor.data_mut());
@@ -2,9 +2,12 @@ use std::collections::HashMap;
 
 use geo::{prelude::EuclideanDistance, Coordinate, Line, Triangle};
 use uuid::Uuid;
-use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use wasm_bindgen::UnwrapThrowExt;
 
-use crate::{renderer::PrimitiveRenderer, style::Style};
+use crate::{
+    renderer::{Canvas, PrimitiveRenderer, RenderError},
+    style::Style,
+};
 
 pub enum Axis {
     X,
@@ -47,9 +50,9 @@ where
     );
     fn render(
         &self,
-        context: &web_sys::CanvasRenderingContext2d,
+        context: &mut dyn Canvas,
         elements: impl Iterator<Item = &'a T>,
-    ) -> Result<(), JsValue>;
+    ) -> Result<(), RenderError>;
 }
 
 impl GetPosition for MoveGizmo {
@@ -138,17 +141,18 @@ impl<'a, T: GetPosition + SetPosition + Id + 'a> Gizmo<'a, T> for MoveGizmo {
 
     fn render(
         &self,
-        context: &web_sys::CanvasRenderingContext2d,
+        context: &mut dyn Canvas,
         elements: impl Iterator<Item = &'a T>,
-    ) -> Result<(), JsValue> {
+    ) -> Result<(), RenderError> {
         if elements.peekable().peek().is_some() {
             let position = self.position();
+            context.save();
             context.translate(position.x, position.y)?;
 
-            self.x_handle.render(&context)?;
-            self.y_handle.render(&context)?;
+            self.x_handle.render(context)?;
+            self.y_handle.render(context)?;
 
-            context.set_transform(1., 0., 0., 1., 0., 0.)?;
+            context.restore();
         }
 
         Ok(())
@@ -188,7 +192,7 @@ impl GizmoArrow {
         }
     }
 
-    pub fn render(&self, context: &web_sys::CanvasRenderingContext2d) -> Result<(), JsValue> {
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
         self.line.render(&self.style, context)?;
         self.arrow.render(&self.style, context)?;
 
@@ -211,6 +215,12 @@ pub struct MoveGizmo {
     pub affected_axis: Option<Axis>,
 }
 
+impl Default for MoveGizmo {
+    fn default() -> Self {
+        MoveGizmo::new()
+    }
+}
+
 impl MoveGizmo {
     pub fn new() -> Self {
         MoveGizmo {
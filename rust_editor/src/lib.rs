@@ -9,6 +9,7 @@ pub mod system;
 pub mod ui;
 pub mod input;
 pub mod plugin;
+pub mod viewport;
 
 #[derive(PartialEq)]
 pub enum InformationLayer {
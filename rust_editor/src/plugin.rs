@@ -2,11 +2,12 @@ use std::any::Any;
 
 use geo::Coordinate;
 use rust_internal::PluginExecutionBehaviour;
-use web_sys::{CanvasRenderingContext2d, DragEvent};
+use web_sys::DragEvent;
 use yew::{html, Context, Html};
 
 use crate::{
     input::{keyboard::Key, mouse},
+    renderer::Canvas,
     ui::app::{App, EditorError, Shortkey},
 };
 
@@ -112,7 +113,7 @@ where
         false
     }
 
-    fn render(&self, context: &CanvasRenderingContext2d, editor: &App<Data>) {}
+    fn render(&self, context: &mut dyn Canvas, editor: &App<Data>) {}
 
     fn editor_elements(&mut self, ctx: &Context<App<Data>>, editor: &App<Data>) -> Vec<Html> {
         Vec::default()
@@ -136,6 +137,22 @@ where
         false
     }
 
+    /// React to a turned mouse wheel.
+    ///
+    /// * `mouse_pos` - The position of the cursor in world coordinates
+    /// * `delta` - The vertical scroll amount, positive values scroll down
+    /// * `data` - The data hold by the editor
+    ///
+    /// Use the return value to block following plugins from receiving the event: Return true to block execution for subsequential plugins. The default return is false.
+    fn mouse_wheel(
+        &mut self,
+        _mouse_pos: Coordinate<f64>,
+        _delta: f64,
+        editor: &mut App<Data>,
+    ) -> bool {
+        false
+    }
+
     /// React to a key held down on a keyboard.  
     ///
     /// * 'key' the value of the pressed key. [See here for more informations](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key)
@@ -155,17 +172,17 @@ where
     /// While key_down or key_press react on single key events, shortkey_pressed is not a native web event instead pressed keys are aggregated by the
     /// editor and checked against registered shortkeys. If the pressed shortkey exists in one plugin and the plugin is active than only this function
     /// is called.
-    fn shortkey_pressed(
-        &mut self,
-        key: &Shortkey,
-        ctx: &Context<App<Data>>,
-        editor: &mut App<Data>,
-    ) {
-    }
+    ///
+    /// Use [`App::send_message`] to send messages to the editor, e.g. to activate the plugin.
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Data>) {}
 
     fn property_updated(&mut self, property: &str, editor: &mut App<Data>) {}
 
-    fn on_message(&mut self, message: Box<dyn Any>) {}
+    /// Receives a message that was sent to the plugin with [`EditorMessages::PluginMessage`], e.g. by a button of
+    /// the plugin. Unlike the callbacks of the ui elements, the plugin can change the data of the editor here.
+    ///
+    /// [`EditorMessages::PluginMessage`]: crate::ui::app::EditorMessages::PluginMessage
+    fn on_message(&mut self, message: Box<dyn Any>, editor: &mut App<Data>) {}
 
     /// Called once before the plugin is added to the editor list of plugins. You can use this it to add additional ui elements such as toolbars (and buttons) or
     /// register shortkeys for the plugin.
@@ -0,0 +1,135 @@
+//! Renders to the 2D context of a html canvas, which is how the editor renders in the browser.
+
+use web_sys::CanvasRenderingContext2d;
+
+use super::{Canvas, RenderError};
+
+impl Canvas for CanvasRenderingContext2d {
+    fn save(&mut self) {
+        CanvasRenderingContext2d::save(self);
+    }
+
+    fn restore(&mut self) {
+        CanvasRenderingContext2d::restore(self);
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::translate(self, x, y)?)
+    }
+
+    fn rotate(&mut self, angle: f64) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::rotate(self, angle)?)
+    }
+
+    fn set_transform(
+        &mut self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::set_transform(self, a, b, c, d, e, f)?)
+    }
+
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::clear_rect(self, x, y, width, height);
+    }
+
+    fn set_global_composite_operation(&mut self, operation: &str) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::set_global_composite_operation(
+            self, operation,
+        )?)
+    }
+
+    fn begin_path(&mut self) {
+        CanvasRenderingContext2d::begin_path(self);
+    }
+
+    fn close_path(&mut self) {
+        CanvasRenderingContext2d::close_path(self);
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        CanvasRenderingContext2d::move_to(self, x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        CanvasRenderingContext2d::line_to(self, x, y);
+    }
+
+    fn arc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::arc(
+            self,
+            x,
+            y,
+            radius,
+            start_angle,
+            end_angle,
+        )?)
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::rect(self, x, y, width, height);
+    }
+
+    fn fill(&mut self) {
+        CanvasRenderingContext2d::fill(self);
+    }
+
+    fn stroke(&mut self) {
+        CanvasRenderingContext2d::stroke(self);
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::fill_rect(self, x, y, width, height);
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        CanvasRenderingContext2d::set_fill_style(self, &color.into());
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        CanvasRenderingContext2d::set_stroke_style(self, &color.into());
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        CanvasRenderingContext2d::set_line_width(self, width);
+    }
+
+    fn set_line_join(&mut self, join: &str) {
+        CanvasRenderingContext2d::set_line_join(self, join);
+    }
+
+    fn set_font(&mut self, font: &str) {
+        CanvasRenderingContext2d::set_font(self, font);
+    }
+
+    fn set_text_align(&mut self, align: &str) {
+        CanvasRenderingContext2d::set_text_align(self, align);
+    }
+
+    fn set_text_baseline(&mut self, baseline: &str) {
+        CanvasRenderingContext2d::set_text_baseline(self, baseline);
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::fill_text(self, text, x, y)?)
+    }
+
+    fn stroke_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        Ok(CanvasRenderingContext2d::stroke_text(self, text, x, y)?)
+    }
+
+    fn measure_text(&mut self, text: &str) -> Result<f64, RenderError> {
+        Ok(CanvasRenderingContext2d::measure_text(self, text)?.width())
+    }
+}
@@ -0,0 +1,437 @@
+use std::f64::consts::PI;
+
+use geo::{
+    prelude::EuclideanLength, Coordinate, GeometryCollection, Line, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon, Rect, Triangle,
+};
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+
+use crate::style::{Style, TextStyle};
+
+pub mod canvas2d;
+pub mod recording;
+pub mod svg;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RenderError {
+    #[error("the canvas backend failed: {0}")]
+    Backend(String),
+}
+
+impl From<JsValue> for RenderError {
+    fn from(value: JsValue) -> Self {
+        RenderError::Backend(format!("{:?}", value))
+    }
+}
+
+/// Drawing surface everything in the editor is rendered to. The methods follow the 2D context of the html canvas:
+/// Shapes are described by a path that is filled or stroked with the current styles, all coordinates are
+/// transformed by the current transformation and `save` and `restore` push and pop the styles and the
+/// transformation.
+///
+/// Besides the [html canvas](canvas2d) there are backends that render to an [SVG document](svg::SvgCanvas) and
+/// that [record all calls](recording::RecordingCanvas), both of which work outside of a browser.
+pub trait Canvas {
+    fn save(&mut self);
+    fn restore(&mut self);
+
+    fn translate(&mut self, x: f64, y: f64) -> Result<(), RenderError>;
+
+    /// Rotates clockwise by `angle` in radians.
+    fn rotate(&mut self, angle: f64) -> Result<(), RenderError>;
+
+    /// Replaces the current transformation by the matrix `[a c e; b d f; 0 0 1]`.
+    fn set_transform(
+        &mut self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Result<(), RenderError>;
+
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+
+    /// Sets how shapes are combined with what is already drawn, see
+    /// https://developer.mozilla.org/en-US/docs/Web/API/CanvasRenderingContext2D/globalCompositeOperation
+    fn set_global_composite_operation(&mut self, operation: &str) -> Result<(), RenderError>;
+
+    fn begin_path(&mut self);
+    fn close_path(&mut self);
+    fn move_to(&mut self, x: f64, y: f64);
+    fn line_to(&mut self, x: f64, y: f64);
+
+    /// Adds a clockwise arc around `(x, y)` from `start_angle` to `end_angle` in radians to the path.
+    fn arc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Result<(), RenderError>;
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+    fn fill(&mut self);
+    fn stroke(&mut self);
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+
+    /// Accepts all colors in css format.
+    fn set_fill_style(&mut self, color: &str);
+
+    /// Accepts all colors in css format.
+    fn set_stroke_style(&mut self, color: &str);
+
+    fn set_line_width(&mut self, width: f64);
+
+    /// One of `round`, `bevel` or `miter`.
+    fn set_line_join(&mut self, join: &str);
+
+    /// Accepts a font in the format of the css font shorthand, e.g. `14px Roboto`.
+    fn set_font(&mut self, font: &str);
+
+    /// One of `left`, `right`, `center`, `start` or `end`.
+    fn set_text_align(&mut self, align: &str);
+
+    /// One of `top`, `hanging`, `middle`, `alphabetic`, `ideographic` or `bottom`.
+    fn set_text_baseline(&mut self, baseline: &str);
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError>;
+    fn stroke_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError>;
+
+    /// Returns the width of `text` rendered with the current font.
+    fn measure_text(&mut self, text: &str) -> Result<f64, RenderError>;
+}
+
+/// Estimates the width of `text` rendered with `font` for backends that can not measure text. Each character is
+/// assumed to be 0.6 times as wide as the font size, which is close to the average of common sans serif fonts.
+pub(crate) fn estimate_text_width(text: &str, font: &str) -> f64 {
+    let size = font
+        .split_whitespace()
+        .find_map(|part| part.strip_suffix("px")?.parse::<f64>().ok())
+        .unwrap_or(10.);
+
+    text.chars().count() as f64 * size * 0.6
+}
+
+pub fn apply_style(style: &Style, context: &mut dyn Canvas) {
+    context.set_fill_style(&style.background_color);
+    context.fill();
+
+    if style.border_width > 0 {
+        context.set_line_width(style.border_width.into());
+        context.set_stroke_style(&style.border_color);
+        context.stroke();
+    }
+}
+pub trait PrimitiveRenderer {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError>;
+}
+
+impl PrimitiveRenderer for Polygon<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        let mut it = self.exterior().points();
+
+        if let Some(start) = it.next() {
+            context.begin_path();
+            context.move_to(start.x(), start.y());
+            for point in it {
+                context.line_to(point.x(), point.y());
+            }
+
+            context.close_path();
+            apply_style(style, context);
+        }
+
+        context.save();
+        context.set_global_composite_operation("xor")?;
+
+        for line_string in self.interiors() {
+            line_string.render(style, context)?;
+        }
+
+        context.restore();
+        
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for MultiPolygon<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        for polygon in self {
+            polygon.render(style, context)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for Rect<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        let min = self.min();
+        let max = self.max();
+
+        context.rect(min.x, min.y, max.x - min.x, max.y - min.y);
+        apply_style(style, context);
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for Line<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        context.begin_path();
+        context.move_to(self.start.x, self.start.y);
+        context.line_to(self.end.x, self.end.y);
+        context.set_line_width(std::cmp::max(style.border_width, 1) as f64);
+        context.set_stroke_style(&style.background_color);
+        context.stroke();
+        context.close_path();
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for Coordinate<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        context.begin_path();
+        context.arc(self.x, self.y, 5.0, 0.0, 2.0 * PI)?;
+        context.set_fill_style("#FF8C00");
+        apply_style(style, context);
+        context.close_path();
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for Point<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        context.begin_path();
+        context.arc(self.x(), self.y(), 5.0, 0.0, 2.0 * PI)?;
+        context.set_fill_style("#FF8C00");
+        apply_style(style, context);
+        context.close_path();
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for MultiPoint<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        for point in self {
+            point.render(style, context)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for LineString<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        let mut it = self.points();
+
+        if let Some(start) = it.next() {
+            context.begin_path();
+            context.move_to(start.x(), start.y());
+            for point in it {
+                context.line_to(point.x(), point.y());
+            }
+
+            context.close_path();
+            apply_style(style, context);
+        }
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for MultiLineString<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        for line_string in self {
+            line_string.render(style, context)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for Triangle<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        context.begin_path();
+        context.move_to(self.0.x, self.0.y);
+        context.line_to(self.1.x, self.1.y);
+        context.line_to(self.2.x, self.2.y);
+        context.close_path();
+        apply_style(style, context);
+
+        Ok(())
+    }
+}
+
+impl PrimitiveRenderer for GeometryCollection<f64> {
+    fn render(&self, style: &Style, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        for geom in self {
+            match geom {
+                geo::Geometry::Point(x) => x.render(style, context)?,
+                geo::Geometry::Line(x) => x.render(style, context)?,
+                geo::Geometry::LineString(x) => x.render(style, context)?,
+                geo::Geometry::Polygon(x) => x.render(style, context)?,
+                geo::Geometry::MultiPoint(x) => x.render(style, context)?,
+                geo::Geometry::MultiLineString(x) => x.render(style, context)?,
+                geo::Geometry::MultiPolygon(x) => x.render(style, context)?,
+                geo::Geometry::GeometryCollection(x) => x.render(style, context)?,
+                geo::Geometry::Rect(x) => x.render(style, context)?,
+                geo::Geometry::Triangle(x) => x.render(style, context)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn apply_text_style(style: &TextStyle, context: &mut dyn Canvas) {
+    context.set_font(&style.css_font());
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_line_join("round");
+    context.set_line_width((style.halo_width * 2).into());
+    context.set_stroke_style(&style.halo_color);
+    context.set_fill_style(&style.color);
+}
+
+/// Renders `text` centered at `position`.
+pub fn render_text(
+    text: &str,
+    position: Coordinate<f64>,
+    style: &TextStyle,
+    context: &mut dyn Canvas,
+) -> Result<(), RenderError> {
+    context.save();
+    apply_text_style(style, context);
+
+    if style.halo_width > 0 {
+        context.stroke_text(text, position.x, position.y)?;
+    }
+    context.fill_text(text, position.x, position.y)?;
+
+    context.restore();
+
+    Ok(())
+}
+
+/// Renders `text` along `line_string`, centered on its length. Each character is rotated in the direction
+/// of the line string at its position. Line strings that run from right to left are followed in reverse so
+/// that the text is never upside down. Text that is longer than the line string is not rendered at all.
+pub fn render_text_along(
+    text: &str,
+    line_string: &LineString<f64>,
+    style: &TextStyle,
+    context: &mut dyn Canvas,
+) -> Result<(), RenderError> {
+    let (first, last) = match (line_string.0.first(), line_string.0.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(()),
+    };
+
+    context.save();
+    apply_text_style(style, context);
+
+    let length = line_string.euclidean_length();
+    let text_width = context.measure_text(text)?;
+    if text_width > length {
+        context.restore();
+        return Ok(());
+    }
+
+    let mut points = line_string.0.clone();
+    if first.x > last.x {
+        points.reverse();
+    }
+    let line_string = LineString::new(points);
+
+    let mut characters = vec![];
+    let mut distance = (length - text_width) / 2.;
+    for character in text.chars() {
+        let character = character.to_string();
+        let width = context.measure_text(&character)?;
+
+        if let Some((position, angle)) = position_along(&line_string, distance + width / 2.) {
+            characters.push((character, position, angle));
+        }
+
+        distance += width;
+    }
+
+    // All halos are rendered first as they would otherwise overlap the previous characters
+    for halo in [true, false] {
+        if halo && style.halo_width == 0 {
+            continue;
+        }
+
+        for (character, position, angle) in &characters {
+            context.save();
+            context.translate(position.x, position.y)?;
+            context.rotate(*angle)?;
+
+            match halo {
+                true => context.stroke_text(character, 0., 0.)?,
+                false => context.fill_text(character, 0., 0.)?,
+            }
+
+            context.restore();
+        }
+    }
+
+    context.restore();
+
+    Ok(())
+}
+
+/// Returns the point at `distance` along `line_string` together with the angle of the segment the point
+/// lies on. Returns `None` if `distance` is not on the line string.
+fn position_along(line_string: &LineString<f64>, distance: f64) -> Option<(Coordinate<f64>, f64)> {
+    if distance < 0. {
+        return None;
+    }
+
+    let mut travelled = 0.;
+    for line in line_string.lines() {
+        let length = line.euclidean_length();
+        if length > 0. && distance <= travelled + length {
+            let delta = line.delta();
+            let t = (distance - travelled) / length;
+
+            return Some((line.start + delta * t, delta.y.atan2(delta.x)));
+        }
+
+        travelled += length;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use geo::{Coordinate, LineString};
+
+    use super::position_along;
+
+    #[test]
+    fn unit_position_along_follows_segments() {
+        let line_string = LineString::from(vec![(0., 0.), (100., 0.), (100., 100.)]);
+
+        assert_eq!(
+            position_along(&line_string, 50.),
+            Some((Coordinate { x: 50., y: 0. }, 0.))
+        );
+        assert_eq!(
+            position_along(&line_string, 150.),
+            Some((Coordinate { x: 100., y: 50. }, FRAC_PI_2))
+        );
+        assert_eq!(position_along(&line_string, 250.), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! Records all calls to the canvas instead of rendering them, which allows tests to check what was rendered.
+
+use super::{estimate_text_width, Canvas, RenderError};
+
+/// A single call to a [`Canvas`] together with its arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Save,
+    Restore,
+    Translate(f64, f64),
+    Rotate(f64),
+    SetTransform(f64, f64, f64, f64, f64, f64),
+    ClearRect(f64, f64, f64, f64),
+    SetGlobalCompositeOperation(String),
+    BeginPath,
+    ClosePath,
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    Arc(f64, f64, f64, f64, f64),
+    Rect(f64, f64, f64, f64),
+    Fill,
+    Stroke,
+    FillRect(f64, f64, f64, f64),
+    SetFillStyle(String),
+    SetStrokeStyle(String),
+    SetLineWidth(f64),
+    SetLineJoin(String),
+    SetFont(String),
+    SetTextAlign(String),
+    SetTextBaseline(String),
+    FillText(String, f64, f64),
+    StrokeText(String, f64, f64),
+    MeasureText(String),
+}
+
+/// Canvas that records every call in order. Text is measured with an estimated width as there is no font to
+/// measure it with.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingCanvas {
+    commands: Vec<DrawCommand>,
+
+    /// Fonts of the saved states, the last one is the current font.
+    fonts: Vec<String>,
+}
+
+impl RecordingCanvas {
+    pub fn new() -> Self {
+        RecordingCanvas::default()
+    }
+
+    pub fn commands(&self) -> &Vec<DrawCommand> {
+        &self.commands
+    }
+
+    /// Returns all texts that were filled in the order they were rendered.
+    pub fn texts(&self) -> Vec<&str> {
+        self.commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::FillText(text, _, _) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Removes all recorded commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    fn font(&self) -> &str {
+        self.fonts
+            .last()
+            .map_or("10px sans-serif", |font| font.as_str())
+    }
+}
+
+impl Canvas for RecordingCanvas {
+    fn save(&mut self) {
+        self.fonts.push(self.font().to_string());
+        self.commands.push(DrawCommand::Save);
+    }
+
+    fn restore(&mut self) {
+        self.fonts.pop();
+        self.commands.push(DrawCommand::Restore);
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> Result<(), RenderError> {
+        self.commands.push(DrawCommand::Translate(x, y));
+        Ok(())
+    }
+
+    fn rotate(&mut self, angle: f64) -> Result<(), RenderError> {
+        self.commands.push(DrawCommand::Rotate(angle));
+        Ok(())
+    }
+
+    fn set_transform(
+        &mut self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Result<(), RenderError> {
+        self.commands
+            .push(DrawCommand::SetTransform(a, b, c, d, e, f));
+        Ok(())
+    }
+
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.commands
+            .push(DrawCommand::ClearRect(x, y, width, height));
+    }
+
+    fn set_global_composite_operation(&mut self, operation: &str) -> Result<(), RenderError> {
+        self.commands.push(DrawCommand::SetGlobalCompositeOperation(
+            operation.to_string(),
+        ));
+        Ok(())
+    }
+
+    fn begin_path(&mut self) {
+        self.commands.push(DrawCommand::BeginPath);
+    }
+
+    fn close_path(&mut self) {
+        self.commands.push(DrawCommand::ClosePath);
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.commands.push(DrawCommand::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        self.commands.push(DrawCommand::LineTo(x, y));
+    }
+
+    fn arc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Result<(), RenderError> {
+        self.commands
+            .push(DrawCommand::Arc(x, y, radius, start_angle, end_angle));
+        Ok(())
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.commands.push(DrawCommand::Rect(x, y, width, height));
+    }
+
+    fn fill(&mut self) {
+        self.commands.push(DrawCommand::Fill);
+    }
+
+    fn stroke(&mut self) {
+        self.commands.push(DrawCommand::Stroke);
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.commands
+            .push(DrawCommand::FillRect(x, y, width, height));
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.commands
+            .push(DrawCommand::SetFillStyle(color.to_string()));
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.commands
+            .push(DrawCommand::SetStrokeStyle(color.to_string()));
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.commands.push(DrawCommand::SetLineWidth(width));
+    }
+
+    fn set_line_join(&mut self, join: &str) {
+        self.commands
+            .push(DrawCommand::SetLineJoin(join.to_string()));
+    }
+
+    fn set_font(&mut self, font: &str) {
+        match self.fonts.last_mut() {
+            Some(current) => *current = font.to_string(),
+            None => self.fonts.push(font.to_string()),
+        }
+        self.commands.push(DrawCommand::SetFont(font.to_string()));
+    }
+
+    fn set_text_align(&mut self, align: &str) {
+        self.commands
+            .push(DrawCommand::SetTextAlign(align.to_string()));
+    }
+
+    fn set_text_baseline(&mut self, baseline: &str) {
+        self.commands
+            .push(DrawCommand::SetTextBaseline(baseline.to_string()));
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        self.commands
+            .push(DrawCommand::FillText(text.to_string(), x, y));
+        Ok(())
+    }
+
+    fn stroke_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        self.commands
+            .push(DrawCommand::StrokeText(text.to_string(), x, y));
+        Ok(())
+    }
+
+    fn measure_text(&mut self, text: &str) -> Result<f64, RenderError> {
+        self.commands
+            .push(DrawCommand::MeasureText(text.to_string()));
+        Ok(estimate_text_width(text, self.font()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString, Polygon};
+
+    use crate::{
+        renderer::{render_text, Canvas, PrimitiveRenderer},
+        style::{Style, TextStyle},
+    };
+
+    use super::{DrawCommand, RecordingCanvas};
+
+    #[test]
+    fn unit_polygon_is_recorded_as_filled_path() {
+        let mut canvas = RecordingCanvas::new();
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 0.)]),
+            vec![],
+        );
+
+        polygon
+            .render(
+                &Style {
+                    border_width: 0,
+                    border_color: "#000000".to_string(),
+                    background_color: "#FF0000".to_string(),
+                },
+                &mut canvas,
+            )
+            .unwrap();
+
+        assert_eq!(
+            canvas.commands()[..7],
+            [
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(0., 0.),
+                DrawCommand::LineTo(10., 0.),
+                DrawCommand::LineTo(10., 10.),
+                DrawCommand::LineTo(0., 0.),
+                DrawCommand::ClosePath,
+                DrawCommand::SetFillStyle("#FF0000".to_string()),
+            ]
+        );
+        assert!(!canvas.commands().contains(&DrawCommand::Stroke));
+    }
+
+    #[test]
+    fn unit_text_is_measured_with_the_current_font() {
+        let mut canvas = RecordingCanvas::new();
+        assert_eq!(canvas.measure_text("abcde"), Ok(30.));
+
+        canvas.save();
+        canvas.set_font("20px Roboto");
+        assert_eq!(canvas.measure_text("abcde"), Ok(60.));
+        canvas.restore();
+
+        assert_eq!(canvas.measure_text("abcde"), Ok(30.));
+    }
+
+    #[test]
+    fn unit_rendered_texts_are_listed() {
+        let mut canvas = RecordingCanvas::new();
+        render_text(
+            "Market",
+            Coordinate { x: 5., y: 5. },
+            &TextStyle::default(),
+            &mut canvas,
+        )
+        .unwrap();
+
+        assert_eq!(canvas.texts(), vec!["Market"]);
+        assert!(canvas
+            .commands()
+            .contains(&DrawCommand::StrokeText("Market".to_string(), 5., 5.)));
+    }
+}
@@ -0,0 +1,464 @@
+//! Renders to an SVG document, e.g. to export a map or to render outside of a browser.
+
+use std::f64::consts::PI;
+
+use geo::Coordinate;
+
+use super::{estimate_text_width, Canvas, RenderError};
+
+/// Styles and transformation that are pushed by `save` and popped by `restore`.
+#[derive(Clone, Debug)]
+struct State {
+    /// The matrix `[a c e; b d f; 0 0 1]` stored as `[a, b, c, d, e, f]` like in the html canvas.
+    transform: [f64; 6],
+
+    fill: String,
+    stroke: String,
+    line_width: f64,
+    line_join: String,
+    font: String,
+    text_align: String,
+    text_baseline: String,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            transform: [1., 0., 0., 1., 0., 0.],
+            fill: "#000000".to_string(),
+            stroke: "#000000".to_string(),
+            line_width: 1.,
+            line_join: "miter".to_string(),
+            font: "10px sans-serif".to_string(),
+            text_align: "start".to_string(),
+            text_baseline: "alphabetic".to_string(),
+        }
+    }
+}
+
+impl State {
+    fn apply(&self, x: f64, y: f64) -> Coordinate<f64> {
+        let [a, b, c, d, e, f] = self.transform;
+
+        Coordinate {
+            x: a * x + c * y + e,
+            y: b * x + d * y + f,
+        }
+    }
+
+    /// Factor by which the transformation scales lengths on average.
+    fn scale(&self) -> f64 {
+        let [a, b, c, d, _, _] = self.transform;
+
+        (a * d - b * c).abs().sqrt()
+    }
+
+    fn multiply(&mut self, [a2, b2, c2, d2, e2, f2]: [f64; 6]) {
+        let [a, b, c, d, e, f] = self.transform;
+
+        self.transform = [
+            a * a2 + c * b2,
+            b * a2 + d * b2,
+            a * c2 + c * d2,
+            b * c2 + d * d2,
+            a * e2 + c * f2 + e,
+            b * e2 + d * f2 + f,
+        ];
+    }
+}
+
+/// Canvas that builds an SVG document. Paths are transformed while they are built, just like in the html canvas, so
+/// each filled or stroked path becomes a `path` element in document coordinates.
+///
+/// Composite operations and clearing have no equivalent in SVG and are ignored. Text is measured with an estimated
+/// width as there is no font to measure it with.
+pub struct SvgCanvas {
+    width: f64,
+    height: f64,
+
+    elements: Vec<String>,
+    path: String,
+    states: Vec<State>,
+}
+
+impl SvgCanvas {
+    pub fn new(width: f64, height: f64) -> Self {
+        SvgCanvas {
+            width,
+            height,
+            elements: vec![],
+            path: String::new(),
+            states: vec![State::default()],
+        }
+    }
+
+    /// Returns the SVG document of everything rendered so far.
+    pub fn to_svg(&self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">{}</svg>",
+            self.elements.concat(),
+            w = number(self.width),
+            h = number(self.height),
+        )
+    }
+
+    fn state(&self) -> &State {
+        self.states.last().unwrap()
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        self.states.last_mut().unwrap()
+    }
+
+    fn push_point(&mut self, command: char, x: f64, y: f64) {
+        let point = self.state().apply(x, y);
+
+        self.path.push_str(&format!(
+            "{}{} {}",
+            command,
+            number(point.x),
+            number(point.y)
+        ));
+    }
+
+    fn push_text(&mut self, text: &str, x: f64, y: f64, paint: String) {
+        let state = self.state();
+        let anchor = match state.text_align.as_str() {
+            "center" => "middle",
+            "right" | "end" => "end",
+            _ => "start",
+        };
+        let baseline = match state.text_baseline.as_str() {
+            "top" => "text-before-edge",
+            "bottom" => "text-after-edge",
+            baseline => baseline,
+        };
+
+        let [a, b, c, d, e, f] = state.transform;
+        let transform = match state.transform == State::default().transform {
+            true => String::new(),
+            false => format!(
+                " transform=\"matrix({} {} {} {} {} {})\"",
+                number(a),
+                number(b),
+                number(c),
+                number(d),
+                number(e),
+                number(f)
+            ),
+        };
+
+        let element = format!(
+            "<text x=\"{}\" y=\"{}\"{} style=\"font: {}\" text-anchor=\"{}\" dominant-baseline=\"{}\" {}>{}</text>",
+            number(x),
+            number(y),
+            transform,
+            escape(&state.font),
+            anchor,
+            baseline,
+            paint,
+            escape(text)
+        );
+        self.elements.push(element);
+    }
+
+    fn stroke_paint(&self) -> String {
+        let state = self.state();
+
+        format!(
+            "stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"{}\"",
+            escape(&state.stroke),
+            number(state.line_width * state.scale()),
+            escape(&state.line_join)
+        )
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn save(&mut self) {
+        let state = self.state().clone();
+        self.states.push(state);
+    }
+
+    fn restore(&mut self) {
+        if self.states.len() > 1 {
+            self.states.pop();
+        }
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> Result<(), RenderError> {
+        self.state_mut().multiply([1., 0., 0., 1., x, y]);
+        Ok(())
+    }
+
+    fn rotate(&mut self, angle: f64) -> Result<(), RenderError> {
+        let (sin, cos) = angle.sin_cos();
+        self.state_mut().multiply([cos, sin, -sin, cos, 0., 0.]);
+        Ok(())
+    }
+
+    fn set_transform(
+        &mut self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        e: f64,
+        f: f64,
+    ) -> Result<(), RenderError> {
+        self.state_mut().transform = [a, b, c, d, e, f];
+        Ok(())
+    }
+
+    fn clear_rect(&mut self, _x: f64, _y: f64, _width: f64, _height: f64) {}
+
+    fn set_global_composite_operation(&mut self, _operation: &str) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    fn begin_path(&mut self) {
+        self.path.clear();
+    }
+
+    fn close_path(&mut self) {
+        if !self.path.is_empty() {
+            self.path.push('Z');
+        }
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.push_point('M', x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let command = match self.path.is_empty() {
+            true => 'M',
+            false => 'L',
+        };
+        self.push_point(command, x, y);
+    }
+
+    fn arc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Result<(), RenderError> {
+        let sweep = match end_angle - start_angle >= 2. * PI {
+            true => 2. * PI,
+            false => (end_angle - start_angle).rem_euclid(2. * PI),
+        };
+        let point_at = |angle: f64| (x + radius * angle.cos(), y + radius * angle.sin());
+
+        let (start_x, start_y) = point_at(start_angle);
+        self.line_to(start_x, start_y);
+
+        // SVG arcs can not describe a full circle, the arc is therefore split into parts of at most half a circle
+        let [a, b, c, d, _, _] = self.state().transform;
+        let clockwise = match a * d - b * c >= 0. {
+            true => 1,
+            false => 0,
+        };
+        let radius = number(radius * self.state().scale());
+        let parts = (sweep / PI).ceil() as usize;
+        for i in 1..=parts {
+            let (end_x, end_y) = point_at(start_angle + sweep * i as f64 / parts as f64);
+            let end = self.state().apply(end_x, end_y);
+
+            self.path.push_str(&format!(
+                "A{r} {r} 0 0 {} {} {}",
+                clockwise,
+                number(end.x),
+                number(end.y),
+                r = radius
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.push_point('M', x, y);
+        self.push_point('L', x + width, y);
+        self.push_point('L', x + width, y + height);
+        self.push_point('L', x, y + height);
+        self.path.push('Z');
+    }
+
+    fn fill(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+
+        let element = format!(
+            "<path d=\"{}\" fill=\"{}\"/>",
+            self.path,
+            escape(&self.state().fill)
+        );
+        self.elements.push(element);
+    }
+
+    fn stroke(&mut self) {
+        if self.path.is_empty() {
+            return;
+        }
+
+        let element = format!(
+            "<path d=\"{}\" fill=\"none\" {}/>",
+            self.path,
+            self.stroke_paint()
+        );
+        self.elements.push(element);
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let path = std::mem::take(&mut self.path);
+
+        self.rect(x, y, width, height);
+        self.fill();
+
+        self.path = path;
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.state_mut().fill = color.to_string();
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.state_mut().stroke = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.state_mut().line_width = width;
+    }
+
+    fn set_line_join(&mut self, join: &str) {
+        self.state_mut().line_join = join.to_string();
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.state_mut().font = font.to_string();
+    }
+
+    fn set_text_align(&mut self, align: &str) {
+        self.state_mut().text_align = align.to_string();
+    }
+
+    fn set_text_baseline(&mut self, baseline: &str) {
+        self.state_mut().text_baseline = baseline.to_string();
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        let paint = format!("fill=\"{}\"", escape(&self.state().fill));
+        self.push_text(text, x, y, paint);
+
+        Ok(())
+    }
+
+    fn stroke_text(&mut self, text: &str, x: f64, y: f64) -> Result<(), RenderError> {
+        let paint = format!("fill=\"none\" {}", self.stroke_paint());
+        self.push_text(text, x, y, paint);
+
+        Ok(())
+    }
+
+    fn measure_text(&mut self, text: &str) -> Result<f64, RenderError> {
+        Ok(estimate_text_width(text, &self.state().font))
+    }
+}
+
+/// Formats a number with at most three decimal places to keep the document small.
+fn number(value: f64) -> String {
+    let rounded = (value * 1000.).round() / 1000.;
+
+    match rounded == 0. {
+        true => "0".to_string(),
+        false => rounded.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use crate::renderer::Canvas;
+
+    use super::SvgCanvas;
+
+    #[test]
+    fn unit_filled_rect_becomes_path() {
+        let mut canvas = SvgCanvas::new(100., 50.);
+        canvas.set_fill_style("#FF0000");
+        canvas.fill_rect(0., 0., 10., 20.);
+
+        assert_eq!(
+            canvas.to_svg(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 100 50\">\
+             <path d=\"M0 0L10 0L10 20L0 20Z\" fill=\"#FF0000\"/></svg>"
+        );
+    }
+
+    #[test]
+    fn unit_paths_are_transformed_and_styles_restored() {
+        let mut canvas = SvgCanvas::new(100., 100.);
+
+        canvas.save();
+        canvas.translate(10., 20.).unwrap();
+        canvas.rotate(FRAC_PI_2).unwrap();
+        canvas.set_stroke_style("blue");
+        canvas.set_line_width(2.);
+        canvas.begin_path();
+        canvas.move_to(0., 0.);
+        canvas.line_to(5., 0.);
+        canvas.stroke();
+        canvas.restore();
+
+        canvas.begin_path();
+        canvas.move_to(0., 0.);
+        canvas.line_to(5., 0.);
+        canvas.stroke();
+
+        let svg = canvas.to_svg();
+        assert!(svg.contains(
+            "<path d=\"M10 20L10 25\" fill=\"none\" stroke=\"blue\" stroke-width=\"2\" stroke-linejoin=\"miter\"/>"
+        ));
+        assert!(svg.contains(
+            "<path d=\"M0 0L5 0\" fill=\"none\" stroke=\"#000000\" stroke-width=\"1\" stroke-linejoin=\"miter\"/>"
+        ));
+    }
+
+    #[test]
+    fn unit_full_circle_is_split_into_two_arcs() {
+        let mut canvas = SvgCanvas::new(100., 100.);
+        canvas.begin_path();
+        canvas.arc(50., 50., 10., 0., 2. * PI).unwrap();
+        canvas.fill();
+
+        assert!(canvas
+            .to_svg()
+            .contains("<path d=\"M60 50A10 10 0 0 1 40 50A10 10 0 0 1 60 50\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn unit_text_is_escaped() {
+        let mut canvas = SvgCanvas::new(100., 100.);
+        canvas.set_font("14px Roboto");
+        canvas.set_text_align("center");
+        canvas.fill_text("Fish & <Chips>", 10., 10.).unwrap();
+
+        assert!(canvas.to_svg().contains(
+            "<text x=\"10\" y=\"10\" style=\"font: 14px Roboto\" text-anchor=\"middle\" dominant-baseline=\"alphabetic\" \
+             fill=\"#000000\">Fish &amp; &lt;Chips&gt;</text>"
+        ));
+    }
+}
@@ -24,6 +24,44 @@ impl Default for Style {
     }
 }
 
+/// Defines how text like labels will be displayed.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct TextStyle {
+    /// Accepts all font families in css format see https://developer.mozilla.org/en-US/docs/Web/CSS/font-family
+    pub font: String,
+
+    /// In pixels.
+    pub size: u32,
+
+    /// The fill color of the text. Accepts all values in css format (RGB, RGBA, #000000, HSL etc.) see https://developer.mozilla.org/en-US/docs/Web/CSS/color_value
+    pub color: String,
+
+    /// In pixels. The halo is an outline around the text that keeps it readable on any background. A halo width of 0 disables it.
+    pub halo_width: u32,
+
+    /// Accepts all values in css format (RGB, RGBA, #000000, HSL etc.) see https://developer.mozilla.org/en-US/docs/Web/CSS/color_value
+    pub halo_color: String,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            font: "Roboto, sans-serif".to_string(),
+            size: 14,
+            color: "#FFFFFF".to_string(),
+            halo_width: 2,
+            halo_color: "#2A2A2B".to_string(),
+        }
+    }
+}
+
+impl TextStyle {
+    /// The font in the format of the css font shorthand as it is expected by the canvas.
+    pub fn css_font(&self) -> String {
+        format!("{}px {}", self.size, self.font)
+    }
+}
+
 /// Defines multiple styles that can applied to an element based on the current state of it
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct InteractiveElementStyle {
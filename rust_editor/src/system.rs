@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
 use geo::Coordinate;
-use wasm_bindgen::JsValue;
-use web_sys::CanvasRenderingContext2d;
 
-use crate::{plugin::PluginWithOptions, InformationLayer};
+use crate::{
+    plugin::PluginWithOptions,
+    renderer::{Canvas, RenderError},
+    InformationLayer,
+};
 
 
 /// Editing functionality is encapsuled into different states. Each state is responsible to render the map and all additional information needed.
@@ -64,10 +66,10 @@ pub trait System<Data>
     fn render(
         &self,
         data: &Data,
-        context: &CanvasRenderingContext2d,
+        context: &mut dyn Canvas,
         additional_information_layer: &Vec<InformationLayer>,
         plugins: &HashMap<&'static str, Box<dyn PluginWithOptions<Data>>>
-    ) -> Result<(), JsValue> {
+    ) -> Result<(), RenderError> {
         Ok(())
     }
 
@@ -9,14 +9,17 @@ use wasm_bindgen::JsCast;
 use yew::html::Scope;
 
 use crate::input::keyboard::Key;
-//use crate::plugins::camera::Camera;
 //use crate::plugins::plugin::{PluginWithOptions, SpecialKey};
 
 use crate::error;
+use crate::input::mouse;
 use crate::plugin::{PluginWithOptions, SpecialKey};
+use crate::renderer::Canvas;
+use crate::viewport::Viewport;
 use geo::Coordinate;
 use web_sys::{
     CanvasRenderingContext2d, DragEvent, HtmlCanvasElement, KeyboardEvent, MouseEvent, PointerEvent,
+    WheelEvent,
 };
 
 use yew::{html, AppHandle, Component, Context, Html, NodeRef, Properties};
@@ -41,6 +44,7 @@ pub enum EditorMessages<Data> {
     MouseMove(MouseEvent),
     MouseDown(MouseEvent),
     MouseUp(MouseEvent),
+    Wheel(WheelEvent),
     KeyDown(KeyboardEvent),
     KeyUp(KeyboardEvent),
     ShortkeyPressed(Shortkey),
@@ -53,6 +57,9 @@ pub enum EditorMessages<Data> {
 
 pub type Shortkey = Vec<Key>;
 
+/// Height of a line in pixels for wheel events that are scrolled by lines.
+const WHEEL_LINE_HEIGHT: f64 = 33.;
+
 pub type PluginId = &'static str;
 
 #[derive(Error, Debug)]
@@ -94,10 +101,17 @@ where
 
     canvas_size: Coordinate<i32>,
 
+    /// Transforms the screen coordinates of the canvas to the world coordinates of the data.
+    viewport: Viewport,
+
     last_mouse_pos: Coordinate<f64>,
+
+    /// Messages sent by plugins while an event is handled. They are processed after the event, see
+    /// [`App::send_message`].
+    messages: Vec<EditorMessages<Data>>,
 }
 
-// Not functional. Is used for test cases
+// Has no canvas attached. Is used for test cases and by the headless app
 impl<Data> Default for App<Data>
 where
     Data: Default,
@@ -112,7 +126,9 @@ where
             context: Default::default(),
             pressed_keys: Default::default(),
             canvas_size: Default::default(),
+            viewport: Viewport::default(),
             last_mouse_pos: Coordinate { x: 0., y: 0. },
+            messages: Vec::new(),
         }
     }
 }
@@ -136,6 +152,25 @@ where
         self.data = data
     }
 
+    /// Returns the size of the canvas in pixels.
+    pub fn canvas_size(&self) -> Coordinate<i32> {
+        self.canvas_size
+    }
+
+    pub(crate) fn set_canvas_size(&mut self, size: Coordinate<i32>) {
+        self.canvas_size = size
+    }
+
+    /// Returns the view on the data. Plugins receive all positions in world coordinates already, use
+    /// [`Viewport::world_to_screen`] for positions that have to be given in pixels.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn viewport_mut(&mut self) -> &mut Viewport {
+        &mut self.viewport
+    }
+
     /// Finds a plugin that was registered to the editor instance and let you perform non mutable actions on it.
     /// To perform the action you need to specify a closure `f`.
     ///
@@ -244,7 +279,7 @@ where
     ///         Ok(())
     ///     }
     ///
-    ///     fn shortkey_pressed(&mut self, key: &Shortkey, _: &Context<App<Data>>, editor: &mut App<Data>) {
+    ///     fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Data>) {
     ///         if *key == vec![Key::Ctrl, Key::Z] {
     ///             ...
     ///         }
@@ -274,6 +309,27 @@ where
     pub fn has_shortkey(&self, key: Shortkey) -> bool {
         self.shortkeys.values().any(|x| x.contains(&&key))
     }
+
+    /// Sends a message to the editor, e.g. to activate a plugin. The message is processed after the current event
+    /// was handled by all plugins.
+    ///
+    /// # Example
+    ///
+    /// ```
+    ///
+    /// fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Data>) {
+    ///     editor.send_message(EditorMessages::ActivatePlugin(MyPlugin::identifier()));
+    /// }
+    ///
+    /// ```
+    pub fn send_message(&mut self, message: EditorMessages<Data>) {
+        self.messages.push(message);
+    }
+
+    /// Removes and returns all messages that were sent since the last call.
+    pub(crate) fn take_messages(&mut self) -> Vec<EditorMessages<Data>> {
+        std::mem::take(&mut self.messages)
+    }
 }
 
 #[derive(Properties, PartialEq, Default)]
@@ -306,7 +362,9 @@ where
                 x: body.client_width(),
                 y: body.client_height(),
             },
+            viewport: Viewport::default(),
             last_mouse_pos: Coordinate { x: 0., y: 0. },
+            messages: Vec::new(),
         }
     }
 
@@ -339,117 +397,49 @@ where
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
+        let rerender = match msg {
             EditorMessages::Drop(e) => {
                 e.prevent_default();
 
                 for (_, plugin) in enabled_plugins(&mut self.plugins) {
                     plugin.as_ref().borrow_mut().drop(e.clone())
                 }
+
+                true
             }
             EditorMessages::DragOver(e) => {
                 e.prevent_default();
-            }
-            EditorMessages::UpdateElements() => return true,
-            EditorMessages::AddPlugin((key, plugin)) => {
-                if let Err(e) = plugin.as_ref().borrow_mut().startup(self) {
-                    error!("{}", e)
-                }
-                self.plugins.insert(key, plugin);
-
-                ctx.link().send_message(EditorMessages::ActivatePlugin(key));
-                return true;
-            }
-            EditorMessages::AddPlugins(plugins) => {
-                for (key, plugin) in plugins {
-                    if let Err(e) = plugin.as_ref().borrow_mut().startup(self) {
-                        error!("{}", e)
-                    }
-
-                    self.plugins.insert(key, plugin);
 
-                    ctx.link().send_message(EditorMessages::ActivatePlugin(key));
-                }
-
-                return true;
+                true
             }
             EditorMessages::MouseMove(e) => {
-                let mouse_pos = self.mouse_pos(e.client_x() as u32, e.client_y() as u32);
-                let mouse_diff = mouse_pos - self.last_mouse_pos;
+                self.handle_mouse_move(client_pos(&e), e.button().into());
 
-                for (_, plugin) in enabled_plugins(&mut self.plugins) {
-                    if plugin.as_ref().borrow_mut().mouse_move(
-                        mouse_pos,
-                        mouse_diff,
-                        e.button().into(),
-                        self,
-                    ) {
-                        break;
-                    }
-                }
+                true
             }
             EditorMessages::MouseDown(e) => {
-                let mouse_pos = self.mouse_pos(e.client_x() as u32, e.client_y() as u32);
-
-                self.last_mouse_pos = mouse_pos;
-
-                let enabled_plugins = enabled_plugins(&mut self.plugins);
-                for (_, plugin) in &enabled_plugins {
-                    if plugin
-                        .as_ref()
-                        .borrow_mut()
-                        .mouse_down(mouse_pos, e.button().into(), self)
-                    {
-                        break;
-                    }
-                }
+                self.handle_mouse_down(client_pos(&e), e.button().into());
+
+                true
             }
             EditorMessages::MouseUp(e) => {
-                let mouse_pos = self.mouse_pos(e.client_x() as u32, e.client_y() as u32);
+                self.handle_mouse_up(client_pos(&e), e.button().into());
 
-                self.last_mouse_pos = mouse_pos;
+                true
+            }
+            EditorMessages::Wheel(e) => {
+                self.handle_mouse_wheel(client_pos(&e), self.wheel_delta(&e));
 
-                for (_, plugin) in enabled_plugins(&mut self.plugins) {
-                    if plugin
-                        .as_ref()
-                        .borrow_mut()
-                        .mouse_up(mouse_pos, e.button().into(), self)
-                    {
-                        break;
-                    }
-                }
+                true
             }
             EditorMessages::KeyDown(e) => {
                 e.prevent_default();
 
-                let key: Key = e.key().into();
-                match self.pressed_keys.last() {
-                    Some(last) => {
-                        if *last != key {
-                            self.pressed_keys.push(key)
-                        }
-                    }
-                    None => self.pressed_keys.push(key),
-                }
-
-                for shortkeys in self.shortkeys.values() {
-                    for shortkey in shortkeys {
-                        if self.pressed_keys.ends_with(shortkey) {
-                            ctx.link()
-                                .send_message(EditorMessages::ShortkeyPressed(shortkey.clone()));
-                        }
-                    }
-                }
-
-                for (_, plugin) in enabled_plugins(&mut self.plugins) {
-                    plugin.as_ref().borrow_mut().key_down(e.key().into(), self);
-                }
+                self.handle_key_down(e.key().into());
 
-                return true;
+                true
             }
             EditorMessages::KeyUp(e) => {
-                self.pressed_keys.retain(|value| *value != e.key().into());
-
                 let mut special_keys = vec![];
                 if e.ctrl_key() {
                     special_keys.push(SpecialKey::Ctrl);
@@ -461,88 +451,23 @@ where
                     special_keys.push(SpecialKey::Shift);
                 }
 
-                for (_, plugin) in enabled_plugins(&mut self.plugins) {
-                    plugin.as_ref().borrow_mut().key_up(e.key().into(), self);
-                }
+                self.handle_key_up(e.key().into());
 
-                return true;
-            }
-            EditorMessages::ShortkeyPressed(shortkey) => {
-                for (plugin_id, shortkeys) in self.shortkeys.clone().iter() {
-                    if shortkeys.contains(&shortkey) {
-                        let plugin = Rc::clone(self.plugins.get(plugin_id).unwrap());
-                        let mut plugin = plugin.as_ref().borrow_mut();
-                        plugin.shortkey_pressed(&shortkey, ctx, self);
-                    }
-                }
-
-                return true;
+                true
             }
             EditorMessages::Render(_) => {
                 self.render(ctx.link());
-            }
-            EditorMessages::PluginOptionUpdated((plugin, attribute, value)) => {
-                let plugin = Rc::clone(self.plugins.get(plugin).unwrap_or_else(|| panic!("plugin with key {} is not present but received an option update. Make sure that the plugin is not destroyed during runtime", plugin)));
-                plugin
-                    .as_ref()
-                    .borrow_mut()
-                    .update_property(attribute, value);
 
-                plugin
-                    .as_ref()
-                    .borrow_mut()
-                    .property_updated(attribute, self);
-
-                return true;
+                true
             }
-            EditorMessages::ActivatePlugin(plugin_id) => {
-                if !self.plugins.contains_key(plugin_id) {
-                    error!(
-                        "tried to activate plugin with id {} which is not registered",
-                        plugin_id
-                    );
-                    return true;
-                }
-
-                if let Some((_, exclusive_active_plugin)) =
-                    self.plugins.iter_mut().find(|(_, x)| {
-                        let x = x.borrow();
-                        x.enabled()
-                            && x.execution_behaviour() == &PluginExecutionBehaviour::Exclusive
-                    })
-                {
-                    exclusive_active_plugin.as_ref().borrow_mut().disable();
-                }
-
-                self.plugins
-                    .get_mut(plugin_id)
-                    .unwrap()
-                    .as_ref()
-                    .borrow_mut()
-                    .enable();
-
-                return true;
-            }
-            EditorMessages::PluginMessage(plugin_id, message) => {
-                if !self.plugins.contains_key(plugin_id) {
-                    error!(
-                        "tried to send message to a plugin with id {} which is not registered",
-                        plugin_id
-                    );
-                    return true;
-                }
+            msg => self.dispatch(msg),
+        };
 
-                self.plugins
-                    .get_mut(plugin_id)
-                    .unwrap()
-                    .as_ref()
-                    .borrow_mut()
-                    .on_message(message);
-            }
-            EditorMessages::RerenderView => return true,
+        for message in self.take_messages() {
+            ctx.link().send_message(message);
         }
 
-        true
+        rerender
     }
 
     fn view(&self, ctx: &yew::Context<Self>) -> Html {
@@ -550,6 +475,7 @@ where
         let onmousedown = ctx.link().callback(|e| EditorMessages::MouseDown(e));
         let onmouseup = ctx.link().callback(|e| EditorMessages::MouseUp(e));
         let onmousemove = ctx.link().callback(|e| EditorMessages::MouseMove(e));
+        let onwheel = ctx.link().callback(|e| EditorMessages::Wheel(e));
 
         // Context menu event aka right click
         /*
@@ -601,6 +527,7 @@ where
                     {onmousedown}
                     {onmouseup}
                     {onmousemove}
+                    {onwheel}
                     {onkeyup}
                     {onkeydown}
                     {onpointermove}
@@ -623,26 +550,247 @@ where
         .collect()
 }
 
+/// Returns the position of the cursor of a mouse event on the screen.
+fn client_pos(e: &MouseEvent) -> Coordinate<f64> {
+    Coordinate {
+        x: e.client_x() as f64,
+        y: e.client_y() as f64,
+    }
+}
+
 impl<Data> App<Data>
 where
     Data: Default + 'static,
 {
-    fn mouse_pos(&self, x: u32, y: u32) -> Coordinate<f64> {
-        let offset: Coordinate<f64> = Coordinate { x: 0., y: 0. };
+    fn mouse_pos(&self, screen_pos: Coordinate<f64>) -> Coordinate<f64> {
+        self.viewport.screen_to_world(screen_pos)
+    }
 
-        /*
-        self.plugin(|camera: &Camera| {
-            offset = Coordinate {
-                x: camera.x(),
-                y: camera.y(),
-            };
-        });
-        */
+    /// Returns the vertical scroll amount of a wheel event in pixels. Some browsers report it in lines or pages.
+    fn wheel_delta(&self, e: &WheelEvent) -> f64 {
+        match e.delta_mode() {
+            WheelEvent::DOM_DELTA_LINE => e.delta_y() * WHEEL_LINE_HEIGHT,
+            WheelEvent::DOM_DELTA_PAGE => e.delta_y() * f64::from(self.canvas_size.y),
+            _ => e.delta_y(),
+        }
+    }
 
-        return Coordinate {
-            x: x as f64 - offset.x,
-            y: y as f64 - offset.y,
-        };
+    /// Starts the plugin and adds it to the editor. The plugin is activated by a message, so it is only enabled once
+    /// the messages are processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the plugin startup. The plugin is added nevertheless.
+    pub(crate) fn register_plugin(
+        &mut self,
+        key: PluginId,
+        plugin: Rc<RefCell<dyn PluginWithOptions<Data>>>,
+    ) -> Result<(), EditorError> {
+        let result = plugin.as_ref().borrow_mut().startup(self);
+
+        self.plugins.insert(key, plugin);
+        self.send_message(EditorMessages::ActivatePlugin(key));
+
+        result
+    }
+
+    pub(crate) fn handle_mouse_move(&mut self, screen_pos: Coordinate<f64>, button: mouse::Button) {
+        let mouse_pos = self.mouse_pos(screen_pos);
+        let mouse_diff = mouse_pos - self.last_mouse_pos;
+
+        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+            if plugin
+                .as_ref()
+                .borrow_mut()
+                .mouse_move(mouse_pos, mouse_diff, button, self)
+            {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn handle_mouse_down(&mut self, screen_pos: Coordinate<f64>, button: mouse::Button) {
+        let mouse_pos = self.mouse_pos(screen_pos);
+
+        self.last_mouse_pos = mouse_pos;
+
+        let enabled_plugins = enabled_plugins(&mut self.plugins);
+        for (_, plugin) in &enabled_plugins {
+            if plugin
+                .as_ref()
+                .borrow_mut()
+                .mouse_down(mouse_pos, button, self)
+            {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn handle_mouse_up(&mut self, screen_pos: Coordinate<f64>, button: mouse::Button) {
+        let mouse_pos = self.mouse_pos(screen_pos);
+
+        self.last_mouse_pos = mouse_pos;
+
+        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+            if plugin
+                .as_ref()
+                .borrow_mut()
+                .mouse_up(mouse_pos, button, self)
+            {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn handle_mouse_wheel(&mut self, screen_pos: Coordinate<f64>, delta: f64) {
+        let mouse_pos = self.mouse_pos(screen_pos);
+
+        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+            if plugin
+                .as_ref()
+                .borrow_mut()
+                .mouse_wheel(mouse_pos, delta, self)
+            {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn handle_key_down(&mut self, key: Key) {
+        match self.pressed_keys.last() {
+            Some(last) => {
+                if *last != key {
+                    self.pressed_keys.push(key)
+                }
+            }
+            None => self.pressed_keys.push(key),
+        }
+
+        let pressed_shortkeys: Vec<Shortkey> = self
+            .shortkeys
+            .values()
+            .flatten()
+            .filter(|shortkey| self.pressed_keys.ends_with(shortkey))
+            .cloned()
+            .collect();
+        for shortkey in pressed_shortkeys {
+            self.send_message(EditorMessages::ShortkeyPressed(shortkey));
+        }
+
+        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+            plugin.as_ref().borrow_mut().key_down(key, self);
+        }
+    }
+
+    pub(crate) fn handle_key_up(&mut self, key: Key) {
+        self.pressed_keys.retain(|value| *value != key);
+
+        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+            plugin.as_ref().borrow_mut().key_up(key, self);
+        }
+    }
+
+    /// Processes all messages that do not carry a native web event. These are handled by the [`Component`]
+    /// implementation directly.
+    pub(crate) fn dispatch(&mut self, msg: EditorMessages<Data>) -> bool {
+        match msg {
+            EditorMessages::UpdateElements() => return true,
+            EditorMessages::AddPlugin((key, plugin)) => {
+                if let Err(e) = self.register_plugin(key, plugin) {
+                    error!("{}", e)
+                }
+
+                return true;
+            }
+            EditorMessages::AddPlugins(plugins) => {
+                for (key, plugin) in plugins {
+                    if let Err(e) = self.register_plugin(key, plugin) {
+                        error!("{}", e)
+                    }
+                }
+
+                return true;
+            }
+            EditorMessages::ShortkeyPressed(shortkey) => {
+                for (plugin_id, shortkeys) in self.shortkeys.clone().iter() {
+                    if shortkeys.contains(&shortkey) {
+                        let plugin = Rc::clone(self.plugins.get(plugin_id).unwrap());
+                        let mut plugin = plugin.as_ref().borrow_mut();
+                        plugin.shortkey_pressed(&shortkey, self);
+                    }
+                }
+
+                return true;
+            }
+            EditorMessages::PluginOptionUpdated((plugin, attribute, value)) => {
+                let plugin = Rc::clone(self.plugins.get(plugin).unwrap_or_else(|| panic!("plugin with key {} is not present but received an option update. Make sure that the plugin is not destroyed during runtime", plugin)));
+                plugin
+                    .as_ref()
+                    .borrow_mut()
+                    .update_property(attribute, value);
+
+                plugin
+                    .as_ref()
+                    .borrow_mut()
+                    .property_updated(attribute, self);
+
+                return true;
+            }
+            EditorMessages::ActivatePlugin(plugin_id) => {
+                if !self.plugins.contains_key(plugin_id) {
+                    error!(
+                        "tried to activate plugin with id {} which is not registered",
+                        plugin_id
+                    );
+                    return true;
+                }
+
+                if let Some((_, exclusive_active_plugin)) =
+                    self.plugins.iter_mut().find(|(_, x)| {
+                        let x = x.borrow();
+                        x.enabled()
+                            && x.execution_behaviour() == &PluginExecutionBehaviour::Exclusive
+                    })
+                {
+                    exclusive_active_plugin.as_ref().borrow_mut().disable();
+                }
+
+                self.plugins
+                    .get_mut(plugin_id)
+                    .unwrap()
+                    .as_ref()
+                    .borrow_mut()
+                    .enable();
+
+                return true;
+            }
+            EditorMessages::PluginMessage(plugin_id, message) => {
+                if !self.plugins.contains_key(plugin_id) {
+                    error!(
+                        "tried to send message to a plugin with id {} which is not registered",
+                        plugin_id
+                    );
+                    return true;
+                }
+
+                let plugin = Rc::clone(self.plugins.get(plugin_id).unwrap());
+                plugin.as_ref().borrow_mut().on_message(message, self);
+            }
+            EditorMessages::RerenderView => return true,
+
+            // Native web events
+            EditorMessages::MouseMove(_)
+            | EditorMessages::MouseDown(_)
+            | EditorMessages::MouseUp(_)
+            | EditorMessages::Wheel(_)
+            | EditorMessages::KeyDown(_)
+            | EditorMessages::KeyUp(_)
+            | EditorMessages::Render(_)
+            | EditorMessages::Drop(_)
+            | EditorMessages::DragOver(_) => return false,
+        }
+
+        true
     }
 
     pub fn plugins(
@@ -655,9 +803,9 @@ where
         self.plugins.iter()
     }
 
-    pub fn render(&mut self, link: &Scope<Self>) {
-        let context = self.context.as_ref().unwrap();
-
+    /// Renders all enabled plugins to `context`. Plugins render in world coordinates, the transform of the viewport is
+    /// applied to the context before.
+    pub fn render_to(&self, context: &mut dyn Canvas) {
         context.set_transform(1., 0., 0., 1., 0., 0.).unwrap();
 
         context.clear_rect(
@@ -667,15 +815,18 @@ where
             self.canvas_size.y.into(),
         );
 
-        /*
-        self.plugin(|camera: &Camera| {
-            context.translate(camera.x(), camera.y()).unwrap();
-        });
-        */
+        let [a, b, c, d, e, f] = self.viewport.transform();
+        context.set_transform(a, b, c, d, e, f).unwrap();
 
-        for (_, plugin) in enabled_plugins(&mut self.plugins) {
+        for (_, plugin) in enabled_plugins(&self.plugins) {
             plugin.as_ref().borrow_mut().render(context, self);
         }
+    }
+
+    pub fn render(&mut self, link: &Scope<Self>) {
+        // The context is a handle to the canvas in the browser, a clone still renders to the same canvas
+        let mut context = self.context.clone().unwrap();
+        self.render_to(&mut context);
 
         let handle = {
             let link = link.clone();
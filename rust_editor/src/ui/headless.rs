@@ -0,0 +1,347 @@
+//! Runs the editor without a browser, e.g. to test plugins with `cargo test`.
+
+use std::{cell::RefCell, rc::Rc};
+
+use geo::Coordinate;
+
+use crate::{
+    input::{keyboard::Key, mouse},
+    plugin::PluginWithOptions,
+    renderer::{recording::RecordingCanvas, svg::SvgCanvas},
+};
+
+use super::app::{App, EditorError, EditorMessages};
+
+/// Drives an [`App`] without a browser. Plugins are registered and started like in the editor and all events are
+/// dispatched to the plugins the same way as the events of the canvas. Messages sent by plugins are processed right
+/// after each event, so the data can be checked as soon as an event returns.
+///
+/// Positions of mouse events are given in screen coordinates just like the positions of native mouse events. Plugins
+/// receive them in world coordinates as transformed by the [`Viewport`](crate::viewport::Viewport) of the app.
+///
+/// # Example
+///
+/// ```
+///
+/// let mut app = HeadlessApp::<Map>::new(800, 600);
+/// app.add_plugin(PlaceLandmarks::default()).unwrap();
+///
+/// app.click(Coordinate { x: 100., y: 100. }, mouse::Button::Left);
+/// assert_eq!(app.data().landmarks().len(), 1);
+///
+/// ```
+pub struct HeadlessApp<Data>
+where
+    Data: Default + 'static,
+{
+    app: App<Data>,
+}
+
+impl<Data> Default for HeadlessApp<Data>
+where
+    Data: Default + 'static,
+{
+    fn default() -> Self {
+        HeadlessApp::new(1920, 1080)
+    }
+}
+
+impl<Data> HeadlessApp<Data>
+where
+    Data: Default + 'static,
+{
+    /// Creates an editor whose canvas has a size of `width` x `height` pixels.
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut app = App::default();
+        app.set_canvas_size(Coordinate {
+            x: width,
+            y: height,
+        });
+
+        HeadlessApp { app }
+    }
+
+    pub fn app(&self) -> &App<Data> {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut App<Data> {
+        &mut self.app
+    }
+
+    /// Returns a non mutable reference to the data hold by the editor.
+    pub fn data(&self) -> &Data {
+        self.app.data()
+    }
+
+    /// Starts the plugin, adds it to the editor and activates it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the plugin startup. The plugin is added nevertheless, just like in the editor.
+    pub fn add_plugin<P>(&mut self, plugin: P) -> Result<(), EditorError>
+    where
+        P: PluginWithOptions<Data> + 'static,
+    {
+        let result = self
+            .app
+            .register_plugin(P::identifier(), Rc::new(RefCell::new(plugin)));
+        self.process_messages();
+
+        result
+    }
+
+    /// Processes `message` and all messages that are sent by plugins in response.
+    ///
+    /// Messages that carry native web events are ignored, use the functions for mouse and key events instead.
+    pub fn send_message(&mut self, message: EditorMessages<Data>) {
+        self.app.dispatch(message);
+        self.process_messages();
+    }
+
+    pub fn mouse_down(&mut self, position: Coordinate<f64>, button: mouse::Button) {
+        self.app.handle_mouse_down(position, button);
+        self.process_messages();
+    }
+
+    pub fn mouse_move(&mut self, position: Coordinate<f64>, button: mouse::Button) {
+        self.app.handle_mouse_move(position, button);
+        self.process_messages();
+    }
+
+    pub fn mouse_up(&mut self, position: Coordinate<f64>, button: mouse::Button) {
+        self.app.handle_mouse_up(position, button);
+        self.process_messages();
+    }
+
+    /// Presses and releases `button` at `position`.
+    pub fn click(&mut self, position: Coordinate<f64>, button: mouse::Button) {
+        self.mouse_down(position, button);
+        self.mouse_up(position, button);
+    }
+
+    /// Presses `button` at `from`, moves the cursor to `to` and releases the button there.
+    pub fn drag(&mut self, from: Coordinate<f64>, to: Coordinate<f64>, button: mouse::Button) {
+        self.mouse_down(from, button);
+        self.mouse_move(to, button);
+        self.mouse_up(to, button);
+    }
+
+    /// Turns the mouse wheel by `delta` pixels at `position`, positive values scroll down.
+    pub fn wheel(&mut self, position: Coordinate<f64>, delta: f64) {
+        self.app.handle_mouse_wheel(position, delta);
+        self.process_messages();
+    }
+
+    pub fn key_down(&mut self, key: Key) {
+        self.app.handle_key_down(key);
+        self.process_messages();
+    }
+
+    pub fn key_up(&mut self, key: Key) {
+        self.app.handle_key_up(key);
+        self.process_messages();
+    }
+
+    /// Presses all `keys` in order and releases them in reverse order afterwards, so `[Key::Ctrl, Key::Z]` triggers
+    /// the shortkey Ctrl+Z.
+    pub fn press_keys(&mut self, keys: &[Key]) {
+        for key in keys {
+            self.key_down(*key);
+        }
+
+        for key in keys.iter().rev() {
+            self.key_up(*key);
+        }
+    }
+
+    /// Renders all enabled plugins and returns everything that was drawn.
+    pub fn render(&self) -> RecordingCanvas {
+        let mut canvas = RecordingCanvas::new();
+        self.app.render_to(&mut canvas);
+
+        canvas
+    }
+
+    /// Renders all enabled plugins to an SVG document of the size of the canvas.
+    pub fn render_svg(&self) -> String {
+        let size = self.app.canvas_size();
+
+        let mut canvas = SvgCanvas::new(size.x.into(), size.y.into());
+        self.app.render_to(&mut canvas);
+
+        canvas.to_svg()
+    }
+
+    /// Processes all pending messages including the messages that are sent while they are processed.
+    fn process_messages(&mut self) {
+        loop {
+            let messages = self.app.take_messages();
+            if messages.is_empty() {
+                break;
+            }
+
+            for message in messages {
+                self.app.dispatch(message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+
+    use crate::{
+        input::{keyboard::Key, mouse},
+        renderer::recording::DrawCommand,
+        ui::app::EditorError,
+    };
+
+    use super::HeadlessApp;
+
+    use self::{draw_points::DrawPoints, idle::Idle};
+
+    #[derive(Default)]
+    struct Drawing {
+        points: Vec<Coordinate<f64>>,
+    }
+
+    // Each plugin needs its own module as the plugin macro adds imports
+    mod draw_points {
+        use geo::Coordinate;
+        use rust_macro::editor_plugin;
+
+        use crate::{
+            input::{keyboard::Key, mouse},
+            plugin::{Plugin, PluginWithOptions},
+            renderer::Canvas,
+            ui::app::{EditorError, Shortkey},
+        };
+
+        use super::Drawing;
+
+        #[editor_plugin(skip, specific_to=Drawing, execution=Exclusive)]
+        pub struct DrawPoints {}
+
+        impl Plugin<Drawing> for DrawPoints {
+            fn startup(&mut self, editor: &mut App<Drawing>) -> Result<(), EditorError> {
+                editor.add_shortkey::<DrawPoints>(vec![Key::Ctrl, Key::P])?;
+
+                Ok(())
+            }
+
+            fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Drawing>) {
+                if *key == vec![Key::Ctrl, Key::P] {
+                    editor.send_message(EditorMessages::ActivatePlugin(DrawPoints::identifier()));
+                }
+            }
+
+            fn mouse_up(
+                &mut self,
+                mouse_pos: Coordinate<f64>,
+                button: mouse::Button,
+                editor: &mut App<Drawing>,
+            ) -> bool {
+                if button == mouse::Button::Left {
+                    editor.data_mut().points.push(mouse_pos);
+                }
+
+                false
+            }
+
+            fn render(&self, context: &mut dyn Canvas, editor: &App<Drawing>) {
+                context.set_fill_style("#FF0000");
+                for point in &editor.data().points {
+                    context.fill_rect(point.x, point.y, 1., 1.);
+                }
+            }
+        }
+    }
+
+    mod idle {
+        use rust_macro::editor_plugin;
+
+        use crate::plugin::Plugin;
+
+        use super::Drawing;
+
+        #[editor_plugin(skip, specific_to=Drawing, execution=Exclusive)]
+        pub struct Idle {}
+
+        impl Plugin<Drawing> for Idle {}
+    }
+
+    #[test]
+    fn integration_clicks_are_dispatched_to_enabled_plugins() {
+        let mut app = HeadlessApp::<Drawing>::new(100, 100);
+        app.add_plugin(DrawPoints::default()).unwrap();
+
+        app.click(Coordinate { x: 10., y: 20. }, mouse::Button::Left);
+        app.click(Coordinate { x: 30., y: 40. }, mouse::Button::Right);
+
+        assert_eq!(app.data().points, vec![Coordinate { x: 10., y: 20. }]);
+    }
+
+    #[test]
+    fn integration_pressed_shortkey_activates_plugin() {
+        let mut app = HeadlessApp::<Drawing>::new(100, 100);
+        app.add_plugin(DrawPoints::default()).unwrap();
+        app.add_plugin(Idle::default()).unwrap();
+
+        // The plugin added last is activated and disables the other exclusive plugin
+        app.click(Coordinate { x: 10., y: 20. }, mouse::Button::Left);
+        assert!(app.data().points.is_empty());
+
+        app.press_keys(&[Key::Ctrl, Key::P]);
+        app.click(Coordinate { x: 10., y: 20. }, mouse::Button::Left);
+        assert_eq!(app.data().points.len(), 1);
+    }
+
+    #[test]
+    fn integration_startup_errors_are_returned() {
+        let mut app = HeadlessApp::<Drawing>::new(100, 100);
+        app.add_plugin(DrawPoints::default()).unwrap();
+
+        assert!(matches!(
+            app.add_plugin(DrawPoints::default()),
+            Err(EditorError::ShortkeyExists { .. })
+        ));
+    }
+
+    #[test]
+    fn integration_enabled_plugins_are_rendered() {
+        let mut app = HeadlessApp::<Drawing>::new(100, 100);
+        app.add_plugin(DrawPoints::default()).unwrap();
+        app.click(Coordinate { x: 10., y: 20. }, mouse::Button::Left);
+
+        assert!(app
+            .render()
+            .commands()
+            .contains(&DrawCommand::FillRect(10., 20., 1., 1.)));
+        assert!(app
+            .render_svg()
+            .contains("<path d=\"M10 20L11 20L11 21L10 21Z\" fill=\"#FF0000\"/>"));
+    }
+
+    #[test]
+    fn integration_viewport_transforms_events_and_rendering() {
+        let mut app = HeadlessApp::<Drawing>::new(100, 100);
+        app.add_plugin(DrawPoints::default()).unwrap();
+
+        let viewport = app.app_mut().viewport_mut();
+        viewport.set_zoom(2.);
+        viewport.pan(Coordinate { x: 10., y: 0. });
+
+        app.click(Coordinate { x: 30., y: 40. }, mouse::Button::Left);
+        assert_eq!(app.data().points, vec![Coordinate { x: 10., y: 20. }]);
+
+        assert!(app
+            .render()
+            .commands()
+            .contains(&DrawCommand::SetTransform(2., 0., 0., 2., 10., 0.)));
+        assert!(app
+            .render_svg()
+            .contains("<path d=\"M30 40L32 40L32 42L30 42Z\" fill=\"#FF0000\"/>"));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod headless;
 pub mod panel;
 pub mod dialog;
 pub mod tooltip;
\ No newline at end of file
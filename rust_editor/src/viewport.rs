@@ -0,0 +1,208 @@
+use geo::{Coordinate, Rect};
+
+/// The smallest zoom factor, the view shows 20 times the size of the canvas.
+pub const MIN_ZOOM: f64 = 0.05;
+
+/// The largest zoom factor, one unit of the world covers 20 pixels.
+pub const MAX_ZOOM: f64 = 20.;
+
+/// Transforms between the coordinates on the screen and the coordinates of the world, which are the coordinates
+/// the data of the editor is stored in.
+///
+/// A point of the world is scaled by the zoom and then moved by the offset to get its position on the screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Viewport {
+    /// Position of the origin of the world on the screen.
+    offset: Coordinate<f64>,
+
+    /// Number of pixels per unit of the world.
+    zoom: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            offset: Coordinate { x: 0., y: 0. },
+            zoom: 1.,
+        }
+    }
+}
+
+impl Viewport {
+    pub fn offset(&self) -> Coordinate<f64> {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: Coordinate<f64>) {
+        self.offset = offset;
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Sets the zoom, clamped to [`MIN_ZOOM`] and [`MAX_ZOOM`]. The origin of the world stays at its position on the
+    /// screen, use [`Viewport::zoom_at`] to zoom around another point.
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    pub fn screen_to_world(&self, position: Coordinate<f64>) -> Coordinate<f64> {
+        (position - self.offset) / self.zoom
+    }
+
+    pub fn world_to_screen(&self, position: Coordinate<f64>) -> Coordinate<f64> {
+        position * self.zoom + self.offset
+    }
+
+    /// Moves the view by `delta` pixels on the screen.
+    pub fn pan(&mut self, delta: Coordinate<f64>) {
+        self.offset = self.offset + delta;
+    }
+
+    /// Multiplies the zoom by `factor` while the point of the world at `screen_position` stays under it, e.g. under
+    /// the cursor.
+    pub fn zoom_at(&mut self, screen_position: Coordinate<f64>, factor: f64) {
+        let world_position = self.screen_to_world(screen_position);
+
+        self.set_zoom(self.zoom * factor);
+        self.offset = screen_position - world_position * self.zoom;
+    }
+
+    /// Zooms and pans the view so that `rect` fills a screen of `screen_size` pixels with at least `padding` pixels
+    /// free on each side. The rect is centered on the screen.
+    pub fn zoom_to_fit(&mut self, rect: Rect<f64>, screen_size: Coordinate<f64>, padding: f64) {
+        let available = Coordinate {
+            x: (screen_size.x - 2. * padding).max(1.),
+            y: (screen_size.y - 2. * padding).max(1.),
+        };
+
+        // A rect without an extent along an axis fits along that axis at any zoom
+        let zoom = match (rect.width() > 0., rect.height() > 0.) {
+            (true, true) => (available.x / rect.width()).min(available.y / rect.height()),
+            (true, false) => available.x / rect.width(),
+            (false, true) => available.y / rect.height(),
+            (false, false) => self.zoom,
+        };
+
+        self.set_zoom(zoom);
+        self.offset = screen_size / 2. - rect.center() * self.zoom;
+    }
+
+    /// The transformation matrix from world to screen coordinates as expected by [`Canvas::set_transform`].
+    ///
+    /// [`Canvas::set_transform`]: crate::renderer::Canvas::set_transform
+    pub fn transform(&self) -> [f64; 6] {
+        [self.zoom, 0., 0., self.zoom, self.offset.x, self.offset.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, Rect};
+
+    use super::{Viewport, MAX_ZOOM, MIN_ZOOM};
+
+    fn assert_near(a: Coordinate<f64>, b: Coordinate<f64>) {
+        assert!(
+            (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn screen_and_world_coordinates_are_inverse() {
+        let mut viewport = Viewport::default();
+        viewport.pan(Coordinate { x: 30., y: -20. });
+        viewport.set_zoom(2.5);
+
+        let world = Coordinate { x: 12., y: 34. };
+        let screen = viewport.world_to_screen(world);
+
+        assert_near(screen, Coordinate { x: 60., y: 65. });
+        assert_near(viewport.screen_to_world(screen), world);
+    }
+
+    #[test]
+    fn zoom_keeps_point_under_cursor() {
+        let mut viewport = Viewport::default();
+        viewport.pan(Coordinate { x: 100., y: 50. });
+
+        let cursor = Coordinate { x: 400., y: 300. };
+        let world = viewport.screen_to_world(cursor);
+
+        viewport.zoom_at(cursor, 2.);
+        assert_eq!(viewport.zoom(), 2.);
+        assert_near(viewport.screen_to_world(cursor), world);
+
+        viewport.zoom_at(cursor, 0.25);
+        assert_eq!(viewport.zoom(), 0.5);
+        assert_near(viewport.screen_to_world(cursor), world);
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut viewport = Viewport::default();
+
+        viewport.zoom_at(Coordinate { x: 10., y: 10. }, 1000.);
+        assert_eq!(viewport.zoom(), MAX_ZOOM);
+
+        viewport.set_zoom(0.);
+        assert_eq!(viewport.zoom(), MIN_ZOOM);
+    }
+
+    #[test]
+    fn zoom_to_fit_centers_rect() {
+        let mut viewport = Viewport::default();
+        let rect = Rect::new(
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 300., y: 200. },
+        );
+
+        viewport.zoom_to_fit(rect, Coordinate { x: 820., y: 620. }, 10.);
+
+        // The width limits the zoom, 800 / 200 is less than 600 / 100
+        assert_eq!(viewport.zoom(), 4.);
+        assert_near(
+            viewport.world_to_screen(rect.center()),
+            Coordinate { x: 410., y: 310. },
+        );
+        assert_near(
+            viewport.world_to_screen(rect.min()),
+            Coordinate { x: 10., y: 110. },
+        );
+    }
+
+    #[test]
+    fn zoom_to_fit_a_point_keeps_zoom() {
+        let mut viewport = Viewport::default();
+        let point = Coordinate { x: 50., y: 50. };
+
+        viewport.zoom_to_fit(Rect::new(point, point), Coordinate { x: 200., y: 100. }, 0.);
+
+        assert_eq!(viewport.zoom(), 1.);
+        assert_near(
+            viewport.world_to_screen(point),
+            Coordinate { x: 100., y: 50. },
+        );
+    }
+
+    #[test]
+    fn transform_maps_world_to_screen() {
+        let mut viewport = Viewport::default();
+        viewport.zoom_at(Coordinate { x: 40., y: 40. }, 3.);
+
+        let [a, b, c, d, e, f] = viewport.transform();
+        let world = Coordinate { x: 7., y: 9. };
+
+        assert_near(
+            Coordinate {
+                x: a * world.x + c * world.y + e,
+                y: b * world.x + d * world.y + f,
+            },
+            viewport.world_to_screen(world),
+        );
+    }
+}
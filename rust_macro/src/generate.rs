@@ -3,7 +3,7 @@ use proc_macro_error::abort;
 use quote::TokenStreamExt;
 use quote::{format_ident, quote};
 use std::str::FromStr;
-use syn::{DataStruct, DeriveInput, Ident, Lit, LitStr, Meta};
+use syn::{DataStruct, DeriveInput, Ident};
 
 use crate::GenericParam;
 
@@ -13,7 +13,10 @@ use proc_macro2::{Group, Punct, Spacing, TokenTree};
 
 use crate::structs::Attribute;
 use crate::structs::PluginAttribute;
-use crate::{parse::get_mandatory_meta_value, structs::VisibleAttribute};
+use crate::{
+    parse::get_mandatory_meta_value,
+    structs::{OptionParam, VisibleAttribute},
+};
 
 pub(crate) struct PluginOptionElement {
     pub element: TokenStream2,
@@ -25,7 +28,7 @@ pub(crate) struct PluginOptionElement {
 pub(crate) fn generate_default_arm(
     attr_ident: &Ident,
     ty: &TokenStream2,
-    metas: &Vec<Meta>,
+    metas: &Vec<OptionParam>,
 ) -> TokenStream2 {
     // I guess there is a smarter way to do it but converting it to a str, replace < with :: < and converting it back
     // works and handles the case if the user defines a variable with generic type like
@@ -81,7 +84,7 @@ pub(crate) fn generate_option_element(
     plugin: &String,
     attr: &VisibleAttribute,
     ty: &TokenStream2,
-    metas: &Vec<Meta>,
+    metas: &Vec<OptionParam>,
 ) -> PluginOptionElement {
     let mut result = PluginOptionElement {
         element: quote! {},
@@ -171,8 +174,10 @@ pub(crate) fn generate_option_element(
         </div>};
     } else {
         // String type
-        let validator = get_mandatory_meta_value(&metas, "validator")
-            .unwrap_or_else(|| Lit::Str(LitStr::new("", Span::call_site())));
+        let validator = match get_mandatory_meta_value(&metas, "validator") {
+            Some(validator) => quote! { #validator },
+            None => quote! { "" },
+        };
 
         let element = quote! {
             <div class="setting">
@@ -2,13 +2,12 @@ use proc_macro_error::abort;
 
 use syn::parse::{ParseStream, Parse};
 use syn::punctuated::Punctuated;
-use syn::{DeriveInput, Meta, Data, Fields, Token, Error, Lit};
-use syn::spanned::Spanned;
+use syn::{DeriveInput, Data, Fields, Token, Error, Expr};
 
 use ansi_term::Color::Cyan;
 
 use crate::attribute_type;
-use crate::structs::{Attribute, VisibleAttribute, HiddenAttribute, GenericParam, EditorPluginArg, EditorPluginArgs, PluginAttribute};
+use crate::structs::{Attribute, VisibleAttribute, HiddenAttribute, GenericParam, EditorPluginArg, EditorPluginArgs, OptionParam, PluginAttribute};
 
 /// Parses a single attribute. Depending on the attribute type different tags are necessary. 
 /// The function does not check if necessary tags are present. Instead, it only checks if the defined
@@ -46,26 +45,26 @@ use crate::structs::{Attribute, VisibleAttribute, HiddenAttribute, GenericParam,
 /// * String
 /// * Vec
 /// 
-pub(crate) fn parse_attr(attr: &syn::Attribute) -> (Vec<Meta>, bool) {
+pub(crate) fn parse_attr(attr: &syn::Attribute) -> (Vec<OptionParam>, bool) {
     if attr.path.is_ident("option") {
         let (skip, metas)= attr
-            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .parse_args_with(Punctuated::<OptionParam, Token![,]>::parse_terminated)
             .unwrap_or_else(|_| abort!(attr, format!("Could not parse plugin option due to invalid punctuation.\n\n{}: Have you forgotten an `,`?", Cyan.paint("help"))))
             .into_iter()
             .inspect(|meta| {
-                if !(meta.path().is_ident("default")
-                    || meta.path().is_ident("min")
-                    || meta.path().is_ident("max")
-                    || meta.path().is_ident("label")
-                    || meta.path().is_ident("description")
-                    || meta.path().is_ident("skip")
-                    || meta.path().is_ident("validator"))
+                if !(meta.name == "default"
+                    || meta.name == "min"
+                    || meta.name == "max"
+                    || meta.name == "label"
+                    || meta.name == "description"
+                    || meta.name == "skip"
+                    || meta.name == "validator")
                 {
-                    abort!(meta.path().span(), "unknown parameter {:?}", meta.path().get_ident());
+                    abort!(meta.name.span(), "unknown parameter {:?}", meta.name.to_string());
                 }
             })
-            .fold((false, Vec::<Meta>::new()), |(skip, mut metas), meta| {  
-                if meta.path().is_ident("skip") {
+            .fold((false, Vec::<OptionParam>::new()), |(skip, mut metas), meta| {  
+                if meta.name == "skip" {
                     (true, metas)
                 } else {
                     metas.push(meta);                
@@ -136,19 +135,29 @@ pub(crate) fn parse_attrs(ast: &DeriveInput) -> Vec<PluginAttribute>{
 }
 
 pub(crate) fn get_mandatory_meta_value<'a>(
-    meta_attrs: &'a Vec<Meta>,
+    meta_attrs: &'a Vec<OptionParam>,
     identifier: &str,
-) -> Option<Lit> {
-    if let Some(default_meta) = meta_attrs
+) -> Option<Expr> {
+    meta_attrs
         .iter()
-        .find(|meta| meta.path().is_ident(identifier))
-    {
-        if let Meta::NameValue(e) = &default_meta {
-            return Some(e.lit.clone());
-        }
-    }
+        .find(|meta| meta.name == identifier)
+        .and_then(|meta| meta.value.clone())
+}
+
+impl Parse for OptionParam {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<syn::Ident>()?;
+
+        let value = match input.peek(Token![=]) {
+            true => {
+                input.parse::<Token![=]>()?;
+                Some(input.parse::<Expr>()?)
+            }
+            false => None,
+        };
 
-    None
+        Ok(OptionParam { name, value })
+    }
 }
 
 impl Parse for GenericParam {
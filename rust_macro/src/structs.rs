@@ -1,4 +1,4 @@
-use syn::{Ident, Lit, Meta, Expr};
+use syn::{Ident, Expr};
 
 use proc_macro2::TokenStream as TokenStream2;
 
@@ -9,10 +9,10 @@ pub(crate) struct VisibleAttribute {
     pub name: Ident,
 
     /// Short label that will displayed before the input
-    pub label: Lit,
+    pub label: Expr,
 
     /// Optional longer description to help the user to understand what the attribute represents
-    pub description: Option<Lit>,
+    pub description: Option<Expr>,
 }
 
 #[derive(Debug)]
@@ -26,7 +26,15 @@ pub(crate) enum Attribute {
     Hidden(HiddenAttribute)
 }
 
-pub(crate) type PluginAttribute = (Attribute, TokenStream2, Vec<Meta>);
+/// A parameter of an option like `label = "Label"` or `skip`. The value is an expression, so it can be a literal
+/// as well as the path of a constant, e.g. `validator = StreetClass::VALIDATOR`.
+#[derive(Debug, Clone)]
+pub(crate) struct OptionParam {
+    pub name: Ident,
+    pub value: Option<Expr>,
+}
+
+pub(crate) type PluginAttribute = (Attribute, TokenStream2, Vec<OptionParam>);
 
 #[derive(Debug)]
 pub(crate) struct GenericParam {
@@ -0,0 +1,115 @@
+use geo::{Coordinate, Line};
+
+/// A regular grid of samples. The sample in column `i` and row `j` lies at `(i * cell.x, j * cell.y)`.
+pub struct Grid<'a> {
+    pub samples: &'a [f64],
+    pub columns: usize,
+    pub rows: usize,
+    pub cell: Coordinate<f64>,
+}
+
+impl<'a> Grid<'a> {
+    fn sample(&self, column: usize, row: usize) -> f64 {
+        self.samples[row * self.columns + column]
+    }
+
+    fn position(&self, column: usize, row: usize) -> Coordinate<f64> {
+        Coordinate {
+            x: column as f64 * self.cell.x,
+            y: row as f64 * self.cell.y,
+        }
+    }
+}
+
+/// Traces the iso line at `level` through `grid` by marching squares. The line is returned as unordered segments,
+/// one or two per grid cell that is crossed. Saddle cells are resolved by the average of their corners.
+pub fn contour_lines(grid: &Grid, level: f64) -> Vec<Line<f64>> {
+    let mut lines: Vec<Line<f64>> = Vec::new();
+
+    for row in 0..grid.rows.saturating_sub(1) {
+        for column in 0..grid.columns.saturating_sub(1) {
+            // Corners in clockwise order starting at the top left corner
+            let corners = [
+                (column, row),
+                (column + 1, row),
+                (column + 1, row + 1),
+                (column, row + 1),
+            ];
+            let values = corners.map(|(column, row)| grid.sample(column, row));
+
+            // Crossing of each edge, the edge from corner k to corner k + 1
+            let crossings: Vec<Coordinate<f64>> = (0..4)
+                .filter_map(|k| {
+                    let (a, b) = (values[k], values[(k + 1) % 4]);
+                    if (a >= level) == (b >= level) {
+                        return None;
+                    }
+
+                    let start = grid.position(corners[k].0, corners[k].1);
+                    let end = grid.position(corners[(k + 1) % 4].0, corners[(k + 1) % 4].1);
+
+                    Some(start + (end - start) * ((level - a) / (b - a)))
+                })
+                .collect();
+
+            match crossings[..] {
+                [a, b] => lines.push(Line::new(a, b)),
+                [top, right, bottom, left] => {
+                    let center = values.iter().sum::<f64>() / 4.;
+
+                    // Separate the corners that are on the other side of the level than the center
+                    if (values[0] >= level) == (center >= level) {
+                        lines.push(Line::new(top, right));
+                        lines.push(Line::new(bottom, left));
+                    } else {
+                        lines.push(Line::new(left, top));
+                        lines.push(Line::new(right, bottom));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+
+    use super::{contour_lines, Grid};
+
+    #[test]
+    fn unit_contour_separates_low_and_high_samples() {
+        // A slope rising from left to right
+        let samples: Vec<f64> = (0..3).flat_map(|_| [0., 10., 20.]).collect();
+        let grid = Grid {
+            samples: &samples,
+            columns: 3,
+            rows: 3,
+            cell: Coordinate { x: 10., y: 10. },
+        };
+
+        let lines = contour_lines(&grid, 15.);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines
+            .iter()
+            .all(|line| line.start.x == 15. && line.end.x == 15.));
+    }
+
+    #[test]
+    fn unit_saddle_results_in_two_lines() {
+        let samples = vec![10., 0., 0., 10.];
+        let grid = Grid {
+            samples: &samples,
+            columns: 2,
+            rows: 2,
+            cell: Coordinate { x: 1., y: 1. },
+        };
+
+        assert_eq!(contour_lines(&grid, 5.).len(), 2);
+        assert!(contour_lines(&grid, 20.).is_empty());
+    }
+}
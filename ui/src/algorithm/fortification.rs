@@ -0,0 +1,371 @@
+use std::cmp::Ordering;
+
+use geo::{
+    prelude::{Area, EuclideanDistance, EuclideanLength},
+    Coordinate, LineString, Polygon,
+};
+
+use super::geo::{line_string_crossings, project_on_line_string};
+
+/// The offset of a vertex is limited to this multiple of the wall offset so that acute corners do not
+/// produce spikes.
+const MITER_LIMIT: f64 = 2.;
+
+/// Parameters of a fortification ring.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FortificationParameters {
+    /// Distance between the settlement boundary and the wall.
+    pub offset: f64,
+
+    /// Maximum distance between two towers along the wall.
+    pub tower_spacing: f64,
+
+    /// Minimum turn of the wall in radians at which a tower is placed at a corner.
+    pub corner_angle: f64,
+
+    /// Width of the opening of each gate.
+    pub gate_width: f64,
+}
+
+/// A closed wall together with its towers and the gates where streets cross it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FortificationLayout {
+    /// Closed ring of the wall. The first and the last point are equal.
+    pub ring: LineString<f64>,
+    pub towers: Vec<Coordinate<f64>>,
+    pub gates: Vec<Coordinate<f64>>,
+}
+
+/// Builds a wall around `boundary`. The ring is the boundary offset outwards, towers are placed at its corners
+/// and at regular intervals in between and gates are placed where one of `streets` crosses the ring. Each gate
+/// is flanked by two towers and towers that would stand in a gate are dropped.
+///
+/// Returns `None` if the boundary has no area.
+pub fn fortification_layout<'a, I>(
+    boundary: &Polygon<f64>,
+    streets: I,
+    parameters: &FortificationParameters,
+) -> Option<FortificationLayout>
+where
+    I: Iterator<Item = &'a LineString<f64>>,
+{
+    let ring = offset_ring(boundary, parameters.offset)?;
+
+    let mut gates: Vec<Coordinate<f64>> = Vec::new();
+    for street in streets {
+        for crossing in line_string_crossings(&ring, street) {
+            if gates
+                .iter()
+                .all(|gate| gate.euclidean_distance(&crossing) > parameters.gate_width)
+            {
+                gates.push(crossing);
+            }
+        }
+    }
+
+    let perimeter = ring.euclidean_length();
+    let mut towers: Vec<Coordinate<f64>> =
+        place_towers(&ring, parameters.tower_spacing, parameters.corner_angle)
+            .into_iter()
+            .filter(|tower| {
+                gates
+                    .iter()
+                    .all(|gate| gate.euclidean_distance(tower) > parameters.gate_width)
+            })
+            .collect();
+
+    for gate in &gates {
+        if let Some((along, _)) = project_on_line_string(&ring, gate) {
+            for side in [-0.5, 0.5] {
+                let position = (along + side * parameters.gate_width).rem_euclid(perimeter);
+                towers.push(point_along(&ring, position));
+            }
+        }
+    }
+
+    Some(FortificationLayout {
+        ring,
+        towers,
+        gates,
+    })
+}
+
+/// Offsets the exterior of `polygon` outwards by `distance`. Each vertex is moved along the bisector of its
+/// edges, the movement of vertices at acute corners is limited by [`MITER_LIMIT`].
+///
+/// Returns a closed counter-clockwise ring or `None` if the polygon has no area.
+pub fn offset_ring(polygon: &Polygon<f64>, distance: f64) -> Option<LineString<f64>> {
+    let mut points: Vec<Coordinate<f64>> = polygon.exterior().0.clone();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let area = polygon.signed_area();
+    if points.len() < 3 || area.abs() <= f64::EPSILON {
+        return None;
+    }
+    if area < 0. {
+        points.reverse();
+    }
+
+    let count = points.len();
+    let normal = |from: Coordinate<f64>, to: Coordinate<f64>| {
+        let delta = to - from;
+        let length = from.euclidean_distance(&to);
+
+        Coordinate {
+            x: delta.y / length,
+            y: -delta.x / length,
+        }
+    };
+
+    let mut ring: Vec<Coordinate<f64>> = (0..count)
+        .map(|i| {
+            let previous = points[(i + count - 1) % count];
+            let current = points[i];
+            let next = points[(i + 1) % count];
+
+            let incoming = normal(previous, current);
+            let outgoing = normal(current, next);
+
+            let bisector = incoming + outgoing;
+            let length = (bisector.x * bisector.x + bisector.y * bisector.y).sqrt();
+            if length <= f64::EPSILON {
+                return current + incoming * distance;
+            }
+
+            let bisector = bisector / length;
+            let cos = bisector.x * incoming.x + bisector.y * incoming.y;
+
+            current + bisector * (distance / cos).min(distance * MITER_LIMIT)
+        })
+        .collect();
+    ring.push(ring[0]);
+
+    Some(LineString::new(ring))
+}
+
+/// Places towers along the closed `ring`. A tower is placed at each vertex at which the ring turns by at least
+/// `corner_angle` radians. The stretches between these corners are divided evenly so that no two towers are
+/// further apart than `spacing` along the ring.
+pub fn place_towers(
+    ring: &LineString<f64>,
+    spacing: f64,
+    corner_angle: f64,
+) -> Vec<Coordinate<f64>> {
+    let perimeter = ring.euclidean_length();
+    if perimeter <= 0. || spacing <= 0. {
+        return vec![];
+    }
+
+    let points = &ring.0[..ring.0.len() - 1];
+    let count = points.len();
+
+    let mut along = 0.;
+    let mut corners: Vec<f64> = Vec::new();
+    for i in 0..count {
+        let previous = points[(i + count - 1) % count];
+        let current = points[i];
+        let next = points[(i + 1) % count];
+
+        if turn(previous, current, next) >= corner_angle {
+            corners.push(along);
+        }
+
+        along += current.euclidean_distance(&next);
+    }
+
+    if corners.is_empty() {
+        corners.push(0.);
+    }
+
+    let mut towers: Vec<Coordinate<f64>> = Vec::new();
+    for (i, start) in corners.iter().enumerate() {
+        let end = match corners.get(i + 1) {
+            Some(end) => *end,
+            None => corners[0] + perimeter,
+        };
+
+        let stretch = end - start;
+        let segments = (stretch / spacing).ceil().max(1.) as usize;
+
+        for segment in 0..segments {
+            let position = start + stretch * segment as f64 / segments as f64;
+            towers.push(point_along(ring, position % perimeter));
+        }
+    }
+
+    towers
+}
+
+/// Absolute angle in radians by which the direction changes at `current`.
+fn turn(previous: Coordinate<f64>, current: Coordinate<f64>, next: Coordinate<f64>) -> f64 {
+    let incoming = current - previous;
+    let outgoing = next - current;
+
+    let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+    let dot = incoming.x * outgoing.x + incoming.y * outgoing.y;
+
+    cross.atan2(dot).abs()
+}
+
+/// Returns the point at distance `along` from the start of `line_string`.
+fn point_along(line_string: &LineString<f64>, along: f64) -> Coordinate<f64> {
+    let mut travelled = 0.;
+
+    for line in line_string.lines() {
+        let length = line.euclidean_length();
+
+        if travelled + length >= along && length > 0. {
+            return line.start + line.delta() * ((along - travelled) / length);
+        }
+
+        travelled += length;
+    }
+
+    *line_string.0.last().unwrap()
+}
+
+/// Splits the closed `ring` into the sections of the wall between the gates. Each gate opens a gap of
+/// `gate_width` centered at the projection of the gate onto the ring.
+pub fn wall_sections(
+    ring: &LineString<f64>,
+    gates: &[Coordinate<f64>],
+    gate_width: f64,
+) -> Vec<LineString<f64>> {
+    let perimeter = ring.euclidean_length();
+    if gates.is_empty() || perimeter <= 0. {
+        return vec![ring.clone()];
+    }
+
+    let mut centers: Vec<f64> = gates
+        .iter()
+        .filter_map(|gate| project_on_line_string(ring, gate))
+        .map(|(along, _)| along)
+        .collect();
+    centers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut sections: Vec<LineString<f64>> = Vec::new();
+    for (i, center) in centers.iter().enumerate() {
+        let start = center + gate_width / 2.;
+        let end = match centers.get(i + 1) {
+            Some(next) => next - gate_width / 2.,
+            None => centers[0] + perimeter - gate_width / 2.,
+        };
+
+        if end > start {
+            sections.push(sub_ring(ring, perimeter, start, end));
+        }
+    }
+
+    sections
+}
+
+/// Returns the part of the closed `ring` between the distances `start` and `end` along it. `end` may exceed the
+/// perimeter in which case the part wraps around the first point of the ring.
+fn sub_ring(ring: &LineString<f64>, perimeter: f64, start: f64, end: f64) -> LineString<f64> {
+    let mut points: Vec<Coordinate<f64>> = vec![point_along(ring, start % perimeter)];
+
+    let mut travelled = 0.;
+    for _ in 0..2 {
+        for line in ring.lines() {
+            travelled += line.euclidean_length();
+
+            if travelled > start && travelled < end {
+                points.push(line.end);
+            }
+        }
+    }
+
+    points.push(point_along(ring, end % perimeter));
+    points.dedup();
+
+    LineString::new(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::EuclideanDistance, Coordinate, LineString, Point, Polygon};
+
+    use super::{
+        fortification_layout, offset_ring, place_towers, wall_sections, FortificationParameters,
+    };
+
+    fn square() -> Polygon<f64> {
+        Polygon::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 100.)]),
+            vec![],
+        )
+    }
+
+    fn parameters() -> FortificationParameters {
+        FortificationParameters {
+            offset: 10.,
+            tower_spacing: 50.,
+            corner_angle: 0.5,
+            gate_width: 10.,
+        }
+    }
+
+    #[test]
+    fn unit_offset_ring_grows_outwards() {
+        let ring = offset_ring(&square(), 10.).unwrap();
+
+        assert_eq!(ring.0.first(), ring.0.last());
+        assert!(ring.0.contains(&Coordinate { x: -10., y: -10. }));
+        assert!(ring.0.contains(&Coordinate { x: 110., y: 110. }));
+
+        // The orientation of the boundary does not matter
+        let mut reversed = square();
+        reversed.exterior_mut(|exterior| exterior.0.reverse());
+        assert!(offset_ring(&reversed, 10.)
+            .unwrap()
+            .0
+            .contains(&Coordinate { x: -10., y: -10. }));
+    }
+
+    #[test]
+    fn unit_towers_stand_at_corners_and_at_intervals() {
+        let ring = offset_ring(&square(), 10.).unwrap();
+        let towers = place_towers(&ring, 50., 0.5);
+
+        // Each side of 120 is divided into three stretches of 40
+        assert_eq!(towers.len(), 12);
+        for corner in &ring.0 {
+            assert!(towers.contains(corner));
+        }
+    }
+
+    #[test]
+    fn unit_streets_crossing_the_wall_become_gates() {
+        let street = LineString::from(vec![(50., 50.), (50., 200.)]);
+        let layout =
+            fortification_layout(&square(), vec![&street].into_iter(), &parameters()).unwrap();
+
+        assert_eq!(layout.gates, vec![Coordinate { x: 50., y: 110. }]);
+        assert!(layout
+            .towers
+            .iter()
+            .all(|tower| tower.euclidean_distance(&layout.gates[0]) >= 5. - 1e-9));
+        assert!(layout.towers.contains(&Coordinate { x: 55., y: 110. }));
+    }
+
+    #[test]
+    fn unit_gates_open_the_wall() {
+        let ring = offset_ring(&square(), 10.).unwrap();
+        let gates = vec![
+            Coordinate { x: 50., y: -10. },
+            Coordinate { x: 50., y: 110. },
+        ];
+
+        let sections = wall_sections(&ring, &gates, 10.);
+
+        assert_eq!(sections.len(), 2);
+        for section in &sections {
+            for gate in &gates {
+                assert!(section.euclidean_distance(&Point::from(*gate)) >= 5. - 1e-9);
+            }
+        }
+    }
+}
@@ -3,8 +3,9 @@ use std::f64::consts::PI;
 use geo::{
     euclidean_length::EuclideanLength,
     line_intersection::{line_intersection, LineIntersection},
-    Coordinate, Line, LineString, Point, Polygon, prelude::{ConvexHull, BoundingRect, Centroid, Area}, rotate::RotatePoint,
+    Coordinate, Line, LineString, Point, Polygon, prelude::{ConvexHull, BoundingRect, Centroid, Area, EuclideanDistance}, rotate::RotatePoint,
 };
+use geo_booleanop::boolean::BooleanOp;
 
 type AnnotatedLine = (Line<f64>, bool);
 
@@ -240,13 +241,277 @@ pub fn longest_line(polygon: &AnnotatedPolygon, min_side_length: f64) -> Annotat
     }
 }
 
+/// Clips `polygon` to the half plane in which `f` is not positive. `f` has to be linear. Edges along the border of
+/// the half plane do not face a street.
+pub fn clip<F>(polygon: &AnnotatedPolygon, f: F) -> Option<AnnotatedPolygon>
+where
+    F: Fn(&Coordinate<f64>) -> f64,
+{
+    // Each point is the start of an edge with the street flag of that edge
+    let mut points: Vec<(Coordinate<f64>, bool)> = vec![];
+    for (line, is_street) in polygon.lines() {
+        let (start, end) = (f(&line.start), f(&line.end));
+
+        if start <= 0. {
+            points.push((line.start, is_street));
+        }
+
+        if (start <= 0.) != (end <= 0.) {
+            let intersection = line.start + line.delta() * (start / (start - end));
+            points.push((intersection, start > 0. && is_street));
+        }
+    }
+
+    // Points that coincide with the next one start an edge without length
+    let mut i = 0;
+    while points.len() > 1 && i < points.len() {
+        let next = points[(i + 1) % points.len()].0;
+        let delta = points[i].0 - next;
+        if delta.x.abs() <= f64::EPSILON && delta.y.abs() <= f64::EPSILON {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    if points.len() < 3 {
+        return None;
+    }
+
+    let (coordinates, is_street): (Vec<Coordinate<f64>>, Vec<bool>) = points.into_iter().unzip();
+    let polygon = Polygon::new(LineString(coordinates), vec![]);
+
+    match polygon.unsigned_area() > f64::EPSILON {
+        true => Some(AnnotatedPolygon(polygon, is_street)),
+        false => None,
+    }
+}
+
+/// Returns `true` if the convex hull of `polygon` is larger than the polygon by at most the factor `tolerance`.
+pub fn is_convex(polygon: &Polygon<f64>, tolerance: f64) -> bool {
+    polygon.convex_hull().unsigned_area() <= polygon.unsigned_area() * tolerance
+}
+
+/// Returns all points where the segments of `a` cross the segments of `b`.
+///
+/// Collinear overlaps are ignored since they do not form a single crossing point.
+pub fn line_string_crossings(a: &LineString<f64>, b: &LineString<f64>) -> Vec<Coordinate<f64>> {
+    let mut crossings: Vec<Coordinate<f64>> = Vec::new();
+
+    for line_a in a.lines() {
+        for line_b in b.lines() {
+            if let Some(LineIntersection::SinglePoint {
+                intersection,
+                is_proper: _,
+            }) = line_intersection(line_a, line_b)
+            {
+                if !crossings.contains(&intersection) {
+                    crossings.push(intersection);
+                }
+            }
+        }
+    }
+
+    crossings
+}
+
+/// Projects `point` onto `line_string` and returns the distance along the line string to the projected point
+/// together with the projected point itself. Returns `None` if the line string has no segments.
+pub fn project_on_line_string(
+    line_string: &LineString<f64>,
+    point: &Coordinate<f64>,
+) -> Option<(f64, Coordinate<f64>)> {
+    let mut result: Option<(f64, f64, Coordinate<f64>)> = None;
+    let mut travelled = 0.;
+
+    for line in line_string.lines() {
+        let length = line.euclidean_length();
+        let delta = line.delta();
+
+        let t = if length > 0. {
+            (((point.x - line.start.x) * delta.x + (point.y - line.start.y) * delta.y)
+                / (length * length))
+                .clamp(0., 1.)
+        } else {
+            0.
+        };
+
+        let projected = line.start + delta * t;
+        let distance = projected.euclidean_distance(point);
+
+        if result.is_none() || distance < result.unwrap().0 {
+            result = Some((distance, travelled + length * t, projected));
+        }
+
+        travelled += length;
+    }
+
+    result.map(|(_, along, projected)| (along, projected))
+}
+
+/// Splits `line_string` at the point on it that is closest to `point`. The split point becomes the
+/// last point of the first and the first point of the second line string.
+///
+/// Returns `None` if the split point coincides with the start or the end of the line string since this
+/// would produce a line string without length.
+pub fn split_line_string(
+    line_string: &LineString<f64>,
+    point: &Coordinate<f64>,
+) -> Option<(LineString<f64>, LineString<f64>)> {
+    let (along, projected) = project_on_line_string(line_string, point)?;
+
+    let total = line_string.euclidean_length();
+    if along <= f64::EPSILON || along >= total - f64::EPSILON {
+        return None;
+    }
+
+    let mut first: Vec<Coordinate<f64>> = vec![];
+    let mut second: Vec<Coordinate<f64>> = vec![];
+
+    let mut travelled = 0.;
+    for line in line_string.lines() {
+        let length = line.euclidean_length();
+
+        if travelled + length < along {
+            first.push(line.start);
+        } else if travelled >= along {
+            second.push(line.start);
+        } else {
+            first.push(line.start);
+            second.push(projected);
+        }
+
+        travelled += length;
+    }
+    first.push(projected);
+    second.push(*line_string.0.last().unwrap());
+
+    first.dedup();
+    second.dedup();
+
+    Some((LineString::new(first), LineString::new(second)))
+}
+
+/// Tolerance used to compare the points of two polygons.
+const POLYGON_EPSILON: f64 = 1e-6;
+
+/// Returns `true` if the exteriors of both polygons consist of the same points. The comparison does not depend on
+/// the start point or the orientation of the rings which can differ between two runs of a boolean operation.
+/// Points on a straight edge are ignored, boolean operations add them where an edge was cut.
+pub fn polygons_equal(a: &Polygon<f64>, b: &Polygon<f64>) -> bool {
+    fn sorted_points(polygon: &Polygon<f64>) -> Vec<Coordinate<f64>> {
+        let mut points = polygon.exterior().0.clone();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        let mut i = 0;
+        while points.len() > 3 && i < points.len() {
+            let n = points.len();
+            let edge = Line::new(points[(i + n - 1) % n], points[(i + 1) % n]);
+            if Point::from(points[i]).euclidean_distance(&edge) <= POLYGON_EPSILON {
+                points.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        points.sort_by(|a, b| {
+            a.x.partial_cmp(&b.x)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal))
+        });
+
+        points
+    }
+
+    let a = sorted_points(a);
+    let b = sorted_points(b);
+
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|(a, b)| {
+            (a.x - b.x).abs() <= POLYGON_EPSILON && (a.y - b.y).abs() <= POLYGON_EPSILON
+        })
+}
+
+/// Returns the length of the boundary that the exteriors of `a` and `b` have in common.
+pub fn shared_boundary_length(a: &Polygon<f64>, b: &Polygon<f64>) -> f64 {
+    fn overlap(a: &Line<f64>, b: &Line<f64>) -> f64 {
+        let length = a.euclidean_length();
+        if length <= POLYGON_EPSILON {
+            return 0.;
+        }
+
+        // Both points of `b` have to lie on the line through `a`
+        let direction = a.delta() / length;
+        let offset =
+            |c: Coordinate<f64>| (c - a.start).x * direction.y - (c - a.start).y * direction.x;
+        if offset(b.start).abs() > POLYGON_EPSILON || offset(b.end).abs() > POLYGON_EPSILON {
+            return 0.;
+        }
+
+        let position =
+            |c: Coordinate<f64>| (c - a.start).x * direction.x + (c - a.start).y * direction.y;
+        let (start, end) = (position(b.start), position(b.end));
+
+        (start.max(end).min(length) - start.min(end).max(0.)).max(0.)
+    }
+
+    a.exterior()
+        .lines()
+        .map(|line_a| {
+            b.exterior()
+                .lines()
+                .map(|line_b| overlap(&line_a, &line_b))
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Merges the neighbouring polygons `a` and `b` into one polygon. An edge of the merged polygon faces a street if it
+/// lies on an edge of `a` or `b` that faces a street.
+///
+/// Returns `None` if the union of both polygons is not a single polygon without holes.
+pub fn merge(a: &AnnotatedPolygon, b: &AnnotatedPolygon) -> Option<AnnotatedPolygon> {
+    let polygon = match &a.0.union(&b.0).0[..] {
+        [polygon] if polygon.interiors().is_empty() => polygon.clone(),
+        _ => return None,
+    };
+
+    let streets: Vec<Line<f64>> = a
+        .lines()
+        .into_iter()
+        .chain(b.lines().into_iter())
+        .filter(|(_, is_street)| *is_street)
+        .map(|(line, _)| line)
+        .collect();
+    let is_street = polygon
+        .exterior()
+        .lines()
+        .map(|line| {
+            let center = Point::from(line.start + line.delta() / 2.);
+            streets
+                .iter()
+                .any(|street| center.euclidean_distance(street) <= POLYGON_EPSILON)
+        })
+        .collect();
+
+    Some(AnnotatedPolygon(polygon, is_street))
+}
+
 #[cfg(test)]
 mod tests {
-    use geo::{Coordinate, Line, LineString, Point, Polygon};
+    use geo::{
+        euclidean_length::EuclideanLength, prelude::Area, Coordinate, Line, LineString, Point,
+        Polygon,
+    };
 
     use crate::algorithm::geo::longest_line;
 
-    use super::{split, AnnotatedPolygon};
+    use super::{
+        line_string_crossings, merge, polygons_equal, shared_boundary_length, split,
+        split_line_string, AnnotatedPolygon,
+    };
 
     #[test]
     fn unit_split_polygon() {
@@ -298,4 +563,110 @@ mod tests {
         );
         assert_eq!(result.1, true);
     }
+
+    #[test]
+    fn unit_line_string_crossings() {
+        let a = LineString::from(vec![(0., 0.), (100., 100.)]);
+        let b = LineString::from(vec![(0., 100.), (100., 0.)]);
+
+        let crossings = line_string_crossings(&a, &b);
+
+        assert_eq!(crossings, vec![Coordinate { x: 50., y: 50. }]);
+    }
+
+    #[test]
+    fn unit_split_line_string() {
+        let line_string = LineString::from(vec![(0., 0.), (100., 0.), (100., 100.)]);
+
+        let (first, second) =
+            split_line_string(&line_string, &Coordinate { x: 50., y: 10. }).unwrap();
+
+        assert_eq!(first, LineString::from(vec![(0., 0.), (50., 0.)]));
+        assert_eq!(second, LineString::from(vec![(50., 0.), (100., 0.), (100., 100.)]));
+    }
+
+    #[test]
+    fn unit_split_line_string_at_end_is_none() {
+        let line_string = LineString::from(vec![(0., 0.), (100., 0.)]);
+
+        assert!(split_line_string(&line_string, &Coordinate { x: 120., y: 0. }).is_none());
+    }
+
+    #[test]
+    fn unit_polygons_equal_ignores_start_and_orientation() {
+        let a = Polygon::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 100.)]),
+            vec![],
+        );
+        let b = Polygon::new(
+            LineString::from(vec![(100., 100.), (100., 0.), (0., 0.), (0., 100.)]),
+            vec![],
+        );
+        let c = Polygon::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 50.), (0., 50.)]),
+            vec![],
+        );
+
+        assert!(polygons_equal(&a, &b));
+        assert!(!polygons_equal(&a, &c));
+    }
+
+    #[test]
+    fn unit_polygons_equal_ignores_points_on_straight_edges() {
+        let a = Polygon::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 100.)]),
+            vec![],
+        );
+        let b = Polygon::new(
+            LineString::from(vec![
+                (0., 0.),
+                (40., 0.),
+                (100., 0.),
+                (100., 100.),
+                (0., 100.),
+                (0., 30.),
+            ]),
+            vec![],
+        );
+
+        assert!(polygons_equal(&a, &b));
+    }
+
+    fn rect(x: f64, width: f64, is_street: Vec<bool>) -> AnnotatedPolygon {
+        AnnotatedPolygon(
+            Polygon::new(
+                LineString::from(vec![(x, 0.), (x + width, 0.), (x + width, 100.), (x, 100.)]),
+                vec![],
+            ),
+            is_street,
+        )
+    }
+
+    #[test]
+    fn unit_merge_keeps_streets() {
+        // The right edge of `a` is shared with `b` which does not face a street
+        let a = rect(0., 100., vec![true, false, true, true]);
+        let b = rect(100., 50., vec![false, false, false, false]);
+        assert_eq!(shared_boundary_length(&a.0, &b.0), 100.);
+
+        let merged = merge(&a, &b).unwrap();
+        let street_length: f64 = merged
+            .lines()
+            .iter()
+            .filter(|(_, is_street)| *is_street)
+            .map(|(line, _)| line.euclidean_length())
+            .sum();
+
+        assert!((merged.0.unsigned_area() - 15000.).abs() < 1e-6);
+        assert!((street_length - 300.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unit_merge_requires_neighbours() {
+        let a = rect(0., 100., vec![true; 4]);
+        let b = rect(200., 50., vec![false; 4]);
+
+        assert_eq!(shared_boundary_length(&a.0, &b.0), 0.);
+        assert!(merge(&a, &b).is_none());
+    }
 }
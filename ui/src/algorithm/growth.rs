@@ -0,0 +1,328 @@
+use std::{cmp::Ordering, collections::VecDeque, f64::consts::PI};
+
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    prelude::EuclideanDistance,
+    Coordinate, Line, LineString, Point,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Parameters of [`grow_street_network`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrowthParameters {
+    /// Average length of a single street segment. Each segment varies by up to 20 percent.
+    pub segment_length: f64,
+
+    /// Angle in degrees between a street and the streets branching off from it.
+    pub branching_angle: f64,
+
+    /// Probability in [0, 1] that a new street branches off at the end of each segment.
+    pub density: f64,
+
+    /// Maximum deviation in degrees of each segment from the direction of the previous segment.
+    pub deviation: f64,
+
+    /// Streets do not grow further away from the start point.
+    pub radius: f64,
+
+    /// The growth stops once this number of segments was created.
+    pub max_segments: usize,
+
+    /// Maximum difference in elevation per unit of length of a segment.
+    pub max_slope: f64,
+}
+
+impl Default for GrowthParameters {
+    fn default() -> Self {
+        GrowthParameters {
+            segment_length: 80.,
+            branching_angle: 90.,
+            density: 0.3,
+            deviation: 10.,
+            radius: 600.,
+            max_segments: 200,
+            max_slope: f64::INFINITY,
+        }
+    }
+}
+
+/// Turns in degrees an agent tries in this order if its next segment would be too steep.
+const SLOPE_TURNS: [f64; 7] = [0., 15., -15., 30., -30., 45., -45.];
+
+/// The tip of a growing street.
+struct Agent {
+    /// Index of the street the agent is growing.
+    street: usize,
+    position: Coordinate<f64>,
+    direction: f64,
+}
+
+/// Grows a street network from `start`. Each street is grown by an agent that adds one segment after
+/// another, slightly changing its direction each time. At the end of each segment new agents branch off
+/// with a probability of [`GrowthParameters::density`].
+///
+/// An agent stops if its next segment would cross an existing one, then the segment ends at the crossing,
+/// or if it ends close to the end of an existing segment, then it is connected to it. Agents also stop
+/// outside of [`GrowthParameters::radius`]. Agents turn away from slopes steeper than
+/// [`GrowthParameters::max_slope`] of the terrain given by `elevation` and stop if they can not avoid them.
+///
+/// Returns the streets in the order they were started. Branches therefore always come after the street
+/// they branch off from. The same `seed` results in the same network.
+pub fn grow_street_network<E>(
+    start: Coordinate<f64>,
+    parameters: &GrowthParameters,
+    elevation: E,
+    seed: u64,
+) -> Vec<LineString<f64>>
+where
+    E: Fn(&Coordinate<f64>) -> f64,
+{
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let mut streets: Vec<Vec<Coordinate<f64>>> = vec![];
+    let mut segments: Vec<Line<f64>> = vec![];
+    let mut agents: VecDeque<Agent> = VecDeque::new();
+
+    // Start with a single street through the start point that grows in both directions
+    let direction = rng.gen_range(0. ..2. * PI);
+    for direction in [direction, direction + PI] {
+        agents.push_back(Agent {
+            street: streets.len(),
+            position: start,
+            direction,
+        });
+        streets.push(vec![start]);
+    }
+
+    let snap_distance = parameters.segment_length * 0.4;
+    let deviation = parameters.deviation.to_radians();
+    let branching_angle = parameters.branching_angle.to_radians();
+
+    while let Some(mut agent) = agents.pop_front() {
+        if segments.len() >= parameters.max_segments {
+            break;
+        }
+
+        if deviation > 0. {
+            agent.direction += rng.gen_range(-deviation..deviation);
+        }
+
+        let length = parameters.segment_length * rng.gen_range(0.8..1.2);
+        let slope = |direction: f64| {
+            let end = agent.position
+                + Coordinate {
+                    x: direction.cos(),
+                    y: direction.sin(),
+                } * length;
+
+            (elevation(&end) - elevation(&agent.position)).abs() / length
+        };
+
+        match SLOPE_TURNS
+            .iter()
+            .map(|turn| agent.direction + turn.to_radians())
+            .find(|direction| slope(*direction) <= parameters.max_slope)
+        {
+            Some(direction) => agent.direction = direction,
+            None => continue,
+        }
+
+        let end = agent.position
+            + Coordinate {
+                x: agent.direction.cos(),
+                y: agent.direction.sin(),
+            } * length;
+
+        let (end, stop) = match next_obstacle(&Line::new(agent.position, end), &segments) {
+            Some(crossing) => (crossing, true),
+            // Connecting to a close point must not cross any other segment
+            None => match closest_point(&end, &segments, snap_distance).filter(|point| {
+                let distance = Point::from(*point).euclidean_distance(&Point::from(agent.position));
+
+                next_obstacle(&Line::new(agent.position, *point), &segments)
+                    .map_or(true, |crossing| crossing == *point)
+                    && (elevation(point) - elevation(&agent.position)).abs()
+                        <= parameters.max_slope * distance
+            }) {
+                Some(point) => (point, true),
+                None => (end, false),
+            },
+        };
+
+        if Point::from(end).euclidean_distance(&Point::from(start)) > parameters.radius
+            || Point::from(end).euclidean_distance(&Point::from(agent.position))
+                < snap_distance / 2.
+        {
+            continue;
+        }
+
+        segments.push(Line::new(agent.position, end));
+        streets[agent.street].push(end);
+
+        if stop {
+            continue;
+        }
+
+        for side in [-1., 1.] {
+            if rng.gen_bool(parameters.density.clamp(0., 1.)) {
+                agents.push_back(Agent {
+                    street: streets.len(),
+                    position: end,
+                    direction: agent.direction + side * branching_angle,
+                });
+                streets.push(vec![end]);
+            }
+        }
+
+        agents.push_back(Agent {
+            position: end,
+            ..agent
+        });
+    }
+
+    streets
+        .into_iter()
+        .filter(|points| points.len() > 1)
+        .map(LineString::new)
+        .collect()
+}
+
+/// Returns the point closest to the start of `line` where it crosses one of `segments`. Segments that only
+/// touch the start of `line` are ignored as they are the segments the line continues.
+fn next_obstacle(line: &Line<f64>, segments: &[Line<f64>]) -> Option<Coordinate<f64>> {
+    let start = Point::from(line.start);
+
+    segments
+        .iter()
+        .filter_map(|segment| match line_intersection(*line, *segment) {
+            Some(LineIntersection::SinglePoint { intersection, .. }) => Some(intersection),
+            Some(LineIntersection::Collinear { intersection }) => Some(intersection.end),
+            None => None,
+        })
+        .filter(|crossing| *crossing != line.start)
+        .min_by(|a, b| {
+            start
+                .euclidean_distance(&Point::from(*a))
+                .partial_cmp(&start.euclidean_distance(&Point::from(*b)))
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+/// Returns the segment end closest to `position` if it is closer than `max_distance`.
+fn closest_point(
+    position: &Coordinate<f64>,
+    segments: &[Line<f64>],
+    max_distance: f64,
+) -> Option<Coordinate<f64>> {
+    let position = Point::from(*position);
+
+    segments
+        .iter()
+        .flat_map(|segment| [segment.start, segment.end])
+        .map(|point| (position.euclidean_distance(&Point::from(point)), point))
+        .filter(|(distance, _)| *distance < max_distance)
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+        .map(|(_, point)| point)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{
+        line_intersection::{line_intersection, LineIntersection},
+        prelude::EuclideanDistance,
+        Coordinate, Line, Point,
+    };
+
+    use super::{grow_street_network, GrowthParameters};
+
+    const START: Coordinate<f64> = Coordinate { x: 500., y: 500. };
+
+    fn flat(_: &Coordinate<f64>) -> f64 {
+        0.
+    }
+
+    #[test]
+    fn unit_same_seed_grows_same_network() {
+        let parameters = GrowthParameters::default();
+
+        assert_eq!(
+            grow_street_network(START, &parameters, flat, 7),
+            grow_street_network(START, &parameters, flat, 7)
+        );
+        assert_ne!(
+            grow_street_network(START, &parameters, flat, 7),
+            grow_street_network(START, &parameters, flat, 8)
+        );
+    }
+
+    #[test]
+    fn unit_network_stays_within_limits() {
+        let parameters = GrowthParameters {
+            max_segments: 50,
+            ..Default::default()
+        };
+        let streets = grow_street_network(START, &parameters, flat, 3);
+
+        let segments: Vec<Line<f64>> = streets.iter().flat_map(|street| street.lines()).collect();
+        assert!(segments.len() <= 50);
+        assert!(streets
+            .iter()
+            .flat_map(|street| street.0.iter())
+            .all(|point| {
+                Point::from(*point).euclidean_distance(&Point::from(START)) <= parameters.radius
+            }));
+    }
+
+    #[test]
+    fn unit_streets_do_not_cross() {
+        let streets = grow_street_network(START, &GrowthParameters::default(), flat, 11);
+        let segments: Vec<Line<f64>> = streets.iter().flat_map(|street| street.lines()).collect();
+
+        // Streets that end at a crossing may end slightly behind the other street due to rounding
+        let is_end = |point: Coordinate<f64>, line: &Line<f64>| {
+            [line.start, line.end]
+                .iter()
+                .any(|end| Point::from(*end).euclidean_distance(&Point::from(point)) < 1e-6)
+        };
+
+        for (i, a) in segments.iter().enumerate() {
+            for b in segments.iter().skip(i + 1) {
+                if let Some(LineIntersection::SinglePoint {
+                    intersection,
+                    is_proper: true,
+                }) = line_intersection(*a, *b)
+                {
+                    assert!(is_end(intersection, a) || is_end(intersection, b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unit_branches_start_on_existing_streets() {
+        let streets = grow_street_network(START, &GrowthParameters::default(), flat, 5);
+
+        assert!(streets.len() > 2);
+        for (i, street) in streets.iter().enumerate().skip(2) {
+            let start = street.0[0];
+            assert!(streets[..i].iter().any(|other| other.0.contains(&start)));
+        }
+    }
+
+    #[test]
+    fn unit_streets_avoid_steep_slopes() {
+        let parameters = GrowthParameters {
+            max_slope: 0.5,
+            ..Default::default()
+        };
+        // A slope rising from left to right, streets must not run from left to right
+        let streets = grow_street_network(START, &parameters, |position| position.x, 13);
+
+        assert!(!streets.is_empty());
+        for line in streets.iter().flat_map(|street| street.lines()) {
+            let length = Point::from(line.start).euclidean_distance(&Point::from(line.end));
+            assert!((line.end.x - line.start.x).abs() <= 0.5 * length + 1e-9);
+        }
+    }
+}
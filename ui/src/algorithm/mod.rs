@@ -1 +1,11 @@
-pub mod geo;
\ No newline at end of file
+pub mod contour;
+pub mod fortification;
+pub mod geo;
+pub mod growth;
+pub mod noise;
+pub mod roads;
+pub mod settlement;
+pub mod spline;
+pub mod subdivision;
+pub mod tensor_field;
+pub mod voronoi;
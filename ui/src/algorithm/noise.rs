@@ -0,0 +1,123 @@
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Gradients of the lattice points. The hash of a lattice point selects one of them.
+const GRADIENTS: [(f64, f64); 8] = [
+    (1., 0.),
+    (-1., 0.),
+    (0., 1.),
+    (0., -1.),
+    (FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    (FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+];
+
+/// Two dimensional gradient noise as described by Ken Perlin. The permutation of the lattice hashes is
+/// shuffled with the seed, the same seed therefore always results in the same noise.
+pub struct PerlinNoise {
+    permutation: Vec<usize>,
+}
+
+impl PerlinNoise {
+    pub fn new(seed: u64) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        permutation.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+
+        PerlinNoise {
+            permutation: permutation.repeat(2),
+        }
+    }
+
+    /// Noise at the point (`x`, `y`) in about [-1, 1]. Lattice points are one unit apart.
+    pub fn get(&self, x: f64, y: f64) -> f64 {
+        let cell_x = x.floor();
+        let cell_y = y.floor();
+
+        let i = (cell_x as i64).rem_euclid(256) as usize;
+        let j = (cell_y as i64).rem_euclid(256) as usize;
+
+        let dx = x - cell_x;
+        let dy = y - cell_y;
+
+        let influence = |di: usize, dj: usize| {
+            let hash = self.permutation[self.permutation[i + di] + j + dj];
+            let (gx, gy) = GRADIENTS[hash % GRADIENTS.len()];
+
+            gx * (dx - di as f64) + gy * (dy - dj as f64)
+        };
+
+        let u = fade(dx);
+        let v = fade(dy);
+
+        let top = lerp(influence(0, 0), influence(1, 0), u);
+        let bottom = lerp(influence(0, 1), influence(1, 1), u);
+
+        // The extreme of 2D gradient noise is sqrt(1/2), scale it to [-1, 1]
+        lerp(top, bottom, v) * SQRT_2
+    }
+
+    /// Sum of `octaves` layers of noise. Each layer has twice the frequency and half the amplitude of the previous
+    /// one. The result is normalized to about [-1, 1].
+    pub fn fractal(&self, x: f64, y: f64, octaves: usize) -> f64 {
+        let mut sum = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+        let mut total = 0.;
+
+        for _ in 0..octaves.max(1) {
+            sum += self.get(x * frequency, y * frequency) * amplitude;
+            total += amplitude;
+
+            amplitude /= 2.;
+            frequency *= 2.;
+        }
+
+        sum / total
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PerlinNoise;
+
+    #[test]
+    fn unit_same_seed_results_in_same_noise() {
+        let a = PerlinNoise::new(42);
+        let b = PerlinNoise::new(42);
+        let c = PerlinNoise::new(43);
+
+        let samples = |noise: &PerlinNoise| -> Vec<f64> {
+            (0..20)
+                .map(|i| noise.fractal(i as f64 * 0.37, i as f64 * 0.21, 4))
+                .collect()
+        };
+
+        assert_eq!(samples(&a), samples(&b));
+        assert_ne!(samples(&a), samples(&c));
+    }
+
+    #[test]
+    fn unit_noise_is_bounded_and_zero_at_lattice_points() {
+        let noise = PerlinNoise::new(7);
+
+        for i in 0..50 {
+            for j in 0..50 {
+                let value = noise.fractal(i as f64 * 0.13, j as f64 * 0.17, 3);
+                assert!((-1. ..=1.).contains(&value));
+            }
+        }
+
+        assert_eq!(noise.get(3., 5.), 0.);
+    }
+}
@@ -0,0 +1,293 @@
+use std::cmp::Ordering;
+
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    prelude::{Contains, EuclideanDistance, EuclideanLength},
+    Coordinate, Line, LineString, Point, Polygon,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::subdivision::OrientedBoundingBox;
+
+/// Anchors closer to each other than this are merged into a single one, e.g. a gate landmark placed on the gate of
+/// a wall.
+const ANCHOR_MERGE_DISTANCE: f64 = 20.;
+
+/// Maximum shift of a local street from its regular position relative to the spacing of the streets.
+const LOCAL_STREET_JITTER: f64 = 0.2;
+
+/// Each local street is extended by this at both ends so that it ends within the arterial road around the block
+/// instead of on its edge.
+const LOCAL_STREET_EXTENSION: f64 = 1.;
+
+/// Parameters of [`local_streets`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalStreetParameters {
+    /// Distance between two local streets that run along the long side of the block.
+    pub spacing: f64,
+
+    /// Distance between two cross streets that run along the short side of the block.
+    pub cross_spacing: f64,
+
+    /// Local streets shorter than this are dropped.
+    pub minimum_length: f64,
+}
+
+impl Default for LocalStreetParameters {
+    fn default() -> Self {
+        LocalStreetParameters {
+            spacing: 120.,
+            cross_spacing: 240.,
+            minimum_length: 40.,
+        }
+    }
+}
+
+/// Connects `anchors` like gates and landmarks by straight arterial roads. Two anchors are connected if no other
+/// anchor lies within the circle whose diameter is the road between them, so each anchor is connected to its
+/// natural neighbours and no two arterial roads cross each other.
+///
+/// Anchors closer than [`ANCHOR_MERGE_DISTANCE`] to a previous anchor are ignored.
+pub fn arterial_network(anchors: &[Coordinate<f64>]) -> Vec<LineString<f64>> {
+    let mut nodes: Vec<Coordinate<f64>> = vec![];
+    for anchor in anchors {
+        if nodes
+            .iter()
+            .all(|node| node.euclidean_distance(anchor) >= ANCHOR_MERGE_DISTANCE)
+        {
+            nodes.push(*anchor);
+        }
+    }
+
+    let mut arterials = vec![];
+    for (i, a) in nodes.iter().enumerate() {
+        for b in nodes.iter().skip(i + 1) {
+            // A point lies within the circle over the diameter ab if a and b are seen from it at an angle of
+            // at least 90 degrees
+            let blocked = nodes
+                .iter()
+                .filter(|c| *c != a && *c != b)
+                .any(|c| dot(*a - *c, *b - *c) <= 0.);
+
+            if !blocked {
+                arterials.push(LineString::new(vec![*a, *b]));
+            }
+        }
+    }
+
+    arterials
+}
+
+/// Fills `block` with a grid of local streets aligned with its oriented bounding box. The streets are shifted
+/// randomly from their regular positions and are clipped to the block. Both ends of each street reach slightly
+/// beyond the block so that they can be connected to the arterial roads around it.
+///
+/// The same `seed` results in the same streets.
+pub fn local_streets(
+    block: &Polygon<f64>,
+    parameters: &LocalStreetParameters,
+    seed: u64,
+) -> Vec<LineString<f64>> {
+    let bbox = match OrientedBoundingBox::new(block) {
+        Some(bbox) => bbox,
+        None => return vec![],
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let mut streets = vec![];
+    for (spacing, extent, across, along) in [
+        (parameters.spacing, bbox.width, bbox.normal, bbox.axis),
+        (
+            parameters.cross_spacing,
+            bbox.length,
+            bbox.axis,
+            bbox.normal,
+        ),
+    ] {
+        for offset in grid_offsets(extent, spacing, &mut rng) {
+            let line = Line::new(
+                bbox.origin + across * offset - along * bbox.length,
+                bbox.origin + across * offset + along * (2. * bbox.length),
+            );
+
+            streets.extend(
+                clip(&line, block)
+                    .into_iter()
+                    .filter(|street| street.euclidean_length() >= parameters.minimum_length)
+                    .map(|street| {
+                        let direction = street.delta() / street.euclidean_length();
+
+                        LineString::new(vec![
+                            street.start - direction * LOCAL_STREET_EXTENSION,
+                            street.end + direction * LOCAL_STREET_EXTENSION,
+                        ])
+                    }),
+            );
+        }
+    }
+
+    streets
+}
+
+/// Offsets of the streets across an extent. The streets are centered within the extent and each one is shifted
+/// by up to [`LOCAL_STREET_JITTER`] of the spacing.
+fn grid_offsets(extent: f64, spacing: f64, rng: &mut ChaCha8Rng) -> Vec<f64> {
+    if spacing <= 0. {
+        return vec![];
+    }
+
+    let count = (extent / spacing).floor() as usize;
+    let first = (extent - (count as f64 - 1.) * spacing) / 2.;
+
+    (0..count)
+        .map(|i| {
+            let jitter = rng.gen_range(-LOCAL_STREET_JITTER..LOCAL_STREET_JITTER) * spacing;
+
+            first + i as f64 * spacing + jitter
+        })
+        .collect()
+}
+
+/// Returns the parts of `line` that lie within `polygon`.
+fn clip(line: &Line<f64>, polygon: &Polygon<f64>) -> Vec<Line<f64>> {
+    let length = line.euclidean_length();
+    let direction = line.delta() / length;
+
+    let mut crossings: Vec<f64> = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors().iter())
+        .flat_map(|ring| ring.lines())
+        .filter_map(|edge| match line_intersection(*line, edge) {
+            Some(LineIntersection::SinglePoint { intersection, .. }) => {
+                Some(dot(intersection - line.start, direction))
+            }
+            _ => None,
+        })
+        .collect();
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    crossings.dedup_by(|a, b| (*a - *b).abs() < f64::EPSILON * length);
+
+    crossings
+        .windows(2)
+        .map(|pair| {
+            Line::new(
+                line.start + direction * pair[0],
+                line.start + direction * pair[1],
+            )
+        })
+        .filter(|part| polygon.contains(&Point::from(part.start + part.delta() / 2.)))
+        .collect()
+}
+
+fn dot(a: Coordinate<f64>, b: Coordinate<f64>) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{
+        line_intersection::{line_intersection, LineIntersection},
+        prelude::{BoundingRect, EuclideanLength},
+        Coordinate, Line, LineString, Polygon,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::{arterial_network, local_streets, LocalStreetParameters, LOCAL_STREET_EXTENSION};
+
+    #[test]
+    fn unit_arterials_connect_neighbours_without_crossings() {
+        let square = [
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 100., y: 0. },
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 0., y: 100. },
+        ];
+        assert_eq!(arterial_network(&square).len(), 4);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let anchors: Vec<Coordinate<f64>> = (0..20)
+            .map(|_| Coordinate {
+                x: rng.gen_range(0. ..1000.),
+                y: rng.gen_range(0. ..1000.),
+            })
+            .collect();
+        let arterials: Vec<Line<f64>> = arterial_network(&anchors)
+            .iter()
+            .map(|arterial| Line::new(arterial.0[0], arterial.0[1]))
+            .collect();
+
+        assert!(arterials.len() >= anchors.len() - 1);
+        for (i, a) in arterials.iter().enumerate() {
+            for b in arterials.iter().skip(i + 1) {
+                assert!(!matches!(
+                    line_intersection(*a, *b),
+                    Some(LineIntersection::SinglePoint {
+                        is_proper: true,
+                        ..
+                    })
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn unit_close_anchors_are_merged() {
+        let anchors = [
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 5., y: 5. },
+            Coordinate { x: 200., y: 0. },
+        ];
+
+        assert_eq!(
+            arterial_network(&anchors),
+            vec![LineString::from(vec![(0., 0.), (200., 0.)])]
+        );
+    }
+
+    #[test]
+    fn unit_local_streets_fill_the_block() {
+        let block = Polygon::new(
+            LineString::from(vec![
+                (0., 0.),
+                (400., 0.),
+                (400., 200.),
+                (0., 200.),
+                (0., 0.),
+            ]),
+            vec![],
+        );
+        let parameters = LocalStreetParameters {
+            spacing: 100.,
+            cross_spacing: 200.,
+            minimum_length: 40.,
+        };
+
+        let streets = local_streets(&block, &parameters, 1);
+        assert_eq!(streets.len(), 4);
+        assert_eq!(streets, local_streets(&block, &parameters, 1));
+        assert_ne!(streets, local_streets(&block, &parameters, 2));
+
+        for street in &streets {
+            let rect = street.bounding_rect().unwrap();
+            assert!(rect.min().x >= -LOCAL_STREET_EXTENSION - 1e-6);
+            assert!(rect.min().y >= -LOCAL_STREET_EXTENSION - 1e-6);
+            assert!(rect.max().x <= 400. + LOCAL_STREET_EXTENSION + 1e-6);
+            assert!(rect.max().y <= 200. + LOCAL_STREET_EXTENSION + 1e-6);
+
+            // Each street crosses the whole block
+            let length = street.euclidean_length() - 2. * LOCAL_STREET_EXTENSION;
+            assert!((length - 200.).abs() < 1e-6 || (length - 400.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn unit_small_block_gets_no_local_streets() {
+        let block = Polygon::new(
+            LineString::from(vec![(0., 0.), (50., 0.), (50., 50.), (0., 50.), (0., 0.)]),
+            vec![],
+        );
+
+        assert!(local_streets(&block, &LocalStreetParameters::default(), 1).is_empty());
+    }
+}
@@ -0,0 +1,479 @@
+use std::{cmp::Ordering, f64::consts::PI, fmt, str::FromStr};
+
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    prelude::{Contains, EuclideanDistance},
+    Coordinate, Line, LineString, Point, Polygon,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::growth::{grow_street_network, GrowthParameters};
+
+/// Number of segments of each arterial road.
+const ARTERIAL_SEGMENTS: usize = 6;
+
+/// Arterial roads reach this multiple of the settlement radius so that they lead out of the settlement.
+const ARTERIAL_REACH: f64 = 1.4;
+
+/// Maximum deviation of each arterial segment from the direction of the road in radians.
+const ARTERIAL_WOBBLE: f64 = 0.15;
+
+/// Number of segments of a river.
+const RIVER_SEGMENTS: usize = 12;
+
+/// A river flows past the center at this multiple of the settlement radius.
+const RIVER_OFFSET: f64 = 0.3;
+
+/// The coast is at this multiple of the settlement radius from the center.
+const COAST_DISTANCE: f64 = 0.5;
+
+/// Half the number of vertices of the coast within the settlement.
+const COAST_VERTICES: i32 = 8;
+
+/// The sea reaches this multiple of the settlement radius beyond the coast so that it covers the whole side of
+/// the map.
+const SEA_EXTENT: f64 = 10.;
+
+/// Size of a settlement like in classic tabletop settlement tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettlementPreset {
+    Hamlet,
+    Village,
+    Town,
+    WalledCity,
+    PortCity,
+}
+
+impl SettlementPreset {
+    pub const ALL: [SettlementPreset; 5] = [
+        SettlementPreset::Hamlet,
+        SettlementPreset::Village,
+        SettlementPreset::Town,
+        SettlementPreset::WalledCity,
+        SettlementPreset::PortCity,
+    ];
+
+    pub fn parameters(&self) -> SettlementParameters {
+        match self {
+            SettlementPreset::Hamlet => SettlementParameters {
+                radius: 150.,
+                arterials: 1,
+                growth: GrowthParameters {
+                    segment_length: 60.,
+                    density: 0.15,
+                    radius: 150.,
+                    max_segments: 12,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            SettlementPreset::Village => SettlementParameters {
+                radius: 300.,
+                arterials: 2,
+                growth: GrowthParameters {
+                    segment_length: 70.,
+                    density: 0.25,
+                    radius: 300.,
+                    max_segments: 40,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            SettlementPreset::Town => SettlementParameters {
+                radius: 500.,
+                arterials: 3,
+                river: true,
+                growth: GrowthParameters {
+                    radius: 500.,
+                    max_segments: 120,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            SettlementPreset::WalledCity => SettlementParameters {
+                radius: 600.,
+                arterials: 4,
+                walls: true,
+                growth: GrowthParameters {
+                    density: 0.4,
+                    radius: 600.,
+                    max_segments: 200,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            SettlementPreset::PortCity => SettlementParameters {
+                radius: 600.,
+                arterials: 3,
+                sea: true,
+                growth: GrowthParameters {
+                    density: 0.4,
+                    radius: 600.,
+                    max_segments: 200,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Default for SettlementPreset {
+    fn default() -> Self {
+        SettlementPreset::Village
+    }
+}
+
+impl fmt::Display for SettlementPreset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SettlementPreset::Hamlet => "hamlet",
+            SettlementPreset::Village => "village",
+            SettlementPreset::Town => "town",
+            SettlementPreset::WalledCity => "walled_city",
+            SettlementPreset::PortCity => "port_city",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SettlementPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SettlementPreset::ALL
+            .iter()
+            .find(|preset| preset.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown settlement preset \"{}\"", s))
+    }
+}
+
+/// Parameters of [`settlement_layout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementParameters {
+    /// Distance between the center and the outermost houses.
+    pub radius: f64,
+
+    /// Number of main roads that lead from the center out of the settlement.
+    pub arterials: usize,
+
+    /// Parameters of the streets grown between the arterial roads.
+    pub growth: GrowthParameters,
+
+    /// Whether a river flows past the center.
+    pub river: bool,
+
+    /// Whether the settlement lies at the coast.
+    pub sea: bool,
+
+    /// Whether the settlement is enclosed by a wall.
+    pub walls: bool,
+}
+
+impl Default for SettlementParameters {
+    fn default() -> Self {
+        SettlementParameters {
+            radius: 300.,
+            arterials: 2,
+            growth: GrowthParameters::default(),
+            river: false,
+            sea: false,
+            walls: false,
+        }
+    }
+}
+
+/// Streets and water of a settlement. Walls are not part of the layout as they depend on the final street
+/// network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementLayout {
+    pub arterials: Vec<LineString<f64>>,
+    pub streets: Vec<LineString<f64>>,
+
+    /// Center line of the river and its width at each vertex.
+    pub river: Option<(LineString<f64>, Vec<f64>)>,
+
+    /// Closed outline of the sea.
+    pub sea: Option<LineString<f64>>,
+}
+
+/// Lays out a settlement around `center`. Arterial roads lead from the center in evenly spread directions, the
+/// streets in between are grown with [`grow_street_network`] on the terrain given by `elevation`. Streets end
+/// at the coast if the settlement lies at the sea, rivers are crossed by bridges later on.
+///
+/// The same `seed` results in the same layout.
+pub fn settlement_layout<E>(
+    center: Coordinate<f64>,
+    parameters: &SettlementParameters,
+    elevation: E,
+    seed: u64,
+) -> SettlementLayout
+where
+    E: Fn(&Coordinate<f64>) -> f64,
+{
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let sea = match parameters.sea {
+        true => Some(coast(
+            center,
+            parameters.radius,
+            rng.gen_range(0. ..2. * PI),
+            &mut rng,
+        )),
+        false => None,
+    };
+    let river = match parameters.river {
+        true => Some(river(
+            center,
+            parameters.radius,
+            rng.gen_range(0. ..PI),
+            &mut rng,
+        )),
+        false => None,
+    };
+
+    let on_land = |line_string: LineString<f64>| match &sea {
+        Some(sea) => truncate_at(&line_string, &Polygon::new(sea.clone(), vec![])),
+        None => Some(line_string),
+    };
+
+    let first_direction = rng.gen_range(0. ..2. * PI);
+    let arterials = (0..parameters.arterials)
+        .map(|i| {
+            let direction = first_direction + 2. * PI * i as f64 / parameters.arterials as f64;
+            arterial(
+                center,
+                parameters.radius * ARTERIAL_REACH,
+                direction,
+                &mut rng,
+            )
+        })
+        .filter_map(on_land)
+        .collect();
+
+    let streets = grow_street_network(center, &parameters.growth, elevation, rng.gen())
+        .into_iter()
+        .filter_map(on_land)
+        .collect();
+
+    SettlementLayout {
+        arterials,
+        streets,
+        river,
+        sea,
+    }
+}
+
+/// Returns a road from `center` that leads `length` away in `direction`, slightly winding along the way.
+fn arterial<R: Rng>(
+    center: Coordinate<f64>,
+    length: f64,
+    direction: f64,
+    rng: &mut R,
+) -> LineString<f64> {
+    let segment_length = length / ARTERIAL_SEGMENTS as f64;
+
+    let mut points = vec![center];
+    let mut position = center;
+    for _ in 0..ARTERIAL_SEGMENTS {
+        let angle = direction + rng.gen_range(-ARTERIAL_WOBBLE..ARTERIAL_WOBBLE);
+        position = position
+            + Coordinate {
+                x: angle.cos(),
+                y: angle.sin(),
+            } * segment_length;
+        points.push(position);
+    }
+
+    LineString::new(points)
+}
+
+/// Returns a meandering river that flows past `center` in `direction` and widens downstream.
+fn river<R: Rng>(
+    center: Coordinate<f64>,
+    radius: f64,
+    direction: f64,
+    rng: &mut R,
+) -> (LineString<f64>, Vec<f64>) {
+    let along = Coordinate {
+        x: direction.cos(),
+        y: direction.sin(),
+    };
+    let across = Coordinate {
+        x: -along.y,
+        y: along.x,
+    };
+
+    let length = 2. * radius * ARTERIAL_REACH;
+    let start = center + across * (radius * RIVER_OFFSET) - along * (length / 2.);
+
+    let mut points = vec![];
+    let mut widths = vec![];
+    for i in 0..=RIVER_SEGMENTS {
+        let t = i as f64 / RIVER_SEGMENTS as f64;
+        let meander = rng.gen_range(-0.05..0.05) * radius;
+
+        points.push(start + along * (t * length) + across * meander);
+        widths.push(12. + 12. * t);
+    }
+
+    (LineString::new(points), widths)
+}
+
+/// Returns the closed outline of the sea whose coast runs at [`COAST_DISTANCE`] from `center` on the side
+/// given by `direction`.
+fn coast<R: Rng>(
+    center: Coordinate<f64>,
+    radius: f64,
+    direction: f64,
+    rng: &mut R,
+) -> LineString<f64> {
+    let seaward = Coordinate {
+        x: direction.cos(),
+        y: direction.sin(),
+    };
+    let along = Coordinate {
+        x: -seaward.y,
+        y: seaward.x,
+    };
+
+    let coast_center = center + seaward * (radius * COAST_DISTANCE);
+    let step = 2. * radius / COAST_VERTICES as f64;
+    let extent = radius * SEA_EXTENT;
+
+    let mut points = vec![coast_center - along * extent];
+    for i in -COAST_VERTICES..=COAST_VERTICES {
+        let bay = rng.gen_range(-0.05..0.05) * radius;
+        points.push(coast_center + along * (i as f64 * step) + seaward * bay);
+    }
+    points.push(coast_center + along * extent);
+    points.push(coast_center + along * extent + seaward * extent);
+    points.push(coast_center - along * extent + seaward * extent);
+    points.push(coast_center - along * extent);
+
+    LineString::new(points)
+}
+
+/// Returns the part of `line_string` from its start up to the point where it first enters `area`, or `None`
+/// if that part is too short to be a street.
+fn truncate_at(line_string: &LineString<f64>, area: &Polygon<f64>) -> Option<LineString<f64>> {
+    let mut points: Vec<Coordinate<f64>> = vec![];
+
+    for line in line_string.lines() {
+        if points.is_empty() {
+            if area.contains(&Point::from(line.start)) {
+                return None;
+            }
+            points.push(line.start);
+        }
+
+        if !area.contains(&Point::from(line.end)) {
+            points.push(line.end);
+            continue;
+        }
+
+        if let Some(entry) = first_crossing(&line, area.exterior()) {
+            points.push(entry);
+        }
+        break;
+    }
+
+    points.dedup();
+    (points.len() >= 2).then(|| LineString::new(points))
+}
+
+/// Returns the crossing of `line` with `ring` that is closest to the start of `line`.
+fn first_crossing(line: &Line<f64>, ring: &LineString<f64>) -> Option<Coordinate<f64>> {
+    let start = Point::from(line.start);
+
+    ring.lines()
+        .filter_map(|edge| match line_intersection(*line, edge) {
+            Some(LineIntersection::SinglePoint { intersection, .. }) => Some(intersection),
+            _ => None,
+        })
+        .min_by(|a, b| {
+            start
+                .euclidean_distance(&Point::from(*a))
+                .partial_cmp(&start.euclidean_distance(&Point::from(*b)))
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{
+        prelude::{Contains, EuclideanDistance},
+        Coordinate, Point, Polygon,
+    };
+
+    use super::{settlement_layout, SettlementPreset};
+
+    const CENTER: Coordinate<f64> = Coordinate { x: 1000., y: 1000. };
+    const EPSILON: f64 = 1e-6;
+
+    fn flat(_: &Coordinate<f64>) -> f64 {
+        0.
+    }
+
+    #[test]
+    fn unit_settlement_preset_is_parsed() {
+        for preset in SettlementPreset::ALL {
+            assert_eq!(preset.to_string().parse::<SettlementPreset>(), Ok(preset));
+        }
+        assert!("metropolis".parse::<SettlementPreset>().is_err());
+    }
+
+    #[test]
+    fn unit_same_seed_lays_out_same_settlement() {
+        let parameters = SettlementPreset::Town.parameters();
+
+        assert_eq!(
+            settlement_layout(CENTER, &parameters, flat, 5),
+            settlement_layout(CENTER, &parameters, flat, 5)
+        );
+        assert_ne!(
+            settlement_layout(CENTER, &parameters, flat, 5),
+            settlement_layout(CENTER, &parameters, flat, 6)
+        );
+    }
+
+    #[test]
+    fn unit_presets_have_their_features() {
+        for preset in SettlementPreset::ALL {
+            let parameters = preset.parameters();
+            let layout = settlement_layout(CENTER, &parameters, flat, 1);
+
+            assert_eq!(layout.arterials.len(), parameters.arterials, "{}", preset);
+            assert!(!layout.streets.is_empty(), "{}", preset);
+            assert_eq!(layout.river.is_some(), parameters.river, "{}", preset);
+            assert_eq!(layout.sea.is_some(), parameters.sea, "{}", preset);
+        }
+    }
+
+    #[test]
+    fn unit_port_city_streets_end_at_the_coast() {
+        for seed in 0..10 {
+            let layout =
+                settlement_layout(CENTER, &SettlementPreset::PortCity.parameters(), flat, seed);
+            let sea = Polygon::new(layout.sea.unwrap(), vec![]);
+
+            // Streets that reach the coast end on it, which is within the sea after rounding
+            let on_land = |point: &Coordinate<f64>| {
+                let point = Point::from(*point);
+
+                !sea.contains(&point) || point.euclidean_distance(sea.exterior()) < EPSILON
+            };
+
+            assert!(on_land(&CENTER));
+            assert!(layout
+                .arterials
+                .iter()
+                .chain(layout.streets.iter())
+                .flat_map(|street| street.0.iter())
+                .all(on_land));
+        }
+    }
+}
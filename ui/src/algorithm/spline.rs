@@ -0,0 +1,372 @@
+use geo::{
+    euclidean_length::EuclideanLength, prelude::EuclideanDistance, Coordinate, Line, LineString,
+    Point,
+};
+
+use super::geo::project_on_line_string;
+
+/// Maximum distance in pixels between the tessellated line string and the curve.
+pub const CURVE_TOLERANCE: f64 = 0.5;
+
+/// Limits the number of subdivisions per curve segment to 2^MAX_DEPTH.
+const MAX_DEPTH: u32 = 8;
+
+/// Uniform Catmull-Rom spline that passes through all of its control points.
+pub struct CatmullRom<'a> {
+    control_points: &'a [Coordinate<f64>],
+}
+
+impl<'a> CatmullRom<'a> {
+    pub fn new(control_points: &'a [Coordinate<f64>]) -> Self {
+        CatmullRom { control_points }
+    }
+
+    /// Returns the control point at `index`. Indices outside of the control points are clamped so that
+    /// the first and last segment use their end point as missing neighbour.
+    fn control_point(&self, index: isize) -> Coordinate<f64> {
+        let last = self.control_points.len() as isize - 1;
+
+        self.control_points[index.clamp(0, last) as usize]
+    }
+
+    /// Evaluates the segment between the control points `segment` and `segment + 1` at `t` in [0, 1].
+    pub fn point(&self, segment: usize, t: f64) -> Coordinate<f64> {
+        let segment = segment as isize;
+        let p0 = self.control_point(segment - 1);
+        let p1 = self.control_point(segment);
+        let p2 = self.control_point(segment + 1);
+        let p3 = self.control_point(segment + 2);
+
+        let t2 = t * t;
+        let t3 = t2 * t;
+
+        (p1 * 2.
+            + (p2 - p0) * t
+            + (p0 * 2. - p1 * 5. + p2 * 4. - p3) * t2
+            + (p1 * 3. - p0 - p2 * 3. + p3) * t3)
+            * 0.5
+    }
+
+    /// Returns the handles of the cubic Bézier segments that form the same curve, one pair per segment.
+    pub fn bezier_handles(&self) -> Vec<[Coordinate<f64>; 2]> {
+        (0..self.control_points.len().saturating_sub(1) as isize)
+            .map(|segment| {
+                let p0 = self.control_point(segment - 1);
+                let p1 = self.control_point(segment);
+                let p2 = self.control_point(segment + 1);
+                let p3 = self.control_point(segment + 2);
+
+                [p1 + (p2 - p0) / 6., p2 - (p3 - p1) / 6.]
+            })
+            .collect()
+    }
+
+    /// Converts the curve into a line string. Each segment is subdivided until the curve deviates less than
+    /// `tolerance` from the line string, straight segments therefore result in a single line.
+    pub fn tessellate(&self, tolerance: f64) -> LineString<f64> {
+        BezierSpline::new(self.control_points.to_vec(), self.bezier_handles()).tessellate(tolerance)
+    }
+}
+
+/// Piecewise cubic Bézier curve through all of its points. The segment between the points `segment` and
+/// `segment + 1` is shaped by the two handles at `segment`. Unlike a Catmull-Rom spline it keeps its shape when it
+/// is split, see [`BezierSpline::split`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BezierSpline {
+    pub points: Vec<Coordinate<f64>>,
+    pub handles: Vec<[Coordinate<f64>; 2]>,
+}
+
+impl BezierSpline {
+    pub fn new(points: Vec<Coordinate<f64>>, handles: Vec<[Coordinate<f64>; 2]>) -> Self {
+        BezierSpline { points, handles }
+    }
+
+    /// Evaluates the segment between the points `segment` and `segment + 1` at `t` in [0, 1].
+    pub fn point(&self, segment: usize, t: f64) -> Coordinate<f64> {
+        let [p0, p1, p2, p3] = self.segment(segment);
+        let s = 1. - t;
+
+        p0 * (s * s * s) + p1 * (3. * s * s * t) + p2 * (3. * s * t * t) + p3 * (t * t * t)
+    }
+
+    fn segment(&self, segment: usize) -> [Coordinate<f64>; 4] {
+        let [a, b] = self.handles[segment];
+
+        [self.points[segment], a, b, self.points[segment + 1]]
+    }
+
+    /// Converts the curve into a line string. Each segment is subdivided until the curve deviates less than
+    /// `tolerance` from the line string, straight segments therefore result in a single line.
+    pub fn tessellate(&self, tolerance: f64) -> LineString<f64> {
+        // A curve without segments is just its point, if any
+        if self.handles.is_empty() {
+            return LineString::new(self.points.clone());
+        }
+
+        LineString::new(
+            self.tessellation_parameters(tolerance)
+                .into_iter()
+                .map(|(segment, t)| self.point(segment, t))
+                .collect(),
+        )
+    }
+
+    /// Returns the segment and the parameter of each point of the tessellation, see [`BezierSpline::tessellate`].
+    pub fn tessellation_parameters(&self, tolerance: f64) -> Vec<(usize, f64)> {
+        if self.handles.is_empty() {
+            return vec![];
+        }
+
+        let mut parameters = vec![(0, 0.)];
+        for segment in 0..self.points.len() - 1 {
+            self.subdivide(segment, 0., 1., tolerance, 0, &mut parameters);
+        }
+
+        parameters
+    }
+
+    fn subdivide(
+        &self,
+        segment: usize,
+        t0: f64,
+        t1: f64,
+        tolerance: f64,
+        depth: u32,
+        parameters: &mut Vec<(usize, f64)>,
+    ) {
+        let start = self.point(segment, t0);
+        let end = self.point(segment, t1);
+
+        let t = (t0 + t1) / 2.;
+        let middle = self.point(segment, t);
+
+        // Also check the quarter points as the middle of an s-shaped segment may lie on the chord
+        let deviation = [
+            middle,
+            self.point(segment, (t0 + t) / 2.),
+            self.point(segment, (t + t1) / 2.),
+        ]
+        .iter()
+        .map(|point| Point::from(*point).euclidean_distance(&Line::new(start, end)))
+        .fold(0., f64::max);
+
+        if deviation > tolerance && depth < MAX_DEPTH {
+            self.subdivide(segment, t0, t, tolerance, depth + 1, parameters);
+            self.subdivide(segment, t, t1, tolerance, depth + 1, parameters);
+
+            return;
+        }
+
+        parameters.push((segment, t1));
+    }
+
+    /// Returns the segment and the parameter of the point on the curve that is closest to `position`. The point is
+    /// searched on the tessellation with the given `tolerance`.
+    ///
+    /// Returns `None` if the closest point is one of the ends of the curve.
+    pub fn closest_parameter(
+        &self,
+        position: &Coordinate<f64>,
+        tolerance: f64,
+    ) -> Option<(usize, f64)> {
+        let parameters = self.tessellation_parameters(tolerance);
+        let line_string = LineString::new(
+            parameters
+                .iter()
+                .map(|(segment, t)| self.point(*segment, *t))
+                .collect(),
+        );
+
+        let (along, _) = project_on_line_string(&line_string, position)?;
+        if along <= f64::EPSILON || along >= line_string.euclidean_length() - f64::EPSILON {
+            return None;
+        }
+
+        let mut travelled = 0.;
+        for (line, (start, end)) in line_string
+            .lines()
+            .zip(parameters.iter().zip(parameters.iter().skip(1)))
+        {
+            let length = line.euclidean_length();
+            if length > 0. && travelled + length >= along {
+                // A line that starts a segment starts at the end of the previous segment
+                let t0 = if start.0 == end.0 { start.1 } else { 0. };
+
+                return Some((end.0, t0 + (end.1 - t0) * (along - travelled) / length));
+            }
+
+            travelled += length;
+        }
+
+        None
+    }
+
+    /// Splits the curve at `t` in [0, 1] of the segment `segment` with De Casteljau's algorithm. Both parts together
+    /// form exactly the same curve, the first one ends and the second one starts at the split point.
+    pub fn split(&self, segment: usize, t: f64) -> (BezierSpline, BezierSpline) {
+        // Splits at the ends of a segment would leave a segment without length
+        if t <= 0. || t >= 1. {
+            let index = if t <= 0. { segment } else { segment + 1 };
+
+            return (
+                BezierSpline::new(
+                    self.points[..=index].to_vec(),
+                    self.handles[..index].to_vec(),
+                ),
+                BezierSpline::new(
+                    self.points[index..].to_vec(),
+                    self.handles[index..].to_vec(),
+                ),
+            );
+        }
+
+        let [p0, p1, p2, p3] = self.segment(segment);
+        let lerp = |a: Coordinate<f64>, b: Coordinate<f64>| a + (b - a) * t;
+
+        let (q0, q1, q2) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+        let (r0, r1) = (lerp(q0, q1), lerp(q1, q2));
+        let split = lerp(r0, r1);
+
+        let mut first = BezierSpline::new(
+            self.points[..=segment].to_vec(),
+            self.handles[..segment].to_vec(),
+        );
+        first.points.push(split);
+        first.handles.push([q0, r0]);
+
+        let mut second = BezierSpline::new(vec![split], vec![[r1, q2]]);
+        second.points.extend_from_slice(&self.points[segment + 1..]);
+        second
+            .handles
+            .extend_from_slice(&self.handles[segment + 1..]);
+
+        (first, second)
+    }
+
+    /// Appends `other`, which has to start where this curve ends. This reverses [`BezierSpline::split`].
+    pub fn join(&self, other: &BezierSpline) -> BezierSpline {
+        BezierSpline::new(
+            self.points
+                .iter()
+                .chain(other.points.iter().skip(1))
+                .copied()
+                .collect(),
+            self.handles
+                .iter()
+                .chain(other.handles.iter())
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Tessellates a Catmull-Rom spline through `control_points` with the default [`CURVE_TOLERANCE`].
+pub fn curve_line_string(control_points: &[Coordinate<f64>]) -> LineString<f64> {
+    CatmullRom::new(control_points).tessellate(CURVE_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::EuclideanDistance, Coordinate, Point};
+
+    use super::{curve_line_string, BezierSpline, CatmullRom};
+
+    #[test]
+    fn unit_curve_passes_through_control_points() {
+        let control_points = vec![
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 100., y: 50. },
+            Coordinate { x: 200., y: 0. },
+        ];
+        let curve = CatmullRom::new(&control_points);
+
+        assert_eq!(curve.point(0, 0.), control_points[0]);
+        assert_eq!(curve.point(0, 1.), control_points[1]);
+        assert_eq!(curve.point(1, 1.), control_points[2]);
+    }
+
+    #[test]
+    fn unit_straight_curve_is_not_subdivided() {
+        let line_string = curve_line_string(&[
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 100., y: 0. },
+        ]);
+
+        assert_eq!(line_string.0.len(), 2);
+    }
+
+    #[test]
+    fn unit_bent_curve_is_within_tolerance() {
+        let control_points = vec![
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 200., y: 0. },
+        ];
+        let line_string = curve_line_string(&control_points);
+        let curve = CatmullRom::new(&control_points);
+
+        assert!(line_string.0.len() > 3);
+        for i in 0..=20 {
+            let point = curve.point(0, i as f64 / 20.);
+            assert!(Point::from(point).euclidean_distance(&line_string) < 1.);
+        }
+    }
+
+    fn assert_near(a: Coordinate<f64>, b: Coordinate<f64>) {
+        assert!(
+            (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn control_points() -> Vec<Coordinate<f64>> {
+        vec![
+            Coordinate { x: 0., y: 0. },
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 200., y: 0. },
+            Coordinate { x: 300., y: 50. },
+        ]
+    }
+
+    #[test]
+    fn unit_bezier_handles_form_same_curve() {
+        let control_points = control_points();
+        let curve = CatmullRom::new(&control_points);
+        let spline = BezierSpline::new(control_points.clone(), curve.bezier_handles());
+
+        for segment in 0..3 {
+            for i in 0..=10 {
+                let t = i as f64 / 10.;
+                assert_near(spline.point(segment, t), curve.point(segment, t));
+            }
+        }
+    }
+
+    #[test]
+    fn unit_split_spline_keeps_shape() {
+        let control_points = control_points();
+        let spline = BezierSpline::new(
+            control_points.clone(),
+            CatmullRom::new(&control_points).bezier_handles(),
+        );
+
+        let (first, second) = spline.split(1, 0.3);
+
+        assert_eq!(first.points.len(), 3);
+        assert_eq!(second.points.len(), 3);
+        for i in 0..=10 {
+            let t = i as f64 / 10.;
+            assert_near(first.point(0, t), spline.point(0, t));
+            assert_near(first.point(1, t), spline.point(1, 0.3 * t));
+            assert_near(second.point(0, t), spline.point(1, 0.3 + 0.7 * t));
+            assert_near(second.point(1, t), spline.point(2, t));
+        }
+
+        let joined = first.join(&second);
+        assert_eq!(joined.points.len(), 5);
+        assert_eq!(joined.handles.len(), 4);
+    }
+}
@@ -0,0 +1,698 @@
+use std::{cmp::Ordering, ops::Range};
+
+use geo::{
+    euclidean_length::EuclideanLength,
+    prelude::{Area, Centroid, ConvexHull},
+    Coordinate, Line, Polygon,
+};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use super::geo::{
+    clip, is_convex, longest_and_shortest_diameter, longest_line, split, AnnotatedPolygon,
+};
+
+/// Half the length of the lines that cut a polygon. It is long enough to cross any district.
+const CUT_LENGTH: f64 = 100000.;
+
+/// Lots with less street frontage than this are treated as not touching a street at all.
+const FRONTAGE_EPSILON: f64 = 1e-3;
+
+/// Relative difference between the area of a polygon and the total area of its pieces up to which a cut is
+/// treated as valid.
+const AREA_TOLERANCE: f64 = 1e-6;
+
+/// Maximum number of nested splits. It prevents an endless recursion on degenerate polygons.
+const MAXIMUM_DEPTH: usize = 32;
+
+/// Consecutive street edges that turn by less than this angle (in radians) form one side of a block.
+const SIDE_ANGLE: f64 = 0.35;
+
+/// Polygons whose convex hull is larger than their area by this factor are not treated as convex.
+const CONVEXITY_TOLERANCE: f64 = 1.05;
+
+/// Parameters shared by all subdivision strategies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubdivisionParameters {
+    /// Lots are not split further once they are shorter than this in each direction. Strip and grid lots are
+    /// about this wide.
+    pub minimum_side: f64,
+
+    /// Range from which the relative position of each cut is drawn. A ratio of 0.5 splits a polygon in the middle
+    /// and results in strip and grid lots of exactly `minimum_side`.
+    pub split_ratio: Range<f64>,
+
+    /// Minimum length of the street edges of each lot. Cuts that would leave a lot with less frontage are not
+    /// made, which may result in larger lots.
+    pub minimum_frontage: f64,
+}
+
+/// Subdivides a block into lots. The street flags of the [`AnnotatedPolygon`] mark the edges of the block that
+/// face a street, every lot returned by a strategy has at least one of these edges.
+pub trait SubdivisionStrategy {
+    fn subdivide(
+        &self,
+        polygon: &AnnotatedPolygon,
+        parameters: &SubdivisionParameters,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<AnnotatedPolygon>;
+}
+
+/// Recursively splits a polygon perpendicular to its longest street edge until the lots are small enough. This is
+/// how the editor has always subdivided districts.
+pub struct LongestStreetSplit;
+
+/// Recursively splits a polygon across the long side of its oriented bounding box until the lots are small enough.
+/// If that cut is not possible without cutting off a lot from the street, the polygon is split along its long side.
+pub struct ObbSplit;
+
+/// Divides a block into the faces of its straight skeleton, one for each side of the block. Each face is cut into
+/// strips that run from the street to the middle of the block. Concave blocks are split into smaller blocks first.
+pub struct SkeletonStrips;
+
+/// Lays out a grid aligned with the oriented bounding box of the block. Grid lines that would cut off a lot from
+/// the street are skipped, blocks that are two lots deep therefore end up with back to back lots.
+pub struct GridLots;
+
+/// The minimum area rectangle that contains a polygon.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct OrientedBoundingBox {
+    pub origin: Coordinate<f64>,
+
+    /// Unit vector along the long side of the box.
+    pub axis: Coordinate<f64>,
+
+    /// Unit vector along the short side of the box.
+    pub normal: Coordinate<f64>,
+
+    pub length: f64,
+    pub width: f64,
+}
+
+impl OrientedBoundingBox {
+    /// Finds the box by rotating calipers, one of the sides of the minimum area rectangle is collinear with an edge
+    /// of the convex hull.
+    pub(crate) fn new(polygon: &Polygon<f64>) -> Option<Self> {
+        let hull = polygon.convex_hull();
+
+        let mut best: Option<(f64, OrientedBoundingBox)> = None;
+        for line in hull.exterior().lines() {
+            let length = line.euclidean_length();
+            if length <= f64::EPSILON {
+                continue;
+            }
+
+            let u = line.delta() / length;
+            let v = Coordinate { x: -u.y, y: u.x };
+
+            let (mut min_u, mut max_u) = (f64::MAX, f64::MIN);
+            let (mut min_v, mut max_v) = (f64::MAX, f64::MIN);
+            for point in hull.exterior().0.iter().copied() {
+                min_u = min_u.min(dot(point, u));
+                max_u = max_u.max(dot(point, u));
+                min_v = min_v.min(dot(point, v));
+                max_v = max_v.max(dot(point, v));
+            }
+
+            let area = (max_u - min_u) * (max_v - min_v);
+            if matches!(best, Some((best_area, _)) if best_area <= area) {
+                continue;
+            }
+
+            let origin = u * min_u + v * min_v;
+            let bbox = match max_u - min_u >= max_v - min_v {
+                true => OrientedBoundingBox {
+                    origin,
+                    axis: u,
+                    normal: v,
+                    length: max_u - min_u,
+                    width: max_v - min_v,
+                },
+                false => OrientedBoundingBox {
+                    origin,
+                    axis: v,
+                    normal: u,
+                    length: max_v - min_v,
+                    width: max_u - min_u,
+                },
+            };
+            best = Some((area, bbox));
+        }
+
+        best.map(|(_, bbox)| bbox)
+    }
+
+    /// Line across the long side at the relative position `ratio`.
+    fn cut_across(&self, ratio: f64) -> Line<f64> {
+        cut_line(
+            self.origin + self.axis * self.length * ratio + self.normal * self.width / 2.,
+            self.normal,
+        )
+    }
+
+    /// Line along the long side at the relative position `ratio` of the short side.
+    fn cut_along(&self, ratio: f64) -> Line<f64> {
+        cut_line(
+            self.origin + self.axis * self.length / 2. + self.normal * self.width * ratio,
+            self.axis,
+        )
+    }
+}
+
+impl SubdivisionStrategy for LongestStreetSplit {
+    fn subdivide(
+        &self,
+        polygon: &AnnotatedPolygon,
+        parameters: &SubdivisionParameters,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<AnnotatedPolygon> {
+        let mut lots = vec![];
+        longest_street_split(polygon.clone(), parameters, rng, 0, &mut lots);
+
+        lots
+    }
+}
+
+fn longest_street_split(
+    polygon: AnnotatedPolygon,
+    parameters: &SubdivisionParameters,
+    rng: &mut ChaCha8Rng,
+    depth: usize,
+    lots: &mut Vec<AnnotatedPolygon>,
+) {
+    let (length, width) = longest_and_shortest_diameter(&polygon.0);
+    if depth >= MAXIMUM_DEPTH
+        || (length < parameters.minimum_side && width < parameters.minimum_side)
+    {
+        lots.push(polygon);
+        return;
+    }
+
+    let line = longest_line(&polygon, parameters.minimum_side).0;
+    let direction = line.delta() / line.euclidean_length();
+    let split_point = line.start + line.delta() * split_ratio(rng, &parameters.split_ratio);
+    let cut = cut_line(
+        split_point,
+        Coordinate {
+            x: -direction.y,
+            y: direction.x,
+        },
+    );
+
+    match try_cut(&polygon, &cut, parameters) {
+        Some(pieces) => {
+            for piece in pieces {
+                longest_street_split(piece, parameters, rng, depth + 1, lots);
+            }
+        }
+        None => lots.push(polygon),
+    }
+}
+
+impl SubdivisionStrategy for ObbSplit {
+    fn subdivide(
+        &self,
+        polygon: &AnnotatedPolygon,
+        parameters: &SubdivisionParameters,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<AnnotatedPolygon> {
+        let mut lots = vec![];
+        obb_split(polygon.clone(), parameters, rng, 0, &mut lots);
+
+        lots
+    }
+}
+
+fn obb_split(
+    polygon: AnnotatedPolygon,
+    parameters: &SubdivisionParameters,
+    rng: &mut ChaCha8Rng,
+    depth: usize,
+    lots: &mut Vec<AnnotatedPolygon>,
+) {
+    let bbox = match OrientedBoundingBox::new(&polygon.0) {
+        Some(bbox) if depth < MAXIMUM_DEPTH && bbox.length >= parameters.minimum_side => bbox,
+        _ => {
+            lots.push(polygon);
+            return;
+        }
+    };
+
+    let ratio = split_ratio(rng, &parameters.split_ratio);
+    let mut cuts = vec![bbox.cut_across(ratio)];
+    if bbox.width >= parameters.minimum_side {
+        cuts.push(bbox.cut_along(ratio));
+    }
+
+    match cuts
+        .iter()
+        .find_map(|line| try_cut(&polygon, line, parameters))
+    {
+        Some(pieces) => {
+            for piece in pieces {
+                obb_split(piece, parameters, rng, depth + 1, lots);
+            }
+        }
+        None => lots.push(polygon),
+    }
+}
+
+impl SubdivisionStrategy for SkeletonStrips {
+    fn subdivide(
+        &self,
+        polygon: &AnnotatedPolygon,
+        parameters: &SubdivisionParameters,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<AnnotatedPolygon> {
+        let mut lots = vec![];
+        skeleton_strips(polygon.clone(), parameters, rng, 0, &mut lots);
+
+        lots
+    }
+}
+
+fn skeleton_strips(
+    polygon: AnnotatedPolygon,
+    parameters: &SubdivisionParameters,
+    rng: &mut ChaCha8Rng,
+    depth: usize,
+    lots: &mut Vec<AnnotatedPolygon>,
+) {
+    if is_convex(&polygon.0, CONVEXITY_TOLERANCE) {
+        if let Some(faces) = skeleton_faces(&polygon) {
+            for (face, side) in faces {
+                lots.append(&mut strips(face, &side, parameters, rng));
+            }
+
+            return;
+        }
+    }
+
+    // The skeleton of concave blocks is not made of one face per side, they are split into convex blocks first
+    let pieces = OrientedBoundingBox::new(&polygon.0)
+        .filter(|_| depth < MAXIMUM_DEPTH)
+        .and_then(|bbox| {
+            [bbox.cut_across(0.5), bbox.cut_along(0.5)]
+                .iter()
+                .find_map(|line| try_cut(&polygon, line, parameters))
+        });
+
+    match pieces {
+        Some(pieces) => {
+            for piece in pieces {
+                skeleton_strips(piece, parameters, rng, depth + 1, lots);
+            }
+        }
+        None => obb_split(polygon, parameters, rng, depth, lots),
+    }
+}
+
+/// Returns the faces of the straight skeleton of a convex polygon, each with the chord of the side of the polygon
+/// it belongs to. Only street edges form sides, the area next to other edges is added to the closest side.
+///
+/// Returns `None` if a face would not touch a street.
+fn skeleton_faces(polygon: &AnnotatedPolygon) -> Option<Vec<(AnnotatedPolygon, Line<f64>)>> {
+    // Signed distances are positive within the polygon
+    let orientation = polygon.0.signed_area().signum();
+    let sides = street_sides(polygon);
+
+    let mut faces = vec![];
+    for (index, side) in sides.iter().enumerate() {
+        let distance = |line: &Line<f64>, point: &Coordinate<f64>| {
+            let u = line.delta() / line.euclidean_length();
+            orientation * cross(u, *point - line.start)
+        };
+
+        let mut face = Some(polygon.clone());
+        for (_, other) in sides
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+        {
+            face = face.and_then(|face| {
+                clip(&face, |point| {
+                    distance(side, point) - distance(other, point)
+                })
+            });
+        }
+
+        match face {
+            Some(face) if frontage(&face) >= FRONTAGE_EPSILON => faces.push((face, *side)),
+            Some(_) => return None,
+            None => continue,
+        }
+    }
+
+    match faces.is_empty() {
+        true => None,
+        false => Some(faces),
+    }
+}
+
+/// Groups consecutive street edges of the polygon that turn only slightly into sides and returns the chord of each
+/// side. Curved streets therefore form a single side instead of one side per segment.
+fn street_sides(polygon: &AnnotatedPolygon) -> Vec<Line<f64>> {
+    let lines = polygon.lines();
+    let count = lines.len();
+
+    let turn = |a: &Line<f64>, b: &Line<f64>| {
+        let (a, b) = (a.delta(), b.delta());
+        cross(a, b).atan2(dot(a, b)).abs()
+    };
+
+    // Start at the beginning of a side so that no side is split at the start of the ring
+    let start = (0..count)
+        .find(|i| {
+            let (line, is_street) = &lines[*i];
+            let (previous, previous_is_street) = &lines[(i + count - 1) % count];
+
+            *is_street && (!previous_is_street || turn(previous, line) >= SIDE_ANGLE)
+        })
+        .unwrap_or(0);
+
+    let mut sides: Vec<(Line<f64>, Line<f64>)> = vec![];
+    let mut open = false;
+    for i in 0..count {
+        let (line, is_street) = lines[(start + i) % count];
+        if line.euclidean_length() <= f64::EPSILON {
+            continue;
+        }
+
+        if !is_street {
+            open = false;
+            continue;
+        }
+
+        match sides.last_mut() {
+            Some((first, chord)) if open && turn(first, &line) < SIDE_ANGLE => chord.end = line.end,
+            _ => sides.push((line, line)),
+        }
+        open = true;
+    }
+
+    sides
+        .into_iter()
+        .map(|(_, chord)| chord)
+        .filter(|chord| chord.euclidean_length() > f64::EPSILON)
+        .collect()
+}
+
+/// Cuts the face of `side` into strips perpendicular to the side.
+fn strips(
+    face: AnnotatedPolygon,
+    side: &Line<f64>,
+    parameters: &SubdivisionParameters,
+    rng: &mut ChaCha8Rng,
+) -> Vec<AnnotatedPolygon> {
+    let u = side.delta() / side.euclidean_length();
+    let v = Coordinate { x: -u.y, y: u.x };
+
+    let (start, end) = face
+        .0
+        .exterior()
+        .0
+        .iter()
+        .map(|point| dot(*point, u))
+        .fold((f64::MAX, f64::MIN), |(min, max), x| {
+            (min.min(x), max.max(x))
+        });
+
+    let mut lots = vec![];
+    let mut remaining = face;
+    let mut position = start;
+    loop {
+        let width = parameters.minimum_side * 2. * split_ratio(rng, &parameters.split_ratio);
+        position += width;
+
+        // A narrow rest is added to the last strip
+        if width <= f64::EPSILON || end - position < width / 2. {
+            break;
+        }
+
+        let line = cut_line(u * position + v * dot(side.start, v), v);
+        if let Some(pieces) = try_cut(&remaining, &line, parameters) {
+            if pieces.len() != 2 {
+                continue;
+            }
+
+            let (mut before, after): (Vec<AnnotatedPolygon>, Vec<AnnotatedPolygon>) = pieces
+                .into_iter()
+                .partition(|piece| match piece.0.centroid() {
+                    Some(centroid) => dot(centroid.into(), u) < position,
+                    None => false,
+                });
+
+            if let (Some(lot), Some(rest)) = (before.pop(), after.into_iter().next()) {
+                lots.push(lot);
+                remaining = rest;
+            }
+        }
+    }
+    lots.push(remaining);
+
+    lots
+}
+
+impl SubdivisionStrategy for GridLots {
+    fn subdivide(
+        &self,
+        polygon: &AnnotatedPolygon,
+        parameters: &SubdivisionParameters,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<AnnotatedPolygon> {
+        let bbox = match OrientedBoundingBox::new(&polygon.0) {
+            Some(bbox) => bbox,
+            None => return vec![polygon.clone()],
+        };
+
+        let mut lots = vec![polygon.clone()];
+        let mut apply = |line: Line<f64>| {
+            lots = lots
+                .drain(..)
+                .flat_map(|lot| try_cut(&lot, &line, parameters).unwrap_or_else(|| vec![lot]))
+                .collect();
+        };
+
+        for position in grid_positions(bbox.length, parameters, rng) {
+            apply(bbox.cut_across(position / bbox.length));
+        }
+
+        // Rows are cut from the middle of the block outwards so that blocks two lots deep are split in the middle
+        let mut rows = grid_positions(bbox.width, parameters, rng);
+        rows.sort_by(|a, b| {
+            (a - bbox.width / 2.)
+                .abs()
+                .partial_cmp(&(b - bbox.width / 2.).abs())
+                .unwrap_or(Ordering::Equal)
+        });
+        for position in rows {
+            apply(bbox.cut_along(position / bbox.width));
+        }
+
+        lots
+    }
+}
+
+/// Positions of the grid lines along a side of the given length.
+fn grid_positions(
+    length: f64,
+    parameters: &SubdivisionParameters,
+    rng: &mut ChaCha8Rng,
+) -> Vec<f64> {
+    let mut positions = vec![];
+    let mut position = 0.;
+    loop {
+        let width = parameters.minimum_side * 2. * split_ratio(rng, &parameters.split_ratio);
+        position += width;
+
+        if width <= f64::EPSILON || length - position < width / 2. {
+            return positions;
+        }
+
+        positions.push(position);
+    }
+}
+
+/// Cuts `polygon` by `line`. Returns `None` if the line does not cut the polygon into valid pieces or if a piece
+/// would have less street frontage than allowed.
+fn try_cut(
+    polygon: &AnnotatedPolygon,
+    line: &Line<f64>,
+    parameters: &SubdivisionParameters,
+) -> Option<Vec<AnnotatedPolygon>> {
+    let pieces = split(polygon, line);
+    if pieces.len() < 2 {
+        return None;
+    }
+
+    let area = polygon.0.unsigned_area();
+    let pieces_area: f64 = pieces.iter().map(|piece| piece.0.unsigned_area()).sum();
+    if (area - pieces_area).abs() > area * AREA_TOLERANCE {
+        return None;
+    }
+
+    let minimum_frontage = parameters.minimum_frontage.max(FRONTAGE_EPSILON);
+    match pieces
+        .iter()
+        .all(|piece| piece.0.unsigned_area() > f64::EPSILON && frontage(piece) >= minimum_frontage)
+    {
+        true => Some(pieces),
+        false => None,
+    }
+}
+
+/// Total length of the street edges of the polygon.
+pub fn frontage(polygon: &AnnotatedPolygon) -> f64 {
+    polygon
+        .lines()
+        .iter()
+        .filter(|(_, is_street)| *is_street)
+        .map(|(line, _)| line.euclidean_length())
+        .sum()
+}
+
+fn split_ratio(rng: &mut ChaCha8Rng, range: &Range<f64>) -> f64 {
+    match range.start < range.end {
+        true => rng.gen_range(range.clone()),
+        false => range.start,
+    }
+}
+
+fn cut_line(center: Coordinate<f64>, direction: Coordinate<f64>) -> Line<f64> {
+    Line::new(
+        center - direction * CUT_LENGTH,
+        center + direction * CUT_LENGTH,
+    )
+}
+
+fn dot(a: Coordinate<f64>, b: Coordinate<f64>) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+fn cross(a: Coordinate<f64>, b: Coordinate<f64>) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::Area, LineString, Polygon};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::algorithm::geo::AnnotatedPolygon;
+
+    use super::{
+        frontage, GridLots, LongestStreetSplit, ObbSplit, SkeletonStrips, SubdivisionParameters,
+        SubdivisionStrategy,
+    };
+
+    fn block(points: Vec<(f64, f64)>) -> AnnotatedPolygon {
+        let polygon = Polygon::new(LineString::from(points), vec![]);
+        let is_street = polygon.exterior().lines().map(|_| true).collect();
+
+        AnnotatedPolygon(polygon, is_street)
+    }
+
+    fn parameters(minimum_frontage: f64) -> SubdivisionParameters {
+        SubdivisionParameters {
+            minimum_side: 50.,
+            split_ratio: 0.3..0.7,
+            minimum_frontage,
+        }
+    }
+
+    fn strategies() -> Vec<Box<dyn SubdivisionStrategy>> {
+        vec![
+            Box::new(LongestStreetSplit),
+            Box::new(ObbSplit),
+            Box::new(SkeletonStrips),
+            Box::new(GridLots),
+        ]
+    }
+
+    fn assert_lots(polygon: &AnnotatedPolygon, lots: &[AnnotatedPolygon], minimum_frontage: f64) {
+        assert!(lots.len() > 1);
+        assert!(lots
+            .iter()
+            .all(|lot| !lot.enclosed() && frontage(lot) >= minimum_frontage));
+
+        let area: f64 = lots.iter().map(|lot| lot.0.unsigned_area()).sum();
+        assert!((area - polygon.0.unsigned_area()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn unit_every_lot_touches_a_street() {
+        let polygon = block(vec![(0., 0.), (400., 0.), (400., 250.), (0., 250.)]);
+
+        for strategy in strategies() {
+            let lots =
+                strategy.subdivide(&polygon, &parameters(0.), &mut ChaCha8Rng::seed_from_u64(3));
+
+            assert_lots(&polygon, &lots, 0.);
+        }
+    }
+
+    #[test]
+    fn unit_lots_have_minimum_frontage() {
+        let polygon = block(vec![(0., 0.), (300., 20.), (350., 260.), (-20., 200.)]);
+
+        for strategy in strategies() {
+            let lots = strategy.subdivide(
+                &polygon,
+                &parameters(40.),
+                &mut ChaCha8Rng::seed_from_u64(5),
+            );
+
+            assert_lots(&polygon, &lots, 40.);
+        }
+    }
+
+    #[test]
+    fn unit_concave_block_is_subdivided() {
+        let polygon = block(vec![
+            (0., 0.),
+            (300., 0.),
+            (300., 100.),
+            (100., 100.),
+            (100., 300.),
+            (0., 300.),
+        ]);
+
+        for strategy in strategies() {
+            let lots =
+                strategy.subdivide(&polygon, &parameters(0.), &mut ChaCha8Rng::seed_from_u64(8));
+
+            assert_lots(&polygon, &lots, 0.);
+        }
+    }
+
+    #[test]
+    fn unit_subdivision_is_reproducible() {
+        let polygon = block(vec![(0., 0.), (400., 0.), (400., 250.), (0., 250.)]);
+
+        for strategy in strategies() {
+            let a =
+                strategy.subdivide(&polygon, &parameters(0.), &mut ChaCha8Rng::seed_from_u64(1));
+            let b =
+                strategy.subdivide(&polygon, &parameters(0.), &mut ChaCha8Rng::seed_from_u64(1));
+
+            assert_eq!(
+                a.iter().map(|lot| lot.0.clone()).collect::<Vec<_>>(),
+                b.iter().map(|lot| lot.0.clone()).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn unit_strips_face_their_side() {
+        let polygon = block(vec![(0., 0.), (400., 0.), (400., 200.), (0., 200.)]);
+        let lots =
+            SkeletonStrips.subdivide(&polygon, &parameters(0.), &mut ChaCha8Rng::seed_from_u64(2));
+
+        // Strips reach from the street to the skeleton in the middle of the block
+        assert!(lots.iter().all(|lot| {
+            let rect = geo::prelude::BoundingRect::bounding_rect(&lot.0).unwrap();
+            rect.width() <= 100. + 1e-6 || rect.height() <= 100. + 1e-6
+        }));
+    }
+}
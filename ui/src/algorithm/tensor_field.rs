@@ -0,0 +1,690 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    f64::consts::FRAC_PI_2,
+};
+
+use geo::{
+    prelude::{BoundingRect, ClosestPoint, Contains, EuclideanDistance},
+    Closest, Coordinate, LineString, Point, Rect,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Tensors with a smaller norm have no meaningful direction, streamlines stop there.
+const DEGENERATE_NORM: f64 = 1e-6;
+
+/// A symmetric, traceless 2x2 tensor stored as `r * (cos 2θ, sin 2θ)`. Its major eigenvector points in the
+/// direction θ, its minor eigenvector is perpendicular to it. Unlike directions, tensors can be added without
+/// caring about the orientation of the vectors.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tensor {
+    a: f64,
+    b: f64,
+}
+
+impl Tensor {
+    /// Creates a tensor whose major eigenvector points in the direction of `angle` in radians.
+    pub fn from_angle(angle: f64, norm: f64) -> Self {
+        Tensor {
+            a: norm * (2. * angle).cos(),
+            b: norm * (2. * angle).sin(),
+        }
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.a.hypot(self.b)
+    }
+
+    /// Returns the angle in radians of the major eigenvector.
+    pub fn major_angle(&self) -> f64 {
+        self.b.atan2(self.a) / 2.
+    }
+
+    /// Returns the unit vector along the major or minor eigenvector or `None` if the tensor is degenerate.
+    pub fn direction(&self, direction: Direction) -> Option<Coordinate<f64>> {
+        if self.norm() < DEGENERATE_NORM {
+            return None;
+        }
+
+        let angle = match direction {
+            Direction::Major => self.major_angle(),
+            Direction::Minor => self.major_angle() + FRAC_PI_2,
+        };
+
+        Some(Coordinate {
+            x: angle.cos(),
+            y: angle.sin(),
+        })
+    }
+
+    fn add(&self, other: &Tensor, weight: f64) -> Tensor {
+        Tensor {
+            a: self.a + weight * other.a,
+            b: self.b + weight * other.b,
+        }
+    }
+}
+
+/// The two families of hyperstreamlines of a tensor field. Streamlines of the same family never cross,
+/// streamlines of different families cross at right angles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Major,
+    Minor,
+}
+
+/// Shape of a [`BasisField`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BasisFieldKind {
+    /// Parallel major streamlines in the direction of `angle` in radians.
+    Grid { angle: f64 },
+
+    /// Major streamlines form circles around the center of the field, minor streamlines run outwards.
+    Radial,
+
+    /// Major streamlines run along the line string, e.g. a river bank or an existing street.
+    Boundary { line_string: LineString<f64> },
+}
+
+/// A simple tensor field that is blended with others into a [`TensorField`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasisField {
+    pub kind: BasisFieldKind,
+    pub center: Coordinate<f64>,
+
+    /// Distance from the center, or the line string of a boundary field, at which the influence of the field
+    /// has dropped by a factor of `e^decay`.
+    pub size: f64,
+    pub decay: f64,
+    pub weight: f64,
+}
+
+impl BasisField {
+    pub fn new(kind: BasisFieldKind, center: Coordinate<f64>, size: f64) -> Self {
+        BasisField {
+            kind,
+            center,
+            size,
+            decay: 1.,
+            weight: 1.,
+        }
+    }
+
+    /// Returns the area the field mainly influences.
+    pub fn bounds(&self) -> Rect<f64> {
+        let rect = match &self.kind {
+            BasisFieldKind::Boundary { line_string } => line_string
+                .bounding_rect()
+                .unwrap_or_else(|| Rect::new(self.center, self.center)),
+            _ => Rect::new(self.center, self.center),
+        };
+        let size = Coordinate {
+            x: self.size,
+            y: self.size,
+        };
+
+        Rect::new(rect.min() - size, rect.max() + size)
+    }
+
+    pub fn tensor(&self, position: &Coordinate<f64>) -> Tensor {
+        match &self.kind {
+            BasisFieldKind::Grid { angle } => Tensor::from_angle(*angle, 1.),
+            BasisFieldKind::Radial => {
+                let offset = *position - self.center;
+                Tensor::from_angle(offset.y.atan2(offset.x) + FRAC_PI_2, 1.)
+            }
+            BasisFieldKind::Boundary { line_string } => {
+                let point = Point::from(*position);
+                let segment = line_string.lines().min_by(|a, b| {
+                    a.euclidean_distance(&point)
+                        .partial_cmp(&b.euclidean_distance(&point))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+                match segment {
+                    Some(segment) => Tensor::from_angle(segment.dy().atan2(segment.dx()), 1.),
+                    None => Tensor::default(),
+                }
+            }
+        }
+    }
+
+    /// Returns the weight of the field at `position`. It drops from [`BasisField::weight`] with the squared
+    /// distance to the field.
+    pub fn influence(&self, position: &Coordinate<f64>) -> f64 {
+        let point = Point::from(*position);
+        let distance = match &self.kind {
+            BasisFieldKind::Boundary { line_string } => match line_string.closest_point(&point) {
+                Closest::Intersection(closest) | Closest::SinglePoint(closest) => {
+                    closest.euclidean_distance(&point)
+                }
+                Closest::Indeterminate => return 0.,
+            },
+            _ => point.euclidean_distance(&Point::from(self.center)),
+        };
+
+        self.weight * (-self.decay * (distance / self.size).powi(2)).exp()
+    }
+}
+
+/// Weighted sum of basis fields.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TensorField {
+    fields: Vec<BasisField>,
+}
+
+impl TensorField {
+    pub fn new() -> Self {
+        TensorField::default()
+    }
+
+    pub fn fields(&self) -> &Vec<BasisField> {
+        &self.fields
+    }
+
+    pub fn add_field(&mut self, field: BasisField) {
+        self.fields.push(field);
+    }
+
+    pub fn remove_field(&mut self, index: usize) -> Option<BasisField> {
+        (index < self.fields.len()).then(|| self.fields.remove(index))
+    }
+
+    /// Returns the area influenced by the fields or `None` if there are no fields.
+    pub fn bounds(&self) -> Option<Rect<f64>> {
+        self.fields
+            .iter()
+            .map(|field| field.bounds())
+            .reduce(|a, b| {
+                Rect::new(
+                    Coordinate {
+                        x: a.min().x.min(b.min().x),
+                        y: a.min().y.min(b.min().y),
+                    },
+                    Coordinate {
+                        x: a.max().x.max(b.max().x),
+                        y: a.max().y.max(b.max().y),
+                    },
+                )
+            })
+    }
+
+    pub fn tensor(&self, position: &Coordinate<f64>) -> Tensor {
+        self.fields.iter().fold(Tensor::default(), |sum, field| {
+            sum.add(&field.tensor(position), field.influence(position))
+        })
+    }
+}
+
+/// Parameters of [`trace_hyperstreamlines`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamlineParameters {
+    /// Minimum distance between the seed of a major streamline and any other major streamline.
+    pub major_separation: f64,
+
+    /// Minimum distance between the seed of a minor streamline and any other minor streamline.
+    pub minor_separation: f64,
+
+    /// Distance between two points of a streamline.
+    pub step: f64,
+
+    /// Streamlines that are shorter are dropped.
+    pub minimum_length: f64,
+
+    /// Streamlines are only traced within these bounds.
+    pub bounds: Rect<f64>,
+}
+
+/// A hyperstreamline of a tensor field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hyperstreamline {
+    pub line_string: LineString<f64>,
+    pub direction: Direction,
+}
+
+/// Points of the traced streamlines of one direction, bucketed by a grid to find close points quickly.
+struct SampleGrid {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<Coordinate<f64>>>,
+}
+
+impl SampleGrid {
+    fn new(cell_size: f64) -> Self {
+        SampleGrid {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, position: &Coordinate<f64>) -> (i64, i64) {
+        (
+            (position.x / self.cell_size).floor() as i64,
+            (position.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    fn add(&mut self, position: Coordinate<f64>) {
+        let cell = self.cell(&position);
+        self.cells.entry(cell).or_default().push(position);
+    }
+
+    /// Returns the sample closest to `position` if it is closer than `distance`.
+    fn closest_within(&self, position: &Coordinate<f64>, distance: f64) -> Option<Coordinate<f64>> {
+        let (x, y) = self.cell(position);
+        let reach = (distance / self.cell_size).ceil() as i64;
+        let point = Point::from(*position);
+
+        (x - reach..=x + reach)
+            .flat_map(|x| (y - reach..=y + reach).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|sample| (point.euclidean_distance(&Point::from(*sample)), *sample))
+            .filter(|(sample_distance, _)| *sample_distance < distance)
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, sample)| sample)
+    }
+}
+
+/// Traces the major and minor hyperstreamlines of `field` within the bounds of `parameters`.
+///
+/// Streamlines are started at random seeds and at the points of already traced streamlines of the other
+/// direction, so that major and minor streamlines form a connected network. A seed is only used if no
+/// streamline of the same direction is closer than the separation of that direction. Streamlines stop at the
+/// bounds, at degenerate points of the field, when they close a loop or when they come closer than half the
+/// separation to another streamline of the same direction. In the latter case they are connected to it.
+///
+/// The same `seed` results in the same streamlines.
+pub fn trace_hyperstreamlines(
+    field: &TensorField,
+    parameters: &StreamlineParameters,
+    seed: u64,
+) -> Vec<Hyperstreamline> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let bounds = parameters.bounds;
+
+    let mut grids = [
+        SampleGrid::new(parameters.major_separation),
+        SampleGrid::new(parameters.minor_separation),
+    ];
+    let mut seeds: [VecDeque<Coordinate<f64>>; 2] = [VecDeque::new(), VecDeque::new()];
+    let mut streamlines = vec![];
+
+    // Enough random seeds to cover the bounds with streamlines of the smaller separation
+    let separation = parameters.major_separation.min(parameters.minor_separation);
+    let attempts = (bounds.width() * bounds.height() / separation.powi(2)).ceil() as usize;
+
+    let random_seed = |rng: &mut ChaCha8Rng| Coordinate {
+        x: rng.gen_range(bounds.min().x..=bounds.max().x),
+        y: rng.gen_range(bounds.min().y..=bounds.max().y),
+    };
+
+    for _ in 0..attempts {
+        for (index, direction) in [Direction::Major, Direction::Minor].into_iter().enumerate() {
+            let separation = match direction {
+                Direction::Major => parameters.major_separation,
+                Direction::Minor => parameters.minor_separation,
+            };
+
+            let start = match seeds[index].pop_front() {
+                Some(start) => start,
+                None => random_seed(&mut rng),
+            };
+            if grids[index].closest_within(&start, separation).is_some() {
+                continue;
+            }
+
+            let line_string = trace(
+                field,
+                direction,
+                start,
+                &grids[index],
+                parameters,
+                separation,
+            );
+            if line_string_length(&line_string) < parameters.minimum_length {
+                continue;
+            }
+
+            for point in line_string.0.iter() {
+                grids[index].add(*point);
+            }
+
+            // Spread the seeds of the other direction by its separation along the new streamline
+            let other = 1 - index;
+            let spacing = match direction {
+                Direction::Major => parameters.minor_separation,
+                Direction::Minor => parameters.major_separation,
+            };
+            let every = ((spacing / parameters.step).round() as usize).max(1);
+            seeds[other].extend(line_string.0.iter().step_by(every));
+
+            streamlines.push(Hyperstreamline {
+                line_string,
+                direction,
+            });
+        }
+    }
+
+    streamlines
+}
+
+fn line_string_length(line_string: &LineString<f64>) -> f64 {
+    line_string
+        .lines()
+        .map(|line| Point::from(line.start).euclidean_distance(&Point::from(line.end)))
+        .sum()
+}
+
+/// Traces a streamline through `start` in both orientations of `direction`.
+fn trace(
+    field: &TensorField,
+    direction: Direction,
+    start: Coordinate<f64>,
+    grid: &SampleGrid,
+    parameters: &StreamlineParameters,
+    separation: f64,
+) -> LineString<f64> {
+    let initial = match field.tensor(&start).direction(direction) {
+        Some(initial) => initial,
+        None => return LineString(vec![start]),
+    };
+
+    let (forward, closed) = integrate(
+        field, direction, start, initial, grid, parameters, separation,
+    );
+    if closed {
+        return LineString(forward);
+    }
+
+    let (backward, _) = integrate(
+        field,
+        direction,
+        start,
+        initial * -1.,
+        grid,
+        parameters,
+        separation,
+    );
+
+    LineString(
+        backward
+            .into_iter()
+            .rev()
+            .chain(forward.into_iter().skip(1))
+            .collect(),
+    )
+}
+
+/// Follows the field from `start` in the orientation of `initial` and returns the points and whether the
+/// streamline was closed.
+fn integrate(
+    field: &TensorField,
+    direction: Direction,
+    start: Coordinate<f64>,
+    initial: Coordinate<f64>,
+    grid: &SampleGrid,
+    parameters: &StreamlineParameters,
+    separation: f64,
+) -> (Vec<Coordinate<f64>>, bool) {
+    let step = parameters.step;
+    let bounds = parameters.bounds;
+    let max_steps = (2. * (bounds.width() + bounds.height()) / step).ceil() as usize * 2;
+
+    // Points of the streamline itself, added with a lag so that the points right behind the current
+    // position are not found
+    let lag = (separation / step).ceil() as usize + 1;
+    let mut own = SampleGrid::new(separation);
+
+    let mut points = vec![start];
+    let mut previous = initial;
+
+    for _ in 0..max_steps {
+        let position = *points.last().unwrap();
+        let velocity = match runge_kutta(field, direction, &position, &previous, step) {
+            Some(velocity) => velocity,
+            None => break,
+        };
+        let next = position + velocity * step;
+
+        if !bounds.contains(&next) {
+            break;
+        }
+
+        // Close loops, e.g. around the center of a radial field
+        if points.len() > lag
+            && Point::from(next).euclidean_distance(&Point::from(start)) < separation / 2.
+        {
+            points.push(start);
+            return (points, true);
+        }
+
+        // Stop spirals before they run into themselves
+        if own.closest_within(&next, separation / 2.).is_some() {
+            break;
+        }
+
+        // Join streamlines that come too close
+        if let Some(sample) = grid.closest_within(&next, separation / 2.) {
+            points.push(sample);
+            break;
+        }
+
+        points.push(next);
+        previous = velocity;
+
+        if points.len() > lag {
+            own.add(points[points.len() - 1 - lag]);
+        }
+    }
+
+    (points, false)
+}
+
+/// Returns the direction of `direction` at `position` oriented like `previous`.
+fn oriented_direction(
+    field: &TensorField,
+    direction: Direction,
+    position: &Coordinate<f64>,
+    previous: &Coordinate<f64>,
+) -> Option<Coordinate<f64>> {
+    let vector = field.tensor(position).direction(direction)?;
+
+    match vector.x * previous.x + vector.y * previous.y < 0. {
+        true => Some(vector * -1.),
+        false => Some(vector),
+    }
+}
+
+/// Integrates one step of length `step` with the fourth order Runge-Kutta method and returns the normalized
+/// direction of the step.
+fn runge_kutta(
+    field: &TensorField,
+    direction: Direction,
+    position: &Coordinate<f64>,
+    previous: &Coordinate<f64>,
+    step: f64,
+) -> Option<Coordinate<f64>> {
+    let k1 = oriented_direction(field, direction, position, previous)?;
+    let k2 = oriented_direction(field, direction, &(*position + k1 * (step / 2.)), &k1)?;
+    let k3 = oriented_direction(field, direction, &(*position + k2 * (step / 2.)), &k1)?;
+    let k4 = oriented_direction(field, direction, &(*position + k3 * step), &k1)?;
+
+    let sum = k1 + k2 * 2. + k3 * 2. + k4;
+    let length = sum.x.hypot(sum.y);
+    if length < DEGENERATE_NORM {
+        return None;
+    }
+
+    Some(sum / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use geo::{prelude::Contains, Coordinate, LineString, Rect};
+
+    use super::{
+        trace_hyperstreamlines, BasisField, BasisFieldKind, Direction, StreamlineParameters,
+        TensorField,
+    };
+
+    fn assert_parallel(a: Coordinate<f64>, b: Coordinate<f64>) {
+        assert!((a.x * b.y - a.y * b.x).abs() < 1e-9, "{:?} {:?}", a, b);
+    }
+
+    #[test]
+    fn unit_grid_field_follows_angle() {
+        let field = BasisField::new(
+            BasisFieldKind::Grid { angle: 0.3 },
+            Coordinate { x: 0., y: 0. },
+            100.,
+        );
+        let tensor = field.tensor(&Coordinate { x: 40., y: -20. });
+
+        assert!((tensor.major_angle() - 0.3).abs() < 1e-9);
+        assert_parallel(
+            tensor.direction(Direction::Minor).unwrap(),
+            Coordinate {
+                x: -(0.3_f64.sin()),
+                y: 0.3_f64.cos(),
+            },
+        );
+    }
+
+    #[test]
+    fn unit_radial_field_circles_center() {
+        let field = BasisField::new(BasisFieldKind::Radial, Coordinate { x: 0., y: 0. }, 100.);
+        let tensor = field.tensor(&Coordinate { x: 50., y: 0. });
+
+        assert_parallel(
+            tensor.direction(Direction::Major).unwrap(),
+            Coordinate { x: 0., y: 1. },
+        );
+        assert_parallel(
+            tensor.direction(Direction::Minor).unwrap(),
+            Coordinate { x: 1., y: 0. },
+        );
+    }
+
+    #[test]
+    fn unit_boundary_field_follows_closest_segment() {
+        let line_string = LineString::from(vec![(0., 0.), (100., 0.), (100., 100.)]);
+        let field = BasisField::new(
+            BasisFieldKind::Boundary { line_string },
+            Coordinate { x: 0., y: 0. },
+            100.,
+        );
+
+        assert_parallel(
+            field
+                .tensor(&Coordinate { x: 30., y: 10. })
+                .direction(Direction::Major)
+                .unwrap(),
+            Coordinate { x: 1., y: 0. },
+        );
+        assert_parallel(
+            field
+                .tensor(&Coordinate { x: 120., y: 70. })
+                .direction(Direction::Major)
+                .unwrap(),
+            Coordinate { x: 0., y: 1. },
+        );
+        assert!((field.influence(&Coordinate { x: 50., y: 0. }) - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unit_closer_field_dominates() {
+        let mut field = TensorField::new();
+        field.add_field(BasisField::new(
+            BasisFieldKind::Grid { angle: 0. },
+            Coordinate { x: 0., y: 0. },
+            100.,
+        ));
+        field.add_field(BasisField::new(
+            BasisFieldKind::Grid { angle: FRAC_PI_2 },
+            Coordinate { x: 1000., y: 0. },
+            100.,
+        ));
+
+        assert_parallel(
+            field
+                .tensor(&Coordinate { x: 100., y: 0. })
+                .direction(Direction::Major)
+                .unwrap(),
+            Coordinate { x: 1., y: 0. },
+        );
+        assert_parallel(
+            field
+                .tensor(&Coordinate { x: 900., y: 0. })
+                .direction(Direction::Major)
+                .unwrap(),
+            Coordinate { x: 0., y: 1. },
+        );
+    }
+
+    #[test]
+    fn unit_grid_field_traces_grid() {
+        let mut field = TensorField::new();
+        let mut grid = BasisField::new(
+            BasisFieldKind::Grid { angle: 0. },
+            Coordinate { x: 250., y: 250. },
+            500.,
+        );
+        grid.decay = 0.;
+        field.add_field(grid);
+
+        let parameters = StreamlineParameters {
+            major_separation: 100.,
+            minor_separation: 100.,
+            step: 10.,
+            minimum_length: 50.,
+            bounds: Rect::new(Coordinate { x: 0., y: 0. }, Coordinate { x: 500., y: 500. }),
+        };
+        let streamlines = trace_hyperstreamlines(&field, &parameters, 1);
+
+        assert_eq!(streamlines, trace_hyperstreamlines(&field, &parameters, 1));
+        for direction in [Direction::Major, Direction::Minor] {
+            assert!(streamlines
+                .iter()
+                .any(|streamline| streamline.direction == direction));
+        }
+
+        for streamline in &streamlines {
+            let points = &streamline.line_string.0;
+            for point in points {
+                assert!(parameters.bounds.contains(point));
+            }
+
+            // Major streamlines are horizontal, minor ones vertical
+            let (first, last) = (points[0], *points.last().unwrap());
+            match streamline.direction {
+                Direction::Major => assert!((first.y - last.y).abs() < 1e-6),
+                Direction::Minor => assert!((first.x - last.x).abs() < 1e-6),
+            }
+        }
+    }
+
+    #[test]
+    fn unit_radial_field_traces_rings() {
+        let mut field = TensorField::new();
+        field.add_field(BasisField::new(
+            BasisFieldKind::Radial,
+            Coordinate { x: 0., y: 0. },
+            300.,
+        ));
+
+        let parameters = StreamlineParameters {
+            major_separation: 80.,
+            minor_separation: 80.,
+            step: 5.,
+            minimum_length: 50.,
+            bounds: field.bounds().unwrap(),
+        };
+        let streamlines = trace_hyperstreamlines(&field, &parameters, 2);
+
+        assert!(streamlines.iter().any(|streamline| {
+            streamline.direction == Direction::Major && streamline.line_string.is_closed()
+        }));
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    f64::consts::{PI, SQRT_2},
+};
+
+use geo::{
+    prelude::{Area, BoundingRect, Contains, EuclideanDistance},
+    Coordinate, Line, LineString, Point, Polygon,
+};
+use geo_booleanop::boolean::BooleanOp;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Number of candidates that are tried around each sample before it is no longer used to spawn new samples.
+const POISSON_CANDIDATES: usize = 30;
+
+/// Cell vertices closer than this fraction of the spacing of the sites are merged into one.
+const WELD_FACTOR: f64 = 0.1;
+
+/// A cell of a Voronoi diagram clipped to a boundary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoronoiCell {
+    /// The site the cell was created for. Each point of the cell is closer to its site than to any other.
+    pub site: Coordinate<f64>,
+    pub polygon: Polygon<f64>,
+
+    /// `true` if the cell was cut by the boundary.
+    pub at_boundary: bool,
+}
+
+/// The cells of a Voronoi diagram together with the edges between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoronoiLayout {
+    pub cells: Vec<VoronoiCell>,
+
+    /// The edges of the cells. Edges shared by two cells are only contained once and edges that meet at a
+    /// vertex that is not shared by any other edge are joined into a single line string.
+    pub edges: Vec<LineString<f64>>,
+}
+
+/// Scatters points within `boundary` that are at least `radius` apart from each other by Bridson's Poisson
+/// disk sampling. Unlike uniformly distributed points they result in Voronoi cells of similar size.
+pub fn poisson_disk_sample<R: Rng>(
+    boundary: &Polygon<f64>,
+    radius: f64,
+    rng: &mut R,
+) -> Vec<Coordinate<f64>> {
+    let rect = match boundary.bounding_rect() {
+        Some(rect) => rect,
+        None => return vec![],
+    };
+
+    let cell_size = radius / SQRT_2;
+    let columns = (rect.width() / cell_size).ceil() as usize + 1;
+    let rows = (rect.height() / cell_size).ceil() as usize + 1;
+    let cell = |point: &Coordinate<f64>| {
+        (
+            ((point.x - rect.min().x) / cell_size) as usize,
+            ((point.y - rect.min().y) / cell_size) as usize,
+        )
+    };
+
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let mut samples: Vec<Coordinate<f64>> = vec![];
+    let mut active: Vec<usize> = vec![];
+
+    let mut accept = |point: Coordinate<f64>,
+                      samples: &mut Vec<Coordinate<f64>>,
+                      active: &mut Vec<usize>|
+     -> bool {
+        if !rect.contains(&point) || !boundary.contains(&point) {
+            return false;
+        }
+
+        // Samples in the cells two steps away can still be closer than the radius
+        let (x, y) = cell(&point);
+        for column in x.saturating_sub(2)..(x + 3).min(columns) {
+            for row in y.saturating_sub(2)..(y + 3).min(rows) {
+                if let Some(other) = grid[row * columns + column] {
+                    if Point::from(samples[other]).euclidean_distance(&Point::from(point)) < radius
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        grid[y * columns + x] = Some(samples.len());
+        active.push(samples.len());
+        samples.push(point);
+
+        true
+    };
+
+    // The boundary may cover only a small part of its bounding box
+    for _ in 0..POISSON_CANDIDATES * 10 {
+        let point = Coordinate {
+            x: rng.gen_range(rect.min().x..=rect.max().x),
+            y: rng.gen_range(rect.min().y..=rect.max().y),
+        };
+
+        if accept(point, &mut samples, &mut active) {
+            break;
+        }
+    }
+
+    while !active.is_empty() {
+        let index = rng.gen_range(0..active.len());
+        let origin = samples[active[index]];
+
+        let mut found = false;
+        for _ in 0..POISSON_CANDIDATES {
+            let angle = rng.gen_range(0. ..2. * PI);
+            let distance = rng.gen_range(radius..2. * radius);
+            let point = origin
+                + Coordinate {
+                    x: angle.cos(),
+                    y: angle.sin(),
+                } * distance;
+
+            if accept(point, &mut samples, &mut active) {
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            active.swap_remove(index);
+        }
+    }
+
+    samples
+}
+
+/// Clips the convex `polygon` to the half plane on the side of `point` that `normal` points away from.
+fn clip_half_plane(
+    polygon: &[Coordinate<f64>],
+    point: Coordinate<f64>,
+    normal: Coordinate<f64>,
+) -> Vec<Coordinate<f64>> {
+    let side = |p: &Coordinate<f64>| (p.x - point.x) * normal.x + (p.y - point.y) * normal.y;
+
+    let mut clipped = vec![];
+    for (i, current) in polygon.iter().enumerate() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        let (current_side, next_side) = (side(current), side(next));
+
+        if current_side <= 0. {
+            clipped.push(*current);
+        }
+
+        if (current_side < 0.) != (next_side < 0.) && current_side != next_side {
+            let t = current_side / (current_side - next_side);
+            clipped.push(*current + (*next - *current) * t);
+        }
+    }
+
+    clipped
+}
+
+/// Calculates the Voronoi cell of each site clipped to `boundary`. Sites whose cell lies outside of the
+/// boundary are skipped.
+pub fn voronoi_cells(sites: &[Coordinate<f64>], boundary: &Polygon<f64>) -> Vec<VoronoiCell> {
+    let rect = match boundary.bounding_rect() {
+        Some(rect) => rect,
+        None => return vec![],
+    };
+    let frame = vec![
+        rect.min(),
+        Coordinate {
+            x: rect.max().x,
+            y: rect.min().y,
+        },
+        rect.max(),
+        Coordinate {
+            x: rect.min().x,
+            y: rect.max().y,
+        },
+    ];
+
+    let mut cells = vec![];
+    for (i, site) in sites.iter().enumerate() {
+        // The cell is the intersection of the half planes that are closer to the site than to each other site
+        let mut cell = frame.clone();
+        for (_, other) in sites.iter().enumerate().filter(|(j, _)| *j != i) {
+            let middle = (*site + *other) / 2.;
+            cell = clip_half_plane(&cell, middle, *other - *site);
+
+            if cell.len() < 3 {
+                break;
+            }
+        }
+
+        if cell.len() < 3 {
+            continue;
+        }
+
+        let at_boundary = !cell.iter().all(|vertex| boundary.contains(vertex));
+        let convex = Polygon::new(LineString(cell), vec![]);
+
+        let polygon = match at_boundary {
+            false => Some(convex),
+            // A concave boundary can split the cell, the part with the site is kept
+            true => convex.intersection(boundary).into_iter().max_by(|a, b| {
+                let contains = |polygon: &Polygon<f64>| polygon.contains(site);
+
+                contains(a).cmp(&contains(b)).then_with(|| {
+                    a.unsigned_area()
+                        .partial_cmp(&b.unsigned_area())
+                        .unwrap_or(Ordering::Equal)
+                })
+            }),
+        };
+
+        if let Some(polygon) = polygon {
+            cells.push(VoronoiCell {
+                site: *site,
+                polygon,
+                at_boundary,
+            });
+        }
+    }
+
+    cells
+}
+
+/// Returns the edges of `cells` as line strings. Vertices closer than `weld_distance` are merged, shared edges
+/// are only returned once and chains of edges whose inner vertices are not shared with any other edge are joined.
+pub fn cell_edges(cells: &[VoronoiCell], weld_distance: f64) -> Vec<LineString<f64>> {
+    let mut vertices: Vec<Coordinate<f64>> = vec![];
+    let mut weld = |position: Coordinate<f64>| -> usize {
+        let existing = vertices.iter().position(|vertex| {
+            Point::from(*vertex).euclidean_distance(&Point::from(position)) < weld_distance
+        });
+
+        existing.unwrap_or_else(|| {
+            vertices.push(position);
+            vertices.len() - 1
+        })
+    };
+
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for cell in cells {
+        for line in cell.polygon.exterior().lines() {
+            let (start, end) = (weld(line.start), weld(line.end));
+            if start != end {
+                edges.insert((start.min(end), start.max(end)));
+            }
+        }
+    }
+
+    // An edge with a vertex of the neighbouring cell on it is covered by the two edges of that cell
+    let edges: Vec<(usize, usize)> = edges
+        .iter()
+        .filter(|(start, end)| {
+            let line = Line::new(vertices[*start], vertices[*end]);
+
+            !(0..vertices.len())
+                .filter(|vertex| vertex != start && vertex != end)
+                .any(|vertex| {
+                    Point::from(vertices[vertex]).euclidean_distance(&line) < weld_distance
+                        && edges.contains(&(vertex.min(*start), vertex.max(*start)))
+                        && edges.contains(&(vertex.min(*end), vertex.max(*end)))
+                })
+        })
+        .copied()
+        .collect();
+
+    let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (start, end) in &edges {
+        neighbours.entry(*start).or_default().push(*end);
+        neighbours.entry(*end).or_default().push(*start);
+    }
+
+    // Sorted so that the result does not depend on the iteration order of the hash map
+    let mut starts: Vec<usize> = neighbours.keys().copied().collect();
+    starts.sort_by_key(|vertex| (neighbours[vertex].len() == 2, *vertex));
+
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    let mut chains: Vec<Vec<usize>> = vec![];
+    for start in starts {
+        let mut next_vertices = neighbours[&start].clone();
+        next_vertices.sort();
+
+        for next in next_vertices {
+            if !visited.insert((start.min(next), start.max(next))) {
+                continue;
+            }
+
+            let mut chain = vec![start, next];
+            let (mut previous, mut current) = (start, next);
+            while neighbours[&current].len() == 2 {
+                let following = match neighbours[&current].iter().find(|v| **v != previous) {
+                    Some(following) => *following,
+                    None => break,
+                };
+                if !visited.insert((current.min(following), current.max(following))) {
+                    break;
+                }
+
+                chain.push(following);
+                previous = current;
+                current = following;
+            }
+
+            chains.push(chain);
+        }
+    }
+
+    chains
+        .into_iter()
+        .flat_map(|chain| {
+            // A closed chain is split as a line string must connect two different vertices
+            match chain.first() == chain.last() && chain.len() > 3 {
+                true => {
+                    let middle = chain.len() / 2;
+                    vec![chain[..=middle].to_vec(), chain[middle..].to_vec()]
+                }
+                false => vec![chain],
+            }
+        })
+        .map(|chain| chain.into_iter().map(|vertex| vertices[vertex]).collect())
+        .collect()
+}
+
+/// Scatters sites within `boundary` that are about `spacing` apart and returns their Voronoi cells and edges.
+/// The same `seed` results in the same layout.
+pub fn voronoi_layout(boundary: &Polygon<f64>, spacing: f64, seed: u64) -> VoronoiLayout {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let sites = poisson_disk_sample(boundary, spacing, &mut rng);
+    let cells = voronoi_cells(&sites, boundary);
+    let edges = cell_edges(&cells, spacing * WELD_FACTOR);
+
+    VoronoiLayout { cells, edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{
+        prelude::{Area, Contains, EuclideanDistance},
+        Coordinate, LineString, Point, Polygon,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::{cell_edges, poisson_disk_sample, voronoi_cells, voronoi_layout};
+
+    fn square(size: f64) -> Polygon<f64> {
+        Polygon::new(
+            LineString::from(vec![(0., 0.), (size, 0.), (size, size), (0., size)]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn unit_poisson_disk_samples_keep_distance() {
+        let boundary = square(1000.);
+        let samples = poisson_disk_sample(&boundary, 100., &mut ChaCha8Rng::seed_from_u64(1));
+
+        // A disk of radius 50 around each sample does not overlap with any other
+        assert!(samples.len() > 40);
+        for (i, a) in samples.iter().enumerate() {
+            assert!(boundary.contains(a));
+
+            for b in samples.iter().skip(i + 1) {
+                assert!(Point::from(*a).euclidean_distance(&Point::from(*b)) >= 100.);
+            }
+        }
+    }
+
+    #[test]
+    fn unit_voronoi_cells_cover_boundary() {
+        let boundary = square(1000.);
+        let sites = poisson_disk_sample(&boundary, 150., &mut ChaCha8Rng::seed_from_u64(2));
+        let cells = voronoi_cells(&sites, &boundary);
+
+        assert_eq!(cells.len(), sites.len());
+        assert!(cells.iter().all(|cell| cell.polygon.contains(&cell.site)));
+        assert!(cells.iter().any(|cell| !cell.at_boundary));
+
+        let area: f64 = cells.iter().map(|cell| cell.polygon.unsigned_area()).sum();
+        assert!((area - boundary.unsigned_area()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn unit_shared_edges_are_returned_once() {
+        let boundary = square(200.);
+        let cells = voronoi_cells(
+            &[
+                Coordinate { x: 50., y: 100. },
+                Coordinate { x: 150., y: 100. },
+            ],
+            &boundary,
+        );
+        let mut edges = cell_edges(&cells, 1.);
+        edges.sort_by_key(|edge| edge.0.len());
+
+        // The shared edge in the middle and the two halves of the outline on either side of it
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges[0].0.len(), 2);
+        assert!(edges[0].0.iter().all(|point| point.x == 100.));
+    }
+
+    #[test]
+    fn unit_same_seed_results_in_same_layout() {
+        let boundary = square(800.);
+
+        assert_eq!(
+            voronoi_layout(&boundary, 120., 5),
+            voronoi_layout(&boundary, 120., 5)
+        );
+    }
+}
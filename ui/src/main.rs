@@ -13,13 +13,29 @@ use plugin_undo_redo::{Redo, Undo};
 use plugins::create_district::CreateDistrict;
 use plugins::delete_district::DeleteDistrict;
 use plugins::delete_street::DeleteStreet;
+use plugins::draw_water::DrawWater;
+use plugins::edit_street_curve::EditStreetCurve;
+use plugins::edit_street_vertices::EditStreetVertices;
+use plugins::fit_view::FitView;
+use plugins::generate_roads::GenerateRoads;
+use plugins::generate_settlement::GenerateSettlement;
+use plugins::generate_terrain::GenerateTerrain;
+use plugins::generate_wards::GenerateWards;
+use plugins::generate_walls::GenerateWalls;
+use plugins::grow_street_network::GrowStreetNetwork;
+use plugins::inspect_district::InspectDistrict;
+use plugins::tensor_field_roads::TensorFieldRoads;
 use plugins::load::Load;
 use plugins::map_render::MapRender;
+use plugins::name_elements::NameElements;
 use plugins::new::New;
+use plugins::place_landmarks::PlaceLandmarks;
 use plugins::reference_image::ReferenceImage;
+use plugins::reroll_district::RerollDistrict;
 use plugins::save::Save;
 use plugins::settings::Settings;
 use plugins::sync::Sync;
+use plugins::validation::Validation;
 
 use plugins::create_freeform_street::CreateFreeformStreet;
 use rust_editor::{
@@ -55,14 +71,30 @@ fn main() {
     editor.add_plugin(Load::default());
     editor.add_plugin(Settings::default());
     editor.add_plugin(Camera::default());
+    editor.add_plugin(FitView::default());
     editor.add_plugin(Undo::<Map>::default());
     editor.add_plugin(Redo::<Map>::default());
     editor.add_plugin(CreateFreeformStreet::default());
     editor.add_plugin(DeleteStreet::default());
+    editor.add_plugin(EditStreetCurve::default());
+    editor.add_plugin(EditStreetVertices::default());
+    editor.add_plugin(GrowStreetNetwork::default());
+    editor.add_plugin(TensorFieldRoads::default());
     editor.add_plugin(CreateDistrict::default());
     editor.add_plugin(DeleteDistrict::default());
+    editor.add_plugin(RerollDistrict::default());
+    editor.add_plugin(InspectDistrict::default());
+    editor.add_plugin(GenerateWards::default());
+    editor.add_plugin(GenerateWalls::default());
+    editor.add_plugin(GenerateSettlement::default());
+    editor.add_plugin(GenerateRoads::default());
+    editor.add_plugin(GenerateTerrain::default());
+    editor.add_plugin(DrawWater::default());
+    editor.add_plugin(PlaceLandmarks::default());
+    editor.add_plugin(NameElements::default());
     editor.add_plugin(MapRender::default());
     editor.add_plugin(ReferenceImage::default());
     editor.add_plugin(RibbonPlugin::default());
     editor.add_plugin(Sync::default());
+    editor.add_plugin(Validation::default());
 }
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{district::District, map::Map};
+
+/// Removes a district from the map. The district is kept so that undo restores it including its houses.
+pub struct DeleteDistrict {
+    district_id: Uuid,
+    district: Option<District>,
+}
+
+impl DeleteDistrict {
+    pub fn new(district_id: Uuid) -> Self {
+        DeleteDistrict {
+            district_id,
+            district: None,
+        }
+    }
+}
+
+impl Undo<Map> for DeleteDistrict {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(district) = self.district.take() {
+            map.add_district(district);
+        }
+    }
+}
+
+impl Redo<Map> for DeleteDistrict {
+    fn redo(&mut self, map: &mut Map) {
+        self.district = map.district(&self.district_id).cloned();
+        map.remove_district(&self.district_id);
+    }
+}
+
+impl Action<Map> for DeleteDistrict {}
+
+impl fmt::Display for DeleteDistrict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[delete_district] district={}", self.district_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::DeleteDistrict;
+
+    fn create_map() -> Map {
+        let mut map = Map::new(1000, 1000);
+
+        for line in [
+            vec![(0., 0.), (400., 0.)],
+            vec![(400., 0.), (400., 400.)],
+            vec![(400., 400.), (0., 400.)],
+            vec![(0., 400.), (0., 0.)],
+        ] {
+            map.add_street(&Street::new(LineString::from(line)));
+        }
+
+        map
+    }
+
+    #[test]
+    fn district_delete_redo_works() {
+        let mut map = create_map();
+        let id = *map.districts().keys().next().unwrap();
+
+        let mut action = DeleteDistrict::new(id);
+        action.execute(&mut map);
+
+        assert!(map.district(&id).is_none());
+    }
+
+    #[test]
+    fn district_delete_undo_works() {
+        let mut map = create_map();
+        let id = *map.districts().keys().next().unwrap();
+
+        let mut action = DeleteDistrict::new(id);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.district(&id).is_some());
+    }
+}
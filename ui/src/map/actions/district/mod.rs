@@ -0,0 +1,5 @@
+pub mod delete;
+pub mod rename;
+pub mod reroll;
+pub mod set_kind;
+pub mod set_ward;
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Sets the name of a district. A name of `None` removes the name.
+pub struct RenameDistrict {
+    district_id: Uuid,
+    name: Option<String>,
+    old_name: Option<String>,
+}
+
+impl RenameDistrict {
+    pub fn new(district_id: Uuid, name: Option<String>) -> Self {
+        RenameDistrict {
+            district_id,
+            name,
+            old_name: None,
+        }
+    }
+}
+
+impl Undo<Map> for RenameDistrict {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            district.set_name(self.old_name.take());
+        }
+    }
+}
+
+impl Redo<Map> for RenameDistrict {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            self.old_name = district.name().cloned();
+            district.set_name(self.name.clone());
+        }
+    }
+}
+
+impl Action<Map> for RenameDistrict {}
+
+impl fmt::Display for RenameDistrict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[rename_district] district={}, name={:?}",
+            self.district_id, self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::RenameDistrict;
+
+    #[test]
+    fn district_rename_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        for line in [
+            vec![(0., 0.), (400., 0.)],
+            vec![(400., 0.), (400., 400.)],
+            vec![(400., 400.), (0., 400.)],
+            vec![(0., 400.), (0., 0.)],
+        ] {
+            map.add_street(&Street::new(LineString::from(line)));
+        }
+        let id = *map.districts().keys().next().unwrap();
+
+        let mut action = RenameDistrict::new(id, Some("Old Town".to_string()));
+        action.execute(&mut map);
+        assert_eq!(
+            map.district(&id).unwrap().name(),
+            Some(&"Old Town".to_string())
+        );
+
+        action.undo(&mut map);
+        assert_eq!(map.district(&id).unwrap().name(), None);
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{district::Seed, map::Map};
+
+/// Regenerates the houses of a single district with a new seed that is derived from its current one.
+pub struct RerollDistrict {
+    district_id: Uuid,
+    old_seed: Option<Seed>,
+}
+
+impl RerollDistrict {
+    pub fn new(district_id: Uuid) -> Self {
+        RerollDistrict {
+            district_id,
+            old_seed: None,
+        }
+    }
+}
+
+impl Undo<Map> for RerollDistrict {
+    fn undo(&mut self, map: &mut Map) {
+        if let (Some(district), Some(seed)) = (map.district_mut(&self.district_id), self.old_seed)
+        {
+            district.set_seed(seed);
+        }
+    }
+}
+
+impl Redo<Map> for RerollDistrict {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            self.old_seed = Some(district.seed());
+            district.reroll();
+        }
+    }
+}
+
+impl Action<Map> for RerollDistrict {}
+
+impl fmt::Display for RerollDistrict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[reroll_district] district={}", self.district_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Redo, Undo};
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::RerollDistrict;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        for line in [
+            vec![(0., 0.), (400., 0.)],
+            vec![(400., 0.), (400., 400.)],
+            vec![(400., 400.), (0., 400.)],
+            vec![(0., 400.), (0., 0.)],
+        ] {
+            map.add_street(&Street::new(LineString::from(line)));
+        }
+
+        let id = *map.districts().keys().next().unwrap();
+        (map, id)
+    }
+
+    #[test]
+    fn district_reroll_redo_changes_seed() {
+        let (mut map, id) = create_map();
+        let seed = map.district(&id).unwrap().seed();
+
+        let mut action = RerollDistrict::new(id);
+        action.execute(&mut map);
+
+        assert_ne!(map.district(&id).unwrap().seed(), seed);
+    }
+
+    #[test]
+    fn district_reroll_is_reproducible() {
+        let (mut map, id) = create_map();
+
+        let mut action = RerollDistrict::new(id);
+        action.execute(&mut map);
+        let seed = map.district(&id).unwrap().seed();
+
+        action.undo(&mut map);
+        action.redo(&mut map);
+
+        assert_eq!(map.district(&id).unwrap().seed(), seed);
+    }
+
+    #[test]
+    fn district_reroll_undo_restores_seed() {
+        let (mut map, id) = create_map();
+        let seed = map.district(&id).unwrap().seed();
+
+        let mut action = RerollDistrict::new(id);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.district(&id).unwrap().seed(), seed);
+    }
+}
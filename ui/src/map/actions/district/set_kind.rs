@@ -0,0 +1,90 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{district_kind::DistrictKind, map::Map};
+
+/// Sets the kind of a district, which regenerates its content.
+pub struct SetDistrictKind {
+    district_id: Uuid,
+    kind: DistrictKind,
+    old_kind: DistrictKind,
+}
+
+impl SetDistrictKind {
+    pub fn new(district_id: Uuid, kind: DistrictKind) -> Self {
+        SetDistrictKind {
+            district_id,
+            kind,
+            old_kind: DistrictKind::default(),
+        }
+    }
+}
+
+impl Undo<Map> for SetDistrictKind {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            district.set_kind(self.old_kind);
+        }
+    }
+}
+
+impl Redo<Map> for SetDistrictKind {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            self.old_kind = district.kind();
+            district.set_kind(self.kind);
+        }
+    }
+}
+
+impl Action<Map> for SetDistrictKind {}
+
+impl fmt::Display for SetDistrictKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[set_district_kind] district={}, kind={}",
+            self.district_id, self.kind
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{district_kind::DistrictKind, map::Map, street::Street};
+
+    use super::SetDistrictKind;
+
+    #[test]
+    fn district_set_kind_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        for line in [
+            vec![(0., 0.), (400., 0.)],
+            vec![(400., 0.), (400., 400.)],
+            vec![(400., 400.), (0., 400.)],
+            vec![(0., 400.), (0., 0.)],
+        ] {
+            map.add_street(&Street::new(LineString::from(line)));
+        }
+        let id = *map.districts().keys().next().unwrap();
+        let houses = map.district(&id).unwrap().houses.len();
+
+        let mut action = SetDistrictKind::new(id, DistrictKind::Park);
+        action.execute(&mut map);
+        let district = map.district(&id).unwrap();
+        assert_eq!(district.kind(), DistrictKind::Park);
+        assert!(district.houses.is_empty());
+        assert!(!district.features.is_empty());
+
+        action.undo(&mut map);
+        let district = map.district(&id).unwrap();
+        assert_eq!(district.kind(), DistrictKind::Residential);
+        assert_eq!(district.houses.len(), houses);
+        assert!(district.features.is_empty());
+    }
+}
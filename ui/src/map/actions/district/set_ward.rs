@@ -0,0 +1,83 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{map::Map, ward::Ward};
+
+/// Sets the ward of a district. A ward of `None` removes the ward.
+pub struct SetWard {
+    district_id: Uuid,
+    ward: Option<Ward>,
+    old_ward: Option<Ward>,
+}
+
+impl SetWard {
+    pub fn new(district_id: Uuid, ward: Option<Ward>) -> Self {
+        SetWard {
+            district_id,
+            ward,
+            old_ward: None,
+        }
+    }
+}
+
+impl Undo<Map> for SetWard {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            district.set_ward(self.old_ward.take());
+        }
+    }
+}
+
+impl Redo<Map> for SetWard {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(district) = map.district_mut(&self.district_id) {
+            self.old_ward = district.ward();
+            district.set_ward(self.ward);
+        }
+    }
+}
+
+impl Action<Map> for SetWard {}
+
+impl fmt::Display for SetWard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[set_ward] district={}, ward={:?}",
+            self.district_id, self.ward
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street::Street, ward::Ward};
+
+    use super::SetWard;
+
+    #[test]
+    fn district_set_ward_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        for line in [
+            vec![(0., 0.), (400., 0.)],
+            vec![(400., 0.), (400., 400.)],
+            vec![(400., 400.), (0., 400.)],
+            vec![(0., 400.), (0., 0.)],
+        ] {
+            map.add_street(&Street::new(LineString::from(line)));
+        }
+        let id = *map.districts().keys().next().unwrap();
+
+        let mut action = SetWard::new(id, Some(Ward::Temple));
+        action.execute(&mut map);
+        assert_eq!(map.district(&id).unwrap().ward(), Some(Ward::Temple));
+
+        action.undo(&mut map);
+        assert_eq!(map.district(&id).unwrap().ward(), None);
+    }
+}
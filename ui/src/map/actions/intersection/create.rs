@@ -22,13 +22,13 @@ impl CreateIntersection {
 
 impl Undo<Map> for CreateIntersection {
     fn undo(&mut self, map: &mut Map) {
-        map.intersections.remove(&self.id);
+        map.remove_intersection(&self.id);
     }
 }
 
 impl Redo<Map> for CreateIntersection {
     fn redo(&mut self, map: &mut Map) {
-        map.intersections.insert(self.id, Intersection::new_with_id(self.position, self.id));
+        map.add_intersection(Intersection::new_with_id(self.position, self.id));
     }
 }
 
@@ -3,6 +3,7 @@ use std::fmt;
 use geo::Coordinate;
 use rust_editor::{
     actions::{Action, Redo, Undo},
+    gizmo::{GetPosition, Id},
 };
 use uuid::Uuid;
 
@@ -13,7 +14,6 @@ pub(crate) struct DeleteIntersection {
     position: Coordinate<f64>,
 }
 
-/*
 impl DeleteIntersection {
     pub fn new(intersection: &Intersection) -> Self {
         DeleteIntersection {
@@ -22,7 +22,6 @@ impl DeleteIntersection {
         }
     }
 }
-*/
 
 impl Undo<Map> for DeleteIntersection {
     fn undo(&mut self, map: &mut Map) {
@@ -32,7 +31,7 @@ impl Undo<Map> for DeleteIntersection {
 
 impl Redo<Map> for DeleteIntersection {
     fn redo(&mut self, map: &mut Map) {
-        map.intersections_mut().remove(&self.id);
+        map.remove_intersection(&self.id);
         /*
         if let Some(removed) =  {
             self.update_bounding_box();
@@ -53,7 +52,6 @@ impl fmt::Display for DeleteIntersection {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
     use geo::Coordinate;
@@ -112,4 +110,3 @@ mod tests {
         assert_eq!(map.intersections.len(), 1);
     }
 }
-*/
\ No newline at end of file
@@ -11,7 +11,6 @@ pub(crate) struct RemoveConnectedStreet {
     intersection_id: Uuid,
 }
 
-/*
 impl RemoveConnectedStreet {
     pub fn new(intersection_id: Uuid, street_id: Uuid) -> Self {
         RemoveConnectedStreet {
@@ -21,7 +20,6 @@ impl RemoveConnectedStreet {
         }
     }
 }
-*/
 
 impl Undo<Map> for RemoveConnectedStreet {
     fn undo(&mut self, map: &mut Map) {
@@ -59,17 +57,17 @@ impl fmt::Display for RemoveConnectedStreet {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use geo::Coordinate;
+    use geo::{Coordinate, LineString};
     use rust_editor::{actions::{Redo, Undo, Action}, gizmo::Id};
     use uuid::Uuid;
 
     use crate::map::{
-        actions::{intersection::remove_connected_street::RemoveConnectedStreet, street::create::CreateStreet},
-        intersection::Intersection, map::Map,
+        actions::intersection::remove_connected_street::RemoveConnectedStreet,
+        map::Map,
     };
+    use crate::plugins::create_freeform_street::CreateFreeFormStreetAction;
 
     fn create_map() -> Map {
         let mut map = Map::new(100, 100);
@@ -85,29 +83,29 @@ mod tests {
         map
     }
 
-    fn find_intersection<'a>(map: &'a mut Map) -> &'a Intersection {
+    fn find_intersection(map: &Map) -> Uuid {
         let (_, intersection) = map
             .intersections
             .iter()
             .find(|(_, intersection)| intersection.get_connected_streets().len() == 2)
             .unwrap();
 
-        intersection
+        intersection.id()
     }
 
 
     fn add_street(start_pos: Coordinate<f64>, end_pos: Coordinate<f64>, map: &mut Map) {
-        let mut action = CreateStreet::new(start_pos, end_pos, Uuid::new_v4());
+        let mut action = CreateFreeFormStreetAction::new(LineString::new(vec![start_pos, end_pos]));
         action.execute(map);
     }
 
     #[test]
     fn remove_connected_street_redo_works() {
         let mut map = create_map();
-        let intersection = find_intersection(&mut map);
+        let intersection = find_intersection(&map);
 
         let mut action = RemoveConnectedStreet::new(
-            intersection.id(),
+            intersection,
             map.streets.values().next().unwrap().id(),
         );
         action.redo(&mut map);
@@ -121,10 +119,10 @@ mod tests {
     #[test]
     fn remove_connected_street_undo_works() {
         let mut map = create_map();
-        let intersection = find_intersection(&mut map);
+        let intersection = find_intersection(&map);
 
         let mut action = RemoveConnectedStreet::new(
-            intersection.id(),
+            intersection,
             map.streets.values().next().unwrap().id(),
         );
         action.redo(&mut map);
@@ -136,7 +134,6 @@ mod tests {
 
         action.undo(&mut map);
 
-        find_intersection(&mut map);
+        find_intersection(&map);
     }
 }
-*/
\ No newline at end of file
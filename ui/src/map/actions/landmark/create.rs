@@ -0,0 +1,90 @@
+use std::fmt;
+
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    gizmo::Id,
+};
+
+use crate::map::{landmark::Landmark, map::Map};
+
+/// Places a landmark on the map.
+pub struct CreateLandmark {
+    landmark: Landmark,
+}
+
+impl CreateLandmark {
+    pub fn new(landmark: Landmark) -> Self {
+        CreateLandmark { landmark }
+    }
+}
+
+impl Undo<Map> for CreateLandmark {
+    fn undo(&mut self, map: &mut Map) {
+        map.remove_landmark(&self.landmark.id());
+    }
+}
+
+impl Redo<Map> for CreateLandmark {
+    fn redo(&mut self, map: &mut Map) {
+        map.add_landmark(self.landmark.clone());
+    }
+}
+
+impl Action<Map> for CreateLandmark {}
+
+impl fmt::Display for CreateLandmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[create_landmark] landmark={}, kind={}",
+            self.landmark.id(),
+            self.landmark.kind()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{
+        landmark::{Landmark, LandmarkKind},
+        map::Map,
+    };
+
+    use super::CreateLandmark;
+
+    #[test]
+    fn landmark_create_redo_works() {
+        let mut map = Map::new(1000, 1000);
+        let well = Landmark::new(LandmarkKind::Well, Coordinate { x: 50., y: 50. });
+
+        let mut action = CreateLandmark::new(well.clone());
+        action.execute(&mut map);
+
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 50., y: 50. }),
+            Some(well.id())
+        );
+    }
+
+    #[test]
+    fn landmark_create_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        let well = Landmark::new(LandmarkKind::Well, Coordinate { x: 50., y: 50. });
+
+        let mut action = CreateLandmark::new(well);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.landmarks().is_empty());
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 50., y: 50. }),
+            None
+        );
+    }
+}
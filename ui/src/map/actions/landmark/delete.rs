@@ -0,0 +1,76 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{landmark::Landmark, map::Map};
+
+/// Removes a landmark from the map. The landmark is kept so that undo restores it.
+pub struct DeleteLandmark {
+    landmark_id: Uuid,
+    landmark: Option<Landmark>,
+}
+
+impl DeleteLandmark {
+    pub fn new(landmark_id: Uuid) -> Self {
+        DeleteLandmark {
+            landmark_id,
+            landmark: None,
+        }
+    }
+}
+
+impl Undo<Map> for DeleteLandmark {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(landmark) = self.landmark.take() {
+            map.add_landmark(landmark);
+        }
+    }
+}
+
+impl Redo<Map> for DeleteLandmark {
+    fn redo(&mut self, map: &mut Map) {
+        self.landmark = map.remove_landmark(&self.landmark_id);
+    }
+}
+
+impl Action<Map> for DeleteLandmark {}
+
+impl fmt::Display for DeleteLandmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[delete_landmark] landmark={}", self.landmark_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{
+        landmark::{Landmark, LandmarkKind},
+        map::Map,
+    };
+
+    use super::DeleteLandmark;
+
+    #[test]
+    fn landmark_delete_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        let castle = Landmark::new(LandmarkKind::Castle, Coordinate { x: 50., y: 50. });
+        map.add_landmark(castle.clone());
+
+        let mut action = DeleteLandmark::new(castle.id());
+        action.execute(&mut map);
+        assert!(map.landmark(&castle.id()).is_none());
+
+        action.undo(&mut map);
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 50., y: 50. }),
+            Some(castle.id())
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{
+    landmark::{Landmark, LandmarkKind},
+    map::Map,
+};
+
+/// Changes the kind, rotation, name and notes of a landmark.
+pub struct EditLandmark {
+    landmark_id: Uuid,
+    kind: LandmarkKind,
+    rotation: f64,
+    name: Option<String>,
+    notes: String,
+
+    /// The landmark before it was edited.
+    old_landmark: Option<Landmark>,
+}
+
+impl EditLandmark {
+    pub fn new(
+        landmark_id: Uuid,
+        kind: LandmarkKind,
+        rotation: f64,
+        name: Option<String>,
+        notes: String,
+    ) -> Self {
+        EditLandmark {
+            landmark_id,
+            kind,
+            rotation,
+            name,
+            notes,
+            old_landmark: None,
+        }
+    }
+}
+
+impl Undo<Map> for EditLandmark {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(old) = self.old_landmark.take() {
+            map.update_landmark(&self.landmark_id, |landmark| {
+                landmark.set_kind(old.kind());
+                landmark.set_rotation(old.rotation());
+                landmark.set_name(old.name().cloned());
+                landmark.set_notes(old.notes().clone());
+            });
+        }
+    }
+}
+
+impl Redo<Map> for EditLandmark {
+    fn redo(&mut self, map: &mut Map) {
+        self.old_landmark = map.landmark(&self.landmark_id).cloned();
+
+        map.update_landmark(&self.landmark_id, |landmark| {
+            landmark.set_kind(self.kind);
+            landmark.set_rotation(self.rotation);
+            landmark.set_name(self.name.clone());
+            landmark.set_notes(self.notes.clone());
+        });
+    }
+}
+
+impl Action<Map> for EditLandmark {}
+
+impl fmt::Display for EditLandmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[edit_landmark] landmark={}, kind={}, rotation={}, name={:?}",
+            self.landmark_id, self.kind, self.rotation, self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{
+        landmark::{Landmark, LandmarkKind},
+        map::Map,
+    };
+
+    use super::EditLandmark;
+
+    #[test]
+    fn landmark_edit_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        let id = map.add_landmark(Landmark::new(
+            LandmarkKind::Temple,
+            Coordinate { x: 50., y: 50. },
+        ));
+
+        let mut action = EditLandmark::new(
+            id,
+            LandmarkKind::Tavern,
+            90.,
+            Some("The Green Dragon".to_string()),
+            "Best beer in the Shire".to_string(),
+        );
+        action.execute(&mut map);
+
+        let landmark = map.landmark(&id).unwrap();
+        assert_eq!(landmark.kind(), LandmarkKind::Tavern);
+        assert_eq!(landmark.rotation(), 90.);
+        assert_eq!(landmark.name(), Some(&"The Green Dragon".to_string()));
+        assert_eq!(landmark.notes(), "Best beer in the Shire");
+
+        action.undo(&mut map);
+
+        let landmark = map.landmark(&id).unwrap();
+        assert_eq!(landmark.kind(), LandmarkKind::Temple);
+        assert_eq!(landmark.rotation(), 0.);
+        assert_eq!(landmark.name(), None);
+        assert!(landmark.notes().is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    gizmo::SetPosition,
+};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Moves a landmark from `from` to `to`. Both positions are stored so that a landmark that was already moved
+/// while it was dragged can be recorded afterwards.
+pub struct MoveLandmark {
+    landmark_id: Uuid,
+    from: Coordinate<f64>,
+    to: Coordinate<f64>,
+}
+
+impl MoveLandmark {
+    pub fn new(landmark_id: Uuid, from: Coordinate<f64>, to: Coordinate<f64>) -> Self {
+        MoveLandmark {
+            landmark_id,
+            from,
+            to,
+        }
+    }
+}
+
+impl Undo<Map> for MoveLandmark {
+    fn undo(&mut self, map: &mut Map) {
+        let from = self.from;
+        map.update_landmark(&self.landmark_id, |landmark| landmark.set_position(from));
+    }
+}
+
+impl Redo<Map> for MoveLandmark {
+    fn redo(&mut self, map: &mut Map) {
+        let to = self.to;
+        map.update_landmark(&self.landmark_id, |landmark| landmark.set_position(to));
+    }
+}
+
+impl Action<Map> for MoveLandmark {}
+
+impl fmt::Display for MoveLandmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[move_landmark] landmark={}, from={:?}, to={:?}",
+            self.landmark_id, self.from, self.to
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::GetPosition,
+    };
+
+    use crate::map::{
+        landmark::{Landmark, LandmarkKind},
+        map::Map,
+    };
+
+    use super::MoveLandmark;
+
+    #[test]
+    fn landmark_move_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        let from = Coordinate { x: 50., y: 50. };
+        let to = Coordinate { x: 200., y: 50. };
+        let gate = map.add_landmark(Landmark::new(LandmarkKind::Gate, from));
+
+        let mut action = MoveLandmark::new(gate, from, to);
+        action.execute(&mut map);
+        assert_eq!(map.landmark(&gate).unwrap().position(), to);
+        assert_eq!(map.get_landmark_at_position(&to), Some(gate));
+
+        action.undo(&mut map);
+        assert_eq!(map.landmark(&gate).unwrap().position(), from);
+        assert_eq!(map.get_landmark_at_position(&to), None);
+    }
+}
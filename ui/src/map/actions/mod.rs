@@ -1,2 +1,7 @@
+pub mod district;
 pub mod intersection;
+pub mod landmark;
 pub mod street;
+pub mod terrain;
+pub mod wall;
+pub mod water;
@@ -1,79 +1,60 @@
 use std::fmt;
 
-use geo::Coordinate;
-use rust_editor::actions::{Action, MultiAction, Redo, Undo};
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    gizmo::Id,
+};
 use uuid::Uuid;
 
-use crate::map::{actions::intersection::create::CreateIntersection, map::Map};
+use crate::map::{map::Map, street::Street};
 
+/// Adds `street` to the map so that it runs from the intersection `start_intersection_id` to
+/// the intersection `end_intersection_id`. Both intersections must already exist.
 pub(crate) struct CreateStreet {
-    start_intersection_id: Option<Uuid>,
-    start_pos: Coordinate<f64>,
-    end_intersection_id: Option<Uuid>,
-    end_pos: Coordinate<f64>,
-    street_ids: Vec<Uuid>,
-
-    action_stack: MultiAction<Map>,
+    id: Uuid,
+    street: Street,
+    start_intersection_id: Uuid,
+    end_intersection_id: Uuid,
 }
 
-/*
 impl CreateStreet {
-    pub fn new(start_pos: Coordinate<f64>, end_pos: Coordinate<f64>, street_id: Uuid) -> Self {
+    pub fn new(street: Street, start_intersection_id: Uuid, end_intersection_id: Uuid) -> Self {
         CreateStreet {
-            start_intersection_id: None,
-            start_pos,
-            end_intersection_id: None,
-            end_pos,
-            street_ids: vec![street_id],
-            action_stack: MultiAction::new(),
+            id: street.id(),
+            street,
+            start_intersection_id,
+            end_intersection_id,
         }
     }
 }
-*/
 
 impl Undo<Map> for CreateStreet {
     fn undo(&mut self, map: &mut Map) {
-        for action in self.action_stack.actions.iter_mut() {
-            action.undo(map);
+        if let Some(end) = map.intersection_mut(&self.end_intersection_id) {
+            end.remove_connected_street(&self.id);
+        }
+
+        if let Some(start) = map.intersection_mut(&self.start_intersection_id) {
+            start.remove_connected_street(&self.id);
+        }
+
+        if let Some(street) = map.street(&self.id).cloned() {
+            map.remove_street(&street);
         }
     }
 }
 
 impl Redo<Map> for CreateStreet {
     fn redo(&mut self, map: &mut Map) {
-        self.action_stack.clear();
-
-        // Check if an intersection at start and end exists and if not add CreateIntersection action(s) to the stack
-        let mut redo_intersection = |position: &Coordinate<f64>| match map
-            .get_intersection_at_position(&position, 1., &vec![])
-        {
-            Some(intersection) => intersection,
-            None => {
-                let id = Uuid::new_v4();
-                self.action_stack.push(CreateIntersection::new_with_id(
-                    position.clone(),
-                    id.clone(),
-                ));
-
-                id
-            }
-        };
-
-        let mut pts = vec![];
-        pts.push((redo_intersection(&self.start_pos), self.start_pos));
-        pts.push((redo_intersection(&self.end_pos), self.end_pos));
-
-        for i in 0..pts.len() {
-            if let Some(next) = pts.get(i + 1) {
-                let start = pts[i];
-                let end = *next;
-
-                self.start_intersection_id = Some(start.0);
-                self.end_intersection_id = Some(end.0);
-            }
+        map.add_street(&self.street);
+
+        if let Some(start) = map.intersection_mut(&self.start_intersection_id) {
+            start.add_outgoing_street(&self.id);
         }
 
-        self.action_stack.redo(map);
+        if let Some(end) = map.intersection_mut(&self.end_intersection_id) {
+            end.add_incoming_street(&self.id);
+        }
     }
 }
 
@@ -83,11 +64,83 @@ impl fmt::Display for CreateStreet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "[create_street] street={:?}\n\u{251C}  {}",
-            self.street_ids, self.action_stack
+            "[create_street] street={}, class={}, width={}, start={}, end={}",
+            self.id,
+            self.street.class(),
+            self.street.width(),
+            self.start_intersection_id,
+            self.end_intersection_id
         )
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+    use uuid::Uuid;
+
+    use crate::map::{
+        actions::street::create::CreateStreet, intersection::Intersection, map::Map,
+        street::Street, street_class::StreetClass,
+    };
+
+    fn create_map() -> (Map, Uuid, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let start = map.add_intersection(Intersection::new(Coordinate { x: 100., y: 100. }));
+        let end = map.add_intersection(Intersection::new(Coordinate { x: 300., y: 100. }));
+
+        (map, start, end)
+    }
+
+    #[test]
+    fn street_create_redo_connects_intersections() {
+        let (mut map, start, end) = create_map();
+
+        let street = Street::new(LineString::from(vec![(100., 100.), (300., 100.)]));
+        let id = street.id();
+        let mut action = CreateStreet::new(street, start, end);
+        action.execute(&mut map);
+
+        assert_eq!(map.streets().len(), 1);
+        assert_eq!(map.street_intersections(&id), (Some(start), Some(end)));
+    }
+
+    #[test]
+    fn street_create_redo_uses_class_and_width() {
+        let (mut map, start, end) = create_map();
+
+        let mut street = Street::new_with_class(
+            LineString::from(vec![(100., 100.), (300., 100.)]),
+            StreetClass::Alley,
+        );
+        street.set_width(12.);
+        let id = street.id();
+        let mut action = CreateStreet::new(street, start, end);
+        action.execute(&mut map);
+
+        let street = map.street(&id).unwrap();
+        assert_eq!(street.class(), StreetClass::Alley);
+        assert_eq!(street.width(), 12.);
+    }
+
+    #[test]
+    fn street_create_undo_works() {
+        let (mut map, start, end) = create_map();
+
+        let street = Street::new(LineString::from(vec![(100., 100.), (300., 100.)]));
+        let mut action = CreateStreet::new(street, start, end);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map
+            .intersections()
+            .values()
+            .all(|intersection| intersection.get_connected_streets().is_empty()));
+    }
+}
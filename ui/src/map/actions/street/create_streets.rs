@@ -0,0 +1,150 @@
+use std::fmt;
+
+use geo::LineString;
+use rust_editor::actions::{Action, MultiAction, Redo, Undo};
+use uuid::Uuid;
+
+use crate::{
+    map::{map::Map, street_class::StreetClass},
+    plugins::create_freeform_street::CreateFreeFormStreetAction,
+};
+
+/// Adds streets one after another, e.g. the streets of a generated road network. Each street is created by a
+/// [`CreateFreeFormStreetAction`] so that it is connected to the streets added before and the districts between
+/// them are updated.
+///
+/// [`CreateFreeFormStreetAction`]: crate::plugins::create_freeform_street::CreateFreeFormStreetAction
+pub(crate) struct CreateStreets {
+    streets: Vec<(LineString<f64>, StreetClass)>,
+
+    /// Width of all streets. Each street gets the default width of its class if not set.
+    width: Option<f64>,
+
+    split_intersections: Vec<Uuid>,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl CreateStreets {
+    pub fn new(streets: Vec<(LineString<f64>, StreetClass)>) -> Self {
+        CreateStreets {
+            streets,
+            width: None,
+            split_intersections: vec![],
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    pub fn new_with_width(streets: Vec<(LineString<f64>, StreetClass)>, width: f64) -> Self {
+        CreateStreets {
+            width: Some(width),
+            ..CreateStreets::new(streets)
+        }
+    }
+
+    /// Ids of the intersections at which streets that existed before were split to connect the new streets,
+    /// including streets added earlier by this action.
+    pub fn split_intersections(&self) -> &Vec<Uuid> {
+        &self.split_intersections
+    }
+}
+
+impl Undo<Map> for CreateStreets {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for CreateStreets {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+        self.split_intersections.clear();
+
+        for (street, class) in &self.streets {
+            let mut action = CreateFreeFormStreetAction::new_with_class(
+                street.clone(),
+                *class,
+                self.width.unwrap_or_else(|| class.default_width()),
+            );
+            action.execute(map);
+            self.split_intersections
+                .extend(action.split_intersections().iter().copied());
+            self.action_stack.push(action);
+        }
+    }
+}
+
+impl Action<Map> for CreateStreets {}
+
+impl fmt::Display for CreateStreets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[create_streets] streets={}\n\u{251C}  {}",
+            self.streets.len(),
+            self.action_stack
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street_class::StreetClass};
+
+    use super::CreateStreets;
+
+    fn create_action() -> CreateStreets {
+        CreateStreets::new(vec![
+            (
+                LineString::from(vec![(100., 200.), (300., 200.)]),
+                StreetClass::MainRoad,
+            ),
+            (
+                LineString::from(vec![(200., 100.), (200., 300.)]),
+                StreetClass::Alley,
+            ),
+        ])
+    }
+
+    #[test]
+    fn streets_create_redo_connects_streets() {
+        let mut map = Map::new(1000, 1000);
+
+        create_action().execute(&mut map);
+
+        // The second street splits the first one where both cross
+        assert_eq!(map.streets().len(), 4);
+        assert_eq!(map.intersections().len(), 5);
+    }
+
+    #[test]
+    fn streets_create_undo_works() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = create_action();
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map.intersections().is_empty());
+    }
+
+    #[test]
+    fn streets_create_uses_given_width() {
+        let mut map = Map::new(1000, 1000);
+
+        CreateStreets::new_with_width(
+            vec![(
+                LineString::from(vec![(100., 200.), (300., 200.)]),
+                StreetClass::MainRoad,
+            )],
+            7.,
+        )
+        .execute(&mut map);
+
+        assert!(map.streets().values().all(|street| street.width() == 7.));
+    }
+}
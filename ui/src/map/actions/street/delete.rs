@@ -1,35 +1,79 @@
 use std::fmt;
 
-use rust_editor::{actions::{Action, Redo, Undo}, interactive_element::{InteractiveElement, InteractiveElementState}};
+use rust_editor::{
+    actions::{Action, MultiAction, Redo, Undo},
+    gizmo::Id,
+    interactive_element::{InteractiveElement, InteractiveElementState},
+};
 use uuid::Uuid;
 
-use crate::map::{map::Map, street::Street};
+use crate::map::{
+    actions::intersection::{
+        delete::DeleteIntersection, remove_connected_street::RemoveConnectedStreet,
+    },
+    map::Map,
+    street::Street,
+};
 
+/// Removes a street from the map and disconnects it from its intersections.
+/// Intersections that are left without any connected street are removed as well.
 pub struct DeleteStreet {
     street_id: Uuid,
-    street: Option<Street>
+    street: Option<Street>,
+
+    action_stack: MultiAction<Map>,
 }
 
 impl DeleteStreet {
     pub fn new(street_id: Uuid) -> Self {
         DeleteStreet {
             street_id,
-            street: None
+            street: None,
+            action_stack: MultiAction::new(),
         }
     }
 }
 
 impl Undo<Map> for DeleteStreet {
-    fn undo(&mut self, map: &mut Map) {        
+    fn undo(&mut self, map: &mut Map) {
         map.add_street(self.street.as_ref().unwrap());
+
+        self.action_stack.undo(map);
     }
 }
 
 impl Redo<Map> for DeleteStreet {
     fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
         self.street = Some(map.street(&self.street_id).unwrap().clone());
-        self.street.as_mut().unwrap().set_state(InteractiveElementState::Normal);
-        
+        self.street
+            .as_mut()
+            .unwrap()
+            .set_state(InteractiveElementState::Normal);
+
+        for intersection in map.intersections().values() {
+            let connected_streets = intersection.get_connected_streets();
+
+            let connections = connected_streets
+                .iter()
+                .filter(|(_, id)| *id == self.street_id)
+                .count();
+
+            for _ in 0..connections {
+                self.action_stack.push(RemoveConnectedStreet::new(
+                    intersection.id(),
+                    self.street_id,
+                ));
+            }
+
+            if connections > 0 && connections == connected_streets.len() {
+                self.action_stack.push(DeleteIntersection::new(intersection));
+            }
+        }
+
+        self.action_stack.redo(map);
+
         map.remove_street(self.street.as_ref().unwrap());
     }
 }
@@ -40,11 +84,59 @@ impl fmt::Display for DeleteStreet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "[delete_street] street={}\n\u{251C}",
-            self.street_id
+            "[delete_street] street={}\n\u{251C}  {}",
+            self.street_id, self.action_stack
         )
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::{map::map::Map, plugins::create_freeform_street::CreateFreeFormStreetAction};
+
+    use super::DeleteStreet;
+
+    fn create_map() -> Map {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = CreateFreeFormStreetAction::new(LineString::from(vec![
+            (100., 100.),
+            (300., 100.),
+        ]));
+        action.execute(&mut map);
+
+        map
+    }
+
+    #[test]
+    fn street_delete_redo_removes_orphaned_intersections() {
+        let mut map = create_map();
+        let id = *map.streets().keys().next().unwrap();
+
+        let mut action = DeleteStreet::new(id);
+        action.execute(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map.intersections().is_empty());
+    }
+
+    #[test]
+    fn street_delete_undo_works() {
+        let mut map = create_map();
+        let id = *map.streets().keys().next().unwrap();
+
+        let mut action = DeleteStreet::new(id);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.streets().len(), 1);
+        assert_eq!(map.intersections().len(), 2);
+        assert!(map
+            .intersections()
+            .values()
+            .all(|intersection| intersection.get_connected_streets().len() == 1));
+    }
+}
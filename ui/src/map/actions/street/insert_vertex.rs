@@ -0,0 +1,111 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Inserts a new vertex at `position` into a street so that it gets the index `index`.
+pub struct InsertVertex {
+    street_id: Uuid,
+    index: usize,
+    position: Coordinate<f64>,
+}
+
+impl InsertVertex {
+    pub fn new(street_id: Uuid, index: usize, position: Coordinate<f64>) -> Self {
+        InsertVertex {
+            street_id,
+            index,
+            position,
+        }
+    }
+}
+
+impl Undo<Map> for InsertVertex {
+    fn undo(&mut self, map: &mut Map) {
+        let index = self.index;
+        map.update_street(&self.street_id, |street| {
+            let mut vertices = street.vertices().clone();
+            if index < vertices.len() {
+                vertices.remove(index);
+                street.set_vertices(vertices);
+            }
+        });
+    }
+}
+
+impl Redo<Map> for InsertVertex {
+    fn redo(&mut self, map: &mut Map) {
+        let (index, position) = (self.index, self.position);
+        map.update_street(&self.street_id, |street| {
+            let mut vertices = street.vertices().clone();
+            if index <= vertices.len() {
+                vertices.insert(index, position);
+                street.set_vertices(vertices);
+            }
+        });
+    }
+}
+
+impl Action<Map> for InsertVertex {}
+
+impl fmt::Display for InsertVertex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[insert_vertex] street={}, index={}, position={:?}",
+            self.street_id, self.index, self.position
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::InsertVertex;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let street = Street::new(LineString::from(vec![(100., 100.), (300., 100.)]));
+        let id = map.add_street(&street);
+
+        (map, id)
+    }
+
+    #[test]
+    fn street_insert_vertex_redo_works() {
+        let (mut map, id) = create_map();
+
+        let mut action = InsertVertex::new(id, 1, Coordinate { x: 200., y: 150. });
+        action.execute(&mut map);
+
+        assert_eq!(
+            map.street(&id).unwrap().vertices(),
+            &vec![
+                Coordinate { x: 100., y: 100. },
+                Coordinate { x: 200., y: 150. },
+                Coordinate { x: 300., y: 100. }
+            ]
+        );
+    }
+
+    #[test]
+    fn street_insert_vertex_undo_works() {
+        let (mut map, id) = create_map();
+        let street = map.street(&id).unwrap().clone();
+
+        let mut action = InsertVertex::new(id, 1, Coordinate { x: 200., y: 150. });
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.street(&id).unwrap().lines, street.lines);
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, MultiAction, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{
+    actions::street::{create::CreateStreet, delete::DeleteStreet},
+    intersection::Direction,
+    map::Map,
+};
+
+/// Merges the two streets that meet at the intersection `intersection_id` into one street and removes the
+/// intersection. This reverses [`SplitStreet`].
+///
+/// Does nothing unless exactly two streets meet at the intersection, one ending and one starting there, and both
+/// can be joined by [`Street::join`].
+///
+/// [`SplitStreet`]: crate::map::actions::street::split::SplitStreet
+/// [`Street::join`]: crate::map::street::Street::join
+pub(crate) struct MergeStreets {
+    intersection_id: Uuid,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl MergeStreets {
+    pub fn new(intersection_id: Uuid) -> Self {
+        MergeStreets {
+            intersection_id,
+            action_stack: MultiAction::new(),
+        }
+    }
+}
+
+impl Undo<Map> for MergeStreets {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for MergeStreets {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        let intersection = match map.intersection(&self.intersection_id) {
+            Some(intersection) => intersection,
+            None => return,
+        };
+
+        let (first, second) = match intersection.get_connected_streets()[..] {
+            [(Direction::In, first), (Direction::Out, second)]
+            | [(Direction::Out, second), (Direction::In, first)]
+                if first != second =>
+            {
+                (first, second)
+            }
+            _ => return,
+        };
+
+        let street = match (map.street(&first), map.street(&second)) {
+            (Some(first), Some(second)) => first.join(second),
+            _ => None,
+        };
+        let (start, _) = map.street_intersections(&first);
+        let (_, end) = map.street_intersections(&second);
+
+        let (street, start, end) = match (street, start, end) {
+            (Some(street), Some(start), Some(end)) => (street, start, end),
+            _ => return,
+        };
+
+        self.action_stack
+            .push(CreateStreet::new(street, start, end));
+
+        // Delete the merged streets last so that the intersections at the ends are still connected to the new
+        // street and are therefore not removed as orphans, unlike the intersection between them.
+        self.action_stack.push(DeleteStreet::new(first));
+        self.action_stack.push(DeleteStreet::new(second));
+
+        self.action_stack.redo(map);
+    }
+}
+
+impl Action<Map> for MergeStreets {}
+
+impl fmt::Display for MergeStreets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[merge_streets] intersection={}\n\u{251C}  {}",
+            self.intersection_id, self.action_stack
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::{
+        map::{actions::street::split::SplitStreet, map::Map, street_class::StreetClass},
+        plugins::create_freeform_street::CreateFreeFormStreetAction,
+    };
+
+    use super::MergeStreets;
+
+    fn create_split_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action =
+            CreateFreeFormStreetAction::new(LineString::from(vec![(100., 100.), (300., 100.)]));
+        action.execute(&mut map);
+
+        let id = *map.streets().keys().next().unwrap();
+        let intersection = Uuid::new_v4();
+        SplitStreet::new(id, Coordinate { x: 200., y: 100. }, intersection).execute(&mut map);
+
+        (map, intersection)
+    }
+
+    #[test]
+    fn street_merge_redo_works() {
+        let (mut map, intersection) = create_split_map();
+
+        let mut action = MergeStreets::new(intersection);
+        action.execute(&mut map);
+
+        assert_eq!(map.streets().len(), 1);
+        assert_eq!(map.intersections().len(), 2);
+        assert!(map.intersection(&intersection).is_none());
+        assert_eq!(
+            map.streets().values().next().unwrap().lines,
+            LineString::from(vec![(100., 100.), (200., 100.), (300., 100.)])
+        );
+    }
+
+    #[test]
+    fn street_merge_undo_works() {
+        let (mut map, intersection) = create_split_map();
+
+        let mut action = MergeStreets::new(intersection);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.streets().len(), 2);
+        assert_eq!(map.intersections().len(), 3);
+        assert_eq!(
+            map.intersection(&intersection)
+                .unwrap()
+                .get_connected_streets()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn street_merge_keeps_streets_of_different_classes() {
+        let (mut map, intersection) = create_split_map();
+
+        let id = *map.streets().keys().next().unwrap();
+        map.update_street(&id, |street| street.set_class(StreetClass::Alley));
+
+        MergeStreets::new(intersection).execute(&mut map);
+
+        assert_eq!(map.streets().len(), 2);
+        assert!(map.intersection(&intersection).is_some());
+    }
+}
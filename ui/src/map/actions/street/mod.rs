@@ -1,3 +1,11 @@
 pub mod create;
+pub mod create_streets;
 pub mod delete;
-pub mod update;
\ No newline at end of file
+pub mod insert_vertex;
+pub mod merge;
+pub mod move_vertex;
+pub mod remove_vertex;
+pub mod rename;
+pub mod set_control_points;
+pub mod split;
+pub mod update;
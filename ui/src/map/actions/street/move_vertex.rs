@@ -0,0 +1,125 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Moves the vertex at `index` of a street from `from` to `to`.
+pub struct MoveVertex {
+    street_id: Uuid,
+    index: usize,
+    from: Coordinate<f64>,
+    to: Coordinate<f64>,
+}
+
+impl MoveVertex {
+    pub fn new(street_id: Uuid, index: usize, from: Coordinate<f64>, to: Coordinate<f64>) -> Self {
+        MoveVertex {
+            street_id,
+            index,
+            from,
+            to,
+        }
+    }
+}
+
+/// Sets the position of the vertex at `index` of the street with `street_id` and updates the map around it.
+fn set_vertex_position(map: &mut Map, street_id: &Uuid, index: usize, position: Coordinate<f64>) {
+    map.update_street(street_id, |street| {
+        let mut vertices = street.vertices().clone();
+        if let Some(vertex) = vertices.get_mut(index) {
+            *vertex = position;
+            street.set_vertices(vertices);
+        }
+    });
+}
+
+impl Undo<Map> for MoveVertex {
+    fn undo(&mut self, map: &mut Map) {
+        set_vertex_position(map, &self.street_id, self.index, self.from);
+    }
+}
+
+impl Redo<Map> for MoveVertex {
+    fn redo(&mut self, map: &mut Map) {
+        set_vertex_position(map, &self.street_id, self.index, self.to);
+    }
+}
+
+impl Action<Map> for MoveVertex {}
+
+impl fmt::Display for MoveVertex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[move_vertex] street={}, index={}, from={:?}, to={:?}",
+            self.street_id, self.index, self.from, self.to
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::MoveVertex;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let street = Street::new(LineString::from(vec![
+            (100., 100.),
+            (200., 100.),
+            (300., 100.),
+        ]));
+        let id = map.add_street(&street);
+
+        (map, id)
+    }
+
+    #[test]
+    fn street_move_vertex_redo_updates_polygon() {
+        let (mut map, id) = create_map();
+
+        let mut action = MoveVertex::new(
+            id,
+            1,
+            Coordinate { x: 200., y: 100. },
+            Coordinate { x: 200., y: 200. },
+        );
+        action.execute(&mut map);
+
+        let street = map.street(&id).unwrap();
+        assert_eq!(street.vertices()[1], Coordinate { x: 200., y: 200. });
+        assert!(street.is_point_on_street(&Coordinate { x: 200., y: 195. }));
+        assert!(map
+            .get_street_at_position(&Coordinate { x: 200., y: 195. }, &vec![])
+            .is_some());
+    }
+
+    #[test]
+    fn street_move_vertex_undo_works() {
+        let (mut map, id) = create_map();
+        let street = map.street(&id).unwrap().clone();
+
+        let mut action = MoveVertex::new(
+            id,
+            1,
+            Coordinate { x: 200., y: 100. },
+            Coordinate { x: 200., y: 200. },
+        );
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.street(&id).unwrap().lines, street.lines);
+        assert!(map
+            .get_street_at_position(&Coordinate { x: 200., y: 195. }, &vec![])
+            .is_none());
+    }
+}
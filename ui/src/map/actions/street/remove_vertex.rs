@@ -0,0 +1,126 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Removes the vertex at `index` from a street. The ends of a street are connected to intersections,
+/// therefore only inner vertices can be removed.
+pub struct RemoveVertex {
+    street_id: Uuid,
+    index: usize,
+    removed_vertex: Option<Coordinate<f64>>,
+}
+
+impl RemoveVertex {
+    pub fn new(street_id: Uuid, index: usize) -> Self {
+        RemoveVertex {
+            street_id,
+            index,
+            removed_vertex: None,
+        }
+    }
+}
+
+impl Undo<Map> for RemoveVertex {
+    fn undo(&mut self, map: &mut Map) {
+        let index = self.index;
+        if let Some(removed_vertex) = self.removed_vertex.take() {
+            map.update_street(&self.street_id, |street| {
+                let mut vertices = street.vertices().clone();
+                vertices.insert(index, removed_vertex);
+                street.set_vertices(vertices);
+            });
+        }
+    }
+}
+
+impl Redo<Map> for RemoveVertex {
+    fn redo(&mut self, map: &mut Map) {
+        let vertices = match map.street(&self.street_id) {
+            Some(street) => street.vertices(),
+            None => return,
+        };
+
+        if self.index == 0 || self.index + 1 >= vertices.len() {
+            return;
+        }
+
+        let mut vertices = vertices.clone();
+        self.removed_vertex = Some(vertices.remove(self.index));
+        map.update_street(&self.street_id, |street| street.set_vertices(vertices));
+    }
+}
+
+impl Action<Map> for RemoveVertex {}
+
+impl fmt::Display for RemoveVertex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[remove_vertex] street={}, index={}",
+            self.street_id, self.index
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::RemoveVertex;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let street = Street::new(LineString::from(vec![
+            (100., 100.),
+            (200., 150.),
+            (300., 100.),
+        ]));
+        let id = map.add_street(&street);
+
+        (map, id)
+    }
+
+    #[test]
+    fn street_remove_vertex_redo_works() {
+        let (mut map, id) = create_map();
+
+        let mut action = RemoveVertex::new(id, 1);
+        action.execute(&mut map);
+
+        assert_eq!(
+            map.street(&id).unwrap().lines,
+            LineString::from(vec![(100., 100.), (300., 100.)])
+        );
+    }
+
+    #[test]
+    fn street_remove_vertex_keeps_street_ends() {
+        let (mut map, id) = create_map();
+
+        let mut action = RemoveVertex::new(id, 2);
+        action.execute(&mut map);
+
+        assert_eq!(map.street(&id).unwrap().vertices().len(), 3);
+    }
+
+    #[test]
+    fn street_remove_vertex_undo_works() {
+        let (mut map, id) = create_map();
+        let street = map.street(&id).unwrap().clone();
+
+        let mut action = RemoveVertex::new(id, 1);
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.street(&id).unwrap().lines, street.lines);
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Sets the name of a street. A name of `None` removes the name.
+pub struct RenameStreet {
+    street_id: Uuid,
+    name: Option<String>,
+    old_name: Option<String>,
+}
+
+impl RenameStreet {
+    pub fn new(street_id: Uuid, name: Option<String>) -> Self {
+        RenameStreet {
+            street_id,
+            name,
+            old_name: None,
+        }
+    }
+}
+
+impl Undo<Map> for RenameStreet {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(street) = map.street_mut(&self.street_id) {
+            street.set_name(self.old_name.take());
+        }
+    }
+}
+
+impl Redo<Map> for RenameStreet {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(street) = map.street_mut(&self.street_id) {
+            self.old_name = street.name().cloned();
+            street.set_name(self.name.clone());
+        }
+    }
+}
+
+impl Action<Map> for RenameStreet {}
+
+impl fmt::Display for RenameStreet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[rename_street] street={}, name={:?}",
+            self.street_id, self.name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::RenameStreet;
+
+    #[test]
+    fn street_rename_redo_works() {
+        let mut map = Map::new(1000, 1000);
+        let id = map.add_street(&Street::new(LineString::from(vec![(0., 0.), (100., 0.)])));
+
+        let mut action = RenameStreet::new(id, Some("Main Street".to_string()));
+        action.execute(&mut map);
+
+        assert_eq!(
+            map.street(&id).unwrap().name(),
+            Some(&"Main Street".to_string())
+        );
+    }
+
+    #[test]
+    fn street_rename_undo_works() {
+        let mut map = Map::new(1000, 1000);
+        let mut street = Street::new(LineString::from(vec![(0., 0.), (100., 0.)]));
+        street.set_name(Some("Old Street".to_string()));
+        let id = map.add_street(&street);
+
+        let mut action = RenameStreet::new(id, None);
+        action.execute(&mut map);
+        assert_eq!(map.street(&id).unwrap().name(), None);
+
+        action.undo(&mut map);
+        assert_eq!(
+            map.street(&id).unwrap().name(),
+            Some(&"Old Street".to_string())
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::map::Map;
+
+/// Replaces the control points of a curved street. The street is removed and added again so that the
+/// street polygon and the districts around the old and the new curve are updated.
+pub struct SetControlPoints {
+    street_id: Uuid,
+    control_points: Vec<Coordinate<f64>>,
+    old_control_points: Option<Vec<Coordinate<f64>>>,
+}
+
+impl SetControlPoints {
+    pub fn new(street_id: Uuid, control_points: Vec<Coordinate<f64>>) -> Self {
+        SetControlPoints {
+            street_id,
+            control_points,
+            old_control_points: None,
+        }
+    }
+
+    fn replace(&self, control_points: &Vec<Coordinate<f64>>, map: &mut Map) {
+        map.update_street(&self.street_id, |street| {
+            street.set_control_points(control_points.clone())
+        });
+    }
+}
+
+impl Undo<Map> for SetControlPoints {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(old_control_points) = &self.old_control_points {
+            self.replace(old_control_points, map);
+        }
+    }
+}
+
+impl Redo<Map> for SetControlPoints {
+    fn redo(&mut self, map: &mut Map) {
+        if let Some(street) = map.street(&self.street_id) {
+            self.old_control_points = Some(street.control_points().clone());
+            self.replace(&self.control_points, map);
+        }
+    }
+}
+
+impl Action<Map> for SetControlPoints {}
+
+impl fmt::Display for SetControlPoints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[set_control_points] street={}, control_points={}",
+            self.street_id,
+            self.control_points.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street, street_class::StreetClass};
+
+    use super::SetControlPoints;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let street = Street::new_curved(
+            vec![
+                Coordinate { x: 100., y: 100. },
+                Coordinate { x: 200., y: 150. },
+                Coordinate { x: 300., y: 100. },
+            ],
+            StreetClass::Street,
+        );
+        let id = map.add_street(&street);
+
+        (map, id)
+    }
+
+    fn moved_control_points() -> Vec<Coordinate<f64>> {
+        vec![
+            Coordinate { x: 100., y: 100. },
+            Coordinate { x: 200., y: 50. },
+            Coordinate { x: 300., y: 100. },
+        ]
+    }
+
+    #[test]
+    fn street_set_control_points_redo_updates_curve() {
+        let (mut map, id) = create_map();
+
+        let mut action = SetControlPoints::new(id, moved_control_points());
+        action.execute(&mut map);
+
+        let street = map.street(&id).unwrap();
+        assert_eq!(street.control_points(), &moved_control_points());
+        assert!(street.is_point_on_street(&Coordinate { x: 200., y: 50. }));
+        assert!(!street.is_point_on_street(&Coordinate { x: 200., y: 150. }));
+        assert!(map
+            .get_street_at_position(&Coordinate { x: 200., y: 50. }, &vec![])
+            .is_some());
+    }
+
+    #[test]
+    fn street_set_control_points_undo_works() {
+        let (mut map, id) = create_map();
+        let street = map.street(&id).unwrap().clone();
+
+        let mut action = SetControlPoints::new(id, moved_control_points());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.street(&id).unwrap().lines, street.lines);
+        assert_eq!(
+            map.street(&id).unwrap().control_points(),
+            street.control_points()
+        );
+    }
+}
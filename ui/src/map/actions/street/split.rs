@@ -0,0 +1,157 @@
+use std::fmt;
+
+use geo::Coordinate;
+use rust_editor::actions::{Action, MultiAction, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{
+    actions::{
+        intersection::create::CreateIntersection,
+        street::{create::CreateStreet, delete::DeleteStreet},
+    },
+    map::Map,
+};
+
+/// Splits a street into two streets at the point on the street that is closest to `position`.
+/// A new intersection with the id `intersection_id` is created at the split point and connects both parts.
+///
+/// Does nothing if the split point coincides with the start or the end of the street.
+pub(crate) struct SplitStreet {
+    street_id: Uuid,
+    position: Coordinate<f64>,
+    intersection_id: Uuid,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl SplitStreet {
+    pub fn new(street_id: Uuid, position: Coordinate<f64>, intersection_id: Uuid) -> Self {
+        SplitStreet {
+            street_id,
+            position,
+            intersection_id,
+            action_stack: MultiAction::new(),
+        }
+    }
+}
+
+impl Undo<Map> for SplitStreet {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for SplitStreet {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        let street = match map.street(&self.street_id) {
+            Some(street) => street,
+            None => return,
+        };
+
+        let (first, second) = match street.split(&self.position) {
+            Some(parts) => parts,
+            None => return,
+        };
+        let split_position = *first.lines.0.last().unwrap();
+
+        // Streets that are not part of the street graph yet get intersections at their ends
+        let (start, end) = map.street_intersections(&self.street_id);
+        let mut intersection_at = |id: Option<Uuid>, position: Coordinate<f64>| match id {
+            Some(id) => id,
+            None => {
+                let id = Uuid::new_v4();
+                self.action_stack
+                    .push(CreateIntersection::new_with_id(position, id));
+
+                id
+            }
+        };
+        let start = intersection_at(start, first.lines.0[0]);
+        let end = intersection_at(end, *second.lines.0.last().unwrap());
+
+        self.action_stack.push(CreateIntersection::new_with_id(
+            split_position,
+            self.intersection_id,
+        ));
+        self.action_stack
+            .push(CreateStreet::new(first, start, self.intersection_id));
+        self.action_stack
+            .push(CreateStreet::new(second, self.intersection_id, end));
+
+        // Delete the original street last so that its intersections are still connected to the new parts
+        // and are therefore not removed as orphans.
+        self.action_stack.push(DeleteStreet::new(self.street_id));
+
+        self.action_stack.redo(map);
+    }
+}
+
+impl Action<Map> for SplitStreet {}
+
+impl fmt::Display for SplitStreet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[split_street] street={}, position=({},{})\n\u{251C}  {}",
+            self.street_id, self.position.x, self.position.y, self.action_stack
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::{map::map::Map, plugins::create_freeform_street::CreateFreeFormStreetAction};
+
+    use super::SplitStreet;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = CreateFreeFormStreetAction::new(LineString::from(vec![
+            (100., 100.),
+            (300., 100.),
+        ]));
+        action.execute(&mut map);
+
+        let id = *map.streets().keys().next().unwrap();
+        (map, id)
+    }
+
+    #[test]
+    fn street_split_redo_works() {
+        let (mut map, id) = create_map();
+
+        let intersection = Uuid::new_v4();
+        let mut action = SplitStreet::new(id, Coordinate { x: 200., y: 105. }, intersection);
+        action.execute(&mut map);
+
+        assert_eq!(map.streets().len(), 2);
+        assert_eq!(map.intersections().len(), 3);
+        assert_eq!(
+            map.intersection(&intersection)
+                .unwrap()
+                .get_connected_streets()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn street_split_undo_works() {
+        let (mut map, id) = create_map();
+
+        let mut action = SplitStreet::new(id, Coordinate { x: 200., y: 100. }, Uuid::new_v4());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.streets().len(), 1);
+        assert!(map.street(&id).is_some());
+        assert_eq!(map.intersections().len(), 2);
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+
+use crate::map::{
+    map::Map,
+    terrain::{Terrain, TerrainSource},
+};
+
+/// Replaces the terrain of the map. The previous terrain is kept so that undo restores it.
+pub struct SetTerrain {
+    terrain: Option<Terrain>,
+    previous: Option<Terrain>,
+}
+
+impl SetTerrain {
+    pub fn new(terrain: Option<Terrain>) -> Self {
+        SetTerrain {
+            terrain,
+            previous: None,
+        }
+    }
+}
+
+impl Undo<Map> for SetTerrain {
+    fn undo(&mut self, map: &mut Map) {
+        map.set_terrain(self.previous.take());
+    }
+}
+
+impl Redo<Map> for SetTerrain {
+    fn redo(&mut self, map: &mut Map) {
+        self.previous = map.set_terrain(self.terrain.clone());
+    }
+}
+
+impl Action<Map> for SetTerrain {}
+
+impl fmt::Display for SetTerrain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = match self.terrain.as_ref().map(|terrain| terrain.source()) {
+            Some(TerrainSource::Noise { seed, .. }) => format!("noise({})", seed),
+            Some(TerrainSource::Image { columns, rows, .. }) => {
+                format!("image({}x{})", columns, rows)
+            }
+            None => "none".to_string(),
+        };
+
+        write!(f, "[set_terrain] source={}", source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{
+        map::Map,
+        terrain::{Terrain, TerrainShading, TerrainSource},
+    };
+
+    use super::SetTerrain;
+
+    fn create_terrain() -> Terrain {
+        Terrain::new(
+            TerrainSource::Image {
+                columns: 2,
+                rows: 2,
+                samples: vec![1., 1., 1., 1.],
+            },
+            50.,
+            TerrainShading::Hillshade,
+            1000,
+            1000,
+        )
+    }
+
+    #[test]
+    fn terrain_set_redo_works() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = SetTerrain::new(Some(create_terrain()));
+        action.execute(&mut map);
+
+        assert_eq!(map.elevation_at(&Coordinate { x: 500., y: 500. }), 50.);
+    }
+
+    #[test]
+    fn terrain_set_undo_works() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = SetTerrain::new(Some(create_terrain()));
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.terrain().is_none());
+    }
+}
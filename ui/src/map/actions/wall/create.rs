@@ -0,0 +1,98 @@
+use std::fmt;
+
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    gizmo::Id,
+};
+
+use crate::map::{map::Map, wall::Wall};
+
+/// Adds a wall to the map.
+pub struct CreateWall {
+    wall: Wall,
+}
+
+impl CreateWall {
+    pub fn new(wall: Wall) -> Self {
+        CreateWall { wall }
+    }
+}
+
+impl Undo<Map> for CreateWall {
+    fn undo(&mut self, map: &mut Map) {
+        map.remove_wall(&self.wall.id());
+    }
+}
+
+impl Redo<Map> for CreateWall {
+    fn redo(&mut self, map: &mut Map) {
+        map.add_wall(self.wall.clone());
+    }
+}
+
+impl Action<Map> for CreateWall {}
+
+impl fmt::Display for CreateWall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[create_wall] wall={}, towers={}, gates={}",
+            self.wall.id(),
+            self.wall.towers().len(),
+            self.wall.gates().len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{map::Map, wall::Wall};
+
+    use super::CreateWall;
+
+    fn create_wall() -> Wall {
+        Wall::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 0.)]),
+            vec![Coordinate { x: 100., y: 0. }],
+            vec![],
+            8.,
+            20.,
+        )
+    }
+
+    #[test]
+    fn wall_create_redo_works() {
+        let mut map = Map::new(1000, 1000);
+        let wall = create_wall();
+
+        let mut action = CreateWall::new(wall.clone());
+        action.execute(&mut map);
+
+        assert!(map.wall(&wall.id()).is_some());
+        assert_eq!(
+            map.get_wall_at_position(&Coordinate { x: 50., y: 0. }),
+            Some(wall.id())
+        );
+    }
+
+    #[test]
+    fn wall_create_undo_works() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = CreateWall::new(create_wall());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.walls().is_empty());
+        assert_eq!(
+            map.get_wall_at_position(&Coordinate { x: 50., y: 0. }),
+            None
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{map::Map, wall::Wall};
+
+/// Removes a wall from the map. The wall is kept so that undo restores it.
+pub struct DeleteWall {
+    wall_id: Uuid,
+    wall: Option<Wall>,
+}
+
+impl DeleteWall {
+    pub fn new(wall_id: Uuid) -> Self {
+        DeleteWall {
+            wall_id,
+            wall: None,
+        }
+    }
+}
+
+impl Undo<Map> for DeleteWall {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(wall) = self.wall.take() {
+            map.add_wall(wall);
+        }
+    }
+}
+
+impl Redo<Map> for DeleteWall {
+    fn redo(&mut self, map: &mut Map) {
+        self.wall = map.remove_wall(&self.wall_id);
+    }
+}
+
+impl Action<Map> for DeleteWall {}
+
+impl fmt::Display for DeleteWall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[delete_wall] wall={}", self.wall_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{map::Map, wall::Wall};
+
+    use super::DeleteWall;
+
+    fn create_map() -> (Map, Wall) {
+        let mut map = Map::new(1000, 1000);
+        let wall = Wall::new(
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 0.)]),
+            vec![Coordinate { x: 100., y: 0. }],
+            vec![],
+            8.,
+            20.,
+        );
+        map.add_wall(wall.clone());
+
+        (map, wall)
+    }
+
+    #[test]
+    fn wall_delete_redo_works() {
+        let (mut map, wall) = create_map();
+
+        let mut action = DeleteWall::new(wall.id());
+        action.execute(&mut map);
+
+        assert!(map.wall(&wall.id()).is_none());
+    }
+
+    #[test]
+    fn wall_delete_undo_works() {
+        let (mut map, wall) = create_map();
+
+        let mut action = DeleteWall::new(wall.id());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.wall(&wall.id()).is_some());
+        assert_eq!(
+            map.get_wall_at_position(&Coordinate { x: 50., y: 0. }),
+            Some(wall.id())
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    gizmo::Id,
+};
+
+use crate::map::{map::Map, water::Water};
+
+/// Adds a river, lake or sea to the map.
+pub struct CreateWater {
+    water: Water,
+}
+
+impl CreateWater {
+    pub fn new(water: Water) -> Self {
+        CreateWater { water }
+    }
+}
+
+impl Undo<Map> for CreateWater {
+    fn undo(&mut self, map: &mut Map) {
+        map.remove_water(&self.water.id());
+    }
+}
+
+impl Redo<Map> for CreateWater {
+    fn redo(&mut self, map: &mut Map) {
+        map.add_water(self.water.clone());
+    }
+}
+
+impl Action<Map> for CreateWater {}
+
+impl fmt::Display for CreateWater {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[create_water] water={}, kind={}",
+            self.water.id(),
+            self.water.kind()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{
+        map::Map,
+        water::{Water, WaterKind},
+    };
+
+    use super::CreateWater;
+
+    fn create_lake() -> Water {
+        Water::new(
+            WaterKind::Lake,
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 100.)]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn water_create_redo_works() {
+        let mut map = Map::new(1000, 1000);
+        let lake = create_lake();
+
+        let mut action = CreateWater::new(lake.clone());
+        action.execute(&mut map);
+
+        assert!(map.water(&lake.id()).is_some());
+        assert_eq!(
+            map.get_water_at_position(&Coordinate { x: 50., y: 50. }),
+            Some(lake.id())
+        );
+    }
+
+    #[test]
+    fn water_create_undo_works() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = CreateWater::new(create_lake());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.waters().is_empty());
+        assert_eq!(
+            map.get_water_at_position(&Coordinate { x: 50., y: 50. }),
+            None
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use rust_editor::actions::{Action, Redo, Undo};
+use uuid::Uuid;
+
+use crate::map::{map::Map, water::Water};
+
+/// Removes a river, lake or sea from the map. The water is kept so that undo restores it.
+pub struct DeleteWater {
+    water_id: Uuid,
+    water: Option<Water>,
+}
+
+impl DeleteWater {
+    pub fn new(water_id: Uuid) -> Self {
+        DeleteWater {
+            water_id,
+            water: None,
+        }
+    }
+}
+
+impl Undo<Map> for DeleteWater {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(water) = self.water.take() {
+            map.add_water(water);
+        }
+    }
+}
+
+impl Redo<Map> for DeleteWater {
+    fn redo(&mut self, map: &mut Map) {
+        self.water = map.remove_water(&self.water_id);
+    }
+}
+
+impl Action<Map> for DeleteWater {}
+
+impl fmt::Display for DeleteWater {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[delete_water] water={}", self.water_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{
+        map::Map,
+        water::{Water, WaterKind},
+    };
+
+    use super::DeleteWater;
+
+    fn create_map() -> (Map, Water) {
+        let mut map = Map::new(1000, 1000);
+        let river = Water::new(
+            WaterKind::River,
+            LineString::from(vec![(0., 50.), (200., 50.)]),
+            vec![10., 30.],
+        );
+        map.add_water(river.clone());
+
+        (map, river)
+    }
+
+    #[test]
+    fn water_delete_redo_works() {
+        let (mut map, river) = create_map();
+
+        let mut action = DeleteWater::new(river.id());
+        action.execute(&mut map);
+
+        assert!(map.water(&river.id()).is_none());
+    }
+
+    #[test]
+    fn water_delete_undo_works() {
+        let (mut map, river) = create_map();
+
+        let mut action = DeleteWater::new(river.id());
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.water(&river.id()).is_some());
+        assert_eq!(
+            map.get_water_at_position(&Coordinate { x: 100., y: 50. }),
+            Some(river.id())
+        );
+    }
+}
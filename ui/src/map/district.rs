@@ -1,24 +1,56 @@
-use geo::{prelude::{Contains}, Coordinate, LineString, Polygon};
-use rand::SeedableRng;
+use geo::{prelude::{Centroid, Contains}, Coordinate, LineString, Polygon};
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use rust_editor::{
     gizmo::Id,
     interactive_element::{InteractiveElement, InteractiveElementState},
-    style::{InteractiveElementStyle, Style},
+    style::{InteractiveElementStyle, Style, TextStyle},
+};
+use rust_editor::{
+    gizmo::SetId,
+    renderer::{render_text, Canvas, PrimitiveRenderer, RenderError},
 };
-use rust_editor::{gizmo::SetId, renderer::PrimitiveRenderer};
 use rust_macro::ElementId;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use wasm_bindgen::JsValue;
-use web_sys::CanvasRenderingContext2d; // TODO
 
-use super::house::generate_houses_from_polygon;
+use super::{
+    district_kind::{generate_features, DistrictKind, Feature},
+    footprint::Footprint,
+    house::{generate_houses_from_polygon, Subdivision},
+    ward::Ward,
+};
+
+/// Minimum side length of the houses of districts that are created automatically from the street network.
+pub(crate) const MINIMUM_HOUSE_SIDE: f64 = 250.;
+
+/// Seed of the random number generator that is used to generate the content of a district.
+pub type Seed = <ChaCha8Rng as SeedableRng>::Seed;
+
+/// Derives the seed of a district from the seed of the map and the position of the district polygon.
+/// The same street layout therefore always results in the same districts, independent of the order
+/// in which the streets were drawn.
+pub fn derive_district_seed(map_seed: u64, polygon: &Polygon<f64>) -> Seed {
+    let mut rng = ChaCha8Rng::seed_from_u64(map_seed);
+
+    if let Some(centroid) = polygon.centroid() {
+        let x = centroid.x().round() as i64 as u64;
+        let y = centroid.y().round() as i64 as u64;
+
+        rng.set_stream((x << 32) ^ (y & 0xFFFF_FFFF));
+    }
+
+    rng.gen()
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct House {
+    /// Footprint of the building.
     pub polygon: Polygon<f64>,
 
+    /// The lot the building stands on.
+    pub lot: Polygon<f64>,
+
     #[serde(skip_serializing)]
     pub line_styles: Vec<Style>,
 
@@ -26,6 +58,41 @@ pub struct House {
     pub style: Style,
 }
 
+impl House {
+    /// Creates a house with the default style from its lot and the footprint of the building on the lot.
+    pub fn new(lot: Polygon<f64>, polygon: Polygon<f64>) -> Self {
+        let line_styles = polygon
+            .exterior()
+            .lines()
+            .map(|_| Style {
+                border_width: 4,
+                border_color: "#FFFFFF".to_string(),
+                background_color: "".to_string(),
+            })
+            .collect();
+
+        House {
+            polygon,
+            lot,
+            line_styles,
+            style: Style {
+                border_width: 2,
+                border_color: "#FFFFFF".to_string(),
+                background_color: "rgba(255, 255, 255, 0.3)".to_string(),
+            },
+        }
+    }
+
+    /// Style of the boundaries of lots that are not completely built over.
+    pub fn lot_style() -> Style {
+        Style {
+            border_width: 1,
+            border_color: "rgba(255, 255, 255, 0.5)".to_string(),
+            background_color: "".to_string(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, ElementId, Clone)]
 pub struct District {
     pub(crate) id: Uuid,
@@ -40,10 +107,29 @@ pub struct District {
     #[serde(skip_serializing)]
     pub(crate) minimum_house_side: f64,
 
-    #[serde(skip_serializing)]
-    pub(crate) seed: <ChaCha8Rng as SeedableRng>::Seed,
+    #[serde(default)]
+    pub(crate) subdivision: Subdivision,
+
+    #[serde(default)]
+    pub(crate) footprint: Footprint,
+
+    pub(crate) seed: Seed,
 
     pub(crate) houses: Vec<House>,
+
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+
+    #[serde(default)]
+    pub(crate) ward: Option<Ward>,
+
+    #[serde(default)]
+    pub(crate) kind: DistrictKind,
+
+    /// Trees, fields, graves or stalls of districts that are not residential. They are generated from the seed
+    /// and are not saved.
+    #[serde(skip)]
+    pub(crate) features: Vec<Feature>,
 }
 
 impl Default for District {
@@ -70,8 +156,14 @@ impl Default for District {
             },
             state: InteractiveElementState::Normal,
             minimum_house_side: 500.0,
+            subdivision: Subdivision::default(),
+            footprint: Footprint::default(),
             houses: Vec::new(),
             seed: Default::default(),
+            name: None,
+            ward: None,
+            kind: DistrictKind::default(),
+            features: Vec::new(),
         }
     }
 }
@@ -103,12 +195,90 @@ impl District {
         &self.polygon
     }
 
-    pub fn update_houses(&mut self) {
-        self.houses =
-            generate_houses_from_polygon(&self.polygon, self.minimum_house_side, self.seed);
+    /// Generates the content of the district according to its kind. Residential districts get houses, all other
+    /// kinds get features.
+    pub fn update_content(&mut self) {
+        match self.kind {
+            DistrictKind::Residential => {
+                self.houses = generate_houses_from_polygon(
+                    &self.polygon,
+                    self.minimum_house_side,
+                    &self.subdivision,
+                    &self.footprint,
+                    self.seed,
+                );
+                self.features.clear();
+            }
+            kind => {
+                self.houses.clear();
+                self.features = generate_features(kind, &self.polygon, self.seed);
+            }
+        }
     }
 
-    pub fn render(&self, context: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    /// Sets the seed of the district and regenerates its content.
+    pub fn set_seed(&mut self, seed: Seed) {
+        self.seed = seed;
+        self.update_content();
+    }
+
+    /// Replaces the seed with the next one derived from the current seed and regenerates the content.
+    /// As the next seed only depends on the current one rerolling is reproducible as well.
+    pub fn reroll(&mut self) {
+        let seed = ChaCha8Rng::from_seed(self.seed).gen();
+        self.set_seed(seed);
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn ward(&self) -> Option<Ward> {
+        self.ward
+    }
+
+    pub fn set_ward(&mut self, ward: Option<Ward>) {
+        self.ward = ward;
+    }
+
+    pub fn kind(&self) -> DistrictKind {
+        self.kind
+    }
+
+    /// Sets the kind of the district and regenerates its content.
+    pub fn set_kind(&mut self, kind: DistrictKind) {
+        self.kind = kind;
+        self.update_content();
+    }
+
+    /// Style of the labels of named districts.
+    pub fn label_style() -> TextStyle {
+        TextStyle {
+            size: 20,
+            halo_color: "rgba(0, 0, 0, 0.6)".to_string(),
+            halo_width: 3,
+            ..Default::default()
+        }
+    }
+
+    /// Renders the name of the district centered in the district. Unnamed districts have no label.
+    pub fn render_label(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        if let (Some(name), Some(centroid)) = (&self.name, self.polygon.centroid()) {
+            render_text(name, centroid.into(), &District::label_style(), context)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
 
         /*
         self.polygon.render(&Style {
@@ -172,7 +342,25 @@ impl District {
         
         context.save();
 
+        if let Some(ward) = self.ward {
+            self.polygon.render(&ward.style(), context)?;
+        }
+
+        if let Some(style) = self.kind.style() {
+            self.polygon.render(&style, context)?;
+        }
+
+        for feature in &self.features {
+            feature.render(context)?;
+        }
+
         for p in &self.houses {
+            if p.lot != p.polygon {
+                for line in p.lot.exterior().lines() {
+                    line.render(&House::lot_style(), context)?;
+                }
+            }
+
             p.polygon.render(&p.style, context)?;
 
             for (line, style) in p.polygon.exterior().lines().zip(p.line_styles.iter()) {
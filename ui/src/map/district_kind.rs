@@ -0,0 +1,442 @@
+use std::{cmp::Ordering, f64::consts::PI, fmt, str::FromStr};
+
+use geo::{
+    prelude::{Contains, EuclideanLength},
+    Coordinate, LineString, Polygon,
+};
+use geo_booleanop::boolean::BooleanOp;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rust_editor::{
+    renderer::{apply_style, Canvas, PrimitiveRenderer, RenderError},
+    style::Style,
+};
+use serde::{Deserialize, Serialize};
+
+use super::district::Seed;
+
+/// Distance between the trees of a park before they are jittered.
+const TREE_SPACING: f64 = 14.;
+
+/// Probability that a tree is planted at a place of the grid of a park.
+const TREE_DENSITY: f64 = 0.7;
+
+/// Width of a field strip of farmland before it is varied.
+const FIELD_WIDTH: f64 = 24.;
+
+/// Probability that a field strip lies fallow.
+const FALLOW_PROBABILITY: f64 = 0.3;
+
+/// Size of a grave along and across its row.
+const GRAVE_SIZE: (f64, f64) = (3., 6.);
+
+/// Distance between two graves of a row and between two rows of graves.
+const GRAVE_SPACING: (f64, f64) = (6., 10.);
+
+/// Side length of a market stall.
+const STALL_SIZE: f64 = 8.;
+
+/// Distance between two market stalls.
+const STALL_SPACING: f64 = 16.;
+
+/// Probability that a place of the grid of a cemetery or a market is left empty.
+const GAP_PROBABILITY: f64 = 0.2;
+
+/// Use of the area of a district. The kind selects what is generated within the district.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DistrictKind {
+    /// Houses on lots along the streets.
+    Residential,
+
+    /// Scattered trees.
+    Park,
+
+    /// An empty paved area.
+    Plaza,
+
+    /// A paved area with rows of stalls.
+    Market,
+
+    /// Strips of fields.
+    Farmland,
+
+    /// Rows of graves.
+    Cemetery,
+}
+
+impl DistrictKind {
+    pub const ALL: [DistrictKind; 6] = [
+        DistrictKind::Residential,
+        DistrictKind::Park,
+        DistrictKind::Plaza,
+        DistrictKind::Market,
+        DistrictKind::Farmland,
+        DistrictKind::Cemetery,
+    ];
+
+    /// Style of the area of a district of this kind. Residential districts have no area of their own.
+    pub fn style(&self) -> Option<Style> {
+        let background_color = match self {
+            DistrictKind::Residential => return None,
+            DistrictKind::Park => "rgba(102, 187, 106, 0.5)",
+            DistrictKind::Plaza => "rgba(215, 204, 200, 0.6)",
+            DistrictKind::Market => "rgba(230, 214, 170, 0.6)",
+            DistrictKind::Farmland => "rgba(220, 231, 117, 0.3)",
+            DistrictKind::Cemetery => "rgba(144, 164, 174, 0.5)",
+        };
+
+        Some(Style {
+            border_width: 0,
+            border_color: "#FFFFFF".to_string(),
+            background_color: background_color.to_string(),
+        })
+    }
+}
+
+impl Default for DistrictKind {
+    fn default() -> Self {
+        DistrictKind::Residential
+    }
+}
+
+impl fmt::Display for DistrictKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DistrictKind::Residential => "residential",
+            DistrictKind::Park => "park",
+            DistrictKind::Plaza => "plaza",
+            DistrictKind::Market => "market",
+            DistrictKind::Farmland => "farmland",
+            DistrictKind::Cemetery => "cemetery",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for DistrictKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DistrictKind::ALL
+            .iter()
+            .find(|kind| kind.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown district kind \"{}\"", s))
+    }
+}
+
+/// Content of a district that is not a house.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Feature {
+    Tree {
+        position: Coordinate<f64>,
+        radius: f64,
+    },
+
+    /// A field strip. Fallow fields are rendered in a different color.
+    Field {
+        polygon: Polygon<f64>,
+        fallow: bool,
+    },
+
+    Grave(Polygon<f64>),
+
+    Stall(Polygon<f64>),
+}
+
+impl Feature {
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        let style = |border_color: &str, background_color: &str| Style {
+            border_width: 1,
+            border_color: border_color.to_string(),
+            background_color: background_color.to_string(),
+        };
+
+        match self {
+            Feature::Tree { position, radius } => {
+                context.begin_path();
+                context.arc(position.x, position.y, *radius, 0.0, 2.0 * PI)?;
+                apply_style(&style("#2e7d32", "rgba(56, 142, 60, 0.9)"), context);
+            }
+            Feature::Field { polygon, fallow } => {
+                let background_color = match fallow {
+                    true => "rgba(188, 170, 164, 0.5)",
+                    false => "rgba(205, 220, 57, 0.5)",
+                };
+
+                polygon.render(
+                    &style("rgba(255, 255, 255, 0.4)", background_color),
+                    context,
+                )?;
+            }
+            Feature::Grave(polygon) => polygon.render(&style("#78909c", "#eceff1"), context)?,
+            Feature::Stall(polygon) => polygon.render(&style("#8d6e63", "#ffcc80"), context)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the features of a district of `kind` within `polygon`. Residential districts get houses instead of
+/// features and plazas stay empty.
+pub fn generate_features(kind: DistrictKind, polygon: &Polygon<f64>, seed: Seed) -> Vec<Feature> {
+    let mut rng = ChaCha8Rng::from_seed(seed);
+
+    match kind {
+        DistrictKind::Residential | DistrictKind::Plaza => vec![],
+        DistrictKind::Park => trees(polygon, &mut rng),
+        DistrictKind::Market => AlignedGrid::new(polygon, STALL_SPACING, STALL_SPACING)
+            .rectangles(STALL_SIZE, STALL_SIZE)
+            .into_iter()
+            .filter(|_| !rng.gen_bool(GAP_PROBABILITY))
+            .map(Feature::Stall)
+            .collect(),
+        DistrictKind::Farmland => field_strips(polygon, &mut rng),
+        DistrictKind::Cemetery => AlignedGrid::new(polygon, GRAVE_SPACING.0, GRAVE_SPACING.1)
+            .rectangles(GRAVE_SIZE.0, GRAVE_SIZE.1)
+            .into_iter()
+            .filter(|_| !rng.gen_bool(GAP_PROBABILITY))
+            .map(Feature::Grave)
+            .collect(),
+    }
+}
+
+/// Plants trees on a jittered grid and leaves some places of the grid empty.
+fn trees<R: Rng>(polygon: &Polygon<f64>, rng: &mut R) -> Vec<Feature> {
+    let jitter = TREE_SPACING / 3.;
+    let mut trees = vec![];
+
+    for center in AlignedGrid::new(polygon, TREE_SPACING, TREE_SPACING).centers() {
+        if !rng.gen_bool(TREE_DENSITY) {
+            continue;
+        }
+
+        let position = center
+            + Coordinate {
+                x: rng.gen_range(-jitter..jitter),
+                y: rng.gen_range(-jitter..jitter),
+            };
+        let radius = rng.gen_range(3.0..6.0);
+
+        if polygon.contains(&position) {
+            trees.push(Feature::Tree { position, radius });
+        }
+    }
+
+    trees
+}
+
+/// Cuts `polygon` into strips parallel to its longest side. The width of each strip varies around
+/// [`FIELD_WIDTH`].
+fn field_strips<R: Rng>(polygon: &Polygon<f64>, rng: &mut R) -> Vec<Feature> {
+    let grid = AlignedGrid::new(polygon, FIELD_WIDTH, FIELD_WIDTH);
+    let mut fields = vec![];
+
+    let mut offset = grid.min.y;
+    while offset < grid.max.y {
+        let width = FIELD_WIDTH * rng.gen_range(0.7..1.3);
+        let strip = grid.rectangle(
+            Coordinate {
+                x: (grid.min.x + grid.max.x) / 2.,
+                y: offset + width / 2.,
+            },
+            grid.max.x - grid.min.x + 2.,
+            width,
+        );
+
+        let fallow = rng.gen_bool(FALLOW_PROBABILITY);
+        for piece in polygon.intersection(&strip).0 {
+            fields.push(Feature::Field {
+                polygon: piece,
+                fallow,
+            });
+        }
+
+        offset += width;
+    }
+
+    fields
+}
+
+/// A regular grid that is aligned to the longest side of a polygon. Grid coordinates are `x` along and `y`
+/// across the longest side.
+struct AlignedGrid<'a> {
+    polygon: &'a Polygon<f64>,
+    along: Coordinate<f64>,
+    across: Coordinate<f64>,
+
+    /// Extent of the polygon in grid coordinates.
+    min: Coordinate<f64>,
+    max: Coordinate<f64>,
+
+    spacing: Coordinate<f64>,
+}
+
+impl<'a> AlignedGrid<'a> {
+    fn new(polygon: &'a Polygon<f64>, spacing_along: f64, spacing_across: f64) -> Self {
+        let along = polygon
+            .exterior()
+            .lines()
+            .max_by(|a, b| {
+                a.euclidean_length()
+                    .partial_cmp(&b.euclidean_length())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .map(|line| line.end - line.start)
+            .filter(|side| side.x != 0. || side.y != 0.)
+            .map(|side| side * (1. / side.x.hypot(side.y)))
+            .unwrap_or(Coordinate { x: 1., y: 0. });
+
+        let mut grid = AlignedGrid {
+            polygon,
+            along,
+            across: Coordinate {
+                x: -along.y,
+                y: along.x,
+            },
+            min: Coordinate {
+                x: f64::INFINITY,
+                y: f64::INFINITY,
+            },
+            max: Coordinate {
+                x: f64::NEG_INFINITY,
+                y: f64::NEG_INFINITY,
+            },
+            spacing: Coordinate {
+                x: spacing_along,
+                y: spacing_across,
+            },
+        };
+
+        for point in polygon.exterior().0.iter() {
+            let local = grid.to_local(point);
+
+            grid.min.x = grid.min.x.min(local.x);
+            grid.min.y = grid.min.y.min(local.y);
+            grid.max.x = grid.max.x.max(local.x);
+            grid.max.y = grid.max.y.max(local.y);
+        }
+
+        grid
+    }
+
+    fn to_local(&self, point: &Coordinate<f64>) -> Coordinate<f64> {
+        Coordinate {
+            x: point.x * self.along.x + point.y * self.along.y,
+            y: point.x * self.across.x + point.y * self.across.y,
+        }
+    }
+
+    fn to_world(&self, local: &Coordinate<f64>) -> Coordinate<f64> {
+        self.along * local.x + self.across * local.y
+    }
+
+    /// Centers of the grid cells that lie within the polygon, in world coordinates.
+    fn centers(&self) -> Vec<Coordinate<f64>> {
+        if self.min.x > self.max.x || self.min.y > self.max.y {
+            return vec![];
+        }
+
+        let columns = ((self.max.x - self.min.x) / self.spacing.x).floor() as usize;
+        let rows = ((self.max.y - self.min.y) / self.spacing.y).floor() as usize;
+
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                self.to_world(&Coordinate {
+                    x: self.min.x + (column as f64 + 0.5) * self.spacing.x,
+                    y: self.min.y + (row as f64 + 0.5) * self.spacing.y,
+                })
+            })
+            .filter(|center| self.polygon.contains(center))
+            .collect()
+    }
+
+    /// Rectangle of `length` along and `width` across the longest side around `center` in grid coordinates.
+    fn rectangle(&self, center: Coordinate<f64>, length: f64, width: f64) -> Polygon<f64> {
+        let corners = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)].map(|(x, y)| {
+            self.to_world(&Coordinate {
+                x: center.x + x * length / 2.,
+                y: center.y + y * width / 2.,
+            })
+        });
+
+        Polygon::new(LineString::from(corners.to_vec()), vec![])
+    }
+
+    /// Rectangles of `length` along and `width` across the longest side around the centers of the grid cells.
+    /// Only rectangles that lie completely within the polygon are returned.
+    fn rectangles(&self, length: f64, width: f64) -> Vec<Polygon<f64>> {
+        self.centers()
+            .into_iter()
+            .map(|center| self.rectangle(self.to_local(&center), length, width))
+            .filter(|rectangle| {
+                rectangle
+                    .exterior()
+                    .0
+                    .iter()
+                    .all(|corner| self.polygon.contains(corner))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::Contains, LineString, Polygon};
+
+    use super::{generate_features, DistrictKind, Feature};
+
+    fn rectangle() -> Polygon<f64> {
+        Polygon::new(
+            LineString::from(vec![(0., 0.), (200., 0.), (200., 100.), (0., 100.)]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn unit_district_kind_from_str_matches_display() {
+        for kind in DistrictKind::ALL {
+            assert_eq!(kind.to_string().parse::<DistrictKind>(), Ok(kind));
+        }
+    }
+
+    #[test]
+    fn unit_features_are_within_district() {
+        let polygon = rectangle();
+
+        for kind in DistrictKind::ALL {
+            for feature in generate_features(kind, &polygon, [3; 32]) {
+                match feature {
+                    Feature::Tree { position, .. } => assert!(polygon.contains(&position)),
+                    Feature::Grave(rectangle) | Feature::Stall(rectangle) => assert!(rectangle
+                        .exterior()
+                        .0
+                        .iter()
+                        .all(|corner| polygon.contains(corner))),
+                    Feature::Field { .. } => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unit_each_kind_gets_its_own_features() {
+        let polygon = rectangle();
+        let features = |kind| generate_features(kind, &polygon, [3; 32]);
+
+        assert!(features(DistrictKind::Residential).is_empty());
+        assert!(features(DistrictKind::Plaza).is_empty());
+        assert!(features(DistrictKind::Park)
+            .iter()
+            .all(|feature| matches!(feature, Feature::Tree { .. })));
+        assert!(features(DistrictKind::Cemetery).len() > 50);
+        assert!(features(DistrictKind::Farmland).len() >= 3);
+        assert_eq!(
+            features(DistrictKind::Market),
+            features(DistrictKind::Market)
+        );
+    }
+}
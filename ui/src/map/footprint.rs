@@ -0,0 +1,449 @@
+use std::cmp::Ordering;
+
+use geo::{
+    euclidean_length::EuclideanLength,
+    prelude::{Area, EuclideanDistance},
+    Coordinate, Line, MultiPolygon, Point, Polygon,
+};
+use geo_booleanop::boolean::BooleanOp;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithm::geo::{clip, is_convex, AnnotatedPolygon};
+
+/// Lots are only merged into a terraced row if the row is at most this much smaller than its convex hull. Rows
+/// therefore follow a straight street and do not turn around corners.
+const ROW_CONVEXITY_TOLERANCE: f64 = 1.05;
+
+/// Setbacks are only applied to lots that are at most this much smaller than their convex hull, as the half planes
+/// of the edges of concave lots cut off parts of the lot that are far away from the edge.
+const LOT_CONVEXITY_TOLERANCE: f64 = 1.05;
+
+/// Maximum distance between two edges that are treated as the same edge.
+const EDGE_EPSILON: f64 = 1e-4;
+
+/// Settings of the buildings that are placed on the lots of a district.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Footprint {
+    /// Distance between the buildings and the street.
+    pub front_setback: f64,
+
+    /// Distance between the buildings and the lot boundaries that meet the street.
+    pub side_setback: f64,
+
+    /// Distance between the buildings and the lot boundaries at the rear.
+    pub back_setback: f64,
+
+    /// Depth of the wings of L and U shaped buildings and of the ring of buildings around a courtyard.
+    pub wing_depth: f64,
+
+    pub courtyard_probability: f64,
+    pub l_shape_probability: f64,
+    pub u_shape_probability: f64,
+
+    /// Maximum number of neighbouring lots along a street that are merged into a terraced row. With a value of 1
+    /// each lot gets its own building.
+    pub terrace_length: usize,
+}
+
+impl Default for Footprint {
+    fn default() -> Self {
+        Footprint {
+            front_setback: 0.,
+            side_setback: 0.,
+            back_setback: 0.,
+            wing_depth: 15.,
+            courtyard_probability: 0.,
+            l_shape_probability: 0.,
+            u_shape_probability: 0.,
+            terrace_length: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edge {
+    Front,
+    Side,
+    Back,
+}
+
+impl Footprint {
+    fn setback(&self, edge: Edge) -> f64 {
+        match edge {
+            Edge::Front => self.front_setback,
+            Edge::Side => self.side_setback,
+            Edge::Back => self.back_setback,
+        }
+    }
+}
+
+/// Places a building on each lot. Neighbouring lots along a street are merged into terraced rows first, then each
+/// lot is inset by the setbacks and by chance shaped into a building around a courtyard or an L or U shaped
+/// building. Lots that are too small for the setbacks are built over completely.
+///
+/// Returns each lot together with the footprint of its building.
+pub fn place_buildings(
+    lots: Vec<AnnotatedPolygon>,
+    footprint: &Footprint,
+    rng: &mut ChaCha8Rng,
+) -> Vec<(Polygon<f64>, Polygon<f64>)> {
+    let lots = match footprint.terrace_length > 1 {
+        true => terraced_rows(lots, footprint.terrace_length),
+        false => lots,
+    };
+
+    lots.into_iter()
+        .map(|lot| {
+            let (choice, left): (f64, bool) = (rng.gen(), rng.gen());
+
+            let building = match inset(&lot, footprint) {
+                Some(building) => shape(&lot, building, footprint, choice, left),
+                None => lot.0.clone(),
+            };
+
+            (lot.0, building)
+        })
+        .collect()
+}
+
+/// Front edges face a street, side edges meet a front edge and all other edges are at the rear of the lot.
+fn edges(lot: &AnnotatedPolygon) -> Vec<Edge> {
+    let lines = lot.lines();
+    let count = lines.len();
+
+    (0..count)
+        .map(|i| {
+            if lines[i].1 {
+                Edge::Front
+            } else if lines[(i + count - 1) % count].1 || lines[(i + 1) % count].1 {
+                Edge::Side
+            } else {
+                Edge::Back
+            }
+        })
+        .collect()
+}
+
+/// Distance of `point` to the line through `line`, positive on the inner side of a polygon with the given
+/// orientation.
+fn inner_distance(orientation: f64, line: &Line<f64>, point: &Coordinate<f64>) -> f64 {
+    let length = line.euclidean_length();
+    let offset = *point - line.start;
+
+    orientation * (line.dx() * offset.y - line.dy() * offset.x) / length
+}
+
+/// Clips `polygon` to the part that is within `distance` of `line` if `within` is set and to the part beyond
+/// `distance` otherwise.
+fn clip_at(
+    polygon: &AnnotatedPolygon,
+    orientation: f64,
+    line: &Line<f64>,
+    distance: f64,
+    within: bool,
+) -> Option<AnnotatedPolygon> {
+    if line.euclidean_length() <= f64::EPSILON {
+        return Some(polygon.clone());
+    }
+
+    clip(polygon, |point| {
+        let offset = inner_distance(orientation, line, point) - distance;
+
+        match within {
+            true => offset,
+            false => -offset,
+        }
+    })
+}
+
+/// Moves each edge of the lot inwards by its setback. Returns `None` if nothing of the lot is left.
+fn inset(lot: &AnnotatedPolygon, footprint: &Footprint) -> Option<AnnotatedPolygon> {
+    if !is_convex(&lot.0, LOT_CONVEXITY_TOLERANCE) {
+        return None;
+    }
+
+    let orientation = lot.0.signed_area().signum();
+
+    let mut building = lot.clone();
+    for ((line, _), edge) in lot.lines().iter().zip(edges(lot)) {
+        let setback = footprint.setback(edge);
+        if setback > 0. {
+            building = clip_at(&building, orientation, line, setback, false)?;
+        }
+    }
+
+    Some(building)
+}
+
+/// Shapes the inset lot into the building that was chosen by `choice`. Buildings that can not be shaped fill the
+/// inset lot.
+fn shape(
+    lot: &AnnotatedPolygon,
+    building: AnnotatedPolygon,
+    footprint: &Footprint,
+    choice: f64,
+    left: bool,
+) -> Polygon<f64> {
+    let courtyard_threshold = footprint.courtyard_probability;
+    let l_shape_threshold = courtyard_threshold + footprint.l_shape_probability;
+    let u_shape_threshold = l_shape_threshold + footprint.u_shape_probability;
+
+    let shaped = if choice < courtyard_threshold {
+        courtyard(&building, footprint.wing_depth)
+    } else if choice < l_shape_threshold {
+        wings(lot, &building, footprint, left, !left)
+    } else if choice < u_shape_threshold {
+        wings(lot, &building, footprint, true, true)
+    } else {
+        None
+    };
+
+    shaped.unwrap_or(building.0)
+}
+
+/// Removes the inner part of the building so that a ring of `depth` remains around a courtyard.
+fn courtyard(building: &AnnotatedPolygon, depth: f64) -> Option<Polygon<f64>> {
+    let orientation = building.0.signed_area().signum();
+
+    let mut inner = building.clone();
+    for (line, _) in building.lines() {
+        inner = clip_at(&inner, orientation, &line, depth, false)?;
+    }
+
+    Some(Polygon::new(
+        building.0.exterior().clone(),
+        vec![inner.0.exterior().clone()],
+    ))
+}
+
+/// Builds a wing along the longest street edge of the lot and wings along the side edges next to it.
+fn wings(
+    lot: &AnnotatedPolygon,
+    building: &AnnotatedPolygon,
+    footprint: &Footprint,
+    left: bool,
+    right: bool,
+) -> Option<Polygon<f64>> {
+    let lines = lot.lines();
+    let count = lines.len();
+    let orientation = lot.0.signed_area().signum();
+    let edges = edges(lot);
+
+    let front = (0..count).filter(|i| lines[*i].1).max_by(|a, b| {
+        lines[*a]
+            .0
+            .euclidean_length()
+            .partial_cmp(&lines[*b].0.euclidean_length())
+            .unwrap_or(Ordering::Equal)
+    })?;
+
+    let mut sides = vec![front];
+    if left {
+        sides.push((front + count - 1) % count);
+    }
+    if right {
+        sides.push((front + 1) % count);
+    }
+
+    let mut result: Option<MultiPolygon<f64>> = None;
+    for side in sides {
+        let depth = footprint.setback(edges[side]) + footprint.wing_depth;
+        let wing = clip_at(building, orientation, &lines[side].0, depth, true)?.0;
+
+        result = Some(match result {
+            Some(result) => result.union(&wing),
+            None => wing.into(),
+        });
+    }
+
+    // Wings that cover the whole building do not shape it
+    let mut polygons = result?.0;
+    match polygons.len() == 1
+        && polygons[0].unsigned_area() < building.0.unsigned_area() - EDGE_EPSILON
+    {
+        true => polygons.pop(),
+        false => None,
+    }
+}
+
+/// Merges neighbouring lots along a straight street into rows of at most `terrace_length` lots.
+fn terraced_rows(lots: Vec<AnnotatedPolygon>, terrace_length: usize) -> Vec<AnnotatedPolygon> {
+    let mut rows: Vec<(AnnotatedPolygon, usize)> = vec![];
+
+    'lots: for lot in lots {
+        for (row, length) in rows.iter_mut() {
+            if *length >= terrace_length {
+                continue;
+            }
+
+            if let Some(merged) = merge(row, &lot) {
+                *row = merged;
+                *length += 1;
+                continue 'lots;
+            }
+        }
+
+        rows.push((lot, 1));
+    }
+
+    rows.into_iter().map(|(row, _)| row).collect()
+}
+
+/// Merges `lot` into `row` if they share an edge that does not face a street and the merged row is convex.
+fn merge(row: &AnnotatedPolygon, lot: &AnnotatedPolygon) -> Option<AnnotatedPolygon> {
+    let on_boundary = |point: Coordinate<f64>| {
+        Point::from(point).euclidean_distance(row.0.exterior()) <= EDGE_EPSILON
+    };
+    let shares_edge = lot.lines().iter().any(|(line, is_street)| {
+        !is_street
+            && line.euclidean_length() > EDGE_EPSILON
+            && on_boundary(line.start)
+            && on_boundary(line.end)
+    });
+    if !shares_edge {
+        return None;
+    }
+
+    let mut union = row.0.union(&lot.0).0;
+    if union.len() != 1 {
+        return None;
+    }
+
+    let polygon = union.pop()?;
+    if !polygon.interiors().is_empty()
+        || !is_convex(&polygon, ROW_CONVEXITY_TOLERANCE)
+        || (polygon.unsigned_area() - row.0.unsigned_area() - lot.0.unsigned_area()).abs()
+            > EDGE_EPSILON
+    {
+        return None;
+    }
+
+    // Edges of the row face a street if they are part of a street edge of the lots
+    let streets: Vec<Line<f64>> = row
+        .lines()
+        .into_iter()
+        .chain(lot.lines())
+        .filter(|(_, is_street)| *is_street)
+        .map(|(line, _)| line)
+        .collect();
+    let is_street = polygon
+        .exterior()
+        .lines()
+        .map(|line| {
+            let middle = Point::from((line.start + line.end) / 2.);
+            streets
+                .iter()
+                .any(|street| middle.euclidean_distance(street) <= EDGE_EPSILON)
+        })
+        .collect();
+
+    Some(AnnotatedPolygon(polygon, is_street))
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::Area, LineString, Polygon};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::algorithm::geo::AnnotatedPolygon;
+
+    use super::{place_buildings, Footprint};
+
+    /// Three lots of 20 x 30 along a street at the bottom.
+    fn lots() -> Vec<AnnotatedPolygon> {
+        (0..3)
+            .map(|i| {
+                let x = 20. * i as f64;
+                let polygon = Polygon::new(
+                    LineString::from(vec![(x, 0.), (x + 20., 0.), (x + 20., 30.), (x, 30.)]),
+                    vec![],
+                );
+
+                AnnotatedPolygon(polygon, vec![true, false, false, false])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unit_default_footprint_covers_lot() {
+        let buildings = place_buildings(
+            lots(),
+            &Footprint::default(),
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+
+        assert_eq!(buildings.len(), 3);
+        assert!(buildings.iter().all(|(lot, building)| lot == building));
+    }
+
+    #[test]
+    fn unit_setbacks_inset_lot() {
+        let footprint = Footprint {
+            front_setback: 5.,
+            side_setback: 2.,
+            back_setback: 10.,
+            ..Footprint::default()
+        };
+        let buildings = place_buildings(lots(), &footprint, &mut ChaCha8Rng::seed_from_u64(1));
+
+        for (lot, building) in buildings {
+            assert_eq!(lot.unsigned_area(), 600.);
+            assert!((building.unsigned_area() - 16. * 15.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn unit_shapes_remove_part_of_building() {
+        for (courtyard, l_shape, u_shape) in [(1., 0., 0.), (0., 1., 0.), (0., 0., 1.)] {
+            let footprint = Footprint {
+                wing_depth: 6.,
+                courtyard_probability: courtyard,
+                l_shape_probability: l_shape,
+                u_shape_probability: u_shape,
+                ..Footprint::default()
+            };
+            let buildings = place_buildings(lots(), &footprint, &mut ChaCha8Rng::seed_from_u64(2));
+
+            for (lot, building) in buildings {
+                assert!(building.unsigned_area() < lot.unsigned_area());
+                assert!(building.unsigned_area() > 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn unit_courtyard_is_interior() {
+        let footprint = Footprint {
+            wing_depth: 6.,
+            courtyard_probability: 1.,
+            ..Footprint::default()
+        };
+        let buildings = place_buildings(lots(), &footprint, &mut ChaCha8Rng::seed_from_u64(3));
+
+        // 20 x 30 with a courtyard of 8 x 18
+        assert!(buildings
+            .iter()
+            .all(|(_, building)| building.interiors().len() == 1));
+        assert!((buildings[0].1.unsigned_area() - (600. - 144.)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unit_terraced_rows_merge_lots() {
+        let footprint = Footprint {
+            front_setback: 2.,
+            side_setback: 3.,
+            terrace_length: 2,
+            ..Footprint::default()
+        };
+        let buildings = place_buildings(lots(), &footprint, &mut ChaCha8Rng::seed_from_u64(4));
+
+        assert_eq!(buildings.len(), 2);
+        assert!((buildings[0].0.unsigned_area() - 1200.).abs() < 1e-6);
+
+        // Side setbacks only separate the ends of a row
+        assert!((buildings[0].1.unsigned_area() - 34. * 28.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,1109 @@
+use geo::{Coordinate, LineString, Polygon};
+use rust_editor::gizmo::{GetPosition, Id, SetId};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonObject, Value};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    district::{derive_district_seed, District, House, Seed, MINIMUM_HOUSE_SIDE},
+    district_kind::DistrictKind,
+    footprint::Footprint,
+    house::Subdivision,
+    intersection::{Direction, Intersection},
+    landmark::{Landmark, LandmarkKind},
+    street::Street,
+    street_class::StreetClass,
+    terrain::{Terrain, TerrainShading, TerrainSource},
+    wall::Wall,
+    ward::Ward,
+    water::{Water, WaterKind},
+};
+
+/// Version of the map file format written by this version of the editor. Increase it each time the
+/// schema changes and add a migration from the previous version to [`MIGRATIONS`].
+pub const CURRENT_VERSION: u32 = 14;
+
+/// Version assumed for files that do not contain a version field. These files were written before
+/// the format was versioned.
+const UNVERSIONED: u32 = 1;
+
+/// Maximum distance between two street ends to be treated as the same intersection while migrating
+/// files that do not contain intersections.
+const INTERSECTION_EPSILON: f64 = 1e-6;
+
+type Migration = fn(Value) -> Result<Value, MapFormatError>;
+
+/// Migrations between consecutive versions. The migration at index `i` converts a file of version
+/// `i + 1` into a file of version `i + 2`.
+const MIGRATIONS: [Migration; (CURRENT_VERSION - UNVERSIONED) as usize] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+    migrate_v10_to_v11,
+    migrate_v11_to_v12,
+    migrate_v12_to_v13,
+    migrate_v13_to_v14,
+];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MapFormatError {
+    #[error(
+        "the map file has version {} but only versions up to {} are supported",
+        found,
+        CURRENT_VERSION
+    )]
+    UnsupportedVersion { found: u32 },
+
+    #[error("the map file is invalid: {}", reason)]
+    Invalid { reason: String },
+}
+
+impl From<serde_json::Error> for MapFormatError {
+    fn from(error: serde_json::Error) -> Self {
+        MapFormatError::Invalid {
+            reason: error.to_string(),
+        }
+    }
+}
+
+/// On-disk representation of a map. It only contains the data that can not be derived from other
+/// data, the street polygon, the district faces and the spatial indices are rebuilt on load.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MapFile {
+    pub version: u32,
+    pub width: u32,
+    pub height: u32,
+    pub seed: u64,
+    pub streets: Vec<StreetRecord>,
+    pub intersections: Vec<IntersectionRecord>,
+    pub districts: Vec<DistrictRecord>,
+    pub walls: Vec<WallRecord>,
+    pub terrain: Option<TerrainRecord>,
+    pub waters: Vec<WaterRecord>,
+    pub landmarks: Vec<LandmarkRecord>,
+    pub block_junctions: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct StreetRecord {
+    pub id: Uuid,
+    pub line_string: LineString<f64>,
+    pub control_points: Vec<Coordinate<f64>>,
+    pub handles: Vec<[Coordinate<f64>; 2]>,
+    pub class: StreetClass,
+    pub width: f64,
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct IntersectionRecord {
+    pub id: Uuid,
+    pub position: Coordinate<f64>,
+    pub connected_streets: Vec<(Direction, Uuid)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DistrictRecord {
+    pub id: Uuid,
+    pub polygon: Polygon<f64>,
+    pub seed: Seed,
+    pub minimum_house_side: f64,
+    pub subdivision: Subdivision,
+    pub footprint: Footprint,
+    pub houses: Vec<HouseRecord>,
+    pub name: Option<String>,
+    pub ward: Option<Ward>,
+    pub kind: DistrictKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct HouseRecord {
+    pub lot: Polygon<f64>,
+    pub footprint: Polygon<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WallRecord {
+    pub id: Uuid,
+    pub ring: LineString<f64>,
+    pub towers: Vec<Coordinate<f64>>,
+    pub gates: Vec<Coordinate<f64>>,
+    pub width: f64,
+    pub gate_width: f64,
+}
+
+/// The area of a water is derived from its line string and is not stored.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WaterRecord {
+    pub id: Uuid,
+    pub kind: WaterKind,
+    pub line_string: LineString<f64>,
+    pub widths: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LandmarkRecord {
+    pub id: Uuid,
+    pub kind: LandmarkKind,
+    pub position: Coordinate<f64>,
+    pub rotation: f64,
+    pub name: Option<String>,
+    pub notes: String,
+}
+
+/// The terrain is stored by its source, the heightmap is generated again on load.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TerrainRecord {
+    pub source: TerrainSource,
+    pub relief: f64,
+    pub shading: TerrainShading,
+}
+
+impl From<&Street> for StreetRecord {
+    fn from(street: &Street) -> Self {
+        StreetRecord {
+            id: street.id(),
+            line_string: street.lines.clone(),
+            control_points: street.control_points().clone(),
+            handles: street.handles().clone(),
+            class: street.class(),
+            width: street.width(),
+            name: street.name().cloned(),
+        }
+    }
+}
+
+impl From<StreetRecord> for Street {
+    fn from(record: StreetRecord) -> Self {
+        let mut street = match record.control_points.len() {
+            0 => Street::new_with_class(record.line_string, record.class),
+            _ => {
+                Street::new_curved_with_handles(record.control_points, record.handles, record.class)
+            }
+        };
+        street.set_id(record.id);
+        street.set_width(record.width);
+        street.set_name(record.name);
+
+        street
+    }
+}
+
+impl From<&Intersection> for IntersectionRecord {
+    fn from(intersection: &Intersection) -> Self {
+        IntersectionRecord {
+            id: intersection.id(),
+            position: intersection.position(),
+            connected_streets: intersection.get_connected_streets().clone(),
+        }
+    }
+}
+
+impl From<IntersectionRecord> for Intersection {
+    fn from(record: IntersectionRecord) -> Self {
+        let mut intersection = Intersection::new_with_id(record.position, record.id);
+        for (direction, street) in record.connected_streets {
+            match direction {
+                Direction::In => intersection.add_incoming_street(&street),
+                Direction::Out => intersection.add_outgoing_street(&street),
+            }
+        }
+
+        intersection
+    }
+}
+
+impl From<&District> for DistrictRecord {
+    fn from(district: &District) -> Self {
+        DistrictRecord {
+            id: district.id(),
+            polygon: district.polygon.clone(),
+            seed: district.seed,
+            minimum_house_side: district.minimum_house_side,
+            subdivision: district.subdivision.clone(),
+            footprint: district.footprint.clone(),
+            houses: district
+                .houses
+                .iter()
+                .map(|house| HouseRecord {
+                    lot: house.lot.clone(),
+                    footprint: house.polygon.clone(),
+                })
+                .collect(),
+            name: district.name.clone(),
+            ward: district.ward,
+            kind: district.kind,
+        }
+    }
+}
+
+/// Houses are stored with the district, the features of other kinds are generated again on load.
+impl From<DistrictRecord> for District {
+    fn from(record: DistrictRecord) -> Self {
+        let mut district = District {
+            id: record.id,
+            polygon: record.polygon,
+            seed: record.seed,
+            minimum_house_side: record.minimum_house_side,
+            subdivision: record.subdivision,
+            footprint: record.footprint,
+            houses: record
+                .houses
+                .into_iter()
+                .map(|house| House::new(house.lot, house.footprint))
+                .collect(),
+            name: record.name,
+            ward: record.ward,
+            kind: record.kind,
+            ..District::default()
+        };
+        if district.kind != DistrictKind::Residential {
+            district.update_content();
+        }
+
+        district
+    }
+}
+
+impl From<&Wall> for WallRecord {
+    fn from(wall: &Wall) -> Self {
+        WallRecord {
+            id: wall.id(),
+            ring: wall.ring().clone(),
+            towers: wall.towers().clone(),
+            gates: wall.gates().clone(),
+            width: wall.width(),
+            gate_width: wall.gate_width(),
+        }
+    }
+}
+
+impl From<WallRecord> for Wall {
+    fn from(record: WallRecord) -> Self {
+        let mut wall = Wall::new(
+            record.ring,
+            record.towers,
+            record.gates,
+            record.width,
+            record.gate_width,
+        );
+        wall.set_id(record.id);
+
+        wall
+    }
+}
+
+impl From<&Water> for WaterRecord {
+    fn from(water: &Water) -> Self {
+        WaterRecord {
+            id: water.id(),
+            kind: water.kind(),
+            line_string: water.line_string().clone(),
+            widths: water.widths().clone(),
+        }
+    }
+}
+
+impl From<WaterRecord> for Water {
+    fn from(record: WaterRecord) -> Self {
+        let mut water = Water::new(record.kind, record.line_string, record.widths);
+        water.set_id(record.id);
+
+        water
+    }
+}
+
+impl From<&Landmark> for LandmarkRecord {
+    fn from(landmark: &Landmark) -> Self {
+        LandmarkRecord {
+            id: landmark.id(),
+            kind: landmark.kind(),
+            position: landmark.position(),
+            rotation: landmark.rotation(),
+            name: landmark.name().cloned(),
+            notes: landmark.notes().clone(),
+        }
+    }
+}
+
+impl From<LandmarkRecord> for Landmark {
+    fn from(record: LandmarkRecord) -> Self {
+        let mut landmark = Landmark::new(record.kind, record.position);
+        landmark.set_id(record.id);
+        landmark.set_rotation(record.rotation);
+        landmark.set_name(record.name);
+        landmark.set_notes(record.notes);
+
+        landmark
+    }
+}
+
+impl From<&Terrain> for TerrainRecord {
+    fn from(terrain: &Terrain) -> Self {
+        TerrainRecord {
+            source: terrain.source().clone(),
+            relief: terrain.relief(),
+            shading: terrain.shading(),
+        }
+    }
+}
+
+/// Brings a map file of any supported version to [`CURRENT_VERSION`] by applying all migrations
+/// between the version of the file and the current one.
+pub fn migrate(mut value: Value) -> Result<MapFile, MapFormatError> {
+    let version = match value.get("version") {
+        Some(version) => version.as_u64().ok_or_else(|| MapFormatError::Invalid {
+            reason: "version is not a positive number".to_string(),
+        })? as u32,
+        None => UNVERSIONED,
+    };
+
+    if version > CURRENT_VERSION {
+        return Err(MapFormatError::UnsupportedVersion { found: version });
+    }
+
+    if version < UNVERSIONED {
+        return Err(MapFormatError::Invalid {
+            reason: format!("unknown version {}", version),
+        });
+    }
+
+    for migration in &MIGRATIONS[(version - UNVERSIONED) as usize..] {
+        value = migration(value)?;
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+fn object(value: Value) -> Result<JsonObject<String, Value>, MapFormatError> {
+    match value {
+        Value::Object(object) => Ok(object),
+        _ => Err(MapFormatError::Invalid {
+            reason: "expected an object".to_string(),
+        }),
+    }
+}
+
+fn array(object: &mut JsonObject<String, Value>, key: &str) -> Vec<Value> {
+    match object.remove(key) {
+        Some(Value::Array(values)) => values,
+        _ => vec![],
+    }
+}
+
+/// Version 1 files store the complete street and district structs and contain neither intersections
+/// nor seeds. The intersections are reconstructed from the ends of the streets, the seeds are derived
+/// from a new master seed.
+///
+/// Like all migrations it only works on the json values so that it is not affected by later changes
+/// of the records.
+fn migrate_v1_to_v2(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let seed = match map.get("seed").and_then(Value::as_u64) {
+        Some(seed) => seed,
+        None => rand::random(),
+    };
+
+    let mut street_ends: Vec<(Uuid, LineString<f64>)> = vec![];
+    let streets = array(&mut map, "streets")
+        .into_iter()
+        .map(|street| -> Result<Value, MapFormatError> {
+            let mut street = object(street)?;
+
+            let id = street.remove("id").unwrap_or_default();
+            let line_string = street.remove("lines").unwrap_or_default();
+            street_ends.push((
+                serde_json::from_value(id.clone())?,
+                serde_json::from_value(line_string.clone())?,
+            ));
+
+            Ok(json!({
+                "id": id,
+                "line_string": line_string,
+                "class": street.remove("class").unwrap_or_else(|| json!("street")),
+                "width": street.remove("width").unwrap_or_else(|| json!(20.)),
+            }))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    let districts = array(&mut map, "districts")
+        .into_iter()
+        .map(|district| -> Result<Value, MapFormatError> {
+            let mut district = object(district)?;
+
+            let polygon = district.remove("polygon").unwrap_or_default();
+            let district_seed = match district.remove("seed") {
+                Some(district_seed) => district_seed,
+                None => json!(derive_district_seed(
+                    seed,
+                    &serde_json::from_value(polygon.clone())?
+                )),
+            };
+
+            let houses = array(&mut district, "houses")
+                .into_iter()
+                .map(|house| -> Result<Value, MapFormatError> {
+                    Ok(object(house)?.remove("polygon").unwrap_or_default())
+                })
+                .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+            Ok(json!({
+                "id": district.remove("id").unwrap_or_default(),
+                "polygon": polygon,
+                "seed": district_seed,
+                "minimum_house_side": MINIMUM_HOUSE_SIDE,
+                "houses": houses,
+            }))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    Ok(json!({
+        "version": 2,
+        "width": map.remove("width").unwrap_or_default(),
+        "height": map.remove("height").unwrap_or_default(),
+        "seed": seed,
+        "streets": streets,
+        "intersections": intersections_from_streets(&street_ends),
+        "districts": districts,
+    }))
+}
+
+/// Version 3 added control points to curved streets. All streets of older files are straight.
+fn migrate_v2_to_v3(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let streets = array(&mut map, "streets")
+        .into_iter()
+        .map(|street| -> Result<Value, MapFormatError> {
+            let mut street = object(street)?;
+            street.insert("control_points".to_string(), json!([]));
+
+            Ok(Value::Object(street))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("streets".to_string(), Value::Array(streets));
+    map.insert("version".to_string(), json!(3));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 4 added optional names to streets and districts. Streets and districts of older files are unnamed.
+fn migrate_v3_to_v4(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    for key in ["streets", "districts"] {
+        let elements = array(&mut map, key)
+            .into_iter()
+            .map(|element| -> Result<Value, MapFormatError> {
+                let mut element = object(element)?;
+                element.insert("name".to_string(), Value::Null);
+
+                Ok(Value::Object(element))
+            })
+            .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+        map.insert(key.to_string(), Value::Array(elements));
+    }
+
+    map.insert("version".to_string(), json!(4));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 5 added the ward of districts. Districts of older files have no ward.
+fn migrate_v4_to_v5(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let districts = array(&mut map, "districts")
+        .into_iter()
+        .map(|district| -> Result<Value, MapFormatError> {
+            let mut district = object(district)?;
+            district.insert("ward".to_string(), Value::Null);
+
+            Ok(Value::Object(district))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("districts".to_string(), Value::Array(districts));
+    map.insert("version".to_string(), json!(5));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 6 added the subdivision settings of districts. Districts of older files were subdivided by splitting
+/// their longest street.
+fn migrate_v5_to_v6(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let districts = array(&mut map, "districts")
+        .into_iter()
+        .map(|district| -> Result<Value, MapFormatError> {
+            let mut district = object(district)?;
+            district.insert(
+                "subdivision".to_string(),
+                json!({
+                    "strategy": "longest_street",
+                    "split_ratio": [0.3, 0.7],
+                    "minimum_frontage": 0.,
+                }),
+            );
+
+            Ok(Value::Object(district))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("districts".to_string(), Value::Array(districts));
+    map.insert("version".to_string(), json!(6));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 7 added building footprints. Houses of older files cover their whole lot.
+fn migrate_v6_to_v7(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let districts = array(&mut map, "districts")
+        .into_iter()
+        .map(|district| -> Result<Value, MapFormatError> {
+            let mut district = object(district)?;
+
+            let houses: Vec<Value> = array(&mut district, "houses")
+                .into_iter()
+                .map(|house| json!({ "lot": house, "footprint": house }))
+                .collect();
+
+            district.insert("houses".to_string(), Value::Array(houses));
+            district.insert(
+                "footprint".to_string(),
+                json!({
+                    "front_setback": 0.,
+                    "side_setback": 0.,
+                    "back_setback": 0.,
+                    "wing_depth": 15.,
+                    "courtyard_probability": 0.,
+                    "l_shape_probability": 0.,
+                    "u_shape_probability": 0.,
+                    "terrace_length": 1,
+                }),
+            );
+
+            Ok(Value::Object(district))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("districts".to_string(), Value::Array(districts));
+    map.insert("version".to_string(), json!(7));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 8 added city walls. Older files have no walls.
+fn migrate_v7_to_v8(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    map.insert("walls".to_string(), json!([]));
+    map.insert("version".to_string(), json!(8));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 9 added the terrain. Older files are flat.
+fn migrate_v8_to_v9(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    map.insert("terrain".to_string(), Value::Null);
+    map.insert("version".to_string(), json!(9));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 10 added rivers, lakes and the sea. Older files have no water.
+fn migrate_v9_to_v10(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    map.insert("waters".to_string(), json!([]));
+    map.insert("version".to_string(), json!(10));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 11 added the kind of districts. Districts of older files are residential.
+fn migrate_v10_to_v11(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let districts = array(&mut map, "districts")
+        .into_iter()
+        .map(|district| -> Result<Value, MapFormatError> {
+            let mut district = object(district)?;
+            district.insert("kind".to_string(), json!("residential"));
+
+            Ok(Value::Object(district))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("districts".to_string(), Value::Array(districts));
+    map.insert("version".to_string(), json!(11));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 12 added landmarks. Older files have no landmarks.
+fn migrate_v11_to_v12(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    map.insert("landmarks".to_string(), json!([]));
+    map.insert("version".to_string(), json!(12));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 13 added the handles of curved streets. Curved streets of older files are Catmull-Rom splines through
+/// their control points, their handles are derived from them on load.
+fn migrate_v12_to_v13(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    let streets = array(&mut map, "streets")
+        .into_iter()
+        .map(|street| -> Result<Value, MapFormatError> {
+            let mut street = object(street)?;
+            street.insert("handles".to_string(), json!([]));
+
+            Ok(Value::Object(street))
+        })
+        .collect::<Result<Vec<Value>, MapFormatError>>()?;
+
+    map.insert("streets".to_string(), Value::Array(streets));
+    map.insert("version".to_string(), json!(13));
+
+    Ok(Value::Object(map))
+}
+
+/// Version 14 added the intersections at which filling a block split an arterial street. Refilling a block of
+/// an older file therefore keeps the arterials around it split.
+fn migrate_v13_to_v14(value: Value) -> Result<Value, MapFormatError> {
+    let mut map = object(value)?;
+
+    map.insert("block_junctions".to_string(), json!([]));
+    map.insert("version".to_string(), json!(14));
+
+    Ok(Value::Object(map))
+}
+
+/// Creates an intersection at each street end. Street ends at the same position share an intersection.
+fn intersections_from_streets(streets: &[(Uuid, LineString<f64>)]) -> Vec<Value> {
+    let mut intersections: Vec<(Coordinate<f64>, Vec<(Direction, Uuid)>)> = vec![];
+
+    let mut connect = |position: Coordinate<f64>, connection: (Direction, Uuid)| {
+        let existing = intersections.iter_mut().find(|(other, _)| {
+            (other.x - position.x).abs() <= INTERSECTION_EPSILON
+                && (other.y - position.y).abs() <= INTERSECTION_EPSILON
+        });
+
+        match existing {
+            Some((_, connected_streets)) => connected_streets.push(connection),
+            None => intersections.push((position, vec![connection])),
+        }
+    };
+
+    for (id, line_string) in streets {
+        if let (Some(start), Some(end)) = (line_string.0.first(), line_string.0.last()) {
+            connect(*start, (Direction::Out, *id));
+            connect(*end, (Direction::In, *id));
+        }
+    }
+
+    intersections
+        .into_iter()
+        .map(|(position, connected_streets)| {
+            json!({
+                "id": Uuid::new_v4(),
+                "position": position,
+                "connected_streets": connected_streets,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::map::{
+        district_kind::DistrictKind, footprint::Footprint, house::Subdivision,
+        street_class::StreetClass, ward::Ward,
+    };
+
+    use super::{migrate, MapFormatError, CURRENT_VERSION};
+
+    #[test]
+    fn unit_unversioned_file_is_migrated() {
+        let value = json!({
+            "width": 1000,
+            "height": 800,
+            "streets": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "lines": [{ "x": 0., "y": 0. }, { "x": 100., "y": 0. }],
+                    "width": 20.
+                },
+                {
+                    "id": "3b2d2b6a-4c3f-4a48-9d3a-1f4b8c1e7a10",
+                    "lines": [{ "x": 100., "y": 0. }, { "x": 100., "y": 100. }],
+                    "width": 20.
+                }
+            ],
+            "districts": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert_eq!(file.width, 1000);
+        assert_eq!(file.streets.len(), 2);
+        assert!(file
+            .streets
+            .iter()
+            .all(|street| street.class == StreetClass::Street));
+        assert_eq!(file.intersections.len(), 3);
+        assert!(file
+            .intersections
+            .iter()
+            .any(|intersection| intersection.connected_streets.len() == 2));
+    }
+
+    #[test]
+    fn unit_version_2_streets_are_straight() {
+        let value = json!({
+            "version": 2,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "line_string": [{ "x": 0., "y": 0. }, { "x": 100., "y": 0. }],
+                    "class": "alley",
+                    "width": 10.
+                }
+            ],
+            "intersections": [],
+            "districts": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.seed, 42);
+        assert_eq!(file.streets[0].class, StreetClass::Alley);
+        assert!(file.streets[0].control_points.is_empty());
+        assert_eq!(file.streets[0].name, None);
+    }
+
+    #[test]
+    fn unit_version_4_districts_have_no_ward() {
+        let seed = [0u8; 32];
+        let value = json!({
+            "version": 4,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "polygon": { "exterior": [], "interiors": [] },
+                    "seed": seed,
+                    "minimum_house_side": 250.,
+                    "houses": [],
+                    "name": "Old Town"
+                }
+            ]
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.districts[0].name, Some("Old Town".to_string()));
+        assert_eq!(file.districts[0].ward, None);
+    }
+
+    #[test]
+    fn unit_version_5_districts_split_longest_street() {
+        let seed = [0u8; 32];
+        let value = json!({
+            "version": 5,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "polygon": { "exterior": [], "interiors": [] },
+                    "seed": seed,
+                    "minimum_house_side": 250.,
+                    "houses": [],
+                    "name": null,
+                    "ward": "market"
+                }
+            ]
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.districts[0].ward, Some(Ward::Market));
+        assert_eq!(file.districts[0].subdivision, Subdivision::default());
+    }
+
+    #[test]
+    fn unit_version_6_houses_cover_their_lots() {
+        let seed = [0u8; 32];
+        let house = json!({
+            "exterior": [
+                { "x": 0., "y": 0. },
+                { "x": 10., "y": 0. },
+                { "x": 10., "y": 10. },
+                { "x": 0., "y": 0. }
+            ],
+            "interiors": []
+        });
+        let value = json!({
+            "version": 6,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "polygon": { "exterior": [], "interiors": [] },
+                    "seed": seed,
+                    "minimum_house_side": 250.,
+                    "subdivision": {
+                        "strategy": "grid",
+                        "split_ratio": [0.4, 0.6],
+                        "minimum_frontage": 10.
+                    },
+                    "houses": [house],
+                    "name": null,
+                    "ward": null
+                }
+            ]
+        });
+
+        let file = migrate(value).unwrap();
+        let district = &file.districts[0];
+
+        assert_eq!(district.footprint, Footprint::default());
+        assert_eq!(district.houses.len(), 1);
+        assert_eq!(district.houses[0].lot, district.houses[0].footprint);
+    }
+
+    #[test]
+    fn unit_version_7_has_no_walls() {
+        let value = json!({
+            "version": 7,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert!(file.walls.is_empty());
+    }
+
+    #[test]
+    fn unit_version_8_is_flat() {
+        let value = json!({
+            "version": 8,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [],
+            "walls": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.terrain, None);
+    }
+
+    #[test]
+    fn unit_version_9_has_no_water() {
+        let value = json!({
+            "version": 9,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [],
+            "walls": [],
+            "terrain": null
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert!(file.waters.is_empty());
+    }
+
+    #[test]
+    fn unit_version_10_districts_are_residential() {
+        let seed = [0u8; 32];
+        let value = json!({
+            "version": 10,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "polygon": { "exterior": [], "interiors": [] },
+                    "seed": seed,
+                    "minimum_house_side": 250.,
+                    "subdivision": {
+                        "strategy": "grid",
+                        "split_ratio": [0.4, 0.6],
+                        "minimum_frontage": 10.
+                    },
+                    "footprint": {
+                        "front_setback": 0.,
+                        "side_setback": 0.,
+                        "back_setback": 0.,
+                        "wing_depth": 15.,
+                        "courtyard_probability": 0.,
+                        "l_shape_probability": 0.,
+                        "u_shape_probability": 0.,
+                        "terrace_length": 1
+                    },
+                    "houses": [],
+                    "name": null,
+                    "ward": null
+                }
+            ],
+            "walls": [],
+            "terrain": null,
+            "waters": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.districts[0].kind, DistrictKind::Residential);
+    }
+
+    #[test]
+    fn unit_version_11_has_no_landmarks() {
+        let value = json!({
+            "version": 11,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [],
+            "walls": [],
+            "terrain": null,
+            "waters": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert!(file.landmarks.is_empty());
+    }
+
+    #[test]
+    fn unit_version_12_streets_have_no_handles() {
+        let value = json!({
+            "version": 12,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [
+                {
+                    "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "line_string": [{ "x": 0., "y": 0. }, { "x": 100., "y": 0. }],
+                    "control_points": [{ "x": 0., "y": 0. }, { "x": 100., "y": 0. }],
+                    "class": "street",
+                    "width": 20.,
+                    "name": null
+                }
+            ],
+            "intersections": [],
+            "districts": [],
+            "walls": [],
+            "terrain": null,
+            "waters": [],
+            "landmarks": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert!(file.streets[0].handles.is_empty());
+    }
+
+    #[test]
+    fn unit_version_13_has_no_block_junctions() {
+        let value = json!({
+            "version": 13,
+            "width": 1000,
+            "height": 800,
+            "seed": 42,
+            "streets": [],
+            "intersections": [],
+            "districts": [],
+            "walls": [],
+            "terrain": null,
+            "waters": [],
+            "landmarks": []
+        });
+
+        let file = migrate(value).unwrap();
+
+        assert_eq!(file.version, CURRENT_VERSION);
+        assert!(file.block_junctions.is_empty());
+    }
+
+    #[test]
+    fn unit_newer_version_is_rejected() {
+        let value = json!({ "version": CURRENT_VERSION + 1 });
+
+        assert_eq!(
+            migrate(value),
+            Err(MapFormatError::UnsupportedVersion {
+                found: CURRENT_VERSION + 1
+            })
+        );
+    }
+
+    #[test]
+    fn unit_missing_fields_are_reported() {
+        let value = json!({ "version": CURRENT_VERSION, "width": 100 });
+
+        assert!(matches!(
+            migrate(value),
+            Err(MapFormatError::Invalid { .. })
+        ));
+    }
+}
@@ -1,11 +1,104 @@
-use geo::{euclidean_length::EuclideanLength, Line, Point, Polygon};
-use rand::{thread_rng, Rng, SeedableRng};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use geo::Polygon;
+use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use rust_editor::{style::Style};
+use serde::{Deserialize, Serialize};
+
+use crate::algorithm::{
+    geo::{merge, shared_boundary_length, AnnotatedPolygon},
+    subdivision::{
+        GridLots, LongestStreetSplit, ObbSplit, SkeletonStrips, SubdivisionParameters,
+        SubdivisionStrategy,
+    },
+};
 
-use crate::algorithm::geo::{longest_line, split, AnnotatedPolygon, longest_and_shortest_diameter};
+use super::{
+    district::{House, Seed},
+    footprint::{place_buildings, Footprint},
+};
 
-use super::district::House;
+/// Strategy that is used to subdivide a district into the lots of its houses.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LotStrategy {
+    LongestStreet,
+    ObbSplit,
+    Strips,
+    Grid,
+}
+
+impl LotStrategy {
+    pub const ALL: [LotStrategy; 4] = [
+        LotStrategy::LongestStreet,
+        LotStrategy::ObbSplit,
+        LotStrategy::Strips,
+        LotStrategy::Grid,
+    ];
+
+    pub fn subdivision_strategy(&self) -> Box<dyn SubdivisionStrategy> {
+        match self {
+            LotStrategy::LongestStreet => Box::new(LongestStreetSplit),
+            LotStrategy::ObbSplit => Box::new(ObbSplit),
+            LotStrategy::Strips => Box::new(SkeletonStrips),
+            LotStrategy::Grid => Box::new(GridLots),
+        }
+    }
+}
+
+impl Default for LotStrategy {
+    fn default() -> Self {
+        LotStrategy::LongestStreet
+    }
+}
+
+impl fmt::Display for LotStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LotStrategy::LongestStreet => "longest_street",
+            LotStrategy::ObbSplit => "obb_split",
+            LotStrategy::Strips => "strips",
+            LotStrategy::Grid => "grid",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LotStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LotStrategy::ALL
+            .iter()
+            .find(|strategy| strategy.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown lot strategy \"{}\"", s))
+    }
+}
+
+/// Settings of the subdivision of a district into lots. They are stored with the district so that rerolling a
+/// district keeps its layout.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Subdivision {
+    pub strategy: LotStrategy,
+
+    /// Range from which the relative position of each cut is drawn.
+    pub split_ratio: (f64, f64),
+
+    /// Minimum length of the street edges of each lot.
+    pub minimum_frontage: f64,
+}
+
+impl Default for Subdivision {
+    fn default() -> Self {
+        Subdivision {
+            strategy: LotStrategy::default(),
+            split_ratio: (0.3, 0.7),
+            minimum_frontage: 0.,
+        }
+    }
+}
 
 /*
 #[derive(Clone)]
@@ -51,95 +144,214 @@ pub fn longest_muu(polygon: &Polygon<f64>) -> Line<f64> {
 }
 */
 
-fn calculate_split_line(_: &mut ChaCha8Rng, polygon: &AnnotatedPolygon, min_side_length: f64) -> Line<f64> {
-    let line = longest_line(&polygon, min_side_length).0; //longest_muu(polygon);
-    let vec = line.end_point() - line.start_point();
-    let length = line.euclidean_length();
-    let norm = Point::new(vec.x() / length, vec.y() / length);
-    let perp = Point::new(-norm.y(), norm.x());
+pub fn generate_houses_from_polygon(
+    polygon: &Polygon<f64>,
+    min_side_length: f64,
+    subdivision: &Subdivision,
+    footprint: &Footprint,
+    seed: Seed,
+) -> Vec<House> {
+    let mut rng = ChaCha8Rng::from_seed(seed);
+
+    let parameters = SubdivisionParameters {
+        minimum_side: min_side_length,
+        split_ratio: subdivision.split_ratio.0..subdivision.split_ratio.1,
+        minimum_frontage: subdivision.minimum_frontage,
+    };
+    let lots = subdivision.strategy.subdivision_strategy().subdivide(
+        &AnnotatedPolygon(
+            polygon.clone(),
+            polygon.exterior().lines().map(|_| true).collect(),
+        ),
+        &parameters,
+        &mut rng,
+    );
+
+    place_buildings(merge_enclosed_lots(lots), footprint, &mut rng)
+        .into_iter()
+        .map(|(lot, building)| House::new(lot, building))
+        .collect()
+}
 
-    let split_pt = line.start_point() + norm * length * 0.70; //* rng.gen_range(0.3..0.7);
+/// Merges each lot that does not face a street into the neighbour facing a street with which it shares the longest
+/// boundary, so that every house can be reached from a street and the lots still cover the whole district. Lots
+/// that are only surrounded by enclosed lots are merged once one of their neighbours faces a street.
+fn merge_enclosed_lots(mut lots: Vec<AnnotatedPolygon>) -> Vec<AnnotatedPolygon> {
+    let mut merged = true;
+    while merged {
+        merged = false;
+
+        let mut i = 0;
+        while i < lots.len() {
+            if !lots[i].enclosed() {
+                i += 1;
+                continue;
+            }
 
-    
-    
-    Line::new(split_pt - perp * 6000.0, split_pt + perp * 6000.0)
+            let mut neighbours: Vec<(usize, f64)> = lots
+                .iter()
+                .enumerate()
+                .filter(|(_, lot)| !lot.enclosed())
+                .map(|(j, lot)| (j, shared_boundary_length(&lots[i].0, &lot.0)))
+                .filter(|(_, length)| *length > 0.)
+                .collect();
+            neighbours.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+            let neighbour = neighbours
+                .into_iter()
+                .find_map(|(j, _)| merge(&lots[i], &lots[j]).map(|lot| (j, lot)));
+            match neighbour {
+                Some((j, lot)) => {
+                    lots[j] = lot;
+                    lots.remove(i);
+                    merged = true;
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    // Lots that could not be merged with any neighbour can not be reached from a street
+    lots.retain(|lot| !lot.enclosed());
+
+    lots
 }
 
-fn split_polygons_into_chunks(rng: &mut ChaCha8Rng, polygon: &AnnotatedPolygon, min_side_length: f64) -> Vec<AnnotatedPolygon> {
-    let mut polygons: Vec<AnnotatedPolygon> = Vec::new();
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::Area, LineString, Polygon};
+
+    use crate::{algorithm::geo::AnnotatedPolygon, map::footprint::Footprint};
 
-    let p = longest_and_shortest_diameter(&polygon.0);
-  
-    //if polygon.0.unsigned_area() < min_side_length * min_side_length {
-    if p.0 < min_side_length && p.1 < min_side_length {
-        polygons.push(polygon.clone());
-        return polygons;
+    use super::{generate_houses_from_polygon, merge_enclosed_lots, LotStrategy, Subdivision};
+
+    #[test]
+    fn unit_lot_strategy_from_str_matches_display() {
+        for strategy in LotStrategy::ALL {
+            assert_eq!(strategy.to_string().parse::<LotStrategy>(), Ok(strategy));
+        }
+
+        assert!("voronoi".parse::<LotStrategy>().is_err());
     }
 
-    let split_line = calculate_split_line(rng, polygon, min_side_length);
-    let mut splits = split(&polygon, &split_line);
-    for sub_polygon in splits.iter_mut() {
-        polygons.append(&mut split_polygons_into_chunks(rng, &sub_polygon, min_side_length));
+    #[test]
+    fn unit_houses_cover_the_district() {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (300., 0.), (300., 200.), (0., 200.)]),
+            vec![],
+        );
+
+        for strategy in LotStrategy::ALL {
+            let subdivision = Subdivision {
+                strategy,
+                minimum_frontage: 20.,
+                ..Subdivision::default()
+            };
+            let houses = generate_houses_from_polygon(
+                &polygon,
+                50.,
+                &subdivision,
+                &Footprint::default(),
+                [1u8; 32],
+            );
+            let area: f64 = houses.iter().map(|house| house.lot.unsigned_area()).sum();
+
+            assert!(houses.len() > 1);
+            assert!((area - polygon.unsigned_area()).abs() < 1e-3);
+        }
     }
 
-    polygons
-}
+    #[test]
+    fn unit_buildings_stand_on_their_lots() {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (300., 0.), (300., 200.), (0., 200.)]),
+            vec![],
+        );
+        let footprint = Footprint {
+            front_setback: 5.,
+            side_setback: 2.,
+            back_setback: 10.,
+            ..Footprint::default()
+        };
 
-pub fn generate_houses_from_polygon(polygon: &Polygon<f64>, min_side_length: f64, seed: <ChaCha8Rng as SeedableRng>::Seed) -> Vec<House> {
-    thread_rng().fill(&mut seed.clone());
-    let mut rng = ChaCha8Rng::from_seed(seed);
+        let houses = generate_houses_from_polygon(
+            &polygon,
+            50.,
+            &Subdivision::default(),
+            &footprint,
+            [1u8; 32],
+        );
 
-    let houses = split_polygons_into_chunks(
-        &mut rng,
-        &AnnotatedPolygon(
-            polygon.clone(),
-            polygon.exterior().lines().map(|_| true).collect(),
-        ),
-        min_side_length
-    );
+        // Lots that are too small for the setbacks are built over completely
+        assert!(houses
+            .iter()
+            .all(|house| house.polygon.unsigned_area() <= house.lot.unsigned_area()));
+        assert!(houses
+            .iter()
+            .any(|house| house.polygon.unsigned_area() < house.lot.unsigned_area()));
+    }
 
-    let polygons = houses.iter().filter(|polygon| !polygon.enclosed());
+    #[test]
+    fn unit_enclosed_lots_are_merged_into_neighbours() {
+        // A block of 3 x 3 lots whose center lot does not face a street
+        let mut lots = vec![];
+        for x in [0., 100., 200.] {
+            for y in [0., 100., 200.] {
+                lots.push(AnnotatedPolygon(
+                    Polygon::new(
+                        LineString::from(vec![
+                            (x, y),
+                            (x + 100., y),
+                            (x + 100., y + 100.),
+                            (x, y + 100.),
+                        ]),
+                        vec![],
+                    ),
+                    vec![y == 0., x == 200., y == 200., x == 0.],
+                ));
+            }
+        }
+        assert_eq!(lots.iter().filter(|lot| lot.enclosed()).count(), 1);
 
-    
-    polygons
-        .map(|sub_polygon| {
-            /*
-            let r: u8 = rng.gen_range(0..255);
-            let g: u8 = rng.gen_range(0..255);
-            let b: u8 = rng.gen_range(0..255);
+        let lots = merge_enclosed_lots(lots);
+        let area: f64 = lots.iter().map(|lot| lot.0.unsigned_area()).sum();
 
-            let rng_color = format!("rgba({},{},{}, 0.3)", r, g, b).to_string();
-            */
+        assert_eq!(lots.len(), 8);
+        assert!(lots.iter().all(|lot| !lot.enclosed()));
+        assert!((area - 90000.).abs() < 1e-3);
+    }
 
-            let line_styles: Vec<Style> = sub_polygon
-                .lines()
-                .iter()
-                .map(|(_, is_street)| {
-                    if *is_street {
-                        return Style {
-                            border_width: 4,
-                            border_color: "#FFFFFF".to_string(),
-                            background_color: "".to_string(),
-                        };
-                    }
-
-                    Style {
-                        border_width: 4,
-                        border_color: "#FFFFFF".to_string(),
-                        background_color: "".to_string(),
-                    }
-                })
-                .collect();
+    #[test]
+    fn unit_lots_cover_large_district() {
+        let polygon = Polygon::new(
+            LineString::from(vec![
+                (0., 0.),
+                (800., 0.),
+                (800., 300.),
+                (300., 300.),
+                (300., 700.),
+                (0., 700.),
+            ]),
+            vec![],
+        );
+
+        for strategy in LotStrategy::ALL {
+            for seed in 0..4u8 {
+                let subdivision = Subdivision {
+                    strategy,
+                    ..Subdivision::default()
+                };
+                let houses = generate_houses_from_polygon(
+                    &polygon,
+                    30.,
+                    &subdivision,
+                    &Footprint::default(),
+                    [seed; 32],
+                );
+                let area: f64 = houses.iter().map(|house| house.lot.unsigned_area()).sum();
 
-            House {
-                polygon: sub_polygon.0.clone(),
-                line_styles: line_styles.clone(),
-                style: Style {
-                    border_width: 2,
-                    border_color: "#FFFFFF".to_string(),
-                    background_color: "rgba(255, 255, 255, 0.3)".to_string(),
-                },
+                assert!((area - polygon.unsigned_area()).abs() < 1e-3);
             }
-        })
-        .collect()
+        }
+    }
 }
@@ -4,13 +4,11 @@ use geo::Coordinate;
 use rust_editor::{
     gizmo::{GetPosition, Id, SetId, SetPosition},
     interactive_element::{InteractiveElement, InteractiveElementState},
-    renderer::apply_style,
+    renderer::{apply_style, Canvas, RenderError},
     style::{InteractiveElementStyle, Style},
 };
 use rust_macro::ElementId;
 use uuid::Uuid;
-use wasm_bindgen::JsValue;
-use web_sys::CanvasRenderingContext2d;
 
 use serde::{Deserialize, Serialize};
 
@@ -79,7 +77,7 @@ impl Intersection {
         }
     }
 
-    pub fn render(&self, context: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
         context.save();
         context.begin_path();
         context.arc(self.position.x, self.position.y, 1.0, 0.0, 2.0 * PI)?;
@@ -88,7 +86,7 @@ impl Intersection {
         context.fill();
         context.restore();
 
-        context.set_fill_style(&"#FFFFFF".into());
+        context.set_fill_style("#FFFFFF");
 
         /*
         context.fill_text(
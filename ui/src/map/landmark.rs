@@ -0,0 +1,316 @@
+use std::{f64::consts::PI, fmt, str::FromStr};
+
+use geo::{prelude::EuclideanDistance, Coordinate, Point, Rect};
+use rust_editor::{
+    gizmo::{GetPosition, Id, SetId, SetPosition},
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    renderer::{apply_style, render_text, Canvas, RenderError},
+    style::{InteractiveElementStyle, Style, TextStyle},
+};
+use rust_macro::ElementId;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Radius of the badge behind the icon of a landmark. Clicks within this radius hit the landmark.
+pub const LANDMARK_RADIUS: f64 = 12.;
+
+/// Distance between the center of a landmark and its name below it.
+const LABEL_OFFSET: f64 = LANDMARK_RADIUS + 12.;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LandmarkKind {
+    Temple,
+    Tavern,
+    Castle,
+    Well,
+    Gate,
+}
+
+impl LandmarkKind {
+    pub const ALL: [LandmarkKind; 5] = [
+        LandmarkKind::Temple,
+        LandmarkKind::Tavern,
+        LandmarkKind::Castle,
+        LandmarkKind::Well,
+        LandmarkKind::Gate,
+    ];
+
+    /// Name of the symbol of this kind in the Material Icons font, which renders the symbol by ligature.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LandmarkKind::Temple => "account_balance",
+            LandmarkKind::Tavern => "sports_bar",
+            LandmarkKind::Castle => "castle",
+            LandmarkKind::Well => "water_drop",
+            LandmarkKind::Gate => "door_front",
+        }
+    }
+}
+
+impl Default for LandmarkKind {
+    fn default() -> Self {
+        LandmarkKind::Temple
+    }
+}
+
+impl fmt::Display for LandmarkKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LandmarkKind::Temple => "temple",
+            LandmarkKind::Tavern => "tavern",
+            LandmarkKind::Castle => "castle",
+            LandmarkKind::Well => "well",
+            LandmarkKind::Gate => "gate",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LandmarkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LandmarkKind::ALL
+            .iter()
+            .find(|kind| kind.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown landmark kind \"{}\"", s))
+    }
+}
+
+/// A point of interest like a temple or a tavern. Landmarks are shown as an icon with their name below it and
+/// carry free-text notes for the game master.
+#[derive(Clone, ElementId)]
+pub struct Landmark {
+    id: Uuid,
+    kind: LandmarkKind,
+    position: Coordinate<f64>,
+
+    /// Clockwise rotation of the icon in degrees.
+    rotation: f64,
+
+    name: Option<String>,
+    notes: String,
+
+    style: InteractiveElementStyle,
+    state: InteractiveElementState,
+}
+
+impl Default for Landmark {
+    fn default() -> Self {
+        Landmark {
+            id: Uuid::new_v4(),
+            kind: LandmarkKind::default(),
+            position: Coordinate { x: 0., y: 0. },
+            rotation: 0.,
+            name: None,
+            notes: String::new(),
+            style: InteractiveElementStyle {
+                normal: Style {
+                    border_width: 2,
+                    border_color: "#FFFFFF".to_string(),
+                    background_color: "#5d4037".to_string(),
+                },
+                hover: Style {
+                    border_width: 2,
+                    border_color: "#FFFFFF".to_string(),
+                    background_color: "#8d6e63".to_string(),
+                },
+                selected: Style {
+                    border_width: 2,
+                    border_color: "#FFFFFF".to_string(),
+                    background_color: "#C45D53".to_string(),
+                },
+            },
+            state: InteractiveElementState::Normal,
+        }
+    }
+}
+
+impl InteractiveElement for Landmark {
+    fn set_state(&mut self, new_state: InteractiveElementState) {
+        self.state = new_state;
+    }
+
+    fn style(&self) -> &Style {
+        match self.state {
+            InteractiveElementState::Normal => &self.style.normal,
+            InteractiveElementState::Hover => &self.style.hover,
+            InteractiveElementState::Selected => &self.style.selected,
+        }
+    }
+
+    fn state(&self) -> InteractiveElementState {
+        self.state.clone()
+    }
+}
+
+impl GetPosition for Landmark {
+    fn position(&self) -> Coordinate<f64> {
+        self.position
+    }
+}
+
+impl SetPosition for Landmark {
+    fn set_position(&mut self, position: Coordinate<f64>) {
+        self.position = position;
+    }
+}
+
+impl Landmark {
+    pub fn new(kind: LandmarkKind, position: Coordinate<f64>) -> Self {
+        Landmark {
+            kind,
+            position,
+            ..Landmark::default()
+        }
+    }
+
+    pub fn kind(&self) -> LandmarkKind {
+        self.kind
+    }
+
+    pub fn set_kind(&mut self, kind: LandmarkKind) {
+        self.kind = kind;
+    }
+
+    pub fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Sets the clockwise rotation in degrees, normalized to `[0, 360)`.
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.rotation = rotation.rem_euclid(360.);
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn notes(&self) -> &String {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, notes: String) {
+        self.notes = notes;
+    }
+
+    /// Area covered by the badge of the landmark, which is used for the spatial index.
+    pub fn bounding_rect(&self) -> Rect<f64> {
+        let offset = Coordinate {
+            x: LANDMARK_RADIUS,
+            y: LANDMARK_RADIUS,
+        };
+
+        Rect::new(self.position - offset, self.position + offset)
+    }
+
+    pub fn is_point_on_landmark(&self, point: &Coordinate<f64>) -> bool {
+        Point::from(self.position).euclidean_distance(&Point::from(*point)) <= LANDMARK_RADIUS
+    }
+
+    fn icon_style() -> TextStyle {
+        TextStyle {
+            font: "'Material Icons'".to_string(),
+            size: (LANDMARK_RADIUS * 1.4) as u32,
+            halo_width: 0,
+            ..Default::default()
+        }
+    }
+
+    fn label_style() -> TextStyle {
+        TextStyle {
+            size: 13,
+            ..Default::default()
+        }
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        context.save();
+        context.translate(self.position.x, self.position.y)?;
+        context.rotate(self.rotation.to_radians())?;
+
+        context.begin_path();
+        context.arc(0., 0., LANDMARK_RADIUS, 0., 2. * PI)?;
+        apply_style(self.style(), context);
+
+        render_text(
+            self.kind.icon(),
+            Coordinate { x: 0., y: 0. },
+            &Landmark::icon_style(),
+            context,
+        )?;
+        context.restore();
+
+        if let Some(name) = &self.name {
+            let position = self.position
+                + Coordinate {
+                    x: 0.,
+                    y: LABEL_OFFSET,
+                };
+            render_text(name, position, &Landmark::label_style(), context)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::renderer::recording::RecordingCanvas;
+
+    use super::{Landmark, LandmarkKind, LANDMARK_RADIUS};
+
+    #[test]
+    fn landmark_kind_is_parsed() {
+        for kind in LandmarkKind::ALL {
+            assert_eq!(kind.to_string().parse::<LandmarkKind>(), Ok(kind));
+        }
+        assert_eq!(" Tavern ".parse::<LandmarkKind>(), Ok(LandmarkKind::Tavern));
+        assert!("inn".parse::<LandmarkKind>().is_err());
+    }
+
+    #[test]
+    fn landmark_is_hit_within_its_radius() {
+        let landmark = Landmark::new(LandmarkKind::Well, Coordinate { x: 100., y: 100. });
+
+        assert!(landmark.is_point_on_landmark(&Coordinate { x: 100., y: 100. }));
+        assert!(landmark.is_point_on_landmark(&Coordinate {
+            x: 100. + LANDMARK_RADIUS,
+            y: 100.
+        }));
+        assert!(!landmark.is_point_on_landmark(&Coordinate {
+            x: 100. + LANDMARK_RADIUS,
+            y: 100. + LANDMARK_RADIUS
+        }));
+    }
+
+    #[test]
+    fn landmark_rotation_is_normalized() {
+        let mut landmark = Landmark::default();
+
+        landmark.set_rotation(450.);
+        assert_eq!(landmark.rotation(), 90.);
+
+        landmark.set_rotation(-90.);
+        assert_eq!(landmark.rotation(), 270.);
+    }
+
+    #[test]
+    fn landmark_renders_icon_and_name() {
+        let mut landmark = Landmark::new(LandmarkKind::Tavern, Coordinate { x: 100., y: 100. });
+        landmark.set_name(Some("Prancing Pony".to_string()));
+
+        let mut canvas = RecordingCanvas::new();
+        landmark.render(&mut canvas).unwrap();
+
+        assert_eq!(canvas.texts(), vec!["sports_bar", "Prancing Pony"]);
+    }
+}
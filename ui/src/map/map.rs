@@ -1,11 +1,8 @@
 use geo::intersects::Intersects;
-use geo::prelude::{BoundingRect, Contains, EuclideanDistance};
-use geo::{Coordinate, Line, LineString, MultiPolygon, Polygon, Rect};
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
+use geo::prelude::{BoundingRect, Centroid, Contains, EuclideanDistance};
+use geo::{Coordinate, Line, LineString, MultiPolygon, Point, Polygon, Rect};
 use rust_editor::gizmo::{GetPosition, Id};
 use rust_editor::interactive_element::{InteractiveElement, InteractiveElementState};
-use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -15,12 +12,28 @@ use geo_booleanop::boolean::BooleanOp;
 
 use std::cmp::Ordering;
 use std::collections::hash_map::Keys;
-use std::collections::HashMap;
-
-use super::district::{District, House};
-use super::house::generate_houses_from_polygon;
-use super::intersection::Intersection;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{self, AtomicU64};
+
+use crate::algorithm::geo::{line_string_crossings, polygons_equal};
+
+use super::district::{derive_district_seed, District, House, MINIMUM_HOUSE_SIDE};
+use super::district_kind::DistrictKind;
+use super::format::{
+    migrate, DistrictRecord, IntersectionRecord, LandmarkRecord, MapFile, StreetRecord,
+    TerrainRecord, WallRecord, WaterRecord, CURRENT_VERSION,
+};
+use super::footprint::Footprint;
+use super::house::{generate_houses_from_polygon, Subdivision};
+use super::intersection::{Direction, Intersection};
+use super::landmark::{Landmark, LANDMARK_RADIUS};
+use super::spatial_index::SpatialIndex;
 use super::street::Street;
+use super::terrain::Terrain;
+use super::validation::{self, Problem};
+use super::wall::Wall;
+use super::ward::Ward;
+use super::water::{Bridge, Water, WaterKind};
 
 
 impl Serialize for Map {
@@ -28,34 +41,181 @@ impl Serialize for Map {
     where
         S: serde::ser::Serializer,
     {
-        // 3 is the number of fields in the struct.
-        let mut state = serializer.serialize_struct("Map", 3)?;
-        state.serialize_field("width", &self.width)?;
-        state.serialize_field("height", &self.height)?;
-        state.serialize_field("streets", &self.streets.values().cloned().collect::<Vec<Street>>())?;
-        state.serialize_field("districts", &self.districts.values().cloned().collect::<Vec<District>>())?;
+        MapFile::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let file = migrate(value).map_err(serde::de::Error::custom)?;
+
+        Ok(Map::from(file))
+    }
+}
+
+impl From<&Map> for MapFile {
+    fn from(map: &Map) -> Self {
+        // Sorted by id so that saving the same map twice results in the same file
+        fn sorted<T>(mut records: Vec<T>, id: fn(&T) -> Uuid) -> Vec<T> {
+            records.sort_by_key(id);
+            records
+        }
 
-        state.end()
+        MapFile {
+            version: CURRENT_VERSION,
+            width: map.width,
+            height: map.height,
+            seed: map.seed,
+            streets: sorted(
+                map.streets.values().map(StreetRecord::from).collect(),
+                |record| record.id,
+            ),
+            intersections: sorted(
+                map.intersections
+                    .values()
+                    .map(IntersectionRecord::from)
+                    .collect(),
+                |record| record.id,
+            ),
+            districts: sorted(
+                map.districts.values().map(DistrictRecord::from).collect(),
+                |record| record.id,
+            ),
+            walls: sorted(
+                map.walls.values().map(WallRecord::from).collect(),
+                |record| record.id,
+            ),
+            terrain: map.terrain.as_ref().map(TerrainRecord::from),
+            waters: sorted(
+                map.waters.values().map(WaterRecord::from).collect(),
+                |record| record.id,
+            ),
+            landmarks: sorted(
+                map.landmarks.values().map(LandmarkRecord::from).collect(),
+                |record| record.id,
+            ),
+            block_junctions: sorted(
+                map.block_junctions
+                    .iter()
+                    .filter(|id| map.intersections.contains_key(id))
+                    .copied()
+                    .collect(),
+                |id| *id,
+            ),
+        }
     }
 }
 
-#[derive(Deserialize, Clone)]
+impl From<MapFile> for Map {
+    fn from(file: MapFile) -> Self {
+        let mut map = Map {
+            width: file.width,
+            height: file.height,
+            seed: file.seed,
+            ..Map::default()
+        };
+
+        for record in file.streets {
+            let street = Street::from(record);
+            map.street_polygon = map.street_polygon.union(street.polygon());
+            map.streets.insert(street.id(), street);
+        }
+
+        for record in file.intersections {
+            let intersection = Intersection::from(record);
+            map.intersections.insert(intersection.id(), intersection);
+        }
+
+        for record in file.districts {
+            let district = District::from(record);
+            map.districts.insert(district.id(), district);
+        }
+
+        for record in file.walls {
+            let wall = Wall::from(record);
+            map.walls.insert(wall.id(), wall);
+        }
+
+        for record in file.waters {
+            let water = Water::from(record);
+            map.water_polygon = map.water_polygon.union(water.polygon());
+            map.waters.insert(water.id(), water);
+        }
+
+        for record in file.landmarks {
+            let landmark = Landmark::from(record);
+            map.landmarks.insert(landmark.id(), landmark);
+        }
+
+        map.block_junctions = file.block_junctions.into_iter().collect();
+
+        map.terrain = file.terrain.map(|record| {
+            Terrain::new(
+                record.source,
+                record.relief,
+                record.shading,
+                file.width,
+                file.height,
+            )
+        });
+
+        map.district_polygons = map.street_faces();
+        map.update_bounding_box();
+        map.rebuild_spatial_index();
+
+        map
+    }
+}
+
+/// Source of the revisions of all maps, so that a map that replaces another one never has the same revision.
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_revision() -> u64 {
+    REVISION.fetch_add(1, atomic::Ordering::Relaxed) + 1
+}
+
+#[derive(Clone)]
 pub struct Map {
     width: u32,
     height: u32,
 
+    /// Changes with every mutable access to the map, see [`Map::revision`].
+    revision: u64,
+
+    /// Master seed of the map from which the seeds of all districts are derived.
+    seed: u64,
+
     pub(crate) street_polygon: MultiPolygon<f64>,
     pub(crate) district_polygons: Vec<Polygon<f64>>,
 
     pub(crate) streets: HashMap<Uuid, Street>,
-
-    #[serde(skip_serializing)]
     pub(crate) intersections: HashMap<Uuid, Intersection>,
-
     districts: HashMap<Uuid, District>,
+    walls: HashMap<Uuid, Wall>,
+    waters: HashMap<Uuid, Water>,
+    landmarks: HashMap<Uuid, Landmark>,
+
+    /// Intersections at which filling a block split an arterial street. Only these are merged again when the
+    /// block is refilled.
+    block_junctions: HashSet<Uuid>,
+
+    /// Union of the areas of all waters. Districts are clipped against it.
+    water_polygon: MultiPolygon<f64>,
+
+    terrain: Option<Terrain>,
 
-    #[serde(skip_serializing)]
     bounding_box: Rect<f64>,
+
+    street_index: SpatialIndex,
+    intersection_index: SpatialIndex,
+    district_index: SpatialIndex,
+    wall_index: SpatialIndex,
+    water_index: SpatialIndex,
+    landmark_index: SpatialIndex,
 }
 
 impl Default for Map {
@@ -63,14 +223,31 @@ impl Default for Map {
         Map {
             width: 2560,
             height: 1440,
+            revision: next_revision(),
+            seed: rand::random(),
             streets: HashMap::new(),
             intersections: HashMap::new(),
             districts: HashMap::new(),
+            walls: HashMap::new(),
+            waters: HashMap::new(),
+            landmarks: HashMap::new(),
+            block_junctions: HashSet::new(),
+
+            water_polygon: MultiPolygon::new(vec![]),
+
+            terrain: None,
 
             street_polygon: MultiPolygon::new(vec![]),
             district_polygons: vec![],
 
             bounding_box: Rect::new(Coordinate { x: 0., y: 0. }, Coordinate { x: 0., y: 0. }),
+
+            street_index: SpatialIndex::new(),
+            intersection_index: SpatialIndex::new(),
+            district_index: SpatialIndex::new(),
+            wall_index: SpatialIndex::new(),
+            water_index: SpatialIndex::new(),
+            landmark_index: SpatialIndex::new(),
         }
     }
 }
@@ -120,17 +297,114 @@ impl Map {
         self.height
     }
 
-    /// Removes all data (streets, districts, intersections) from the instance.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Changes whenever the map is or might be changed, so that results derived from the map can be cached
+    /// until the revision changes.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn changed(&mut self) {
+        self.revision = next_revision();
+    }
+
+    /// Sets the master seed of the map. The seeds of all districts are derived again from the new
+    /// master seed and their houses are regenerated.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.changed();
+
+        self.seed = seed;
+
+        for district in self.districts.values_mut() {
+            district.set_seed(derive_district_seed(seed, district.polygon()));
+        }
+    }
+
+    /// Removes all data (streets, districts, intersections, walls, waters, landmarks, terrain) from the instance.
     /// Be aware that calling this is permanent and not unduable.
     pub fn clear(&mut self) {
+        self.changed();
+
         self.streets.clear();
         self.intersections.clear();
         self.districts.clear();
+        self.walls.clear();
+        self.waters.clear();
+        self.landmarks.clear();
+        self.block_junctions.clear();
+        self.terrain = None;
+
+        self.street_polygon = MultiPolygon::new(vec![]);
+        self.water_polygon = MultiPolygon::new(vec![]);
+        self.district_polygons.clear();
+
+        self.street_index.clear();
+        self.intersection_index.clear();
+        self.district_index.clear();
+        self.wall_index.clear();
+        self.water_index.clear();
+        self.landmark_index.clear();
+    }
+
+    /// Rebuilds the spatial indices of streets, intersections, districts, walls, waters and landmarks from
+    /// scratch.
+    /// Call it after the elements were replaced without using the add and remove functions, e.g. after
+    /// the map was deserialized.
+    pub fn rebuild_spatial_index(&mut self) {
+        self.changed();
+
+        self.street_index.clear();
+        for (id, street) in &self.streets {
+            if let Some(rect) = street.polygon().bounding_rect() {
+                self.street_index.insert(*id, rect);
+            }
+        }
+
+        self.intersection_index.clear();
+        for (id, intersection) in &self.intersections {
+            self.intersection_index
+                .insert(*id, Rect::new(intersection.position(), intersection.position()));
+        }
+
+        self.district_index.clear();
+        for (id, district) in &self.districts {
+            if let Some(rect) = district.polygon().bounding_rect() {
+                self.district_index.insert(*id, rect);
+            }
+        }
+
+        self.wall_index.clear();
+        for (id, wall) in &self.walls {
+            if let Some(rect) = wall.bounding_rect() {
+                self.wall_index.insert(*id, rect);
+            }
+        }
+
+        self.water_index.clear();
+        for (id, water) in &self.waters {
+            if let Some(rect) = water.bounding_rect() {
+                self.water_index.insert(*id, rect);
+            }
+        }
+
+        self.landmark_index.clear();
+        for (id, landmark) in &self.landmarks {
+            self.landmark_index.insert(*id, landmark.bounding_rect());
+        }
     }
 
-    /// Returns `true` if `self` has no streets, no intersections and no districts
+    /// Returns `true` if `self` has no streets, no intersections, no districts, no walls, no waters and no
+    /// landmarks
     pub fn is_empty(&self) -> bool {
-        self.streets.is_empty() && self.intersections.is_empty() && self.districts.is_empty()
+        self.streets.is_empty()
+            && self.intersections.is_empty()
+            && self.districts.is_empty()
+            && self.walls.is_empty()
+            && self.waters.is_empty()
+            && self.landmarks.is_empty()
     }
 
     pub fn intersections(&self) -> &HashMap<Uuid, Intersection> {
@@ -138,6 +412,7 @@ impl Map {
     }
 
     pub fn intersections_mut(&mut self) -> &mut HashMap<Uuid, Intersection> {
+        self.changed();
         &mut self.intersections
     }
 
@@ -150,6 +425,7 @@ impl Map {
     }
 
     pub fn districts_mut(&mut self) -> &mut HashMap<Uuid, District> {
+        self.changed();
         &mut self.districts
     }
 
@@ -158,6 +434,7 @@ impl Map {
     }
 
     pub fn streets_mut(&mut self) -> &mut HashMap<Uuid, Street> {
+        self.changed();
         &mut self.streets
     }
 
@@ -174,18 +451,37 @@ impl Map {
         &'a self,
         rect: &'a Rect<f64>,
     ) -> impl Iterator<Item = &'a Intersection> {
-        self.intersections
-            .values()
+        self.intersection_index
+            .intersecting(rect)
+            .filter_map(move |id| self.intersections.get(&id))
             .filter(|intersection| rect.contains(&intersection.position()))
     }
 
+    /// Same as [`Map::intersections_within_rectangle`] but returns mutable references. If you alter the position of
+    /// an intersection make sure to call [`Map::update_intersection_index`] afterwards.
     pub fn intersections_within_rectangle_mut<'a>(
         &'a mut self,
         rect: &'a Rect<f64>,
     ) -> impl Iterator<Item = &'a mut Intersection> {
+        self.changed();
+
+        let ids: HashSet<Uuid> = self.intersection_index.intersecting(rect).collect();
+
         self.intersections
             .values_mut()
-            .filter(|intersection| rect.contains(&intersection.position()))
+            .filter(move |intersection| {
+                ids.contains(&intersection.id()) && rect.contains(&intersection.position())
+            })
+    }
+
+    /// Returns all streets whose bounding box intersects `rect`.
+    pub fn streets_within_rectangle<'a>(
+        &'a self,
+        rect: &Rect<f64>,
+    ) -> impl Iterator<Item = &'a Street> {
+        self.street_index
+            .intersecting(rect)
+            .filter_map(move |id| self.streets.get(&id))
     }
 
     pub fn intersections_with_state<'a>(
@@ -201,65 +497,270 @@ impl Map {
         &'a mut self,
         state: InteractiveElementState,
     ) -> impl Iterator<Item = &'a mut Intersection> {
+        self.changed();
+
         self.intersections
             .values_mut()
             .filter(move |intersection| intersection.state() == state)
     }
 
-    fn update_districts(&mut self) {
-        self.street_polygon = MultiPolygon::new(vec![]);
-        for (_, street) in &self.streets {
-            self.street_polygon = self.street_polygon.union(street.polygon());
+    /// Returns all faces of the street network, i.e. the areas that are completely enclosed by streets.
+    /// Water is cut out of the faces, a face that is divided by a river results in one face per bank.
+    fn street_faces(&self) -> Vec<Polygon<f64>> {
+        self.street_polygon
+            .iter()
+            .flat_map(|polygon| polygon.interiors().iter())
+            .map(|ring| Polygon::new(ring.clone(), vec![]))
+            .flat_map(|face| self.clip_water(face))
+            .collect()
+    }
+
+    /// Returns the faces of the street network that intersect `rect`. Faces outside of `rect` are skipped before
+    /// water is cut out of them.
+    fn street_faces_intersecting(&self, rect: &Rect<f64>) -> Vec<Polygon<f64>> {
+        let intersects_rect = |bounding_rect: Option<Rect<f64>>| {
+            bounding_rect.map_or(false, |bounding_rect| bounding_rect.intersects(rect))
+        };
+
+        self.street_polygon
+            .iter()
+            .filter(|polygon| intersects_rect(polygon.bounding_rect()))
+            .flat_map(|polygon| polygon.interiors().iter())
+            .filter(|ring| intersects_rect(ring.bounding_rect()))
+            .map(|ring| Polygon::new(ring.clone(), vec![]))
+            .flat_map(|face| self.clip_water(face))
+            .filter(|face| intersects_rect(face.bounding_rect()))
+            .collect()
+    }
+
+    /// Returns the parts of `face` that are not covered by water.
+    fn clip_water(&self, face: Polygon<f64>) -> Vec<Polygon<f64>> {
+        let covers_water = matches!(
+            (face.bounding_rect(), self.water_polygon.bounding_rect()),
+            (Some(face_rect), Some(water_rect)) if face_rect.intersects(&water_rect)
+        );
+
+        match covers_water {
+            true => face.difference(&self.water_polygon).0,
+            false => vec![face],
         }
+    }
 
-        let mut district_polygons = vec![];
-        for polygon in self.street_polygon.iter() {
-            district_polygons.append(&mut polygon.interiors().to_vec());
+    /// Returns the blocks of the map, i.e. the areas that are completely enclosed by arterial streets. Unlike
+    /// districts, blocks ignore local streets so that the local streets of a block can be replaced without touching
+    /// the arterial streets around it.
+    pub fn blocks(&self) -> Vec<Polygon<f64>> {
+        let mut arterial_polygon = MultiPolygon::new(vec![]);
+        for street in self
+            .streets
+            .values()
+            .filter(|street| street.class().is_arterial())
+        {
+            arterial_polygon = arterial_polygon.union(street.polygon());
         }
 
-        self.districts_mut().clear();
-        for i in 0..district_polygons.len() {
-            let polygon = Polygon::new(district_polygons[i].clone(), vec![]);
+        arterial_polygon
+            .iter()
+            .flat_map(|polygon| polygon.interiors().iter())
+            .map(|ring| Polygon::new(ring.clone(), vec![]))
+            .flat_map(|block| self.clip_water(block))
+            .collect()
+    }
+
+    pub fn get_block_at_position(&self, position: &Coordinate<f64>) -> Option<Polygon<f64>> {
+        self.blocks()
+            .into_iter()
+            .find(|block| block.contains(position))
+    }
 
-            let seed = <ChaCha8Rng as SeedableRng>::Seed::default();
-            let houses: Vec<House> = generate_houses_from_polygon(&polygon, 50., seed);
+    /// Updates the districts of all faces of the street network that intersect `rect` which is usually the
+    /// bounding box of a street that was added or removed.
+    ///
+    /// Faces that did not change keep their district as it is. Only districts of faces that vanished are removed
+    /// and only faces that are new get a freshly generated district. This keeps the ids and houses of untouched
+    /// districts stable and does not bring back districts that were deleted by the user.
+    fn update_districts(&mut self, rect: &Rect<f64>) {
+        let intersects_rect = |polygon: &Polygon<f64>| {
+            polygon
+                .bounding_rect()
+                .map_or(false, |bounding_rect| bounding_rect.intersects(rect))
+        };
+
+        let (old_faces, unaffected_faces): (Vec<Polygon<f64>>, Vec<Polygon<f64>>) = self
+            .district_polygons
+            .drain(..)
+            .partition(|face| intersects_rect(face));
+
+        let new_faces = self.street_faces_intersecting(rect);
+
+        // Names, wards and kinds of vanished districts are passed on to the new district that covers their center
+        let mut inherited: Vec<(Point<f64>, Option<String>, Option<Ward>, DistrictKind)> = vec![];
+        for old_face in old_faces
+            .iter()
+            .filter(|old_face| !new_faces.iter().any(|face| polygons_equal(old_face, face)))
+        {
+            let vanished: Vec<Uuid> = self
+                .district_index
+                .intersecting(rect)
+                .filter(|id| {
+                    self.districts
+                        .get(id)
+                        .map_or(false, |district| polygons_equal(district.polygon(), old_face))
+                })
+                .collect();
+
+            for id in vanished {
+                if let Some(district) = self.districts.get(&id) {
+                    if let Some(centroid) = district.polygon().centroid() {
+                        if district.name().is_some()
+                            || district.ward().is_some()
+                            || district.kind() != DistrictKind::Residential
+                        {
+                            inherited.push((
+                                centroid,
+                                district.name().cloned(),
+                                district.ward(),
+                                district.kind(),
+                            ));
+                        }
+                    }
+                }
+
+                self.remove_district(&id);
+            }
+        }
 
-            let district = District {
-                polygon: polygon.clone(),
+        for face in new_faces
+            .iter()
+            .filter(|face| !old_faces.iter().any(|old_face| polygons_equal(old_face, face)))
+        {
+            let seed = derive_district_seed(self.seed, face);
+            let houses: Vec<House> = generate_houses_from_polygon(
+                face,
+                50.,
+                &Subdivision::default(),
+                &Footprint::default(),
+                seed,
+            );
+            let (name, ward, kind) = inherited
+                .iter()
+                .find(|(centroid, _, _, _)| face.contains(centroid))
+                .map_or(
+                    (None, None, DistrictKind::default()),
+                    |(_, name, ward, kind)| (name.clone(), *ward, *kind),
+                );
+
+            let mut district = District {
+                polygon: face.clone(),
+                seed,
                 houses,
-                minimum_house_side: 250.,
+                minimum_house_side: MINIMUM_HOUSE_SIDE,
+                name,
+                ward,
                 ..District::default()
             };
-            self.districts_mut().insert(district.id(), district);
-
-            self.district_polygons.push(polygon);
+            if kind != DistrictKind::Residential {
+                district.set_kind(kind);
+            }
+            self.add_district(district);
         }
+
+        self.district_polygons = unaffected_faces;
+        self.district_polygons.extend(new_faces);
     }
 
+    /// Adds a street to the map. The street polygon is merged into the existing street polygon
+    /// so that only the districts around the new street need to be updated.
     pub fn add_street(&mut self, street: &Street) -> Uuid {
+        self.changed();
+
         let id = street.id();
         self.streets.insert(id, street.clone());
 
-        self.update_districts();
+        self.street_polygon = self.street_polygon.union(street.polygon());
+
+        if let Some(rect) = street.polygon().bounding_rect() {
+            self.street_index.insert(id, rect);
+            self.update_districts(&rect);
+        }
 
         id
     }
 
+    /// Removes a street from the map. As the street polygon can not be reduced by the removed street
+    /// alone, the connected parts of the street polygon the removed street belonged to are rebuilt from the
+    /// remaining streets within them. All other parts are kept. Districts are only updated around the removed
+    /// street.
     pub fn remove_street(&mut self, street: &Street) {
+        self.changed();
+
         self.streets.remove(&street.id());
+        self.street_index.remove(&street.id());
+
+        // The union merges touching streets, so every street lies within exactly one of the parts
+        let (affected, mut kept): (Vec<Polygon<f64>>, Vec<Polygon<f64>>) = self
+            .street_polygon
+            .0
+            .drain(..)
+            .partition(|part| part.intersects(street.polygon()));
+
+        let mut rebuilt = MultiPolygon::new(vec![]);
+        for part in &affected {
+            let rect = match part.bounding_rect() {
+                Some(rect) => rect,
+                None => continue,
+            };
 
-        self.update_districts();
+            for id in self.street_index.intersecting(&rect) {
+                if let Some(street) = self.streets.get(&id) {
+                    if street.polygon().intersects(part) {
+                        rebuilt = rebuilt.union(street.polygon());
+                    }
+                }
+            }
+        }
+
+        kept.extend(rebuilt.0);
+        self.street_polygon = MultiPolygon::new(kept);
+
+        if let Some(rect) = street.polygon().bounding_rect() {
+            self.update_districts(&rect);
+        }
+    }
+
+    /// Changes the street with `id` by applying `update` to it. The street is removed and added again so that
+    /// the street polygon and the districts around the old and the new shape of the street are updated.
+    pub fn update_street<F>(&mut self, id: &Uuid, update: F)
+    where
+        F: FnOnce(&mut Street),
+    {
+        self.changed();
+
+        if let Some(mut street) = self.streets.get(id).cloned() {
+            self.remove_street(&street);
+            update(&mut street);
+            self.add_street(&street);
+        }
     }
 
     pub fn add_district(&mut self, district: District) -> Uuid {
+        self.changed();
+
         let id = district.id();
+        if let Some(rect) = district.polygon().bounding_rect() {
+            self.district_index.insert(id, rect);
+        }
         self.districts.insert(id, district);
 
         id
     }
 
     pub fn add_intersection(&mut self, intersection: Intersection) -> Uuid {
+        self.changed();
+
         let id = intersection.id();
+        self.intersection_index
+            .insert(id, Rect::new(intersection.position(), intersection.position()));
         self.intersections.insert(id, intersection);
 
         self.update_bounding_box();
@@ -267,23 +768,41 @@ impl Map {
         id
     }
 
+    pub fn remove_intersection(&mut self, id: &Uuid) -> Option<Intersection> {
+        self.changed();
+
+        self.intersection_index.remove(id);
+
+        self.intersections.remove(id)
+    }
+
+    /// Updates the position of the intersection `id` in the spatial index. Needs to be called each time
+    /// the position of an intersection was altered directly.
+    pub fn update_intersection_index(&mut self, id: &Uuid) {
+        self.changed();
+
+        if let Some(intersection) = self.intersections.get(id) {
+            self.intersection_index
+                .insert(*id, Rect::new(intersection.position(), intersection.position()));
+        }
+    }
+
     pub fn get_intersection_at_position(
         &self,
         position: &Coordinate<f64>,
         offset: f64,
         ignored_intersections: &Vec<Uuid>,
     ) -> Option<Uuid> {
-        for (id, intersection) in &self.intersections {
-            if ignored_intersections.into_iter().any(|e| e == id) {
-                continue;
-            }
-
-            if intersection.position().euclidean_distance(position) < offset {
-                return Some(*id);
-            }
-        }
-
-        None
+        self.intersection_index
+            .around(position, offset)
+            .filter(|id| !ignored_intersections.contains(id))
+            .filter_map(|id| {
+                let distance = self.intersections.get(&id)?.position().euclidean_distance(position);
+
+                (distance < offset).then(|| (id, distance))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(id, _)| id)
     }
 
     pub fn line_intersection_with_intersections(
@@ -292,7 +811,11 @@ impl Map {
     ) -> Vec<(Uuid, Coordinate<f64>)> {
         let mut intersections: Vec<(Uuid, Coordinate<f64>)> = Vec::new();
 
-        for (_, intersection) in &self.intersections {
+        for intersection in self
+            .intersection_index
+            .intersecting(&line.bounding_rect())
+            .filter_map(|id| self.intersections.get(&id))
+        {
             if line.intersects(&intersection.position())
                 && intersection.position() != line.start
                 && intersection.position() != line.end
@@ -324,20 +847,41 @@ impl Map {
         position: &Coordinate<f64>,
         ignored_streets: &Vec<Uuid>,
     ) -> Option<Uuid> {
-        for (id, street) in &self.streets {
-            if ignored_streets.contains(id) {
-                continue;
-            }
+        self.street_index
+            .at_position(position)
+            .filter(|id| !ignored_streets.contains(id))
+            .find(|id| {
+                self.streets
+                    .get(id)
+                    .map_or(false, |street| street.is_point_on_street(position))
+            })
+    }
 
-            if street.is_point_on_street(position) {
-                return Some(*id);
+    /// Returns the ids of the intersections at the start and at the end of the street with `id`.
+    /// Either one is `None` if the street is not connected to an intersection on that side.
+    pub fn street_intersections(&self, id: &Uuid) -> (Option<Uuid>, Option<Uuid>) {
+        let mut start = None;
+        let mut end = None;
+
+        for (intersection_id, intersection) in &self.intersections {
+            for (direction, street_id) in intersection.get_connected_streets() {
+                if street_id != id {
+                    continue;
+                }
+
+                match direction {
+                    Direction::Out => start = Some(*intersection_id),
+                    Direction::In => end = Some(*intersection_id),
+                }
             }
         }
 
-        None
+        (start, end)
     }
 
     pub fn update_bounding_box(&mut self) {
+        self.changed();
+
         let polygon: Polygon<f64> = self.into();
         if let Some(bb) = polygon.bounding_rect() {
             self.bounding_box = bb;
@@ -369,6 +913,8 @@ impl Map {
     }
 
     pub fn street_mut(&mut self, id: &Uuid) -> Option<&mut Street> {
+        self.changed();
+
         if self.streets.contains_key(id) {
             return Some(self.streets.get_mut(id).unwrap());
         }
@@ -384,7 +930,28 @@ impl Map {
         None
     }
 
+    pub fn block_junctions(&self) -> &HashSet<Uuid> {
+        &self.block_junctions
+    }
+
+    /// Marks the intersection `id` as created by filling a block, see [`FillBlockAction`].
+    ///
+    /// [`FillBlockAction`]: crate::plugins::generate_roads::FillBlockAction
+    pub fn add_block_junction(&mut self, id: Uuid) {
+        self.changed();
+
+        self.block_junctions.insert(id);
+    }
+
+    pub fn remove_block_junction(&mut self, id: &Uuid) -> bool {
+        self.changed();
+
+        self.block_junctions.remove(id)
+    }
+
     pub fn intersection_mut(&mut self, id: &Uuid) -> Option<&mut Intersection> {
+        self.changed();
+
         if self.intersections.contains_key(id) {
             return Some(self.intersections.get_mut(id).unwrap());
         }
@@ -401,6 +968,8 @@ impl Map {
     }
 
     pub fn district_mut(&mut self, id: &Uuid) -> Option<&mut District> {
+        self.changed();
+
         if self.districts.contains_key(id) {
             return Some(self.districts.get_mut(id).unwrap());
         }
@@ -409,16 +978,746 @@ impl Map {
     }
 
     pub fn get_district_at_position(&self, position: &Coordinate<f64>) -> Option<Uuid> {
-        for (_, district) in &self.districts {
-            if district.is_point_on_district(position) {
-                return Some(district.id());
+        self.district_index.at_position(position).find(|id| {
+            self.districts
+                .get(id)
+                .map_or(false, |district| district.is_point_on_district(position))
+        })
+    }
+
+    pub fn remove_district(&mut self, id: &Uuid) {
+        self.changed();
+
+        self.district_index.remove(id);
+        self.districts.remove(id);
+    }
+
+    pub fn walls(&self) -> &HashMap<Uuid, Wall> {
+        &self.walls
+    }
+
+    pub fn wall(&self, id: &Uuid) -> Option<&Wall> {
+        self.walls.get(id)
+    }
+
+    pub fn wall_mut(&mut self, id: &Uuid) -> Option<&mut Wall> {
+        self.changed();
+        self.walls.get_mut(id)
+    }
+
+    pub fn add_wall(&mut self, wall: Wall) -> Uuid {
+        self.changed();
+
+        let id = wall.id();
+        if let Some(rect) = wall.bounding_rect() {
+            self.wall_index.insert(id, rect);
+        }
+        self.walls.insert(id, wall);
+
+        id
+    }
+
+    pub fn remove_wall(&mut self, id: &Uuid) -> Option<Wall> {
+        self.changed();
+
+        self.wall_index.remove(id);
+
+        self.walls.remove(id)
+    }
+
+    pub fn get_wall_at_position(&self, position: &Coordinate<f64>) -> Option<Uuid> {
+        self.wall_index.at_position(position).find(|id| {
+            matches!(self.walls.get(id), Some(wall) if wall.is_point_on_wall(position))
+        })
+    }
+
+    pub fn waters(&self) -> &HashMap<Uuid, Water> {
+        &self.waters
+    }
+
+    pub fn water(&self, id: &Uuid) -> Option<&Water> {
+        self.waters.get(id)
+    }
+
+    pub fn water_mut(&mut self, id: &Uuid) -> Option<&mut Water> {
+        self.changed();
+        self.waters.get_mut(id)
+    }
+
+    /// Adds a water to the map and clips the districts around it against the water.
+    pub fn add_water(&mut self, water: Water) -> Uuid {
+        self.changed();
+
+        let id = water.id();
+        self.water_polygon = self.water_polygon.union(water.polygon());
+
+        let rect = water.bounding_rect();
+        self.waters.insert(id, water);
+
+        if let Some(rect) = rect {
+            self.water_index.insert(id, rect);
+            self.update_districts(&rect);
+        }
+
+        id
+    }
+
+    /// Removes a water from the map. Like the street polygon, the water polygon is rebuilt from the remaining
+    /// waters and the districts around the removed water are updated.
+    pub fn remove_water(&mut self, id: &Uuid) -> Option<Water> {
+        self.changed();
+
+        self.water_index.remove(id);
+        let water = self.waters.remove(id)?;
+
+        self.water_polygon = MultiPolygon::new(vec![]);
+        for (_, water) in &self.waters {
+            self.water_polygon = self.water_polygon.union(water.polygon());
+        }
+
+        if let Some(rect) = water.bounding_rect() {
+            self.update_districts(&rect);
+        }
+
+        Some(water)
+    }
+
+    pub fn get_water_at_position(&self, position: &Coordinate<f64>) -> Option<Uuid> {
+        self.water_index.at_position(position).find(|id| {
+            matches!(self.waters.get(id), Some(water) if water.is_point_on_water(position))
+        })
+    }
+
+    /// Returns a bridge for each crossing of a street with a river.
+    pub fn bridges(&self) -> Vec<Bridge> {
+        let mut bridges = Vec::new();
+
+        for river in self
+            .waters
+            .values()
+            .filter(|water| water.kind() == WaterKind::River)
+        {
+            let rect = match river.bounding_rect() {
+                Some(rect) => rect,
+                None => continue,
+            };
+
+            for street in self
+                .street_index
+                .intersecting(&rect)
+                .filter_map(|id| self.streets.get(&id))
+            {
+                for position in line_string_crossings(&street.lines, river.line_string()) {
+                    bridges.push(Bridge::new(street, river, position));
+                }
             }
         }
 
-        None
+        bridges
     }
 
-    pub fn remove_district(&mut self, id: &Uuid) {
-        self.districts.remove(id);
+    pub fn landmarks(&self) -> &HashMap<Uuid, Landmark> {
+        &self.landmarks
+    }
+
+    pub fn landmark(&self, id: &Uuid) -> Option<&Landmark> {
+        self.landmarks.get(id)
+    }
+
+    pub fn add_landmark(&mut self, landmark: Landmark) -> Uuid {
+        self.changed();
+
+        let id = landmark.id();
+        self.landmark_index.insert(id, landmark.bounding_rect());
+        self.landmarks.insert(id, landmark);
+
+        id
+    }
+
+    pub fn remove_landmark(&mut self, id: &Uuid) -> Option<Landmark> {
+        self.changed();
+
+        self.landmark_index.remove(id);
+        self.landmarks.remove(id)
+    }
+
+    /// Changes the landmark with `id` in place and updates the spatial index afterwards, as `update` might
+    /// move the landmark.
+    pub fn update_landmark(&mut self, id: &Uuid, update: impl FnOnce(&mut Landmark)) {
+        self.changed();
+
+        if let Some(landmark) = self.landmarks.get_mut(id) {
+            update(landmark);
+
+            self.landmark_index.remove(id);
+            self.landmark_index.insert(*id, landmark.bounding_rect());
+        }
+    }
+
+    /// Returns the landmark whose icon is closest to `position` among all landmarks hit at `position`.
+    pub fn get_landmark_at_position(&self, position: &Coordinate<f64>) -> Option<Uuid> {
+        self.landmark_index
+            .around(position, LANDMARK_RADIUS)
+            .filter_map(|id| {
+                let landmark = self.landmarks.get(&id)?;
+                let distance = landmark.position().euclidean_distance(position);
+
+                landmark.is_point_on_landmark(position).then(|| (id, distance))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the smallest rect that contains all streets, intersections, districts, walls, waters and landmarks
+    /// or `None` if the map has no elements.
+    pub fn bounding_rect(&self) -> Option<Rect<f64>> {
+        [
+            &self.street_index,
+            &self.intersection_index,
+            &self.district_index,
+            &self.wall_index,
+            &self.water_index,
+            &self.landmark_index,
+        ]
+        .iter()
+        .filter_map(|index| index.bounding_rect())
+        .reduce(|a, b| {
+            Rect::new(
+                Coordinate {
+                    x: a.min().x.min(b.min().x),
+                    y: a.min().y.min(b.min().y),
+                },
+                Coordinate {
+                    x: a.max().x.max(b.max().x),
+                    y: a.max().y.max(b.max().y),
+                },
+            )
+        })
+    }
+
+    pub fn terrain(&self) -> Option<&Terrain> {
+        self.terrain.as_ref()
+    }
+
+    /// Replaces the terrain of the map and returns the previous one.
+    pub fn set_terrain(&mut self, terrain: Option<Terrain>) -> Option<Terrain> {
+        self.changed();
+        std::mem::replace(&mut self.terrain, terrain)
+    }
+
+    /// Elevation of the ground at `position`. Maps without terrain are flat at zero.
+    pub fn elevation_at(&self, position: &Coordinate<f64>) -> f64 {
+        self.terrain
+            .as_ref()
+            .map_or(0., |terrain| terrain.elevation_at(position))
+    }
+
+    /// Checks the map for problems like streets without length or districts that are too small to
+    /// contain any house. See [`Problem`] for all problems that are reported.
+    pub fn validate(&self) -> Vec<Problem> {
+        validation::validate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::Area, Coordinate, LineString, MultiPolygon};
+    use geo_booleanop::boolean::BooleanOp;
+    use rust_editor::gizmo::{GetPosition, Id, SetPosition};
+    use uuid::Uuid;
+
+    use crate::map::{
+        district_kind::DistrictKind,
+        landmark::{Landmark, LandmarkKind, LANDMARK_RADIUS},
+        street::Street,
+        street_class::StreetClass,
+        terrain::{Terrain, TerrainShading, TerrainSource},
+        wall::Wall,
+        ward::Ward,
+        water::{Water, WaterKind},
+    };
+
+    use crate::algorithm::geo::polygons_equal;
+
+    use super::Map;
+
+    fn add_street(points: Vec<(f64, f64)>, map: &mut Map) -> Street {
+        let street = Street::new(LineString::from(points));
+        map.add_street(&street);
+
+        street
+    }
+
+    /// Creates a map with a closed square of streets resulting in a single district
+    fn create_map() -> Map {
+        let mut map = Map::new(1000, 1000);
+
+        add_street(vec![(0., 0.), (200., 0.)], &mut map);
+        add_street(vec![(200., 0.), (200., 200.)], &mut map);
+        add_street(vec![(200., 200.), (0., 200.)], &mut map);
+        add_street(vec![(0., 200.), (0., 0.)], &mut map);
+
+        map
+    }
+
+    fn district_ids(map: &Map) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = map.districts().keys().cloned().collect();
+        ids.sort();
+
+        ids
+    }
+
+    #[test]
+    fn closed_streets_create_district() {
+        let map = create_map();
+
+        assert_eq!(map.districts().len(), 1);
+    }
+
+    #[test]
+    fn split_district_passes_name_on() {
+        let mut map = create_map();
+        let district = district_ids(&map)[0];
+        map.district_mut(&district)
+            .unwrap()
+            .set_name(Some("Old Town".to_string()));
+
+        add_street(vec![(50., 0.), (50., 200.)], &mut map);
+
+        let names: Vec<&String> = map
+            .districts()
+            .values()
+            .filter_map(|district| district.name())
+            .collect();
+        assert_eq!(map.districts().len(), 2);
+        assert_eq!(names, vec!["Old Town"]);
+    }
+
+    #[test]
+    fn unrelated_street_keeps_districts() {
+        let mut map = create_map();
+        let districts = district_ids(&map);
+
+        add_street(vec![(500., 500.), (700., 500.)], &mut map);
+
+        assert_eq!(district_ids(&map), districts);
+    }
+
+    #[test]
+    fn splitting_street_replaces_district() {
+        let mut map = create_map();
+        let districts = district_ids(&map);
+
+        add_street(vec![(-20., 100.), (220., 100.)], &mut map);
+
+        assert_eq!(map.districts().len(), 2);
+        assert!(district_ids(&map).iter().all(|id| !districts.contains(id)));
+    }
+
+    #[test]
+    fn removed_street_rebuilds_street_polygon_around_it() {
+        let mut map = create_map();
+        add_street(vec![(400., 0.), (600., 0.)], &mut map);
+        add_street(vec![(600., 0.), (600., 200.)], &mut map);
+        add_street(vec![(600., 200.), (400., 200.)], &mut map);
+        add_street(vec![(400., 200.), (400., 0.)], &mut map);
+        let districts = district_ids(&map);
+
+        let street = add_street(vec![(-20., 100.), (220., 100.)], &mut map);
+        assert_eq!(map.districts().len(), 3);
+
+        map.remove_street(&street);
+
+        // The district of the other square is kept and the split district is restored
+        assert_eq!(map.districts().len(), 2);
+        assert_eq!(
+            district_ids(&map)
+                .iter()
+                .filter(|id| districts.contains(id))
+                .count(),
+            1
+        );
+        let faces = map.street_faces();
+        assert!(map.districts().values().all(|district| faces
+            .iter()
+            .any(|face| polygons_equal(face, district.polygon()))));
+    }
+
+    #[test]
+    fn removed_street_next_to_rect_border_leaves_no_seams() {
+        let mut map = create_map();
+
+        // The edge of this street lies just outside of the bounding box of the removed street
+        add_street(vec![(0., 110.000000001), (200., 110.000000001)], &mut map);
+        let street = add_street(vec![(-20., 90.), (220., 90.)], &mut map);
+        map.remove_street(&street);
+
+        let mut rebuilt = MultiPolygon::new(vec![]);
+        for street in map.streets().values() {
+            rebuilt = rebuilt.union(street.polygon());
+        }
+
+        assert_eq!(map.street_polygon.0.len(), rebuilt.0.len());
+        assert!((map.street_polygon.unsigned_area() - rebuilt.unsigned_area()).abs() < 1e-6);
+        assert_eq!(map.street_faces().len(), 2);
+        assert_eq!(map.districts().len(), 2);
+    }
+
+    #[test]
+    fn blocks_are_enclosed_by_arterial_streets_only() {
+        let mut map = create_map();
+        assert!(map.blocks().is_empty());
+
+        let street_ids: Vec<Uuid> = map.streets().keys().cloned().collect();
+        for id in street_ids {
+            map.update_street(&id, |street| street.set_class(StreetClass::MainRoad));
+        }
+        add_street(vec![(50., 0.), (50., 200.)], &mut map);
+
+        assert_eq!(map.districts().len(), 2);
+        assert_eq!(map.blocks().len(), 1);
+        assert!(map
+            .get_block_at_position(&Coordinate { x: 20., y: 100. })
+            .is_some());
+        assert!(map
+            .get_block_at_position(&Coordinate { x: 500., y: 500. })
+            .is_none());
+    }
+
+    #[test]
+    fn same_seed_generates_same_districts() {
+        let seeds = |map: &Map| -> Vec<_> {
+            let mut seeds: Vec<_> = map
+                .districts()
+                .values()
+                .map(|district| district.seed())
+                .collect();
+            seeds.sort();
+
+            seeds
+        };
+
+        let mut map = Map::new(1000, 1000);
+        map.set_seed(42);
+        add_street(vec![(0., 0.), (200., 0.)], &mut map);
+        add_street(vec![(200., 0.), (200., 200.)], &mut map);
+        add_street(vec![(200., 200.), (0., 200.)], &mut map);
+        add_street(vec![(0., 200.), (0., 0.)], &mut map);
+
+        let mut other = Map::new(1000, 1000);
+        other.set_seed(42);
+        add_street(vec![(0., 200.), (0., 0.)], &mut other);
+        add_street(vec![(200., 200.), (0., 200.)], &mut other);
+        add_street(vec![(200., 0.), (200., 200.)], &mut other);
+        add_street(vec![(0., 0.), (200., 0.)], &mut other);
+
+        assert_eq!(seeds(&map), seeds(&other));
+
+        other.set_seed(43);
+        assert_ne!(seeds(&map), seeds(&other));
+    }
+
+    #[test]
+    fn serialized_map_can_be_loaded() {
+        let map = create_map();
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        assert_eq!(loaded.seed(), map.seed());
+        assert_eq!(loaded.streets().len(), map.streets().len());
+        assert_eq!(district_ids(&loaded), district_ids(&map));
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&map).unwrap()
+        );
+    }
+
+    #[test]
+    fn curved_street_is_loaded_with_control_points() {
+        let mut map = Map::new(1000, 1000);
+        let street = Street::new_curved(
+            vec![
+                Coordinate { x: 100., y: 100. },
+                Coordinate { x: 200., y: 150. },
+                Coordinate { x: 300., y: 100. },
+            ],
+            StreetClass::MainRoad,
+        );
+        map.add_street(&street);
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        let loaded_street = loaded.street(&street.id()).unwrap();
+
+        assert!(loaded_street.is_curved());
+        assert_eq!(loaded_street.control_points(), street.control_points());
+        assert_eq!(loaded_street.lines, street.lines);
+    }
+
+    #[test]
+    fn split_district_keeps_ward_and_ward_is_loaded() {
+        let mut map = create_map();
+        let district = district_ids(&map)[0];
+        map.district_mut(&district)
+            .unwrap()
+            .set_ward(Some(Ward::Market));
+
+        add_street(vec![(50., 0.), (50., 200.)], &mut map);
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        let wards: Vec<Ward> = loaded
+            .districts()
+            .values()
+            .filter_map(|district| district.ward())
+            .collect();
+        assert_eq!(loaded.districts().len(), 2);
+        assert_eq!(wards, vec![Ward::Market]);
+    }
+
+    #[test]
+    fn split_district_keeps_kind_and_kind_is_loaded() {
+        let mut map = create_map();
+        let district = district_ids(&map)[0];
+        map.district_mut(&district)
+            .unwrap()
+            .set_kind(DistrictKind::Park);
+
+        add_street(vec![(50., 0.), (50., 200.)], &mut map);
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        assert_eq!(loaded.districts().len(), 2);
+        assert!(loaded.districts().values().all(|district| {
+            district.kind() == DistrictKind::Park
+                && district.houses.is_empty()
+                && !district.features.is_empty()
+        }));
+    }
+
+    #[test]
+    fn names_are_loaded() {
+        let mut map = create_map();
+        let district = district_ids(&map)[0];
+        map.district_mut(&district)
+            .unwrap()
+            .set_name(Some("Old Town".to_string()));
+        let street = *map.streets().keys().next().unwrap();
+        map.street_mut(&street)
+            .unwrap()
+            .set_name(Some("Main Street".to_string()));
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        assert_eq!(
+            loaded.district(&district).unwrap().name(),
+            Some(&"Old Town".to_string())
+        );
+        assert_eq!(
+            loaded.street(&street).unwrap().name(),
+            Some(&"Main Street".to_string())
+        );
+    }
+
+    #[test]
+    fn loaded_map_updates_districts_incrementally() {
+        let map = create_map();
+        let mut loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        add_street(vec![(500., 500.), (700., 500.)], &mut loaded);
+
+        assert_eq!(district_ids(&loaded), district_ids(&map));
+    }
+
+    #[test]
+    fn removing_unrelated_street_keeps_deleted_districts_deleted() {
+        let mut map = create_map();
+        let unrelated = add_street(vec![(500., 500.), (700., 500.)], &mut map);
+
+        let district = district_ids(&map)[0];
+        map.remove_district(&district);
+        map.remove_street(&unrelated);
+
+        assert!(map.districts().is_empty());
+        assert!(map.street(&unrelated.id()).is_none());
+    }
+
+    #[test]
+    fn walls_are_loaded_and_hit() {
+        let mut map = create_map();
+        let wall = Wall::new(
+            LineString::from(vec![
+                (-20., -20.),
+                (220., -20.),
+                (220., 220.),
+                (-20., 220.),
+                (-20., -20.),
+            ]),
+            vec![Coordinate { x: 220., y: 220. }],
+            vec![Coordinate { x: 100., y: -20. }],
+            8.,
+            20.,
+        );
+        let id = map.add_wall(wall);
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        let loaded_wall = loaded.wall(&id).unwrap();
+
+        assert_eq!(loaded_wall.ring(), map.wall(&id).unwrap().ring());
+        assert_eq!(loaded_wall.gates(), map.wall(&id).unwrap().gates());
+        assert_eq!(
+            loaded.get_wall_at_position(&Coordinate { x: 220., y: 100. }),
+            Some(id)
+        );
+        assert_eq!(
+            loaded.get_wall_at_position(&Coordinate { x: 100., y: -20. }),
+            None
+        );
+    }
+
+    #[test]
+    fn terrain_is_loaded() {
+        let mut map = create_map();
+        let position = Coordinate { x: 321., y: 123. };
+        assert_eq!(map.elevation_at(&position), 0.);
+
+        map.set_terrain(Some(Terrain::new(
+            TerrainSource::Noise {
+                seed: 5,
+                scale: 300.,
+                octaves: 4,
+            },
+            100.,
+            TerrainShading::Contours,
+            map.width(),
+            map.height(),
+        )));
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        assert_eq!(loaded.elevation_at(&position), map.elevation_at(&position));
+        assert_eq!(loaded.terrain().unwrap().shading(), TerrainShading::Contours);
+    }
+
+    fn create_river() -> Water {
+        Water::new(
+            WaterKind::River,
+            LineString::from(vec![(100., -50.), (100., 250.)]),
+            vec![20., 20.],
+        )
+    }
+
+    #[test]
+    fn river_divides_district_and_removal_restores_it() {
+        let mut map = create_map();
+
+        let river = map.add_water(create_river());
+
+        assert_eq!(map.districts().len(), 2);
+        assert_eq!(
+            map.get_district_at_position(&Coordinate { x: 100., y: 100. }),
+            None
+        );
+        assert_eq!(
+            map.get_water_at_position(&Coordinate { x: 105., y: 100. }),
+            Some(river)
+        );
+
+        map.remove_water(&river);
+
+        assert_eq!(map.districts().len(), 1);
+        assert!(map
+            .get_district_at_position(&Coordinate { x: 100., y: 100. })
+            .is_some());
+    }
+
+    #[test]
+    fn streets_crossing_rivers_become_bridges_and_waters_are_loaded() {
+        let mut map = create_map();
+        let river = map.add_water(create_river());
+
+        assert_eq!(map.bridges().len(), 2);
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+
+        assert_eq!(
+            loaded.water(&river).unwrap().widths(),
+            map.water(&river).unwrap().widths()
+        );
+        assert_eq!(district_ids(&loaded), district_ids(&map));
+        assert_eq!(loaded.bridges().len(), 2);
+    }
+
+    #[test]
+    fn landmarks_are_hit_moved_and_loaded() {
+        let mut map = Map::new(1000, 1000);
+        let mut landmark = Landmark::new(LandmarkKind::Tavern, Coordinate { x: 100., y: 100. });
+        landmark.set_name(Some("The Prancing Pony".to_string()));
+        landmark.set_notes("Run by Barliman Butterbur".to_string());
+        landmark.set_rotation(45.);
+        let id = map.add_landmark(landmark);
+
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 105., y: 100. }),
+            Some(id)
+        );
+
+        map.update_landmark(&id, |landmark| {
+            landmark.set_position(Coordinate { x: 300., y: 300. })
+        });
+
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 105., y: 100. }),
+            None
+        );
+        assert_eq!(
+            map.get_landmark_at_position(&Coordinate { x: 300., y: 305. }),
+            Some(id)
+        );
+
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        let landmark = loaded.landmark(&id).unwrap();
+
+        assert_eq!(landmark.kind(), LandmarkKind::Tavern);
+        assert_eq!(landmark.position(), Coordinate { x: 300., y: 300. });
+        assert_eq!(landmark.rotation(), 45.);
+        assert_eq!(landmark.name(), Some(&"The Prancing Pony".to_string()));
+        assert_eq!(landmark.notes(), "Run by Barliman Butterbur");
+        assert_eq!(
+            loaded.get_landmark_at_position(&Coordinate { x: 300., y: 305. }),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn bounding_rect_contains_all_elements() {
+        assert_eq!(Map::new(1000, 1000).bounding_rect(), None);
+
+        let mut map = create_map();
+        map.add_landmark(Landmark::new(
+            LandmarkKind::Well,
+            Coordinate { x: 400., y: 100. },
+        ));
+        let rect = map.bounding_rect().unwrap();
+
+        // The streets have a width, so they reach beyond their intersections
+        assert!(rect.min().x < 0. && rect.min().y < 0. && rect.max().y > 200.);
+        assert_eq!(rect.max().x, 400. + LANDMARK_RADIUS);
+
+        map.clear();
+        assert_eq!(map.bounding_rect(), None);
+    }
+
+    #[test]
+    fn revision_changes_with_map() {
+        let mut map = create_map();
+        let revision = map.revision();
+
+        map.validate();
+        assert_eq!(map.revision(), revision);
+
+        add_street(vec![(0., 300.), (200., 300.)], &mut map);
+        assert_ne!(map.revision(), revision);
+
+        // A map that replaces another one, e.g. after it was loaded, never has the same revision
+        let loaded: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_ne!(loaded.revision(), map.revision());
     }
 }
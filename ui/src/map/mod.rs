@@ -1,6 +1,17 @@
 pub mod district;
+pub mod district_kind;
+pub mod footprint;
+pub mod format;
 pub mod house;
 pub mod intersection;
+pub mod landmark;
 pub mod map;
+pub mod spatial_index;
 pub mod street;
+pub mod street_class;
+pub mod terrain;
+pub mod validation;
+pub mod wall;
+pub mod ward;
+pub mod water;
 pub mod actions;
\ No newline at end of file
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use geo::{Coordinate, Rect};
+use rstar::{RTree, RTreeObject, AABB};
+use uuid::Uuid;
+
+/// Entry of a [`SpatialIndex`] that associates the id of a map element with its bounding box.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct IndexedElement {
+    id: Uuid,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for IndexedElement {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+fn envelope_from_rect(rect: &Rect<f64>) -> AABB<[f64; 2]> {
+    AABB::from_corners([rect.min().x, rect.min().y], [rect.max().x, rect.max().y])
+}
+
+/// R-tree based index over the bounding boxes of map elements.
+///
+/// The index only stores bounding boxes, queries therefore return candidates that must be checked
+/// against the exact geometry of the element afterwards.
+#[derive(Clone, Default)]
+pub struct SpatialIndex {
+    tree: RTree<IndexedElement>,
+
+    /// Stores the indexed envelope for each id so that elements can be removed even if their
+    /// geometry was altered after they were inserted.
+    elements: HashMap<Uuid, IndexedElement>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        SpatialIndex::default()
+    }
+
+    /// Adds the element `id` with the bounding box `rect` to the index. An already indexed element
+    /// with the same id is replaced.
+    pub fn insert(&mut self, id: Uuid, rect: Rect<f64>) {
+        self.remove(&id);
+
+        let element = IndexedElement {
+            id,
+            envelope: envelope_from_rect(&rect),
+        };
+
+        self.tree.insert(element);
+        self.elements.insert(id, element);
+    }
+
+    /// Removes the element `id` from the index. Returns `true` if the element was indexed.
+    pub fn remove(&mut self, id: &Uuid) -> bool {
+        match self.elements.remove(id) {
+            Some(element) => self.tree.remove(&element).is_some(),
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = RTree::new();
+        self.elements.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the smallest rect that contains the bounding boxes of all elements or `None` if the index is empty.
+    pub fn bounding_rect(&self) -> Option<Rect<f64>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let envelope = self.tree.root().envelope();
+        let (min, max) = (envelope.lower(), envelope.upper());
+
+        Some(Rect::new(
+            Coordinate {
+                x: min[0],
+                y: min[1],
+            },
+            Coordinate {
+                x: max[0],
+                y: max[1],
+            },
+        ))
+    }
+
+    /// Returns the ids of all elements whose bounding box contains `position`.
+    pub fn at_position<'a>(&'a self, position: &Coordinate<f64>) -> impl Iterator<Item = Uuid> + 'a {
+        self.tree
+            .locate_in_envelope_intersecting(&AABB::from_point([position.x, position.y]))
+            .map(|element| element.id)
+    }
+
+    /// Returns the ids of all elements whose bounding box intersects `rect`.
+    pub fn intersecting<'a>(&'a self, rect: &Rect<f64>) -> impl Iterator<Item = Uuid> + 'a {
+        self.tree
+            .locate_in_envelope_intersecting(&envelope_from_rect(rect))
+            .map(|element| element.id)
+    }
+
+    /// Returns the ids of all elements whose bounding box intersects the square with the side length
+    /// `2 * offset` centered at `position`.
+    pub fn around<'a>(
+        &'a self,
+        position: &Coordinate<f64>,
+        offset: f64,
+    ) -> impl Iterator<Item = Uuid> + 'a {
+        let offset = Coordinate {
+            x: offset,
+            y: offset,
+        };
+
+        self.intersecting(&Rect::new(*position - offset, *position + offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, Rect};
+    use uuid::Uuid;
+
+    use super::SpatialIndex;
+
+    fn rect(min: (f64, f64), max: (f64, f64)) -> Rect<f64> {
+        Rect::new(
+            Coordinate { x: min.0, y: min.1 },
+            Coordinate { x: max.0, y: max.1 },
+        )
+    }
+
+    #[test]
+    fn unit_at_position_returns_containing_elements() {
+        let mut index = SpatialIndex::new();
+
+        let inside = Uuid::new_v4();
+        let outside = Uuid::new_v4();
+        index.insert(inside, rect((0., 0.), (100., 100.)));
+        index.insert(outside, rect((200., 200.), (300., 300.)));
+
+        let result: Vec<Uuid> = index.at_position(&Coordinate { x: 50., y: 50. }).collect();
+
+        assert_eq!(result, vec![inside]);
+    }
+
+    #[test]
+    fn unit_remove_by_id() {
+        let mut index = SpatialIndex::new();
+
+        let id = Uuid::new_v4();
+        index.insert(id, rect((0., 0.), (100., 100.)));
+
+        assert!(index.remove(&id));
+        assert!(index.is_empty());
+        assert_eq!(index.at_position(&Coordinate { x: 50., y: 50. }).count(), 0);
+    }
+
+    #[test]
+    fn unit_insert_replaces_element_with_same_id() {
+        let mut index = SpatialIndex::new();
+
+        let id = Uuid::new_v4();
+        index.insert(id, rect((0., 0.), (100., 100.)));
+        index.insert(id, rect((200., 200.), (300., 300.)));
+
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.around(&Coordinate { x: 50., y: 50. }, 10.).count(), 0);
+        assert_eq!(index.around(&Coordinate { x: 250., y: 250. }, 10.).count(), 1);
+    }
+
+    #[test]
+    fn unit_bounding_rect_contains_all_elements() {
+        let mut index = SpatialIndex::new();
+        assert_eq!(index.bounding_rect(), None);
+
+        let id = Uuid::new_v4();
+        index.insert(id, rect((0., 50.), (100., 100.)));
+        index.insert(Uuid::new_v4(), rect((200., -20.), (300., 30.)));
+        assert_eq!(index.bounding_rect(), Some(rect((0., -20.), (300., 100.))));
+
+        index.remove(&id);
+        assert_eq!(index.bounding_rect(), Some(rect((200., -20.), (300., 30.))));
+    }
+}
@@ -1,35 +1,57 @@
 extern crate rust_editor;
 
+use std::cmp::Ordering;
 
 use geo::{
     line_intersection::LineIntersection,
     prelude::{Contains, EuclideanDistance},
-    Coordinate, Line, LineString, Polygon,
+    Coordinate, Line, LineString, Point, Polygon,
 };
 use rust_editor::{
     gizmo::{Id, SetId},
     interactive_element::{InteractiveElement, InteractiveElementState},
-    renderer::PrimitiveRenderer,
+    renderer::{render_text_along, Canvas, PrimitiveRenderer, RenderError},
     style::{InteractiveElementStyle, Style},
 };
 use rust_macro::ElementId;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use wasm_bindgen::JsValue;
-use web_sys::CanvasRenderingContext2d;
 
 use geo::line_intersection::line_intersection;
 
+use crate::algorithm::{
+    geo::split_line_string,
+    spline::{BezierSpline, CatmullRom, CURVE_TOLERANCE},
+};
+
+use super::street_class::StreetClass;
+
 #[derive(Clone, Serialize, Deserialize, ElementId, Debug, PartialEq)]
 pub struct Street {
     id: Uuid,
 
     pub lines: LineString<f64>,
 
+    /// Control points of curved streets. The lines of a curved street are a tessellation of the
+    /// Bézier spline through these points. Empty for streets that are not curved.
+    #[serde(default)]
+    control_points: Vec<Coordinate<f64>>,
+
+    /// Handles of the Bézier segments between the control points. They form a Catmull-Rom spline unless the
+    /// street was split off a longer one.
+    #[serde(default)]
+    handles: Vec<[Coordinate<f64>; 2]>,
+
     polygon: Polygon<f64>,
 
     width: f64,
 
+    #[serde(default)]
+    class: StreetClass,
+
+    #[serde(default)]
+    name: Option<String>,
+
     #[serde(skip_serializing)]
     norm: Coordinate<f64>,
 
@@ -186,9 +208,13 @@ impl Default for Street {
     fn default() -> Self {
         Street {
             id: Uuid::new_v4(),
-            width: 20.0,
+            width: StreetClass::default().default_width(),
+            class: StreetClass::default(),
             polygon: Polygon::new(LineString::from(vec![Coordinate { x: 0., y: 0. }]), vec![]),
             lines: LineString::new(vec![]),
+            control_points: vec![],
+            handles: vec![],
+            name: None,
 
             norm: Coordinate { x: 0.0, y: 0.0 },
             inverse_norm: Coordinate { x: 0.0, y: 0.0 },
@@ -219,17 +245,184 @@ impl InteractiveElement for Street {
 
 impl Street {
     pub fn new(line_string: LineString<f64>) -> Self {
-        let it = line_string.lines().into_iter().peekable();
-        let polygon = calc_polygon_points(it, 20.);
+        Street::new_with_class(line_string, StreetClass::default())
+    }
+
+    /// Creates a street of the given class that has the default width and style of the class.
+    pub fn new_with_class(line_string: LineString<f64>, class: StreetClass) -> Self {
+        let width = class.default_width();
+        let polygon = calc_polygon_points(line_string.lines(), width);
 
         Street {
             id: Uuid::new_v4(),
             lines: line_string,
             polygon,
+            width,
+            class,
+            style: class.style(),
             ..Default::default()
         }
     }
 
+    /// Creates a curved street of the given class that runs through all `control_points`.
+    pub fn new_curved(control_points: Vec<Coordinate<f64>>, class: StreetClass) -> Self {
+        let handles = CatmullRom::new(&control_points).bezier_handles();
+
+        Street::new_spline(BezierSpline::new(control_points, handles), class)
+    }
+
+    /// Creates a curved street whose segments between the control points are shaped by `handles`. Without one pair
+    /// of handles per segment the street becomes the Catmull-Rom spline through the control points.
+    pub fn new_curved_with_handles(
+        control_points: Vec<Coordinate<f64>>,
+        handles: Vec<[Coordinate<f64>; 2]>,
+        class: StreetClass,
+    ) -> Self {
+        match handles.len() + 1 == control_points.len() {
+            true => Street::new_spline(BezierSpline::new(control_points, handles), class),
+            false => Street::new_curved(control_points, class),
+        }
+    }
+
+    fn new_spline(spline: BezierSpline, class: StreetClass) -> Self {
+        let lines = spline.tessellate(CURVE_TOLERANCE);
+
+        Street {
+            control_points: spline.points,
+            handles: spline.handles,
+            ..Street::new_with_class(lines, class)
+        }
+    }
+
+    pub fn is_curved(&self) -> bool {
+        self.control_points.len() > 1
+    }
+
+    pub fn control_points(&self) -> &Vec<Coordinate<f64>> {
+        &self.control_points
+    }
+
+    pub fn handles(&self) -> &Vec<[Coordinate<f64>; 2]> {
+        &self.handles
+    }
+
+    /// Replaces the control points of a curved street and recalculates its lines and polygon. The curve becomes
+    /// the Catmull-Rom spline through the new control points.
+    pub fn set_control_points(&mut self, control_points: Vec<Coordinate<f64>>) {
+        self.handles = CatmullRom::new(&control_points).bezier_handles();
+        self.control_points = control_points;
+        self.lines = self.spline().tessellate(CURVE_TOLERANCE);
+        self.polygon = calc_polygon_points(self.lines.lines(), self.width);
+    }
+
+    fn spline(&self) -> BezierSpline {
+        BezierSpline::new(self.control_points.clone(), self.handles.clone())
+    }
+
+    /// The points that define the shape of the street: The control points of a curved street, the points of
+    /// the line string otherwise. The first and the last vertex are the ends of the street.
+    pub fn vertices(&self) -> &Vec<Coordinate<f64>> {
+        match self.is_curved() {
+            true => &self.control_points,
+            false => &self.lines.0,
+        }
+    }
+
+    /// Replaces the vertices of the street and recalculates its lines and polygon.
+    pub fn set_vertices(&mut self, vertices: Vec<Coordinate<f64>>) {
+        if self.is_curved() {
+            self.set_control_points(vertices);
+            return;
+        }
+
+        self.lines = LineString::new(vertices);
+        self.polygon = calc_polygon_points(self.lines.lines(), self.width);
+    }
+
+    /// Returns the index of the segment between two consecutive vertices that is closest to `position`.
+    /// A vertex inserted at `position` gets the returned index + 1.
+    pub fn closest_vertex_segment(&self, position: &Coordinate<f64>) -> Option<usize> {
+        let point = Point::from(*position);
+
+        LineString::new(self.vertices().clone())
+            .lines()
+            .map(|line| point.euclidean_distance(&line))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(index, _)| index)
+    }
+
+    /// Splits the street at the point on the street that is closest to `position`. Both parts get new
+    /// ids but keep class, width and name. The curve of a curved street is split with De Casteljau's algorithm, so
+    /// both parts together have exactly the shape of the street.
+    ///
+    /// Returns `None` if the split point coincides with the start or end of the street.
+    pub fn split(&self, position: &Coordinate<f64>) -> Option<(Street, Street)> {
+        let (first, second) = match self.is_curved() {
+            true => {
+                let spline = self.spline();
+                let (segment, t) = spline.closest_parameter(position, CURVE_TOLERANCE)?;
+                let (first, second) = spline.split(segment, t);
+
+                (
+                    Street::new_spline(first, self.class),
+                    Street::new_spline(second, self.class),
+                )
+            }
+            false => {
+                let (first, second) = split_line_string(&self.lines, position)?;
+
+                (
+                    Street::new_with_class(first, self.class),
+                    Street::new_with_class(second, self.class),
+                )
+            }
+        };
+
+        let part = |mut street: Street| {
+            street.set_width(self.width);
+            street.set_name(self.name.clone());
+
+            street
+        };
+
+        Some((part(first), part(second)))
+    }
+
+    /// Joins the street with `other`, which has to start where this street ends, into a single street with a new
+    /// id. This reverses [`Street::split`].
+    ///
+    /// Returns `None` if the streets are not connected this way, if they differ in class, width or name or if
+    /// only one of them is curved.
+    pub fn join(&self, other: &Street) -> Option<Street> {
+        if self.vertices().last() != other.vertices().first()
+            || self.class != other.class
+            || self.width != other.width
+            || self.name != other.name
+            || self.is_curved() != other.is_curved()
+        {
+            return None;
+        }
+
+        let mut street = match self.is_curved() {
+            true => Street::new_spline(self.spline().join(&other.spline()), self.class),
+            false => Street::new_with_class(
+                LineString::new(
+                    self.vertices()
+                        .iter()
+                        .chain(other.vertices().iter().skip(1))
+                        .copied()
+                        .collect(),
+                ),
+                self.class,
+            ),
+        };
+        street.set_width(self.width);
+        street.set_name(self.name.clone());
+
+        Some(street)
+    }
+
     pub fn norm(&self) -> Coordinate<f64> {
         self.norm
     }
@@ -246,12 +439,45 @@ impl Street {
         self.width
     }
 
-    pub fn render(&self, context: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+    /// Sets the width of the street and recalculates its polygon.
+    pub fn set_width(&mut self, width: f64) {
+        self.width = width;
+        self.polygon = calc_polygon_points(self.lines.lines(), width);
+    }
+
+    pub fn class(&self) -> StreetClass {
+        self.class
+    }
+
+    /// Changes the class of the street. The width is kept, only the style is updated to match the new class.
+    pub fn set_class(&mut self, class: StreetClass) {
+        self.class = class;
+        self.style = class.style();
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
         self.polygon.render(self.style(), context)?;
 
         Ok(())
     }
 
+    /// Renders the name of the street along its center line. Unnamed streets have no label.
+    pub fn render_label(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        if let Some(name) = &self.name {
+            render_text_along(name, &self.lines, &self.class.label_style(), context)?;
+        }
+
+        Ok(())
+    }
+
     pub fn perp(&self) -> Coordinate<f64> {
         Coordinate {
             x: -self.norm.y,
@@ -263,3 +489,79 @@ impl Street {
         self.polygon.contains(point)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::EuclideanDistance, Coordinate, LineString, Point};
+
+    use crate::{algorithm::spline::CURVE_TOLERANCE, map::street_class::StreetClass};
+
+    use super::Street;
+
+    fn curved_street() -> Street {
+        Street::new_curved(
+            vec![
+                Coordinate { x: 0., y: 0. },
+                Coordinate { x: 100., y: 100. },
+                Coordinate { x: 200., y: 0. },
+                Coordinate { x: 300., y: 50. },
+            ],
+            StreetClass::Street,
+        )
+    }
+
+    fn distance(point: &Coordinate<f64>, lines: &[&LineString<f64>]) -> f64 {
+        lines
+            .iter()
+            .map(|line_string| Point::from(*point).euclidean_distance(*line_string))
+            .fold(f64::MAX, f64::min)
+    }
+
+    #[test]
+    fn unit_split_keeps_tessellation_of_curved_street() {
+        let street = curved_street();
+
+        let (first, second) = street.split(&Coordinate { x: 150., y: 60. }).unwrap();
+
+        assert!(first.is_curved() && second.is_curved());
+        assert_eq!(first.lines.0.first(), street.lines.0.first());
+        assert_eq!(first.lines.0.last(), second.lines.0.first());
+        assert_eq!(second.lines.0.last(), street.lines.0.last());
+
+        // Both parts follow the tessellated curve of the street and cover it completely
+        for point in first.lines.0.iter().chain(second.lines.0.iter()) {
+            assert!(distance(point, &[&street.lines]) <= CURVE_TOLERANCE);
+        }
+        for point in street.lines.0.iter() {
+            assert!(distance(point, &[&first.lines, &second.lines]) <= CURVE_TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn unit_join_reverses_split_of_curved_street() {
+        let street = curved_street();
+        let (first, second) = street.split(&Coordinate { x: 150., y: 60. }).unwrap();
+
+        let joined = first.join(&second).unwrap();
+
+        // The split point stays a control point of the joined street
+        assert_eq!(
+            joined.control_points().len(),
+            street.control_points().len() + 1
+        );
+        for point in joined.lines.0.iter() {
+            assert!(distance(point, &[&street.lines]) <= CURVE_TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn unit_straight_street_is_split_at_position() {
+        let street = Street::new(LineString::from(vec![(0., 0.), (100., 0.)]));
+
+        let (first, second) = street.split(&Coordinate { x: 40., y: 10. }).unwrap();
+
+        assert_eq!(first.lines, LineString::from(vec![(0., 0.), (40., 0.)]));
+        assert_eq!(second.lines, LineString::from(vec![(40., 0.), (100., 0.)]));
+        assert!(street.split(&Coordinate { x: -10., y: 0. }).is_none());
+    }
+}
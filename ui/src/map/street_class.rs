@@ -0,0 +1,192 @@
+use std::{fmt, str::FromStr};
+
+use rust_editor::style::{InteractiveElementStyle, Style, TextStyle};
+use serde::{Deserialize, Serialize};
+
+/// Classification of a street. The class defines the default width of a street, how it is
+/// displayed and in which order it is rendered compared to streets of other classes.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StreetClass {
+    Highway,
+    MainRoad,
+    Street,
+    Alley,
+    Footpath,
+}
+
+impl Default for StreetClass {
+    fn default() -> Self {
+        StreetClass::Street
+    }
+}
+
+impl StreetClass {
+    /// All street classes ordered from the widest to the narrowest one.
+    pub const ALL: [StreetClass; 5] = [
+        StreetClass::Highway,
+        StreetClass::MainRoad,
+        StreetClass::Street,
+        StreetClass::Alley,
+        StreetClass::Footpath,
+    ];
+
+    /// Validator of plugin options that take the name of a street class. An empty value is valid as well and stands
+    /// for the default class of the option.
+    pub const VALIDATOR: &'static str = r"^(|highway|main_road|street|alley|footpath)$";
+
+    /// Like [`StreetClass::VALIDATOR`], but only accepts arterial classes.
+    pub const ARTERIAL_VALIDATOR: &'static str = r"^(|highway|main_road)$";
+
+    /// Like [`StreetClass::VALIDATOR`], but only accepts local classes.
+    pub const LOCAL_VALIDATOR: &'static str = r"^(|street|alley|footpath)$";
+
+    /// The width a street of this class gets if no explicit width is set.
+    pub fn default_width(&self) -> f64 {
+        match self {
+            StreetClass::Highway => 40.,
+            StreetClass::MainRoad => 30.,
+            StreetClass::Street => 20.,
+            StreetClass::Alley => 10.,
+            StreetClass::Footpath => 5.,
+        }
+    }
+
+    /// Arterial streets form the main road network and enclose the blocks of the map. All other streets are
+    /// local streets within these blocks.
+    pub fn is_arterial(&self) -> bool {
+        matches!(self, StreetClass::Highway | StreetClass::MainRoad)
+    }
+
+    /// Streets with a lower render order are rendered first and are therefore overdrawn by
+    /// streets with a higher one. Wide streets are rendered on top of narrow ones.
+    pub fn render_order(&self) -> u8 {
+        match self {
+            StreetClass::Footpath => 0,
+            StreetClass::Alley => 1,
+            StreetClass::Street => 2,
+            StreetClass::MainRoad => 3,
+            StreetClass::Highway => 4,
+        }
+    }
+
+    pub fn style(&self) -> InteractiveElementStyle {
+        let background_color = match self {
+            StreetClass::Highway => "#4E342E",
+            StreetClass::MainRoad => "#3B3B3D",
+            StreetClass::Street => "#2A2A2B",
+            StreetClass::Alley => "#505052",
+            StreetClass::Footpath => "#8D6E63",
+        };
+
+        InteractiveElementStyle {
+            normal: Style {
+                border_width: 0,
+                border_color: "#FFFFFF".to_string(),
+                background_color: background_color.to_string(),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Style of the labels of named streets. Streets of wider classes get larger labels.
+    pub fn label_style(&self) -> TextStyle {
+        let size = match self {
+            StreetClass::Highway => 18,
+            StreetClass::MainRoad => 16,
+            StreetClass::Street => 14,
+            StreetClass::Alley => 12,
+            StreetClass::Footpath => 10,
+        };
+
+        TextStyle {
+            size,
+            halo_color: self.style().normal.background_color,
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for StreetClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            StreetClass::Highway => "highway",
+            StreetClass::MainRoad => "main_road",
+            StreetClass::Street => "street",
+            StreetClass::Alley => "alley",
+            StreetClass::Footpath => "footpath",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for StreetClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StreetClass::ALL
+            .iter()
+            .find(|class| class.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown street class \"{}\"", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreetClass;
+
+    #[test]
+    fn unit_street_class_from_str_matches_display() {
+        for class in StreetClass::ALL {
+            assert_eq!(class.to_string().parse::<StreetClass>(), Ok(class));
+        }
+
+        assert_eq!(" Main_Road ".parse::<StreetClass>(), Ok(StreetClass::MainRoad));
+        assert!("avenue".parse::<StreetClass>().is_err());
+    }
+
+    #[test]
+    fn unit_validators_accept_the_names_of_their_classes() {
+        fn names(validator: &str) -> Vec<String> {
+            validator
+                .trim_start_matches("^(|")
+                .trim_end_matches(")$")
+                .split('|')
+                .map(String::from)
+                .collect()
+        }
+
+        let (arterials, locals): (Vec<_>, Vec<_>) = StreetClass::ALL
+            .into_iter()
+            .partition(StreetClass::is_arterial);
+        let display = |classes: &[StreetClass]| -> Vec<String> {
+            classes.iter().map(|class| class.to_string()).collect()
+        };
+
+        assert_eq!(names(StreetClass::VALIDATOR), display(&StreetClass::ALL));
+        assert_eq!(names(StreetClass::ARTERIAL_VALIDATOR), display(&arterials));
+        assert_eq!(names(StreetClass::LOCAL_VALIDATOR), display(&locals));
+    }
+
+    #[test]
+    fn unit_only_highways_and_main_roads_are_arterial() {
+        let arterials: Vec<StreetClass> = StreetClass::ALL
+            .into_iter()
+            .filter(StreetClass::is_arterial)
+            .collect();
+
+        assert_eq!(arterials, vec![StreetClass::Highway, StreetClass::MainRoad]);
+    }
+
+    #[test]
+    fn unit_wider_streets_are_rendered_on_top() {
+        let mut classes = StreetClass::ALL.to_vec();
+        classes.sort_by_key(|class| class.render_order());
+
+        assert!(classes
+            .windows(2)
+            .all(|pair| pair[0].default_width() < pair[1].default_width()));
+    }
+}
@@ -0,0 +1,363 @@
+use std::{fmt, str::FromStr};
+
+use geo::{Coordinate, Line, Rect};
+use rust_editor::renderer::Canvas;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithm::{
+    contour::{contour_lines, Grid},
+    noise::PerlinNoise,
+};
+
+/// Distance between two samples of a heightmap generated from noise.
+const NOISE_CELL_SIZE: f64 = 16.;
+
+/// Number of contour lines between the lowest and the highest possible elevation.
+const CONTOUR_LEVELS: usize = 20;
+
+/// Maximum opacity of the shade of a single cell of the hillshade.
+const MAXIMUM_SHADE: f64 = 0.6;
+
+/// Source of the heightmap of a terrain.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TerrainSource {
+    /// Fractal Perlin noise. `scale` is the size of the largest hills in map units.
+    Noise {
+        seed: u64,
+        scale: f64,
+        octaves: usize,
+    },
+
+    /// A grayscale image, e.g. an imported heightmap, stretched over the map. The samples are stored row by row
+    /// and are in [0, 1], black is the lowest point.
+    Image {
+        columns: usize,
+        rows: usize,
+        samples: Vec<f64>,
+    },
+}
+
+/// How the terrain is rendered below the streets.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TerrainShading {
+    Hillshade,
+    Contours,
+}
+
+impl TerrainShading {
+    pub const ALL: [TerrainShading; 2] = [TerrainShading::Hillshade, TerrainShading::Contours];
+}
+
+impl Default for TerrainShading {
+    fn default() -> Self {
+        TerrainShading::Hillshade
+    }
+}
+
+impl fmt::Display for TerrainShading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TerrainShading::Hillshade => "hillshade",
+            TerrainShading::Contours => "contours",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for TerrainShading {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TerrainShading::ALL
+            .iter()
+            .find(|shading| shading.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown terrain shading \"{}\"", s))
+    }
+}
+
+/// Elevation of the ground of a map. The elevation is sampled on a regular grid that covers the whole map and
+/// is interpolated between the samples.
+#[derive(Clone)]
+pub struct Terrain {
+    source: TerrainSource,
+
+    /// Elevation of the highest possible point. The lowest possible point is at zero.
+    relief: f64,
+    shading: TerrainShading,
+
+    columns: usize,
+    rows: usize,
+    cell: Coordinate<f64>,
+    elevations: Vec<f64>,
+
+    /// Shade of each cell of the grid that is not flat, only used for [`TerrainShading::Hillshade`].
+    shades: Vec<(Rect<f64>, String)>,
+
+    /// Contour line segments, only used for [`TerrainShading::Contours`].
+    contours: Vec<Line<f64>>,
+}
+
+impl Terrain {
+    /// Creates a terrain that covers a map of `width` and `height`. Images without samples result in a flat
+    /// terrain.
+    pub fn new(
+        source: TerrainSource,
+        relief: f64,
+        shading: TerrainShading,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let (width, height) = (width as f64, height as f64);
+
+        let (columns, rows, samples) = match &source {
+            TerrainSource::Noise {
+                seed,
+                scale,
+                octaves,
+            } => {
+                let noise = PerlinNoise::new(*seed);
+                let columns = (width / NOISE_CELL_SIZE).ceil() as usize + 1;
+                let rows = (height / NOISE_CELL_SIZE).ceil() as usize + 1;
+
+                let samples = (0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                    .map(|(column, row)| {
+                        let x = column as f64 * NOISE_CELL_SIZE / scale;
+                        let y = row as f64 * NOISE_CELL_SIZE / scale;
+
+                        (noise.fractal(x, y, *octaves) + 1.) / 2.
+                    })
+                    .collect();
+
+                (columns, rows, samples)
+            }
+            TerrainSource::Image {
+                columns,
+                rows,
+                samples,
+            } if *columns > 1 && *rows > 1 && samples.len() == columns * rows => {
+                (*columns, *rows, samples.clone())
+            }
+            TerrainSource::Image { .. } => (2, 2, vec![0.; 4]),
+        };
+
+        let mut terrain = Terrain {
+            source,
+            relief,
+            shading,
+            columns,
+            rows,
+            cell: Coordinate {
+                x: width / (columns - 1) as f64,
+                y: height / (rows - 1) as f64,
+            },
+            elevations: samples
+                .into_iter()
+                .map(|sample| sample.clamp(0., 1.) * relief)
+                .collect(),
+            shades: vec![],
+            contours: vec![],
+        };
+
+        match shading {
+            TerrainShading::Hillshade => terrain.shades = terrain.hillshade(),
+            TerrainShading::Contours => terrain.contours = terrain.contour_lines(),
+        }
+
+        terrain
+    }
+
+    pub fn source(&self) -> &TerrainSource {
+        &self.source
+    }
+
+    pub fn relief(&self) -> f64 {
+        self.relief
+    }
+
+    pub fn shading(&self) -> TerrainShading {
+        self.shading
+    }
+
+    fn elevation(&self, column: usize, row: usize) -> f64 {
+        self.elevations[row * self.columns + column]
+    }
+
+    /// Elevation at `position` interpolated bilinearly between the surrounding samples. Positions outside of
+    /// the map have the elevation of the closest point on the border of the map.
+    pub fn elevation_at(&self, position: &Coordinate<f64>) -> f64 {
+        let x = (position.x / self.cell.x).clamp(0., (self.columns - 1) as f64);
+        let y = (position.y / self.cell.y).clamp(0., (self.rows - 1) as f64);
+
+        let column = (x.floor() as usize).min(self.columns - 2);
+        let row = (y.floor() as usize).min(self.rows - 2);
+        let (tx, ty) = (x - column as f64, y - row as f64);
+
+        let top = self.elevation(column, row) * (1. - tx) + self.elevation(column + 1, row) * tx;
+        let bottom =
+            self.elevation(column, row + 1) * (1. - tx) + self.elevation(column + 1, row + 1) * tx;
+
+        top * (1. - ty) + bottom * ty
+    }
+
+    /// Shades each cell by the angle between its surface and a light from the top left at an altitude of 45
+    /// degrees. Cells that face the light are brightened, the others are darkened.
+    fn hillshade(&self) -> Vec<(Rect<f64>, String)> {
+        let (light_x, light_y, light_z) = (-0.5, -0.5, std::f64::consts::FRAC_1_SQRT_2);
+
+        let mut shades = Vec::new();
+        for row in 0..self.rows - 1 {
+            for column in 0..self.columns - 1 {
+                let (top_left, top_right, bottom_right, bottom_left) = (
+                    self.elevation(column, row),
+                    self.elevation(column + 1, row),
+                    self.elevation(column + 1, row + 1),
+                    self.elevation(column, row + 1),
+                );
+
+                let dx = (top_right + bottom_right - top_left - bottom_left) / (2. * self.cell.x);
+                let dy = (bottom_left + bottom_right - top_left - top_right) / (2. * self.cell.y);
+                let length = (dx * dx + dy * dy + 1.).sqrt();
+
+                let light = (-dx * light_x - dy * light_y + light_z) / length;
+                let shade = ((light - light_z) * 2.).clamp(-MAXIMUM_SHADE, MAXIMUM_SHADE);
+                if shade.abs() < 0.01 {
+                    continue;
+                }
+
+                let color = match shade > 0. {
+                    true => format!("rgba(255, 255, 255, {:.2})", shade),
+                    false => format!("rgba(0, 0, 0, {:.2})", -shade),
+                };
+                let min = Coordinate {
+                    x: column as f64 * self.cell.x,
+                    y: row as f64 * self.cell.y,
+                };
+
+                shades.push((Rect::new(min, min + self.cell), color));
+            }
+        }
+
+        shades
+    }
+
+    fn contour_lines(&self) -> Vec<Line<f64>> {
+        let grid = Grid {
+            samples: &self.elevations,
+            columns: self.columns,
+            rows: self.rows,
+            cell: self.cell,
+        };
+
+        (1..CONTOUR_LEVELS)
+            .flat_map(|level| {
+                contour_lines(&grid, self.relief * level as f64 / CONTOUR_LEVELS as f64)
+            })
+            .collect()
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) {
+        context.save();
+
+        for (rect, color) in &self.shades {
+            context.set_fill_style(color);
+            context.fill_rect(rect.min().x, rect.min().y, rect.width(), rect.height());
+        }
+
+        if !self.contours.is_empty() {
+            context.begin_path();
+            for line in &self.contours {
+                context.move_to(line.start.x, line.start.y);
+                context.line_to(line.end.x, line.end.y);
+            }
+            context.set_line_width(1.);
+            context.set_stroke_style("rgba(255, 255, 255, 0.3)");
+            context.stroke();
+        }
+
+        context.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+
+    use super::{Terrain, TerrainShading, TerrainSource};
+
+    fn ramp() -> Terrain {
+        Terrain::new(
+            TerrainSource::Image {
+                columns: 3,
+                rows: 2,
+                samples: vec![0., 0.5, 1., 0., 0.5, 1.],
+            },
+            100.,
+            TerrainShading::Contours,
+            200,
+            100,
+        )
+    }
+
+    #[test]
+    fn unit_terrain_shading_from_str_matches_display() {
+        for shading in TerrainShading::ALL {
+            assert_eq!(shading.to_string().parse::<TerrainShading>(), Ok(shading));
+        }
+    }
+
+    #[test]
+    fn unit_elevation_is_interpolated() {
+        let terrain = ramp();
+
+        assert_eq!(terrain.elevation_at(&Coordinate { x: 0., y: 0. }), 0.);
+        assert_eq!(terrain.elevation_at(&Coordinate { x: 50., y: 30. }), 25.);
+        assert_eq!(terrain.elevation_at(&Coordinate { x: 200., y: 100. }), 100.);
+
+        // Outside of the map
+        assert_eq!(terrain.elevation_at(&Coordinate { x: 500., y: -20. }), 100.);
+    }
+
+    #[test]
+    fn unit_noise_terrain_is_reproducible() {
+        let create = || {
+            Terrain::new(
+                TerrainSource::Noise {
+                    seed: 3,
+                    scale: 200.,
+                    octaves: 3,
+                },
+                100.,
+                TerrainShading::Hillshade,
+                400,
+                300,
+            )
+        };
+        let position = Coordinate { x: 123., y: 77. };
+
+        let elevation = create().elevation_at(&position);
+        assert_eq!(create().elevation_at(&position), elevation);
+        assert!((0. ..=100.).contains(&elevation));
+    }
+
+    #[test]
+    fn unit_invalid_image_is_flat() {
+        let terrain = Terrain::new(
+            TerrainSource::Image {
+                columns: 2,
+                rows: 2,
+                samples: vec![1.],
+            },
+            100.,
+            TerrainShading::Hillshade,
+            200,
+            100,
+        );
+
+        assert_eq!(terrain.elevation_at(&Coordinate { x: 50., y: 50. }), 0.);
+    }
+}
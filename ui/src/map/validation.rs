@@ -0,0 +1,422 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    prelude::{Area, EuclideanDistance},
+    Coordinate, Point,
+};
+use rust_editor::{actions::Action, gizmo::GetPosition};
+use uuid::Uuid;
+
+use super::{
+    actions::{
+        district::delete::DeleteDistrict, intersection::delete::DeleteIntersection,
+        street::delete::DeleteStreet, street::remove_vertex::RemoveVertex,
+    },
+    map::Map,
+};
+
+/// Districts with a smaller area are reported as slivers. They usually result from streets that run
+/// almost parallel and are too small to contain any house.
+pub const MINIMUM_DISTRICT_AREA: f64 = 1000.;
+
+/// Segments that are shorter are treated as zero-length segments.
+const MINIMUM_SEGMENT_LENGTH: f64 = 1e-6;
+
+/// A problem of a map that is found by [`Map::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// Two segments of the street cross each other at `position`.
+    SelfIntersectingStreet {
+        street_id: Uuid,
+        position: Coordinate<f64>,
+    },
+
+    /// The vertices `index` and `index + 1` of the street coincide. The polygon of such a street can
+    /// not be calculated.
+    ZeroLengthSegment { street_id: Uuid, index: usize },
+
+    /// The intersection is not connected to any street.
+    DanglingIntersection { intersection_id: Uuid },
+
+    /// The area of the district is below [`MINIMUM_DISTRICT_AREA`].
+    SliverDistrict { district_id: Uuid, area: f64 },
+
+    /// The streets are not connected to the largest street network of the map.
+    UnreachableStreets { street_ids: Vec<Uuid> },
+}
+
+impl Problem {
+    /// Returns an action that fixes the problem or `None` if it can not be fixed automatically.
+    pub fn fix(&self, map: &Map) -> Option<Rc<RefCell<dyn Action<Map>>>> {
+        match self {
+            Problem::ZeroLengthSegment { street_id, index } => {
+                let vertices = map.street(street_id)?.vertices().len();
+                if vertices <= 2 {
+                    return Some(Rc::new(RefCell::new(DeleteStreet::new(*street_id))));
+                }
+
+                // The ends of a street can not be removed, remove the other vertex of the segment instead
+                let index = match index + 1 == vertices - 1 {
+                    true => *index,
+                    false => index + 1,
+                };
+
+                Some(Rc::new(RefCell::new(RemoveVertex::new(*street_id, index))))
+            }
+            Problem::DanglingIntersection { intersection_id } => {
+                let intersection = map.intersection(intersection_id)?;
+
+                Some(Rc::new(RefCell::new(DeleteIntersection::new(intersection))))
+            }
+            Problem::SliverDistrict { district_id, .. } => {
+                Some(Rc::new(RefCell::new(DeleteDistrict::new(*district_id))))
+            }
+            Problem::SelfIntersectingStreet { .. } | Problem::UnreachableStreets { .. } => None,
+        }
+    }
+
+    /// Returns a position on the map the problem can be located at.
+    pub fn position(&self, map: &Map) -> Option<Coordinate<f64>> {
+        match self {
+            Problem::SelfIntersectingStreet { position, .. } => Some(*position),
+            Problem::ZeroLengthSegment { street_id, index } => {
+                map.street(street_id)?.vertices().get(*index).copied()
+            }
+            Problem::DanglingIntersection { intersection_id } => {
+                Some(map.intersection(intersection_id)?.position())
+            }
+            Problem::SliverDistrict { district_id, .. } => map
+                .district(district_id)?
+                .polygon()
+                .exterior()
+                .0
+                .first()
+                .copied(),
+            Problem::UnreachableStreets { street_ids } => {
+                map.street(street_ids.first()?)?.vertices().first().copied()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::SelfIntersectingStreet {
+                street_id,
+                position,
+            } => write!(
+                f,
+                "Street {} crosses itself at ({:.0}, {:.0})",
+                street_id, position.x, position.y
+            ),
+            Problem::ZeroLengthSegment { street_id, index } => write!(
+                f,
+                "Street {} has a segment without length at vertex {}",
+                street_id, index
+            ),
+            Problem::DanglingIntersection { intersection_id } => write!(
+                f,
+                "Intersection {} is not connected to any street",
+                intersection_id
+            ),
+            Problem::SliverDistrict { district_id, area } => write!(
+                f,
+                "District {} is too small ({:.0} < {:.0})",
+                district_id, area, MINIMUM_DISTRICT_AREA
+            ),
+            Problem::UnreachableStreets { street_ids } => write!(
+                f,
+                "{} street(s) are not connected to the street network",
+                street_ids.len()
+            ),
+        }
+    }
+}
+
+/// Checks the whole map and returns all problems that were found.
+pub fn validate(map: &Map) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    problems.extend(self_intersecting_streets(map));
+    problems.extend(zero_length_segments(map));
+    problems.extend(dangling_intersections(map));
+    problems.extend(sliver_districts(map));
+    problems.extend(unreachable_streets(map));
+
+    problems
+}
+
+fn self_intersecting_streets(map: &Map) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    for (street_id, street) in map.streets() {
+        // Zero-length segments are reported separately and would share a point with the segments around them
+        let lines: Vec<_> = street
+            .lines
+            .lines()
+            .filter(|line| {
+                Point::from(line.start).euclidean_distance(&Point::from(line.end))
+                    >= MINIMUM_SEGMENT_LENGTH
+            })
+            .collect();
+
+        // Adjacent segments always share a point, only segments further apart can cross. The first and the last
+        // segment of a closed street are adjacent as well
+        let closed = street.lines.is_closed();
+        'street: for (i, a) in lines.iter().enumerate() {
+            for (j, b) in lines.iter().enumerate().skip(i + 2) {
+                if closed && i == 0 && j == lines.len() - 1 {
+                    continue;
+                }
+
+                let position = match line_intersection(*a, *b) {
+                    Some(LineIntersection::SinglePoint { intersection, .. }) => intersection,
+                    Some(LineIntersection::Collinear { intersection }) => intersection.start,
+                    None => continue,
+                };
+
+                problems.push(Problem::SelfIntersectingStreet {
+                    street_id: *street_id,
+                    position,
+                });
+                break 'street;
+            }
+        }
+    }
+
+    problems
+}
+
+fn zero_length_segments(map: &Map) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    for (street_id, street) in map.streets() {
+        for (index, pair) in street.vertices().windows(2).enumerate() {
+            if Point::from(pair[0]).euclidean_distance(&Point::from(pair[1]))
+                < MINIMUM_SEGMENT_LENGTH
+            {
+                problems.push(Problem::ZeroLengthSegment {
+                    street_id: *street_id,
+                    index,
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+fn dangling_intersections(map: &Map) -> Vec<Problem> {
+    map.intersections()
+        .iter()
+        .filter(|(_, intersection)| intersection.get_connected_streets().is_empty())
+        .map(|(intersection_id, _)| Problem::DanglingIntersection {
+            intersection_id: *intersection_id,
+        })
+        .collect()
+}
+
+fn sliver_districts(map: &Map) -> Vec<Problem> {
+    map.districts()
+        .iter()
+        .map(|(district_id, district)| (district_id, district.polygon().unsigned_area()))
+        .filter(|(_, area)| *area < MINIMUM_DISTRICT_AREA)
+        .map(|(district_id, area)| Problem::SliverDistrict {
+            district_id: *district_id,
+            area,
+        })
+        .collect()
+}
+
+/// Groups the streets into networks of streets that are connected by intersections and reports all
+/// networks except the largest one.
+fn unreachable_streets(map: &Map) -> Vec<Problem> {
+    let mut neighbours: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for intersection in map.intersections().values() {
+        let streets = intersection.get_connected_streets();
+        for (_, street_id) in streets {
+            neighbours
+                .entry(*street_id)
+                .or_default()
+                .extend(streets.iter().map(|(_, other)| *other));
+        }
+    }
+
+    let mut visited: HashSet<Uuid> = HashSet::new();
+    let mut networks: Vec<Vec<Uuid>> = vec![];
+    for street_id in map.streets().keys() {
+        if visited.contains(street_id) {
+            continue;
+        }
+
+        let mut network = vec![];
+        let mut stack = vec![*street_id];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+
+            network.push(current);
+            stack.extend(neighbours.get(&current).into_iter().flatten());
+        }
+
+        network.sort();
+        networks.push(network);
+    }
+
+    // Largest network first, ties are resolved by the ids to get the same result on each run
+    networks.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    networks
+        .into_iter()
+        .skip(1)
+        .map(|street_ids| Problem::UnreachableStreets { street_ids })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::gizmo::Id;
+
+    use crate::map::{intersection::Intersection, map::Map, street::Street};
+
+    use super::{validate, Problem};
+
+    fn add_street(points: Vec<(f64, f64)>, map: &mut Map) -> Street {
+        let street = Street::new(LineString::from(points));
+        map.add_street(&street);
+
+        street
+    }
+
+    #[test]
+    fn unit_valid_map_has_no_problems() {
+        let mut map = Map::new(1000, 1000);
+        add_street(vec![(0., 0.), (400., 0.)], &mut map);
+
+        assert!(validate(&map).is_empty());
+    }
+
+    #[test]
+    fn unit_self_intersecting_street_is_reported() {
+        let mut map = Map::new(1000, 1000);
+        let street = add_street(
+            vec![
+                (0., 0.),
+                (200., 0.),
+                (200., 200.),
+                (100., 200.),
+                (100., -100.),
+            ],
+            &mut map,
+        );
+
+        assert_eq!(
+            validate(&map),
+            vec![Problem::SelfIntersectingStreet {
+                street_id: street.id(),
+                position: Coordinate { x: 100., y: 0. }
+            }]
+        );
+    }
+
+    #[test]
+    fn unit_closed_street_is_not_self_intersecting() {
+        let mut map = Map::new(1000, 1000);
+        add_street(
+            vec![(0., 0.), (200., 0.), (200., 200.), (0., 200.), (0., 0.)],
+            &mut map,
+        );
+
+        assert!(validate(&map).is_empty());
+    }
+
+    #[test]
+    fn unit_zero_length_segment_is_fixed() {
+        let mut map = Map::new(1000, 1000);
+
+        // Inserted directly as the polygon of such a street can not be merged into the street polygon
+        let street = Street::new(LineString::from(vec![
+            (0., 0.),
+            (200., 0.),
+            (200., 0.),
+            (400., 0.),
+        ]));
+        map.streets_mut().insert(street.id(), street.clone());
+
+        let problems = validate(&map);
+        assert_eq!(
+            problems,
+            vec![Problem::ZeroLengthSegment {
+                street_id: street.id(),
+                index: 1
+            }]
+        );
+
+        let fix = problems[0].fix(&map).unwrap();
+        fix.borrow_mut().execute(&mut map);
+
+        assert!(validate(&map).is_empty());
+        assert_eq!(map.street(&street.id()).unwrap().vertices().len(), 3);
+    }
+
+    #[test]
+    fn unit_dangling_intersection_is_fixed() {
+        let mut map = Map::new(1000, 1000);
+        let id = map.add_intersection(Intersection::new(Coordinate { x: 10., y: 10. }));
+
+        let problems = validate(&map);
+        assert_eq!(
+            problems,
+            vec![Problem::DanglingIntersection {
+                intersection_id: id
+            }]
+        );
+
+        let fix = problems[0].fix(&map).unwrap();
+        fix.borrow_mut().execute(&mut map);
+
+        assert!(map.intersections().is_empty());
+    }
+
+    #[test]
+    fn unit_disconnected_streets_are_reported() {
+        let mut map = Map::new(1000, 1000);
+
+        let start = map.add_intersection(Intersection::new(Coordinate { x: 0., y: 0. }));
+        let middle = map.add_intersection(Intersection::new(Coordinate { x: 200., y: 0. }));
+        let end = map.add_intersection(Intersection::new(Coordinate { x: 400., y: 0. }));
+        let first = add_street(vec![(0., 0.), (200., 0.)], &mut map);
+        let second = add_street(vec![(200., 0.), (400., 0.)], &mut map);
+        for (intersection, outgoing, incoming) in [
+            (start, Some(first.id()), None),
+            (middle, Some(second.id()), Some(first.id())),
+            (end, None, Some(second.id())),
+        ] {
+            let intersection = map.intersection_mut(&intersection).unwrap();
+            if let Some(outgoing) = outgoing {
+                intersection.add_outgoing_street(&outgoing);
+            }
+            if let Some(incoming) = incoming {
+                intersection.add_incoming_street(&incoming);
+            }
+        }
+
+        let isolated = add_street(vec![(0., 500.), (400., 500.)], &mut map);
+
+        assert_eq!(
+            validate(&map),
+            vec![Problem::UnreachableStreets {
+                street_ids: vec![isolated.id()]
+            }]
+        );
+    }
+}
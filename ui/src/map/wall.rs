@@ -0,0 +1,232 @@
+use std::f64::consts::PI;
+
+use geo::{
+    prelude::{BoundingRect, EuclideanDistance},
+    Coordinate, LineString, Point, Rect,
+};
+use rust_editor::{
+    gizmo::{Id, SetId},
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    renderer::{apply_style, Canvas, RenderError},
+    style::{InteractiveElementStyle, Style},
+};
+use rust_macro::ElementId;
+use uuid::Uuid;
+
+use crate::algorithm::fortification::wall_sections;
+
+/// Radius of the towers relative to the width of the wall.
+const TOWER_RADIUS_FACTOR: f64 = 0.8;
+
+/// A fortification ring around a settlement. The wall is interrupted by a gate wherever a street crosses it
+/// and is reinforced by towers.
+#[derive(Clone, ElementId)]
+pub struct Wall {
+    id: Uuid,
+
+    /// Closed center line of the wall.
+    ring: LineString<f64>,
+
+    towers: Vec<Coordinate<f64>>,
+    gates: Vec<Coordinate<f64>>,
+
+    width: f64,
+    gate_width: f64,
+
+    /// The parts of the ring between the gates.
+    sections: Vec<LineString<f64>>,
+
+    style: InteractiveElementStyle,
+    state: InteractiveElementState,
+}
+
+impl Default for Wall {
+    fn default() -> Self {
+        Wall {
+            id: Uuid::new_v4(),
+            ring: LineString::new(vec![]),
+            towers: vec![],
+            gates: vec![],
+            width: 8.,
+            gate_width: 20.,
+            sections: vec![],
+            style: InteractiveElementStyle {
+                normal: Style {
+                    border_width: 2,
+                    border_color: "#5f5f5f".to_string(),
+                    background_color: "#9e9e9e".to_string(),
+                },
+                hover: Style {
+                    border_width: 2,
+                    border_color: "#5f5f5f".to_string(),
+                    background_color: "#e0e0e0".to_string(),
+                },
+                selected: Style {
+                    border_width: 2,
+                    border_color: "#5f5f5f".to_string(),
+                    background_color: "hsl(0, 100%, 50%)".to_string(),
+                },
+            },
+            state: InteractiveElementState::Normal,
+        }
+    }
+}
+
+impl InteractiveElement for Wall {
+    fn set_state(&mut self, new_state: InteractiveElementState) {
+        self.state = new_state;
+    }
+
+    fn style(&self) -> &Style {
+        match self.state {
+            InteractiveElementState::Normal => &self.style.normal,
+            InteractiveElementState::Hover => &self.style.hover,
+            InteractiveElementState::Selected => &self.style.selected,
+        }
+    }
+
+    fn state(&self) -> InteractiveElementState {
+        self.state.clone()
+    }
+}
+
+impl Wall {
+    pub fn new(
+        ring: LineString<f64>,
+        towers: Vec<Coordinate<f64>>,
+        gates: Vec<Coordinate<f64>>,
+        width: f64,
+        gate_width: f64,
+    ) -> Self {
+        Wall {
+            sections: wall_sections(&ring, &gates, gate_width),
+            ring,
+            towers,
+            gates,
+            width,
+            gate_width,
+            ..Wall::default()
+        }
+    }
+
+    pub fn ring(&self) -> &LineString<f64> {
+        &self.ring
+    }
+
+    pub fn towers(&self) -> &Vec<Coordinate<f64>> {
+        &self.towers
+    }
+
+    pub fn gates(&self) -> &Vec<Coordinate<f64>> {
+        &self.gates
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn gate_width(&self) -> f64 {
+        self.gate_width
+    }
+
+    fn tower_radius(&self) -> f64 {
+        self.width * TOWER_RADIUS_FACTOR
+    }
+
+    /// Bounding rectangle of the wall including its towers.
+    pub fn bounding_rect(&self) -> Option<Rect<f64>> {
+        let rect = self.ring.bounding_rect()?;
+        let margin = Coordinate {
+            x: self.tower_radius().max(self.width / 2.),
+            y: self.tower_radius().max(self.width / 2.),
+        };
+
+        Some(Rect::new(rect.min() - margin, rect.max() + margin))
+    }
+
+    /// Returns `true` if `point` is on a section of the wall or on one of its towers. Points within a gate are
+    /// not on the wall.
+    pub fn is_point_on_wall(&self, point: &Coordinate<f64>) -> bool {
+        let point = Point::from(*point);
+
+        self.sections
+            .iter()
+            .any(|section| section.euclidean_distance(&point) <= self.width / 2.)
+            || self
+                .towers
+                .iter()
+                .any(|tower| Point::from(*tower).euclidean_distance(&point) <= self.tower_radius())
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        let style = self.style();
+
+        context.save();
+        context.set_line_width(self.width);
+        context.set_line_join("round");
+        context.set_stroke_style(&style.background_color);
+
+        for section in &self.sections {
+            let mut it = section.0.iter();
+
+            if let Some(start) = it.next() {
+                context.begin_path();
+                context.move_to(start.x, start.y);
+                for point in it {
+                    context.line_to(point.x, point.y);
+                }
+                context.stroke();
+            }
+        }
+
+        for tower in &self.towers {
+            context.begin_path();
+            context.arc(tower.x, tower.y, self.tower_radius(), 0.0, 2.0 * PI)?;
+            apply_style(style, context);
+        }
+
+        context.restore();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+
+    use super::Wall;
+
+    fn create_wall() -> Wall {
+        Wall::new(
+            LineString::from(vec![
+                (0., 0.),
+                (100., 0.),
+                (100., 100.),
+                (0., 100.),
+                (0., 0.),
+            ]),
+            vec![Coordinate { x: 100., y: 100. }],
+            vec![Coordinate { x: 50., y: 0. }],
+            8.,
+            20.,
+        )
+    }
+
+    #[test]
+    fn unit_point_on_section_is_on_wall() {
+        let wall = create_wall();
+
+        assert!(wall.is_point_on_wall(&Coordinate { x: 100., y: 50. }));
+        assert!(wall.is_point_on_wall(&Coordinate { x: 103., y: 50. }));
+        assert!(!wall.is_point_on_wall(&Coordinate { x: 50., y: 50. }));
+    }
+
+    #[test]
+    fn unit_gate_is_not_on_wall() {
+        let wall = create_wall();
+
+        assert!(!wall.is_point_on_wall(&Coordinate { x: 50., y: 0. }));
+        assert!(wall.is_point_on_wall(&Coordinate { x: 30., y: 0. }));
+    }
+}
@@ -0,0 +1,179 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use geo::{prelude::EuclideanDistance, Coordinate, Point};
+use rand::Rng;
+use rust_editor::style::Style;
+use serde::{Deserialize, Serialize};
+
+/// Share of the wards closest to the center that are inhabited by the wealthy.
+const INNER_WARDS: f64 = 0.3;
+
+/// Probability that a ward at the boundary of the city is farmland.
+const FARM_PROBABILITY: f64 = 0.4;
+
+/// Function of a district within a city, like in classic medieval city generators.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Ward {
+    Market,
+    Temple,
+    Castle,
+    Patriciate,
+    Merchant,
+    Craftsmen,
+    Slum,
+    Farm,
+}
+
+impl Ward {
+    pub const ALL: [Ward; 8] = [
+        Ward::Market,
+        Ward::Temple,
+        Ward::Castle,
+        Ward::Patriciate,
+        Ward::Merchant,
+        Ward::Craftsmen,
+        Ward::Slum,
+        Ward::Farm,
+    ];
+
+    /// Style of the area of a district with this ward.
+    pub fn style(&self) -> Style {
+        let background_color = match self {
+            Ward::Market => "rgba(255, 193, 7, 0.3)",
+            Ward::Temple => "rgba(255, 255, 255, 0.3)",
+            Ward::Castle => "rgba(121, 85, 72, 0.4)",
+            Ward::Patriciate => "rgba(156, 39, 176, 0.3)",
+            Ward::Merchant => "rgba(3, 169, 244, 0.3)",
+            Ward::Craftsmen => "rgba(255, 87, 34, 0.3)",
+            Ward::Slum => "rgba(96, 125, 139, 0.3)",
+            Ward::Farm => "rgba(139, 195, 74, 0.3)",
+        };
+
+        Style {
+            border_width: 0,
+            border_color: "#FFFFFF".to_string(),
+            background_color: background_color.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Ward {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Ward::Market => "market",
+            Ward::Temple => "temple",
+            Ward::Castle => "castle",
+            Ward::Patriciate => "patriciate",
+            Ward::Merchant => "merchant",
+            Ward::Craftsmen => "craftsmen",
+            Ward::Slum => "slum",
+            Ward::Farm => "farm",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Ward {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ward::ALL
+            .iter()
+            .find(|ward| ward.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown ward \"{}\"", s))
+    }
+}
+
+/// Assigns a ward to each of the districts with the given centers. The district closest to `center` becomes
+/// the market, the next ones the temple and the castle. The remaining districts close to the center are
+/// inhabited by patricians and merchants, the outer ones by craftsmen and the poor. Districts at the boundary of
+/// the city may be farmland instead.
+///
+/// Returns the wards in the order of `districts`, each given as its center and whether it touches the boundary.
+pub fn assign_wards<R: Rng>(
+    districts: &[(Coordinate<f64>, bool)],
+    center: Coordinate<f64>,
+    rng: &mut R,
+) -> Vec<Ward> {
+    let center = Point::from(center);
+
+    let mut order: Vec<usize> = (0..districts.len()).collect();
+    order.sort_by(|a, b| {
+        let a = Point::from(districts[*a].0).euclidean_distance(&center);
+        let b = Point::from(districts[*b].0).euclidean_distance(&center);
+
+        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+    });
+
+    let inner = (districts.len() as f64 * INNER_WARDS).ceil() as usize;
+
+    let mut wards = vec![Ward::Craftsmen; districts.len()];
+    for (rank, index) in order.into_iter().enumerate() {
+        let at_boundary = districts[index].1;
+
+        wards[index] = match rank {
+            0 => Ward::Market,
+            1 => Ward::Temple,
+            2 => Ward::Castle,
+            _ if at_boundary && rng.gen_bool(FARM_PROBABILITY) => Ward::Farm,
+            rank if rank < inner => match rng.gen_bool(0.5) {
+                true => Ward::Patriciate,
+                false => Ward::Merchant,
+            },
+            _ => match rng.gen_bool(0.7) {
+                true => Ward::Craftsmen,
+                false => Ward::Slum,
+            },
+        };
+    }
+
+    wards
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::{assign_wards, Ward};
+
+    #[test]
+    fn unit_ward_from_str_matches_display() {
+        for ward in Ward::ALL {
+            assert_eq!(ward.to_string().parse::<Ward>(), Ok(ward));
+        }
+
+        assert!("harbour".parse::<Ward>().is_err());
+    }
+
+    #[test]
+    fn unit_central_wards_are_assigned_first() {
+        let districts: Vec<(Coordinate<f64>, bool)> = (0..10)
+            .map(|i| {
+                (
+                    Coordinate {
+                        x: 100. * i as f64,
+                        y: 0.,
+                    },
+                    i == 9,
+                )
+            })
+            .collect();
+        let wards = assign_wards(
+            &districts,
+            Coordinate { x: 0., y: 0. },
+            &mut ChaCha8Rng::seed_from_u64(1),
+        );
+
+        assert_eq!(wards[..3], [Ward::Market, Ward::Temple, Ward::Castle]);
+        assert!(matches!(wards[3], Ward::Patriciate | Ward::Merchant));
+        assert!(matches!(
+            wards[9],
+            Ward::Craftsmen | Ward::Slum | Ward::Farm
+        ));
+    }
+}
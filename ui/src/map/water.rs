@@ -0,0 +1,410 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use geo::{
+    prelude::{BoundingRect, Contains, EuclideanDistance, EuclideanLength},
+    Coordinate, Line, LineString, Point, Polygon, Rect,
+};
+use rust_editor::{
+    gizmo::{Id, SetId},
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    renderer::{Canvas, PrimitiveRenderer, RenderError},
+    style::{InteractiveElementStyle, Style},
+};
+use rust_macro::ElementId;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::algorithm::geo::project_on_line_string;
+
+use super::street::{calc_polygon_points, Street};
+
+/// Limits the offset of the river banks at sharp bends to this multiple of half the river width.
+const MITER_LIMIT: f64 = 2.;
+
+/// Length of a bridge beyond each bank of the river.
+const BRIDGE_ABUTMENT: f64 = 4.;
+
+/// Width of the parapets on each side of the street of a bridge.
+const BRIDGE_PARAPET: f64 = 2.;
+
+/// Streets that cross a river at a flatter angle than this, measured as sine, get a bridge as long as if they
+/// crossed at this angle.
+const MINIMUM_CROSSING_SINE: f64 = 0.3;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaterKind {
+    /// A linear feature with a center line and a width at each vertex of the center line.
+    River,
+
+    /// An area of inland water.
+    Lake,
+
+    /// An area of open water, usually reaching beyond the border of the map.
+    Sea,
+}
+
+impl WaterKind {
+    pub const ALL: [WaterKind; 3] = [WaterKind::River, WaterKind::Lake, WaterKind::Sea];
+}
+
+impl Default for WaterKind {
+    fn default() -> Self {
+        WaterKind::River
+    }
+}
+
+impl fmt::Display for WaterKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            WaterKind::River => "river",
+            WaterKind::Lake => "lake",
+            WaterKind::Sea => "sea",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for WaterKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WaterKind::ALL
+            .iter()
+            .find(|kind| kind.to_string() == s.trim().to_lowercase())
+            .copied()
+            .ok_or_else(|| format!("unknown water kind \"{}\"", s))
+    }
+}
+
+/// A river, lake or sea. Districts are clipped against water and streets that cross a river become bridges.
+#[derive(Clone, ElementId)]
+pub struct Water {
+    id: Uuid,
+    kind: WaterKind,
+
+    /// Center line of a river or outline of a lake or the sea.
+    line_string: LineString<f64>,
+
+    /// Width of a river at each vertex of its center line. Empty for lakes and the sea.
+    widths: Vec<f64>,
+
+    /// Area covered by the water.
+    polygon: Polygon<f64>,
+
+    style: InteractiveElementStyle,
+    state: InteractiveElementState,
+}
+
+impl Default for Water {
+    fn default() -> Self {
+        Water {
+            id: Uuid::new_v4(),
+            kind: WaterKind::default(),
+            line_string: LineString::new(vec![]),
+            widths: vec![],
+            polygon: Polygon::new(LineString::new(vec![]), vec![]),
+            style: InteractiveElementStyle {
+                normal: Style {
+                    border_width: 1,
+                    border_color: "#6f9ccb".to_string(),
+                    background_color: "#a9cbe8".to_string(),
+                },
+                hover: Style {
+                    border_width: 2,
+                    border_color: "#6f9ccb".to_string(),
+                    background_color: "#cde2f3".to_string(),
+                },
+                selected: Style {
+                    border_width: 2,
+                    border_color: "#6f9ccb".to_string(),
+                    background_color: "hsl(0, 100%, 50%)".to_string(),
+                },
+            },
+            state: InteractiveElementState::Normal,
+        }
+    }
+}
+
+impl InteractiveElement for Water {
+    fn set_state(&mut self, new_state: InteractiveElementState) {
+        self.state = new_state;
+    }
+
+    fn style(&self) -> &Style {
+        match self.state {
+            InteractiveElementState::Normal => &self.style.normal,
+            InteractiveElementState::Hover => &self.style.hover,
+            InteractiveElementState::Selected => &self.style.selected,
+        }
+    }
+
+    fn state(&self) -> InteractiveElementState {
+        self.state.clone()
+    }
+}
+
+impl Water {
+    /// Creates a water feature of `kind`. `line_string` is the center line of a river, which has the width
+    /// `widths[i]` at vertex `i`, or the outline of a lake or the sea, which ignore `widths`.
+    pub fn new(kind: WaterKind, line_string: LineString<f64>, widths: Vec<f64>) -> Self {
+        let (polygon, widths) = match kind {
+            WaterKind::River => (river_outline(&line_string, &widths), widths),
+            WaterKind::Lake | WaterKind::Sea => (Polygon::new(line_string.clone(), vec![]), vec![]),
+        };
+
+        Water {
+            kind,
+            line_string,
+            widths,
+            polygon,
+            ..Water::default()
+        }
+    }
+
+    pub fn kind(&self) -> WaterKind {
+        self.kind
+    }
+
+    pub fn line_string(&self) -> &LineString<f64> {
+        &self.line_string
+    }
+
+    pub fn widths(&self) -> &Vec<f64> {
+        &self.widths
+    }
+
+    pub fn polygon(&self) -> &Polygon<f64> {
+        &self.polygon
+    }
+
+    pub fn bounding_rect(&self) -> Option<Rect<f64>> {
+        self.polygon.bounding_rect()
+    }
+
+    pub fn is_point_on_water(&self, point: &Coordinate<f64>) -> bool {
+        self.polygon.contains(point)
+    }
+
+    /// Width of a river at vertex `i`. Vertices without a width have the width of the last vertex that has one.
+    fn width(&self, i: usize) -> Option<f64> {
+        self.widths
+            .get(i.min(self.widths.len().saturating_sub(1)))
+            .copied()
+    }
+
+    /// Width of a river at the point of its center line closest to `position`, interpolated between the
+    /// widths of the vertices. Lakes and the sea have no width.
+    pub fn width_at(&self, position: &Coordinate<f64>) -> Option<f64> {
+        if self.kind != WaterKind::River {
+            return None;
+        }
+
+        let (distance, _) = project_on_line_string(&self.line_string, position)?;
+
+        let mut travelled = 0.;
+        for (i, line) in self.line_string.lines().enumerate() {
+            let length = line.euclidean_length();
+
+            if distance <= travelled + length || i + 2 == self.line_string.0.len() {
+                let t = match length > 0. {
+                    true => ((distance - travelled) / length).clamp(0., 1.),
+                    false => 0.,
+                };
+                let start = self.width(i)?;
+                let end = self.width(i + 1)?;
+
+                return Some(start + (end - start) * t);
+            }
+
+            travelled += length;
+        }
+
+        None
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        self.polygon.render(self.style(), context)
+    }
+}
+
+/// A street that crosses a river. The deck of the bridge spans the river along the street.
+#[derive(Clone)]
+pub struct Bridge {
+    deck: Polygon<f64>,
+}
+
+impl Bridge {
+    /// Creates the bridge of `street` over `river` at `position`, which is a crossing of their center lines.
+    pub fn new(street: &Street, river: &Water, position: Coordinate<f64>) -> Self {
+        let street_direction =
+            direction_at(&street.lines, &position).unwrap_or(Coordinate { x: 1., y: 0. });
+        let river_direction = direction_at(&river.line_string, &position).unwrap_or(Coordinate {
+            x: -street_direction.y,
+            y: street_direction.x,
+        });
+
+        // A street that crosses at an angle needs a longer bridge than one that crosses at a right angle
+        let sine = (street_direction.x * river_direction.y
+            - street_direction.y * river_direction.x)
+            .abs()
+            .max(MINIMUM_CROSSING_SINE);
+        let length = river.width_at(&position).unwrap_or(0.) / sine + 2. * BRIDGE_ABUTMENT;
+
+        let deck = calc_polygon_points(
+            std::iter::once(Line::new(
+                position - street_direction * (length / 2.),
+                position + street_direction * (length / 2.),
+            )),
+            street.width() + 2. * BRIDGE_PARAPET,
+        );
+
+        Bridge { deck }
+    }
+
+    fn style() -> Style {
+        Style {
+            border_width: 2,
+            border_color: "#6d6256".to_string(),
+            background_color: "#c9bca8".to_string(),
+        }
+    }
+
+    pub fn render(&self, context: &mut dyn Canvas) -> Result<(), RenderError> {
+        self.deck.render(&Bridge::style(), context)
+    }
+}
+
+/// Returns the direction of the segment of `line_string` that is closest to `position` as unit vector.
+fn direction_at(
+    line_string: &LineString<f64>,
+    position: &Coordinate<f64>,
+) -> Option<Coordinate<f64>> {
+    let point = Point::from(*position);
+
+    let line = line_string.lines().min_by(|a, b| {
+        a.euclidean_distance(&point)
+            .partial_cmp(&b.euclidean_distance(&point))
+            .unwrap_or(Ordering::Equal)
+    })?;
+
+    unit(line.end - line.start)
+}
+
+fn unit(vector: Coordinate<f64>) -> Option<Coordinate<f64>> {
+    let length = vector.x.hypot(vector.y);
+
+    (length > 0.).then(|| vector * (1. / length))
+}
+
+/// Outline of a river along `center_line`. The banks are offset by half the width at each vertex along the
+/// bisector of the adjacent segments. Vertices without a width get the width of the last vertex that has one.
+fn river_outline(center_line: &LineString<f64>, widths: &[f64]) -> Polygon<f64> {
+    let points = &center_line.0;
+    if points.len() < 2 || widths.is_empty() {
+        return Polygon::new(LineString::new(vec![]), vec![]);
+    }
+
+    let mut left: Vec<Coordinate<f64>> = Vec::with_capacity(points.len());
+    let mut right: Vec<Coordinate<f64>> = Vec::with_capacity(points.len());
+
+    for (i, point) in points.iter().enumerate() {
+        let incoming = i.checked_sub(1).and_then(|j| unit(*point - points[j]));
+        let outgoing = points.get(i + 1).and_then(|next| unit(*next - *point));
+
+        let segment = match outgoing.or(incoming) {
+            Some(segment) => segment,
+            None => continue,
+        };
+        let tangent = match (incoming, outgoing) {
+            (Some(incoming), Some(outgoing)) => unit(incoming + outgoing).unwrap_or(segment),
+            _ => segment,
+        };
+
+        let normal = Coordinate {
+            x: -tangent.y,
+            y: tangent.x,
+        };
+
+        // Keeps the banks parallel to the center line at bends
+        let cosine = normal.x * -segment.y + normal.y * segment.x;
+        let miter = 1. / cosine.max(1. / MITER_LIMIT);
+        let half_width = widths[i.min(widths.len() - 1)] / 2. * miter;
+
+        left.push(*point + normal * half_width);
+        right.push(*point - normal * half_width);
+    }
+
+    right.reverse();
+    left.append(&mut right);
+
+    Polygon::new(LineString::from(left), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{prelude::BoundingRect, Coordinate, LineString};
+
+    use crate::map::street::Street;
+
+    use super::{Bridge, Water, WaterKind};
+
+    fn river() -> Water {
+        Water::new(
+            WaterKind::River,
+            LineString::from(vec![(0., 100.), (100., 100.), (200., 200.)]),
+            vec![10., 20., 40.],
+        )
+    }
+
+    #[test]
+    fn unit_water_kind_from_str_matches_display() {
+        for kind in WaterKind::ALL {
+            assert_eq!(kind.to_string().parse::<WaterKind>(), Ok(kind));
+        }
+    }
+
+    #[test]
+    fn unit_river_covers_its_width() {
+        let river = river();
+
+        assert!(river.is_point_on_water(&Coordinate { x: 50., y: 106. }));
+        assert!(!river.is_point_on_water(&Coordinate { x: 50., y: 110. }));
+        assert!(river.is_point_on_water(&Coordinate { x: 185., y: 200. }));
+    }
+
+    #[test]
+    fn unit_river_width_is_interpolated() {
+        let river = river();
+
+        assert_eq!(river.width_at(&Coordinate { x: 50., y: 90. }), Some(15.));
+        assert_eq!(river.width_at(&Coordinate { x: 250., y: 250. }), Some(40.));
+    }
+
+    #[test]
+    fn unit_lake_is_its_outline() {
+        let lake = Water::new(
+            WaterKind::Lake,
+            LineString::from(vec![(0., 0.), (100., 0.), (100., 100.), (0., 100.)]),
+            vec![5.],
+        );
+
+        assert!(lake.is_point_on_water(&Coordinate { x: 50., y: 50. }));
+        assert!(!lake.is_point_on_water(&Coordinate { x: 150., y: 50. }));
+        assert!(lake.widths().is_empty());
+        assert_eq!(lake.width_at(&Coordinate { x: 50., y: 50. }), None);
+    }
+
+    #[test]
+    fn unit_bridge_spans_river() {
+        let river = river();
+        let street = Street::new(LineString::from(vec![(50., 0.), (50., 200.)]));
+
+        let bridge = Bridge::new(&street, &river, Coordinate { x: 50., y: 100. });
+
+        let rect = bridge.deck.bounding_rect().unwrap();
+        assert!(rect.min().y < 100. - 7.5 && rect.max().y > 100. + 7.5);
+        assert!(rect.width() > street.width());
+    }
+}
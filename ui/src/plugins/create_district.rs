@@ -2,7 +2,10 @@ use std::fmt;
 
 use rust_macro::editor_plugin;
 
-use crate::Map;
+use crate::{
+    map::{footprint::Footprint, house::Subdivision},
+    Map,
+};
 use geo::Coordinate;
 use rust_editor::{
     actions::{Action, Redo, Undo},
@@ -24,6 +27,94 @@ pub struct CreateDistrict {
     )]
     minimum_house_side: f64,
 
+    #[option(
+        label = "Lot Strategy",
+        description = "One of longest_street, obb_split, strips or grid",
+        validator = r"^(longest_street|obb_split|strips|grid)$"
+    )]
+    lot_strategy: String,
+
+    #[option(
+        default = 0.3,
+        min = 0.1,
+        max = 0.5,
+        label = "Minimum Split Ratio",
+        description = "Lower bound of the random position at which a lot is split"
+    )]
+    minimum_split_ratio: f64,
+
+    #[option(
+        default = 0.7,
+        min = 0.5,
+        max = 0.9,
+        label = "Maximum Split Ratio",
+        description = "Upper bound of the random position at which a lot is split"
+    )]
+    maximum_split_ratio: f64,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 1000.,
+        label = "Minimum Frontage",
+        description = "Minimum length of the street side of each lot"
+    )]
+    minimum_frontage: f64,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Front Setback",
+        description = "Distance between the buildings and the street"
+    )]
+    front_setback: f64,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Side Setback",
+        description = "Distance between the buildings and their neighbours"
+    )]
+    side_setback: f64,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Back Setback",
+        description = "Distance between the buildings and the rear of their lots"
+    )]
+    back_setback: f64,
+
+    #[option(
+        default = 15.,
+        min = 1.,
+        max = 100.,
+        label = "Wing Depth",
+        description = "Depth of the wings of L and U shaped buildings and around courtyards"
+    )]
+    wing_depth: f64,
+
+    #[option(default = 0., min = 0., max = 1., label = "Courtyard Probability")]
+    courtyard_probability: f64,
+
+    #[option(default = 0., min = 0., max = 1., label = "L-Shape Probability")]
+    l_shape_probability: f64,
+
+    #[option(default = 0., min = 0., max = 1., label = "U-Shape Probability")]
+    u_shape_probability: f64,
+
+    #[option(
+        default = 1,
+        min = 1,
+        max = 20,
+        label = "Terrace Length",
+        description = "Maximum number of lots along a street that share one building"
+    )]
+    terrace_length: usize,
+
     //#[option(skip)]
     //seed: <ChaCha8Rng as SeedableRng>::Seed,
 }
@@ -56,23 +147,6 @@ impl fmt::Display for CreateDistrictAction {
     }
 }
 
-/*
-impl CreateDistrictAction {
-    pub fn new(
-        street: Uuid,
-        //minimum_house_side: f64,
-        //seed: <ChaCha8Rng as SeedableRng>::Seed,
-    ) -> Self {
-        CreateDistrictAction {
-            street,
-            //minimum_house_side,
-            //seed,
-            district: None,
-        }
-    }
-}
-*/
-
 impl Plugin<Map> for CreateDistrict {
     fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
         editor.add_shortkey::<CreateDistrict>(vec![Key::Ctrl, Key::D])?;
@@ -100,20 +174,37 @@ impl Plugin<Map> for CreateDistrict {
     }
 
     fn property_updated(&mut self, _: &str, editor: &mut App<Map>) {
+        let subdivision = Subdivision {
+            strategy: self.lot_strategy.parse().unwrap_or_default(),
+            split_ratio: (self.minimum_split_ratio, self.maximum_split_ratio),
+            minimum_frontage: self.minimum_frontage,
+        };
+        let footprint = Footprint {
+            front_setback: self.front_setback,
+            side_setback: self.side_setback,
+            back_setback: self.back_setback,
+            wing_depth: self.wing_depth,
+            courtyard_probability: self.courtyard_probability,
+            l_shape_probability: self.l_shape_probability,
+            u_shape_probability: self.u_shape_probability,
+            terrace_length: self.terrace_length,
+        };
+
         editor
             .data_mut()
             .districts_mut()
             .iter_mut()
             .for_each(|(_, x)| {
                 x.minimum_house_side = self.minimum_house_side.clamp(20.0, 1000.0);
-                x.update_houses();
+                x.subdivision = subdivision.clone();
+                x.footprint = footprint.clone();
+                x.update_content();
             });
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, ctx: &Context<App<Map>>, _: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::D] {
-            ctx.link()
-                .send_message(EditorMessages::ActivatePlugin(CreateDistrict::identifier()));
+            editor.send_message(EditorMessages::ActivatePlugin(CreateDistrict::identifier()));
         }
     }
 
@@ -132,41 +223,39 @@ impl Plugin<Map> for CreateDistrict {
     }
 }
 
-
-/*
 #[cfg(test)]
 mod tests {
-    use plugin_toolbar::toolbar::ToolbarPosition;
-    use rust_editor::input::keyboard::Key;
-    use rust_editor::plugin::Plugin;
-    use rust_editor::ui::app::App;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{input::keyboard::Key, ui::headless::HeadlessApp};
 
     use crate::map::map::Map;
-    use crate::plugins::create_district::CreateDistrict;
 
-    #[test]
-    fn integration_startup_adds_shortcut() {
-        let mut app = App::<Map>::default();
+    use super::CreateDistrict;
 
-        let mut plugin = CreateDistrict::default();
-        plugin.startup(&mut app).unwrap();
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(CreateDistrict::default()).unwrap();
 
-        assert!(app.has_shortkey(vec![Key::Ctrl, Key::D]))
+        app
     }
 
     #[test]
+    fn integration_startup_adds_shortcut() {
+        let app = app();
 
-    fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::D]));
+    }
 
-        let mut plugin = CreateDistrict::default();
-        plugin.startup(&mut app).unwrap();
+    #[test]
+    fn integration_startup_adds_toolbar_button() {
+        let app = app();
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.edit.modes.district", ToolbarPosition::Left)
-            .unwrap();
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.edit.modes.district", "create_district");
+        });
 
-        assert!(toolbar.has_button("create_district"));
+        assert!(has_button);
     }
 }
-*/
@@ -1,24 +1,36 @@
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 
 use futures::executor::block_on;
-use geo::{simplify::Simplify, Coordinate, LineString};
+use geo::{
+    bounding_rect::BoundingRect, euclidean_length::EuclideanLength, simplify::Simplify, Coordinate,
+    LineString,
+};
 use plugin_toolbar::toolbar::ToolbarPosition;
 use rust_editor::{
-    actions::{Action, Redo, Undo},
-    gizmo::Id,
+    actions::{Action, MultiAction, Redo, Undo},
+    gizmo::{GetPosition, Id},
     input::{keyboard::Key, mouse},
     plugin::{Plugin, PluginWithOptions},
-    renderer::PrimitiveRenderer,
-    style::Style,
+    renderer::{Canvas, PrimitiveRenderer},
     ui::app::{EditorError, Shortkey},
 };
 use rust_macro::editor_plugin;
 use uuid::Uuid;
-use web_sys::CanvasRenderingContext2d;
 
-use crate::map::{
-    map::Map,
-    street::{calc_polygon_points, Street},
+use crate::{
+    algorithm::{
+        geo::{line_string_crossings, project_on_line_string},
+        spline::curve_line_string,
+    },
+    map::{
+        actions::{
+            intersection::create::CreateIntersection,
+            street::{create::CreateStreet, split::SplitStreet},
+        },
+        map::Map,
+        street::{calc_polygon_points, Street},
+        street_class::StreetClass,
+    },
 };
 
 #[editor_plugin(specific_to=Map, execution=Exclusive)]
@@ -36,41 +48,260 @@ pub struct CreateFreeformStreet {
 
     #[option(default = 1., min = 0., max = 10., label = "Simplification Factor")]
     simplification_factor: f64,
+
+    #[option(
+        label = "Street Class",
+        description = "One of highway, main_road, street, alley or footpath. Empty for street",
+        validator = StreetClass::VALIDATOR
+    )]
+    street_class: String,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Street Width",
+        description = "Overrides the default width of the street class if greater than 0"
+    )]
+    street_width: f64,
+
+    #[option(
+        default = false,
+        label = "Curved Streets",
+        description = "Uses the drawn points as control points of a smooth curve"
+    )]
+    curved: bool,
+}
+
+/// Minimum simplification of the drawn points before they are used as control points of a curved street.
+/// Without it the curve would follow each jitter of the brush.
+const CURVE_SIMPLIFICATION: f64 = 5.;
+
+impl CreateFreeformStreet {
+    fn class(&self) -> StreetClass {
+        self.street_class.parse().unwrap_or_default()
+    }
+
+    fn width(&self) -> f64 {
+        if self.street_width > 0. {
+            return self.street_width;
+        }
+
+        self.class().default_width()
+    }
+
+    /// Returns the drawn points simplified according to the options of the plugin.
+    fn simplified_points(&self) -> LineString<f64> {
+        let simplification_factor = match self.curved {
+            true => self.simplification_factor.max(CURVE_SIMPLIFICATION),
+            false => self.simplification_factor,
+        };
+
+        LineString(self.raw_points.clone()).simplify(&simplification_factor)
+    }
 }
 
+/// Maximum distance between a street end and an existing intersection for the street to be connected to it.
+pub(crate) const SNAP_DISTANCE: f64 = 10.;
+
+/// Adds a freeform street to the map and integrates it into the street graph: Both ends snap to nearby
+/// intersections or split the street they end on, and every existing street the new one crosses is split at
+/// the crossing. The new street itself is split into one street per pair of consecutive intersections.
+///
+/// Curved streets use the drawn points as control points of a spline, the crossings are then calculated
+/// with the tessellated curve.
 pub struct CreateFreeFormStreetAction {
-    id: Option<Uuid>,
     street: LineString<f64>,
+    class: StreetClass,
+    width: f64,
+    curved: bool,
+    street_ids: Vec<Uuid>,
+    split_intersections: Vec<Uuid>,
+
+    action_stack: MultiAction<Map>,
 }
 
 impl CreateFreeFormStreetAction {
     pub fn new(street: LineString<f64>) -> Self {
-        CreateFreeFormStreetAction { id: None, street }
+        let class = StreetClass::default();
+
+        CreateFreeFormStreetAction::new_with_class(street, class, class.default_width())
     }
-}
 
-impl Undo<Map> for CreateFreeFormStreetAction {
-    fn undo(&mut self, map: &mut Map) {
-        if let None = self.id {
+    pub fn new_with_class(street: LineString<f64>, class: StreetClass, width: f64) -> Self {
+        CreateFreeFormStreetAction {
+            street,
+            class,
+            width,
+            curved: false,
+            street_ids: vec![],
+            split_intersections: vec![],
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    pub fn new_curved(control_points: LineString<f64>, class: StreetClass, width: f64) -> Self {
+        CreateFreeFormStreetAction {
+            curved: true,
+            ..CreateFreeFormStreetAction::new_with_class(control_points, class, width)
+        }
+    }
+
+    /// Ids of the streets the new street was split into.
+    pub fn street_ids(&self) -> &Vec<Uuid> {
+        &self.street_ids
+    }
+
+    /// Ids of the intersections at which existing streets were split to connect the new street.
+    pub fn split_intersections(&self) -> &Vec<Uuid> {
+        &self.split_intersections
+    }
+
+    fn create_street(&self, points: Vec<Coordinate<f64>>) -> Street {
+        let mut street = match self.curved {
+            true => Street::new_curved(points, self.class),
+            false => Street::new_with_class(LineString::new(points), self.class),
+        };
+        street.set_width(self.width);
+
+        street
+    }
+
+    /// Executes `action` immediately and stores it on the stack so that it can be undone later on.
+    /// Used instead of collecting all actions first as each step depends on the street graph altered by the previous ones.
+    fn execute_and_push<A>(&mut self, mut action: A, map: &mut Map)
+    where
+        A: Action<Map> + 'static,
+    {
+        action.execute(map);
+        self.action_stack.push(action);
+    }
+
+    /// Returns the intersection the street end at `position` is connected to. If there is no intersection
+    /// close to the end either the street below it is split or, if there is none, a new intersection is created.
+    fn end_intersection(&mut self, position: &Coordinate<f64>, map: &mut Map) -> Uuid {
+        if let Some(id) = map.get_intersection_at_position(position, SNAP_DISTANCE, &vec![]) {
+            return id;
+        }
+
+        let id = Uuid::new_v4();
+        if let Some(street) = map.get_street_at_position(position, &vec![]) {
+            self.execute_and_push(SplitStreet::new(street, *position, id), map);
+
+            if map.intersection(&id).is_some() {
+                self.split_intersections.push(id);
+                return id;
+            }
+        }
+
+        self.execute_and_push(CreateIntersection::new_with_id(*position, id), map);
+
+        id
+    }
+
+    /// Returns the first point where `line_string` crosses an existing street and that is not already
+    /// covered by an intersection together with the id of the crossed street.
+    fn next_crossing(
+        line_string: &LineString<f64>,
+        map: &Map,
+        ignored: &Vec<Coordinate<f64>>,
+    ) -> Option<(Uuid, Coordinate<f64>)> {
+        let rect = line_string.bounding_rect()?;
+
+        map.streets_within_rectangle(&rect).find_map(|street| {
+            line_string_crossings(line_string, &street.lines)
+                .into_iter()
+                .find(|crossing| {
+                    !ignored.contains(crossing)
+                        && map
+                            .get_intersection_at_position(crossing, SNAP_DISTANCE, &vec![])
+                            .is_none()
+                })
+                .map(|crossing| (street.id(), crossing))
+        })
+    }
+
+    fn push_street(&mut self, street: Street, start: Uuid, end: Uuid, map: &mut Map) {
+        if street.lines.0.len() < 2 || street.lines.euclidean_length() == 0. {
             return;
         }
 
-        let copy = map.street(&self.id.unwrap()).unwrap().clone();
-        map.remove_street(&copy);
+        self.street_ids.push(street.id());
+        self.execute_and_push(CreateStreet::new(street, start, end), map);
+    }
+}
+
+impl Undo<Map> for CreateFreeFormStreetAction {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
     }
 }
 
 impl Redo<Map> for CreateFreeFormStreetAction {
     fn redo(&mut self, map: &mut Map) {
         // TODO: Rework editor logic, if I press a button the action is still triggered if the plugin was activated before (e.g. by drawing a street)
-        if self.street.points().len() == 0 {
+        if self.street.points().len() < 2 {
             return;
         }
 
-        let street = Street::new(self.street.clone());
+        self.action_stack.clear();
+        self.street_ids.clear();
+        self.split_intersections.clear();
+
+        let mut points = self.street.0.clone();
+
+        let start = self.end_intersection(&points[0], map);
+        points[0] = map.intersection(&start).unwrap().position();
+
+        let end = self.end_intersection(points.last().unwrap(), map);
+        *points.last_mut().unwrap() = map.intersection(&end).unwrap().position();
+
+        let mut street = self.create_street(points);
+        let line_string = street.lines.clone();
 
-        self.id = Some(street.id());
-        map.add_street(&street);
+        // Split all crossed streets so that each crossing is represented by an intersection
+        let mut ignored: Vec<Coordinate<f64>> = vec![];
+        while let Some((crossed_street, crossing)) =
+            Self::next_crossing(&line_string, map, &ignored)
+        {
+            let id = Uuid::new_v4();
+            self.execute_and_push(SplitStreet::new(crossed_street, crossing, id), map);
+
+            match map.intersection(&id) {
+                Some(_) => self.split_intersections.push(id),
+                None => ignored.push(crossing),
+            }
+        }
+
+        // Collect the intersections along the new street ordered by their distance to the start
+        let rect = line_string.bounding_rect().unwrap();
+        let mut nodes: Vec<(f64, Uuid, Coordinate<f64>)> = map
+            .streets_within_rectangle(&rect)
+            .flat_map(|street| line_string_crossings(&line_string, &street.lines))
+            .filter_map(|crossing| {
+                map.get_intersection_at_position(&crossing, SNAP_DISTANCE, &vec![])
+            })
+            .filter(|id| *id != start && *id != end)
+            .filter_map(|id| {
+                let position = map.intersection(&id).unwrap().position();
+                project_on_line_string(&line_string, &position)
+                    .map(|(along, _)| (along, id, position))
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        nodes.dedup_by(|a, b| a.1 == b.1);
+
+        let mut previous = start;
+        for (_, id, position) in nodes {
+            if let Some((part, remaining)) = street.split(&position) {
+                self.push_street(part, previous, id, map);
+
+                street = remaining;
+                previous = id;
+            }
+        }
+
+        self.push_street(street, previous, end, map);
     }
 }
 
@@ -78,7 +309,11 @@ impl Action<Map> for CreateFreeFormStreetAction {}
 
 impl fmt::Display for CreateFreeFormStreetAction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[create_freeform_street]\n\u{251C}",)
+        write!(
+            f,
+            "[create_freeform_street] class={}, streets={:?}\n\u{251C}  {}",
+            self.class, self.street_ids, self.action_stack
+        )
     }
 }
 
@@ -149,10 +384,14 @@ impl Plugin<Map> for CreateFreeformStreet {
 
         self.brush_active = false;
 
-        let line_string = LineString(self.raw_points.clone());
-        let simplified = line_string.simplify(&self.simplification_factor);
-
-        let action = Rc::new(RefCell::new(CreateFreeFormStreetAction::new(simplified)));
+        let simplified = self.simplified_points();
+        let action = match self.curved {
+            true => CreateFreeFormStreetAction::new_curved(simplified, self.class(), self.width()),
+            false => {
+                CreateFreeFormStreetAction::new_with_class(simplified, self.class(), self.width())
+            }
+        };
+        let action = Rc::new(RefCell::new(action));
 
         action.borrow_mut().execute(app.data_mut());
 
@@ -174,74 +413,183 @@ impl Plugin<Map> for CreateFreeformStreet {
         false
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, ctx: &Context<App<Map>>, _: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::A] {
-            ctx.link().send_message(EditorMessages::ActivatePlugin(
+            editor.send_message(EditorMessages::ActivatePlugin(
                 CreateFreeformStreet::identifier(),
             ));
         }
     }
 
-    fn render(&self, context: &CanvasRenderingContext2d, _: &App<Map>) {
-        context.set_line_width(20.0);
-        context.set_stroke_style(&"#2A2A2B".into());
-
-        let line_string = LineString(self.raw_points.clone());
-
-        if line_string.lines().len() == 0 {
+    fn render(&self, context: &mut dyn Canvas, _: &App<Map>) {
+        if self.raw_points.len() < 2 {
             return;
         }
 
         // TODO better performance: To simplify and calc the polygon each render time is quite costly.
         // a (slightly) better way is to calculate it each time a point is added.
         // We need to find a way to make this really fast
-        let line_string = line_string.simplify(&self.simplification_factor);
-        let polygon = calc_polygon_points(line_string.lines(), 20.);
+        let mut line_string = self.simplified_points();
+        if self.curved {
+            line_string = curve_line_string(&line_string.0);
+        }
 
-        let style = Style {
-            border_width: 0,
-            border_color: "#0000FF".to_string(),
-            background_color: "#FFFFFFF".to_string(),
-        };
+        let polygon = calc_polygon_points(line_string.lines(), self.width());
 
-        polygon.render(&style, context).unwrap();
+        polygon.render(&self.class().style().normal, context).unwrap();
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use plugin_toolbar::toolbar::ToolbarPosition;
-    use rust_editor::input::keyboard::Key;
-    use rust_editor::plugin::Plugin;
-    use rust_editor::ui::app::App;
+    use geo::Coordinate;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{
+        input::{keyboard::Key, mouse},
+        ui::headless::HeadlessApp,
+    };
 
     use crate::map::map::Map;
-    use crate::plugins::create_freeform_street::CreateFreeformStreet;
+
+    use super::CreateFreeformStreet;
+
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(CreateFreeformStreet::default()).unwrap();
+
+        app
+    }
 
     #[test]
     fn integration_startup_adds_shortcut() {
-        let mut app = App::<Map>::default();
+        let app = app();
+
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::A]));
+    }
 
-        let mut plugin = CreateFreeformStreet::default();
-        plugin.startup(&mut app).unwrap();
+    #[test]
+    fn integration_startup_adds_toolbar_button() {
+        let app = app();
+
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.edit.modes.street", "create_street");
+        });
 
-        assert!(app.has_shortkey(vec![Key::Ctrl, Key::A]))
+        assert!(has_button);
     }
 
     #[test]
+    fn integration_drawing_creates_street() {
+        let mut app = app();
 
-    fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+        app.mouse_down(Coordinate { x: 100., y: 100. }, mouse::Button::Left);
+        for x in [150., 200., 250., 300.] {
+            app.mouse_move(Coordinate { x, y: 100. }, mouse::Button::Left);
+        }
+        app.mouse_up(Coordinate { x: 300., y: 100. }, mouse::Button::Left);
+
+        assert_eq!(app.data().streets().len(), 1);
+        assert_eq!(app.data().intersections().len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{map::Map, street_class::StreetClass};
+
+    use super::CreateFreeFormStreetAction;
+
+    fn add_street(line_string: LineString<f64>, map: &mut Map) -> CreateFreeFormStreetAction {
+        let mut action = CreateFreeFormStreetAction::new(line_string);
+        action.execute(map);
+
+        action
+    }
+
+    #[test]
+    fn crossing_streets_are_split_at_intersection() {
+        let mut map = Map::new(1000, 1000);
+
+        add_street(LineString::from(vec![(0., 100.), (200., 100.)]), &mut map);
+        add_street(LineString::from(vec![(100., 0.), (100., 200.)]), &mut map);
+
+        assert_eq!(map.streets().len(), 4);
+        assert_eq!(map.intersections().len(), 5);
+        assert!(map
+            .intersections()
+            .values()
+            .any(|intersection| intersection.get_connected_streets().len() == 4));
+    }
+
+    #[test]
+    fn street_ends_snap_to_existing_intersections() {
+        let mut map = Map::new(1000, 1000);
+
+        add_street(LineString::from(vec![(0., 0.), (100., 0.)]), &mut map);
+        add_street(LineString::from(vec![(102., 3.), (200., 0.)]), &mut map);
+
+        assert_eq!(map.streets().len(), 2);
+        assert_eq!(map.intersections().len(), 3);
+    }
 
-        let mut plugin = CreateFreeformStreet::default();
-        plugin.startup(&mut app).unwrap();
+    #[test]
+    fn split_streets_keep_their_class() {
+        let mut map = Map::new(1000, 1000);
+
+        let mut action = CreateFreeFormStreetAction::new_with_class(
+            LineString::from(vec![(0., 100.), (200., 100.)]),
+            StreetClass::Highway,
+            StreetClass::Highway.default_width(),
+        );
+        action.execute(&mut map);
+        add_street(LineString::from(vec![(100., 0.), (100., 200.)]), &mut map);
+
+        let highways = map
+            .streets()
+            .values()
+            .filter(|street| street.class() == StreetClass::Highway)
+            .count();
+        assert_eq!(highways, 2);
+        assert_eq!(map.streets().len(), 4);
+    }
+
+    #[test]
+    fn curved_street_is_split_at_crossing() {
+        let mut map = Map::new(1000, 1000);
+
+        add_street(LineString::from(vec![(100., 0.), (100., 300.)]), &mut map);
+
+        let mut action = CreateFreeFormStreetAction::new_curved(
+            LineString::from(vec![(0., 100.), (100., 150.), (200., 100.)]),
+            StreetClass::Street,
+            StreetClass::Street.default_width(),
+        );
+        action.execute(&mut map);
+
+        assert_eq!(map.streets().len(), 4);
+        assert_eq!(
+            map.streets()
+                .values()
+                .filter(|street| street.is_curved())
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn undo_restores_crossed_street() {
+        let mut map = Map::new(1000, 1000);
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
-            .unwrap();
+        add_street(LineString::from(vec![(0., 100.), (200., 100.)]), &mut map);
+        let mut action = add_street(LineString::from(vec![(100., 0.), (100., 200.)]), &mut map);
+        action.undo(&mut map);
 
-        assert!(toolbar.has_button("create_street"));
+        assert_eq!(map.streets().len(), 1);
+        assert_eq!(map.intersections().len(), 2);
     }
 }
-*/
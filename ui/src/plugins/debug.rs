@@ -2,10 +2,10 @@ use plugin_toolbar::toolbar::ToolbarPosition;
 use rust_editor::{
     input::keyboard::Key,
     plugin::Plugin,
+    renderer::Canvas,
     ui::app::{EditorError, Shortkey},
 };
 use rust_macro::editor_plugin;
-use web_sys::CanvasRenderingContext2d;
 
 use crate::map::map::Map;
 
@@ -35,14 +35,14 @@ impl Plugin<Map> for Debug {
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, _: &Context<App<Map>>, _: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, _: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::U] {
             let mut enabled = self.__enabled.borrow_mut();
             *enabled = !*enabled;
         }
     }
 
-    fn render(&self, _: &CanvasRenderingContext2d, _: &App<Map>) {
+    fn render(&self, _: &mut dyn Canvas, _: &App<Map>) {
         /*
         let data = editor.data();
         for (_, street) in data.streets() {
@@ -75,40 +75,60 @@ impl Plugin<Map> for Debug {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use plugin_toolbar::toolbar::ToolbarPosition;
-    use rust_editor::input::keyboard::Key;
-    use rust_editor::plugin::Plugin;
-    use rust_editor::ui::app::App;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{
+        input::keyboard::Key, plugin::PluginWithOptions, ui::headless::HeadlessApp,
+    };
 
     use crate::map::map::Map;
-    use crate::plugins::debug::Debug;
 
-    #[test]
-    fn integration_startup_adds_shortcut() {
-        let mut app = App::<Map>::default();
+    use super::Debug;
 
-        let mut plugin = Debug::default();
-        plugin.startup(&mut app).unwrap();
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(Debug::default()).unwrap();
 
-        assert!(app.has_shortkey(vec![Key::Ctrl, Key::U]))
+        app
     }
 
     #[test]
+    fn integration_startup_adds_shortcut() {
+        let app = app();
 
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::U]));
+    }
+
+    #[test]
     fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+        let app = app();
+
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.actions", "debug");
+        });
 
-        let mut plugin = Debug::default();
-        plugin.startup(&mut app).unwrap();
+        assert!(has_button);
+    }
+
+    fn enabled(app: &HeadlessApp<Map>) -> bool {
+        let mut enabled = false;
+        app.app().plugin(|debug: &Debug| enabled = debug.enabled());
+
+        enabled
+    }
+
+    #[test]
+    fn integration_shortcut_toggles_debug_information() {
+        let mut app = app();
+        let was_enabled = enabled(&app);
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
-            .unwrap();
+        app.press_keys(&[Key::Ctrl, Key::U]);
+        assert_eq!(enabled(&app), !was_enabled);
 
-        assert!(toolbar.has_button("debug"));
+        app.press_keys(&[Key::Ctrl, Key::U]);
+        assert_eq!(enabled(&app), was_enabled);
     }
 }
-*/
\ No newline at end of file
@@ -9,19 +9,16 @@ use rust_editor::{
     ui::app::{EditorError, Shortkey},
 };
 use rust_macro::editor_plugin;
+use uuid::Uuid;
 
 use crate::map::map::Map;
 
 #[editor_plugin(skip, specific_to=Map, execution=Exclusive)]
-pub struct DeleteStreet {}
-
-impl DeleteStreet {
-    fn clean_hovered_street_state(&self, map: &mut Map) {
-        for (_, street) in map.streets_mut() {
-            street.set_state(InteractiveElementState::Normal);
-        }
-    }
+pub struct DeleteStreet {
+    #[option(skip)]
+    hovered_street: Option<Uuid>,
 }
+
 impl Plugin<Map> for DeleteStreet {
     fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
         editor.add_shortkey::<DeleteStreet>(vec![Key::Ctrl, Key::D2])?;
@@ -48,10 +45,9 @@ impl Plugin<Map> for DeleteStreet {
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, ctx: &Context<App<Map>>, _: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::D2] {
-            ctx.link()
-                .send_message(EditorMessages::ActivatePlugin(DeleteStreet::identifier()));
+            editor.send_message(EditorMessages::ActivatePlugin(DeleteStreet::identifier()));
         }
     }
 
@@ -63,14 +59,19 @@ impl Plugin<Map> for DeleteStreet {
         editor: &mut App<Map>,
     ) -> bool {
         let map = editor.data_mut();
-        self.clean_hovered_street_state(map);
-
-        for (_, street) in map.streets_mut() {
-            if street.is_point_on_street(&mouse_pos) {
-                street.set_state(InteractiveElementState::Hover);
+        if let Some(old_hovered_street) = self.hovered_street.take() {
+            if let Some(street) = map.street_mut(&old_hovered_street) {
+                street.set_state(InteractiveElementState::Normal);
             }
         }
 
+        if let Some(hovered_street) = map.get_street_at_position(&mouse_pos, &vec![]) {
+            map.street_mut(&hovered_street)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_street = Some(hovered_street);
+        }
+
         false
     }
 
@@ -93,6 +94,8 @@ impl Plugin<Map> for DeleteStreet {
         }
 
         if let Some(street) = app.data().get_street_at_position(&mouse_pos, &vec![]) {
+            self.hovered_street = None;
+
             let action = Rc::new(RefCell::new(ActionDeleteStreet::new(street)));
             action.as_ref().borrow_mut().execute(app.data_mut());
 
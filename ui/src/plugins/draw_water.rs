@@ -0,0 +1,208 @@
+use futures::executor::block_on;
+use geo::{euclidean_length::EuclideanLength, simplify::Simplify, Coordinate, LineString};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    renderer::Canvas,
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::map::{
+    actions::water::{create::CreateWater, delete::DeleteWater},
+    map::Map,
+    water::{Water, WaterKind},
+};
+
+/// Draws rivers, lakes and the sea. Rivers follow the drawn line and widen from their source to their mouth,
+/// lakes and the sea are the area enclosed by the drawn line. A right click on a water removes it.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct DrawWater {
+    #[option(
+        label = "Kind",
+        description = "One of river, lake or sea",
+        validator = r"^(river|lake|sea)$"
+    )]
+    kind: String,
+
+    #[option(default = 5., min = 0., max = 50., label = "Simplification Factor")]
+    simplification_factor: f64,
+
+    #[option(
+        default = 10.,
+        min = 1.,
+        max = 200.,
+        label = "Source Width",
+        description = "Width of a river where the drawing starts"
+    )]
+    source_width: f64,
+
+    #[option(
+        default = 30.,
+        min = 1.,
+        max = 500.,
+        label = "Mouth Width",
+        description = "Width of a river where the drawing ends"
+    )]
+    mouth_width: f64,
+
+    #[option(skip)]
+    raw_points: Vec<Coordinate<f64>>,
+
+    #[option(skip)]
+    brush_active: bool,
+
+    #[option(skip)]
+    hovered_water: Option<Uuid>,
+}
+
+impl DrawWater {
+    fn kind(&self) -> WaterKind {
+        self.kind.parse().unwrap_or_default()
+    }
+
+    /// Returns the water of the drawn points or `None` if too few points were drawn.
+    fn water(&self) -> Option<Water> {
+        let line_string = LineString(self.raw_points.clone()).simplify(&self.simplification_factor);
+
+        match self.kind() {
+            WaterKind::River if line_string.0.len() >= 2 => {
+                let length = line_string.euclidean_length();
+                let mut travelled = 0.;
+
+                let mut widths = vec![self.source_width];
+                for line in line_string.lines() {
+                    travelled += line.euclidean_length();
+                    widths.push(
+                        self.source_width
+                            + (self.mouth_width - self.source_width) * travelled / length,
+                    );
+                }
+
+                Some(Water::new(WaterKind::River, line_string, widths))
+            }
+            kind @ (WaterKind::Lake | WaterKind::Sea) if line_string.0.len() >= 3 => {
+                Some(Water::new(kind, line_string, vec![]))
+            }
+            _ => None,
+        }
+    }
+
+    fn execute<A: Action<Map> + 'static>(&self, action: A, app: &mut App<Map>) {
+        let action = Rc::new(RefCell::new(action));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+    }
+}
+
+impl Plugin<Map> for DrawWater {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.water", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "water",
+                        "draw_water",
+                        "Draw Water".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(DrawWater::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_down(&mut self, _: Coordinate<f64>, button: mouse::Button, _: &App<Map>) -> bool {
+        if button == mouse::Button::Left {
+            self.brush_active = true;
+        }
+
+        false
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        if self.brush_active {
+            self.raw_points.push(mouse_pos);
+        }
+
+        let map = editor.data_mut();
+        if let Some(old_hovered_water) = self.hovered_water.take() {
+            if let Some(water) = map.water_mut(&old_hovered_water) {
+                water.set_state(InteractiveElementState::Normal);
+            }
+        }
+
+        if let Some(hovered_water) = map.get_water_at_position(&mouse_pos) {
+            map.water_mut(&hovered_water)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_water = Some(hovered_water);
+        }
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        match button {
+            mouse::Button::Left => {
+                self.brush_active = false;
+
+                if let Some(water) = self.water() {
+                    self.hovered_water = None;
+                    self.execute(CreateWater::new(water), app);
+                }
+
+                self.raw_points.clear();
+            }
+            mouse::Button::Right => {
+                if let Some(water) = app.data().get_water_at_position(&mouse_pos) {
+                    self.hovered_water = None;
+                    self.execute(DeleteWater::new(water), app);
+                }
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    fn render(&self, context: &mut dyn Canvas, _: &App<Map>) {
+        if let Some(water) = self.water() {
+            water.render(context).unwrap();
+        }
+    }
+}
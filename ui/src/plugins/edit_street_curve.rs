@@ -0,0 +1,192 @@
+use geo::{prelude::EuclideanDistance, Coordinate, Point};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    plugin::{Plugin, PluginWithOptions},
+    renderer::{Canvas, PrimitiveRenderer},
+    style::Style,
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::{
+    algorithm::spline::curve_line_string,
+    map::{
+        actions::street::set_control_points::SetControlPoints, map::Map,
+        street::calc_polygon_points,
+    },
+};
+
+/// Maximum distance between the mouse and a control point for the control point to be grabbed.
+const HANDLE_RADIUS: f64 = 8.;
+
+/// Edits curved streets by dragging their control points. The first and the last control point are
+/// connected to intersections and can therefore not be moved.
+#[editor_plugin(skip, specific_to=Map, execution=Exclusive)]
+pub struct EditStreetCurve {
+    #[option(skip)]
+    hovered_street: Option<Uuid>,
+
+    /// The index of the dragged control point of the hovered street.
+    #[option(skip)]
+    dragged_control_point: Option<usize>,
+
+    /// Control points of the hovered street including the changes of the current drag.
+    #[option(skip)]
+    control_points: Vec<Coordinate<f64>>,
+}
+
+impl EditStreetCurve {
+    fn control_point_at_position(&self, position: &Coordinate<f64>) -> Option<usize> {
+        let last = self.control_points.len().checked_sub(1)?;
+
+        self.control_points
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 0 && *index != last)
+            .find(|(_, control_point)| {
+                Point::from(**control_point).euclidean_distance(&Point::from(*position))
+                    < HANDLE_RADIUS
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+impl Plugin<Map> for EditStreetCurve {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "gesture",
+                        "edit_street_curve",
+                        "Edit Street Curves".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(EditStreetCurve::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_down(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        _: &App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        self.dragged_control_point = self.control_point_at_position(&mouse_pos);
+
+        false
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        if let Some(index) = self.dragged_control_point {
+            self.control_points[index] = mouse_pos;
+
+            return false;
+        }
+
+        // Keep the handles of the current street while the mouse is over one of them, they
+        // might reach beyond the street
+        if self.control_point_at_position(&mouse_pos).is_some() {
+            return false;
+        }
+
+        let map = editor.data();
+        self.hovered_street = map
+            .get_street_at_position(&mouse_pos, &vec![])
+            .filter(|id| map.street(id).map_or(false, |street| street.is_curved()));
+        self.control_points = self
+            .hovered_street
+            .and_then(|id| map.street(&id))
+            .map_or(vec![], |street| street.control_points().clone());
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        _mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let (street_id, _) = match (self.hovered_street, self.dragged_control_point.take()) {
+            (Some(street_id), Some(index)) => (street_id, index),
+            _ => return false,
+        };
+
+        let action = Rc::new(RefCell::new(SetControlPoints::new(
+            street_id,
+            self.control_points.clone(),
+        )));
+        action.as_ref().borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        false
+    }
+
+    fn render(&self, context: &mut dyn Canvas, app: &App<Map>) {
+        let street = match self.hovered_street.and_then(|id| app.data().street(&id)) {
+            Some(street) => street,
+            None => return,
+        };
+
+        if self.dragged_control_point.is_some() {
+            let line_string = curve_line_string(&self.control_points);
+            let polygon = calc_polygon_points(line_string.lines(), street.width());
+
+            polygon
+                .render(&street.class().style().hover, context)
+                .unwrap();
+        }
+
+        let last = self.control_points.len() - 1;
+        for (index, control_point) in self.control_points.iter().enumerate() {
+            let style = match index == 0 || index == last {
+                true => Style {
+                    border_width: 0,
+                    border_color: "#FFFFFF".to_string(),
+                    background_color: "#9E9E9E".to_string(),
+                },
+                false => Style {
+                    border_width: 2,
+                    border_color: "#FFFFFF".to_string(),
+                    background_color: "#FF8C00".to_string(),
+                },
+            };
+
+            control_point.render(&style, context).unwrap();
+        }
+    }
+}
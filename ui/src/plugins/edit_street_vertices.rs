@@ -0,0 +1,588 @@
+use std::fmt;
+
+use geo::{
+    prelude::{BoundingRect, EuclideanDistance},
+    Coordinate, LineString, Point,
+};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::{Action, MultiAction, Redo, Undo},
+    gizmo::{GetPosition, Gizmo, Id, MoveGizmo, SetPosition},
+    input::{keyboard::Key, mouse},
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    renderer::{Canvas, PrimitiveRenderer},
+    style::Style,
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::{
+    algorithm::geo::line_string_crossings,
+    map::{
+        actions::street::{
+            delete::DeleteStreet, insert_vertex::InsertVertex, move_vertex::MoveVertex,
+            remove_vertex::RemoveVertex, rename::RenameStreet,
+        },
+        map::Map,
+        street::Street,
+    },
+    plugins::create_freeform_street::{CreateFreeFormStreetAction, SNAP_DISTANCE},
+};
+
+/// Maximum distance between the mouse and a vertex for the vertex to be selected.
+const HANDLE_RADIUS: f64 = 8.;
+
+/// The selected vertex of a street, moved by the gizmo.
+struct Vertex {
+    street_id: Uuid,
+    index: usize,
+    position: Coordinate<f64>,
+}
+
+impl Id for Vertex {
+    fn id(&self) -> Uuid {
+        self.street_id
+    }
+}
+
+impl GetPosition for Vertex {
+    fn position(&self) -> Coordinate<f64> {
+        self.position
+    }
+}
+
+impl SetPosition for Vertex {
+    fn set_position(&mut self, position: Coordinate<f64>) {
+        self.position = position;
+    }
+}
+
+/// Edits the vertices of existing streets. A click on a street selects it and shows its vertices, a click on
+/// an inner vertex selects the vertex so that it can be dragged with the gizmo, a click on the selected street
+/// inserts a new vertex and delete removes the selected vertex. The ends of a street are connected to
+/// intersections and can therefore neither be moved nor removed. The map is only changed when a drag ends,
+/// the street and the districts around it are previewed while its vertex is dragged.
+#[editor_plugin(skip, specific_to=Map, execution=Exclusive)]
+pub struct EditStreetVertices {
+    #[option(skip)]
+    selected_street: Option<Uuid>,
+
+    #[option(skip)]
+    selected_vertex: Option<Vertex>,
+
+    /// Position of the selected vertex when the current drag started.
+    #[option(skip)]
+    drag_start: Option<Coordinate<f64>>,
+
+    /// Copy of the map taken when the current drag started, in which the dragged vertex is moved.
+    #[option(skip)]
+    preview: Option<Map>,
+
+    #[option(skip)]
+    gizmo: MoveGizmo,
+}
+
+impl EditStreetVertices {
+    fn inner_vertex_at_position(&self, map: &Map, position: &Coordinate<f64>) -> Option<usize> {
+        let vertices = map.street(&self.selected_street?)?.vertices();
+        let last = vertices.len().saturating_sub(1);
+
+        vertices
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 0 && *index != last)
+            .find(|(_, vertex)| {
+                Point::from(**vertex).euclidean_distance(&Point::from(*position)) < HANDLE_RADIUS
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn select_street(&mut self, street_id: Option<Uuid>, map: &mut Map) {
+        if let Some(street) = self.selected_street.and_then(|id| map.street_mut(&id)) {
+            street.set_state(InteractiveElementState::Normal);
+        }
+
+        if let Some(street) = street_id.and_then(|id| map.street_mut(&id)) {
+            street.set_state(InteractiveElementState::Selected);
+        }
+
+        self.selected_street = street_id;
+        self.selected_vertex = None;
+    }
+
+    fn select_vertex(&mut self, index: Option<usize>, map: &Map) {
+        self.selected_vertex = self
+            .selected_street
+            .zip(index)
+            .and_then(|(street_id, index)| {
+                let position = *map.street(&street_id)?.vertices().get(index)?;
+
+                Some(Vertex {
+                    street_id,
+                    index,
+                    position,
+                })
+            });
+
+        if let Some(vertex) = &self.selected_vertex {
+            self.gizmo.set_position(vertex.position);
+        }
+    }
+
+    fn push_action<A: Action<Map> + 'static>(action: A, app: &mut App<Map>) {
+        let action = Rc::new(RefCell::new(action));
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+    }
+}
+
+/// Moves a vertex of a street and connects the moved street to the street graph again. If the street crosses
+/// other streets after the move it is created again by a [`CreateFreeFormStreetAction`], which adds an
+/// intersection at each crossing like for a newly drawn street.
+pub struct MoveStreetVertexAction {
+    street_id: Uuid,
+    index: usize,
+    from: Coordinate<f64>,
+    to: Coordinate<f64>,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl MoveStreetVertexAction {
+    pub fn new(street_id: Uuid, index: usize, from: Coordinate<f64>, to: Coordinate<f64>) -> Self {
+        MoveStreetVertexAction {
+            street_id,
+            index,
+            from,
+            to,
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    fn execute_and_push<A>(&mut self, mut action: A, map: &mut Map)
+    where
+        A: Action<Map> + 'static,
+    {
+        action.execute(map);
+        self.action_stack.push(action);
+    }
+
+    /// Returns `true` if `street` crosses another street at a point that is not covered by an intersection.
+    fn crosses_streets(street: &Street, map: &Map) -> bool {
+        let rect = match street.lines.bounding_rect() {
+            Some(rect) => rect,
+            None => return false,
+        };
+
+        map.streets_within_rectangle(&rect)
+            .filter(|other| other.id() != street.id())
+            .flat_map(|other| line_string_crossings(&street.lines, &other.lines))
+            .any(|crossing| {
+                map.get_intersection_at_position(&crossing, SNAP_DISTANCE, &vec![])
+                    .is_none()
+            })
+    }
+}
+
+impl Undo<Map> for MoveStreetVertexAction {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for MoveStreetVertexAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        self.execute_and_push(
+            MoveVertex::new(self.street_id, self.index, self.from, self.to),
+            map,
+        );
+
+        let street = match map.street(&self.street_id) {
+            Some(street) if MoveStreetVertexAction::crosses_streets(street, map) => street.clone(),
+            _ => return,
+        };
+
+        self.execute_and_push(DeleteStreet::new(self.street_id), map);
+
+        let mut action = match street.is_curved() {
+            true => CreateFreeFormStreetAction::new_curved(
+                LineString::new(street.vertices().clone()),
+                street.class(),
+                street.width(),
+            ),
+            false => CreateFreeFormStreetAction::new_with_class(
+                street.lines.clone(),
+                street.class(),
+                street.width(),
+            ),
+        };
+        action.execute(map);
+
+        // The parts keep the name of the street
+        let parts = action.street_ids().clone();
+        self.action_stack.push(action);
+        if street.name().is_some() {
+            for part in parts {
+                self.execute_and_push(RenameStreet::new(part, street.name().cloned()), map);
+            }
+        }
+    }
+}
+
+impl Action<Map> for MoveStreetVertexAction {}
+
+impl fmt::Display for MoveStreetVertexAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[move_street_vertex] street={}, index={}\n\u{251C}  {}",
+            self.street_id, self.index, self.action_stack
+        )
+    }
+}
+
+impl Plugin<Map> for EditStreetVertices {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "polyline",
+                        "edit_street_vertices",
+                        "Edit Street Vertices".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(EditStreetVertices::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn deactivated(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        self.select_street(None, editor.data_mut());
+        self.drag_start = None;
+        self.preview = None;
+
+        Ok(())
+    }
+
+    fn mouse_down(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(vertex) = &self.selected_vertex {
+            let start = vertex.position;
+
+            self.gizmo
+                .mouse_down(mouse_pos, button as u32, self.selected_vertex.iter_mut());
+            if self.gizmo.is_active() {
+                self.drag_start = Some(start);
+                self.preview = Some(app.data().clone());
+            }
+        }
+
+        false
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        _: &mut App<Map>,
+    ) -> bool {
+        let from = match self.drag_start {
+            Some(from) => from,
+            None => return false,
+        };
+
+        self.gizmo
+            .mouse_move(mouse_pos, self.selected_vertex.iter_mut());
+
+        // Moving the vertex in the copy updates the street polygon and the districts around the street
+        if let (Some(preview), Some(vertex)) = (&mut self.preview, &self.selected_vertex) {
+            MoveVertex::new(vertex.street_id, vertex.index, from, vertex.position).execute(preview);
+        }
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(from) = self.drag_start.take() {
+            self.preview = None;
+            self.gizmo
+                .mouse_up(mouse_pos, button as u32, self.selected_vertex.iter_mut());
+
+            if let Some(vertex) = self
+                .selected_vertex
+                .as_ref()
+                .filter(|vertex| vertex.position != from)
+            {
+                let street_id = vertex.street_id;
+                let mut action =
+                    MoveStreetVertexAction::new(street_id, vertex.index, from, vertex.position);
+                action.execute(app.data_mut());
+                EditStreetVertices::push_action(action, app);
+
+                // A street that crosses other streets after the move is replaced by its parts
+                if app.data().street(&street_id).is_none() {
+                    self.select_street(None, app.data_mut());
+                }
+            }
+
+            return false;
+        }
+
+        if let Some(index) = self.inner_vertex_at_position(app.data(), &mouse_pos) {
+            self.select_vertex(Some(index), app.data());
+
+            return false;
+        }
+
+        let street_id = app.data().get_street_at_position(&mouse_pos, &vec![]);
+        match (street_id, self.selected_street) {
+            (Some(street_id), Some(selected_street)) if street_id == selected_street => {
+                let index = match app
+                    .data()
+                    .street(&street_id)
+                    .and_then(|street| street.closest_vertex_segment(&mouse_pos))
+                {
+                    Some(segment) => segment + 1,
+                    None => return false,
+                };
+
+                let mut action = InsertVertex::new(street_id, index, mouse_pos);
+                action.execute(app.data_mut());
+                EditStreetVertices::push_action(action, app);
+
+                self.select_vertex(Some(index), app.data());
+            }
+            (street_id, _) => self.select_street(street_id, app.data_mut()),
+        }
+
+        false
+    }
+
+    fn key_down(&mut self, key: Key, app: &mut App<Map>) {
+        if key != Key::Delete && key != Key::Backspace {
+            return;
+        }
+
+        if let Some(vertex) = self.selected_vertex.take() {
+            let mut action = RemoveVertex::new(vertex.street_id, vertex.index);
+            action.execute(app.data_mut());
+            EditStreetVertices::push_action(action, app);
+        }
+    }
+
+    fn render(&self, context: &mut dyn Canvas, app: &App<Map>) {
+        let street = match self.selected_street.and_then(|id| app.data().street(&id)) {
+            Some(street) => street,
+            None => return,
+        };
+
+        let mut vertices = street.vertices();
+        if let Some(preview) = &self.preview {
+            // Districts that are not part of the map changed by the drag
+            for (_, district) in preview
+                .districts()
+                .iter()
+                .filter(|(id, _)| !app.data().districts().contains_key(*id))
+            {
+                district.render(context).unwrap();
+            }
+
+            if let Some(preview) = preview.street(&street.id()) {
+                preview
+                    .polygon()
+                    .render(&street.class().style().hover, context)
+                    .unwrap();
+                vertices = preview.vertices();
+            }
+        }
+
+        let last = vertices.len().saturating_sub(1);
+        for (index, vertex) in vertices.iter().enumerate() {
+            let background_color = if index == 0 || index == last {
+                "#9E9E9E"
+            } else if self.selected_vertex.as_ref().map(|vertex| vertex.index) == Some(index) {
+                "#C45D53"
+            } else {
+                "#FF8C00"
+            };
+
+            let style = Style {
+                border_width: 2,
+                border_color: "#FFFFFF".to_string(),
+                background_color: background_color.to_string(),
+            };
+
+            vertex.render(&style, context).unwrap();
+        }
+
+        self.gizmo
+            .render(context, self.selected_vertex.iter())
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Coordinate, LineString};
+    use plugin_undo_redo::{Redo, Undo};
+    use rust_editor::{
+        input::{keyboard::Key, mouse},
+        ui::headless::HeadlessApp,
+    };
+    use uuid::Uuid;
+
+    use crate::map::{map::Map, street::Street};
+
+    use super::EditStreetVertices;
+
+    fn app() -> (HeadlessApp<Map>, Uuid) {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(Undo::<Map>::default()).unwrap();
+        app.add_plugin(Redo::<Map>::default()).unwrap();
+        app.add_plugin(EditStreetVertices::default()).unwrap();
+
+        let street = Street::new(LineString::from(vec![
+            (100., 300.),
+            (200., 300.),
+            (300., 300.),
+        ]));
+        let id = app.app_mut().data_mut().add_street(&street);
+
+        (app, id)
+    }
+
+    fn vertex(app: &HeadlessApp<Map>, id: &Uuid) -> Coordinate<f64> {
+        app.data().street(id).unwrap().vertices()[1]
+    }
+
+    #[test]
+    fn integration_dragged_vertex_is_moved_once_the_drag_ends() {
+        let (mut app, id) = app();
+
+        // Select the street and then its inner vertex, which places the gizmo on it
+        app.click(Coordinate { x: 150., y: 300. }, mouse::Button::Left);
+        app.click(Coordinate { x: 200., y: 300. }, mouse::Button::Left);
+
+        app.mouse_down(Coordinate { x: 205., y: 295. }, mouse::Button::Left);
+        app.mouse_move(Coordinate { x: 205., y: 320. }, mouse::Button::Left);
+        app.mouse_move(Coordinate { x: 205., y: 345. }, mouse::Button::Left);
+        assert_eq!(vertex(&app, &id), Coordinate { x: 200., y: 300. });
+
+        app.mouse_up(Coordinate { x: 205., y: 345. }, mouse::Button::Left);
+        assert_eq!(vertex(&app, &id), Coordinate { x: 200., y: 350. });
+
+        // The whole drag is undone at once
+        app.press_keys(&[Key::Ctrl, Key::Z]);
+        assert_eq!(vertex(&app, &id), Coordinate { x: 200., y: 300. });
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+    use uuid::Uuid;
+
+    use crate::{map::map::Map, plugins::create_freeform_street::CreateFreeFormStreetAction};
+
+    use super::MoveStreetVertexAction;
+
+    fn create_map() -> (Map, Uuid) {
+        let mut map = Map::new(1000, 1000);
+
+        CreateFreeFormStreetAction::new(LineString::from(vec![(200., 100.), (200., 500.)]))
+            .execute(&mut map);
+
+        let mut action = CreateFreeFormStreetAction::new(LineString::from(vec![
+            (100., 300.),
+            (150., 350.),
+            (180., 300.),
+        ]));
+        action.execute(&mut map);
+
+        let id = action.street_ids()[0];
+        (map, id)
+    }
+
+    #[test]
+    fn moved_vertex_without_crossing_keeps_street() {
+        let (mut map, id) = create_map();
+
+        let mut action = MoveStreetVertexAction::new(
+            id,
+            1,
+            Coordinate { x: 150., y: 350. },
+            Coordinate { x: 150., y: 400. },
+        );
+        action.execute(&mut map);
+
+        assert_eq!(map.streets().len(), 2);
+        assert_eq!(
+            map.street(&id).unwrap().vertices()[1],
+            Coordinate { x: 150., y: 400. }
+        );
+    }
+
+    #[test]
+    fn moved_vertex_splits_crossed_streets() {
+        let (mut map, id) = create_map();
+
+        // Both segments next to the moved vertex cross the vertical street
+        let mut action = MoveStreetVertexAction::new(
+            id,
+            1,
+            Coordinate { x: 150., y: 350. },
+            Coordinate { x: 250., y: 350. },
+        );
+        action.execute(&mut map);
+
+        assert!(map.street(&id).is_none());
+        assert_eq!(map.streets().len(), 6);
+        assert_eq!(map.intersections().len(), 6);
+
+        action.undo(&mut map);
+        assert_eq!(map.streets().len(), 2);
+        assert_eq!(map.intersections().len(), 4);
+        assert_eq!(
+            map.street(&id).unwrap().vertices()[1],
+            Coordinate { x: 150., y: 350. }
+        );
+    }
+}
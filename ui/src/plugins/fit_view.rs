@@ -0,0 +1,159 @@
+use geo::{Coordinate, Rect};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    input::keyboard::Key,
+    plugin::Plugin,
+    ui::app::{EditorError, Shortkey},
+};
+use rust_macro::editor_plugin;
+
+use crate::map::map::Map;
+
+/// Free space around the map in pixels when the view is fitted to it.
+const PADDING: f64 = 40.;
+
+/// Zooms and pans the view so that the whole map is visible. An empty map is fitted with its full size.
+///
+/// Bound to Shift+F, as Ctrl+0 resets the zoom of the browser.
+#[editor_plugin(skip, specific_to=Map)]
+pub struct FitView {}
+
+impl FitView {
+    fn fit(editor: &mut App<Map>) {
+        let map = editor.data();
+        let rect = map.bounding_rect().unwrap_or_else(|| {
+            Rect::new(
+                Coordinate { x: 0., y: 0. },
+                Coordinate {
+                    x: map.width().into(),
+                    y: map.height().into(),
+                },
+            )
+        });
+
+        let canvas_size = editor.canvas_size();
+        let screen_size = Coordinate {
+            x: canvas_size.x.into(),
+            y: canvas_size.y.into(),
+        };
+
+        editor
+            .viewport_mut()
+            .zoom_to_fit(rect, screen_size, PADDING);
+    }
+}
+
+impl Plugin<Map> for FitView {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.add_shortkey::<FitView>(vec![Key::Shift, Key::F])?;
+
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
+                    .unwrap();
+
+                toolbar
+                    .add_button("fit_screen", "fit_view", "Fit View".to_string(), || {
+                        EditorMessages::ShortkeyPressed(vec![Key::Shift, Key::F])
+                    })
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
+        if *key == vec![Key::Shift, Key::F] {
+            FitView::fit(editor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use rust_editor::{
+        gizmo::GetPosition,
+        input::{keyboard::Key, mouse},
+        ui::headless::HeadlessApp,
+    };
+
+    use crate::{
+        map::{
+            landmark::{Landmark, LandmarkKind},
+            map::Map,
+        },
+        plugins::place_landmarks::PlaceLandmarks,
+    };
+
+    use super::FitView;
+
+    fn assert_near(a: Coordinate<f64>, b: Coordinate<f64>) {
+        assert!(
+            (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn integration_startup_adds_shortcut() {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(FitView::default()).unwrap();
+
+        assert!(app.app().has_shortkey(vec![Key::Shift, Key::F]));
+        assert!(!app.app().has_shortkey(vec![Key::Ctrl, Key::D0]));
+    }
+
+    #[test]
+    fn integration_empty_map_is_fitted_with_its_size() {
+        let mut app = HeadlessApp::<Map>::new(1320, 760);
+        app.add_plugin(FitView::default()).unwrap();
+
+        // The default map is 2560 x 1440 and the padding leaves 1240 x 680 pixels
+        app.press_keys(&[Key::Shift, Key::F]);
+
+        let viewport = app.app().viewport();
+        assert_eq!(viewport.zoom(), 680. / 1440.);
+        assert_near(
+            viewport.world_to_screen(Coordinate { x: 1280., y: 720. }),
+            Coordinate { x: 660., y: 380. },
+        );
+    }
+
+    #[test]
+    fn integration_clicks_are_in_world_coordinates_after_fit() {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(FitView::default()).unwrap();
+        app.add_plugin(PlaceLandmarks::default()).unwrap();
+
+        let map = app.app_mut().data_mut();
+        map.add_landmark(Landmark::new(
+            LandmarkKind::Well,
+            Coordinate { x: 100., y: 100. },
+        ));
+        map.add_landmark(Landmark::new(
+            LandmarkKind::Gate,
+            Coordinate { x: 900., y: 500. },
+        ));
+
+        app.press_keys(&[Key::Shift, Key::F]);
+        assert!(app.app().viewport().zoom() < 1.);
+
+        // The center of the screen shows the center of both landmarks
+        app.click(Coordinate { x: 400., y: 300. }, mouse::Button::Left);
+
+        let landmarks: Vec<_> = app
+            .data()
+            .landmarks()
+            .values()
+            .filter(|landmark| landmark.kind() == LandmarkKind::default())
+            .map(|landmark| landmark.position())
+            .collect();
+        assert_eq!(landmarks.len(), 1);
+        assert_near(landmarks[0], Coordinate { x: 500., y: 300. });
+    }
+}
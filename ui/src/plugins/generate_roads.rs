@@ -0,0 +1,676 @@
+use std::{cmp::Ordering, fmt};
+
+use futures::executor::block_on;
+use geo::{
+    prelude::{Centroid, Contains},
+    Coordinate, LineString, Polygon,
+};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rust_editor::{
+    actions::{Action, MultiAction, Redo, Undo},
+    gizmo::{GetPosition, Id},
+    input::mouse,
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::{
+    algorithm::roads::{arterial_network, local_streets, LocalStreetParameters},
+    map::{
+        actions::street::{
+            create_streets::CreateStreets, delete::DeleteStreet, merge::MergeStreets,
+        },
+        district::derive_district_seed,
+        map::Map,
+        street_class::StreetClass,
+    },
+};
+
+/// Generates the road network in two phases. A click outside of any block lays arterial roads between all gates
+/// and landmarks and fills the blocks enclosed by them with local streets. A click into a block replaces the local
+/// streets of that block with new ones while the arterial roads around it are kept.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GenerateRoads {
+    #[option(
+        label = "Arterial Class",
+        description = "One of highway or main_road. Empty for main_road",
+        validator = StreetClass::ARTERIAL_VALIDATOR
+    )]
+    arterial_class: String,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Arterial Width",
+        description = "Overrides the default width of the arterial class if greater than 0"
+    )]
+    arterial_width: f64,
+
+    #[option(
+        label = "Local Class",
+        description = "One of street, alley or footpath. Empty for street",
+        validator = StreetClass::LOCAL_VALIDATOR
+    )]
+    local_class: String,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 100.,
+        label = "Local Width",
+        description = "Overrides the default width of the local class if greater than 0"
+    )]
+    local_width: f64,
+
+    #[option(
+        default = 120.,
+        min = 20.,
+        max = 1000.,
+        label = "Spacing",
+        description = "Distance between the local streets along the long side of a block"
+    )]
+    spacing: f64,
+
+    #[option(
+        default = 240.,
+        min = 0.,
+        max = 2000.,
+        label = "Cross Spacing",
+        description = "Distance between the cross streets of a block, 0 disables them"
+    )]
+    cross_spacing: f64,
+
+    #[option(
+        default = 1,
+        min = 0,
+        max = 999999,
+        label = "Seed",
+        description = "The same seed always fills a block with the same local streets"
+    )]
+    seed: u64,
+}
+
+impl GenerateRoads {
+    fn arterial_class(&self) -> StreetClass {
+        self.arterial_class
+            .parse()
+            .ok()
+            .filter(StreetClass::is_arterial)
+            .unwrap_or(StreetClass::MainRoad)
+    }
+
+    fn local_class(&self) -> StreetClass {
+        self.local_class
+            .parse()
+            .ok()
+            .filter(|class: &StreetClass| !class.is_arterial())
+            .unwrap_or_default()
+    }
+
+    fn arterials(&self) -> RoadClass {
+        RoadClass::new(self.arterial_class(), self.arterial_width)
+    }
+
+    fn locals(&self) -> RoadClass {
+        RoadClass::new(self.local_class(), self.local_width)
+    }
+
+    fn parameters(&self) -> LocalStreetParameters {
+        LocalStreetParameters {
+            spacing: self.spacing,
+            cross_spacing: self.cross_spacing,
+            ..LocalStreetParameters::default()
+        }
+    }
+}
+
+/// Street class and width of one level of the road hierarchy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoadClass {
+    pub class: StreetClass,
+    pub width: f64,
+}
+
+impl RoadClass {
+    /// Creates a road class whose width falls back to the default width of `class` if `width` is not positive.
+    pub fn new(class: StreetClass, width: f64) -> Self {
+        RoadClass {
+            class,
+            width: match width > 0. {
+                true => width,
+                false => class.default_width(),
+            },
+        }
+    }
+}
+
+/// Returns the gates of all walls and the positions of all landmarks ordered by their position, which are the
+/// points the arterial roads connect.
+pub fn road_anchors(map: &Map) -> Vec<Coordinate<f64>> {
+    let mut anchors: Vec<Coordinate<f64>> = map
+        .walls()
+        .values()
+        .flat_map(|wall| wall.gates().iter().copied())
+        .chain(map.landmarks().values().map(|landmark| landmark.position()))
+        .collect();
+    anchors.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap_or(Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+    });
+
+    anchors
+}
+
+/// Replaces the local streets of a block with new ones. Local streets belong to the block if their center lies
+/// within it. The intersections at which a previous fill split the arterial streets around the block are merged
+/// again, so refilling a block does not cut them into ever more pieces. Junctions of arterials that were not
+/// created by a fill are kept.
+pub struct FillBlockAction {
+    block: Polygon<f64>,
+    locals: RoadClass,
+    parameters: LocalStreetParameters,
+    seed: u64,
+
+    /// Block junctions of the map that were merged and those that were added by the new local streets, see
+    /// [`Map::block_junctions`].
+    removed_junctions: Vec<Uuid>,
+    added_junctions: Vec<Uuid>,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl FillBlockAction {
+    pub fn new(
+        block: Polygon<f64>,
+        locals: RoadClass,
+        parameters: LocalStreetParameters,
+        seed: u64,
+    ) -> Self {
+        FillBlockAction {
+            block,
+            locals,
+            parameters,
+            seed,
+            removed_junctions: vec![],
+            added_junctions: vec![],
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    fn local_streets_in_block(&self, map: &Map) -> Vec<Uuid> {
+        map.streets()
+            .values()
+            .filter(|street| !street.class().is_arterial())
+            .filter(|street| {
+                street
+                    .lines
+                    .centroid()
+                    .map_or(false, |center| self.block.contains(&center))
+            })
+            .map(|street| street.id())
+            .collect()
+    }
+
+    /// Block junctions the local `streets` are connected to.
+    fn block_junctions(streets: &[Uuid], map: &Map) -> Vec<Uuid> {
+        map.block_junctions()
+            .iter()
+            .filter(|id| {
+                map.intersection(id).map_or(false, |intersection| {
+                    intersection
+                        .get_connected_streets()
+                        .iter()
+                        .any(|(_, street)| streets.contains(street))
+                })
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns true if the intersection `id` is connected to an arterial street, i.e. an arterial street and
+    /// not a local one was split there.
+    fn is_arterial_junction(id: &Uuid, map: &Map) -> bool {
+        map.intersection(id).map_or(false, |intersection| {
+            intersection
+                .get_connected_streets()
+                .iter()
+                .any(|(_, street)| {
+                    map.street(street)
+                        .map_or(false, |street| street.class().is_arterial())
+                })
+        })
+    }
+}
+
+impl Undo<Map> for FillBlockAction {
+    fn undo(&mut self, map: &mut Map) {
+        for id in &self.added_junctions {
+            map.remove_block_junction(id);
+        }
+
+        self.action_stack.undo(map);
+
+        for id in &self.removed_junctions {
+            map.add_block_junction(*id);
+        }
+    }
+}
+
+impl Redo<Map> for FillBlockAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+        self.removed_junctions.clear();
+        self.added_junctions.clear();
+
+        let locals = self.local_streets_in_block(map);
+        let junctions = FillBlockAction::block_junctions(&locals, map);
+
+        for street in locals {
+            let mut action = DeleteStreet::new(street);
+            action.execute(map);
+            self.action_stack.push(action);
+        }
+
+        for intersection in junctions {
+            let mut action = MergeStreets::new(intersection);
+            action.execute(map);
+            self.action_stack.push(action);
+
+            // Junctions another street was connected to in the meantime are not merged and stay block junctions
+            if map.intersection(&intersection).is_none() {
+                map.remove_block_junction(&intersection);
+                self.removed_junctions.push(intersection);
+            }
+        }
+
+        // Each block gets its own seed so that filling all blocks at once results in the same streets as
+        // filling them one by one
+        let seed = ChaCha8Rng::from_seed(derive_district_seed(self.seed, &self.block)).gen();
+        let streets = local_streets(&self.block, &self.parameters, seed)
+            .into_iter()
+            .map(|street| (street, self.locals.class))
+            .collect();
+
+        let mut action = CreateStreets::new_with_width(streets, self.locals.width);
+        action.execute(map);
+
+        for id in action.split_intersections() {
+            if FillBlockAction::is_arterial_junction(id, map) {
+                map.add_block_junction(*id);
+                self.added_junctions.push(*id);
+            }
+        }
+
+        self.action_stack.push(action);
+    }
+}
+
+impl Action<Map> for FillBlockAction {}
+
+impl fmt::Display for FillBlockAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[fill_block] class={}, seed={}\n\u{251C}  {}",
+            self.locals.class, self.seed, self.action_stack
+        )
+    }
+}
+
+/// Lays arterial roads between `anchors` and fills every block of the map with local streets afterwards.
+/// Arterial roads that already exist are not laid again.
+pub struct GenerateRoadsAction {
+    anchors: Vec<Coordinate<f64>>,
+    arterials: RoadClass,
+    locals: RoadClass,
+    parameters: LocalStreetParameters,
+    seed: u64,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl GenerateRoadsAction {
+    pub fn new(
+        anchors: Vec<Coordinate<f64>>,
+        arterials: RoadClass,
+        locals: RoadClass,
+        parameters: LocalStreetParameters,
+        seed: u64,
+    ) -> Self {
+        GenerateRoadsAction {
+            anchors,
+            arterials,
+            locals,
+            parameters,
+            seed,
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    fn exists(arterial: &LineString<f64>, map: &Map) -> bool {
+        arterial.centroid().map_or(false, |center| {
+            map.get_street_at_position(&center.0, &vec![])
+                .and_then(|id| map.street(&id))
+                .map_or(false, |street| street.class().is_arterial())
+        })
+    }
+}
+
+impl Undo<Map> for GenerateRoadsAction {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for GenerateRoadsAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        let arterials = arterial_network(&self.anchors)
+            .into_iter()
+            .filter(|arterial| !GenerateRoadsAction::exists(arterial, map))
+            .map(|arterial| (arterial, self.arterials.class))
+            .collect();
+
+        let mut action = CreateStreets::new_with_width(arterials, self.arterials.width);
+        action.execute(map);
+        self.action_stack.push(action);
+
+        for block in map.blocks() {
+            let mut action =
+                FillBlockAction::new(block, self.locals, self.parameters.clone(), self.seed);
+            action.execute(map);
+            self.action_stack.push(action);
+        }
+    }
+}
+
+impl Action<Map> for GenerateRoadsAction {}
+
+impl fmt::Display for GenerateRoadsAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[generate_roads] anchors={}, arterial_class={}, local_class={}\n\u{251C}  {}",
+            self.anchors.len(),
+            self.arterials.class,
+            self.locals.class,
+            self.action_stack
+        )
+    }
+}
+
+impl Plugin<Map> for GenerateRoads {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "add_road",
+                        "generate_roads",
+                        "Generate Roads".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GenerateRoads::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let action: Rc<RefCell<dyn Action<Map>>> =
+            match app.data().get_block_at_position(&mouse_pos) {
+                Some(block) => Rc::new(RefCell::new(FillBlockAction::new(
+                    block,
+                    self.locals(),
+                    self.parameters(),
+                    self.seed,
+                ))),
+                None => {
+                    let anchors = road_anchors(app.data());
+                    if anchors.len() < 2 {
+                        return false;
+                    }
+
+                    Rc::new(RefCell::new(GenerateRoadsAction::new(
+                        anchors,
+                        self.arterials(),
+                        self.locals(),
+                        self.parameters(),
+                        self.seed,
+                    )))
+                }
+            };
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push_generic(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::{prelude::EuclideanLength, Coordinate, LineString, Polygon};
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::{
+        algorithm::roads::LocalStreetParameters,
+        map::{
+            actions::street::create_streets::CreateStreets,
+            landmark::{Landmark, LandmarkKind},
+            map::Map,
+            street_class::StreetClass,
+        },
+    };
+
+    use super::{road_anchors, FillBlockAction, GenerateRoadsAction, RoadClass};
+
+    fn create_map() -> Map {
+        let mut map = Map::new(1000, 1000);
+        for (x, y) in [(100., 100.), (500., 100.), (500., 400.), (100., 400.)] {
+            map.add_landmark(Landmark::new(LandmarkKind::Well, Coordinate { x, y }));
+        }
+
+        map
+    }
+
+    fn generate_roads(map: &mut Map) -> GenerateRoadsAction {
+        let mut action = GenerateRoadsAction::new(
+            road_anchors(map),
+            RoadClass::new(StreetClass::MainRoad, 0.),
+            RoadClass::new(StreetClass::Alley, 0.),
+            LocalStreetParameters {
+                spacing: 100.,
+                cross_spacing: 150.,
+                ..LocalStreetParameters::default()
+            },
+            1,
+        );
+        action.execute(map);
+
+        action
+    }
+
+    fn count_streets(map: &Map, class: StreetClass) -> usize {
+        map.streets()
+            .values()
+            .filter(|street| street.class() == class)
+            .count()
+    }
+
+    fn arterial_length(map: &Map) -> f64 {
+        map.streets()
+            .values()
+            .filter(|street| street.class().is_arterial())
+            .map(|street| street.lines.euclidean_length())
+            .sum()
+    }
+
+    #[test]
+    fn arterials_enclose_block_filled_with_local_streets() {
+        let mut map = create_map();
+        let mut action = generate_roads(&mut map);
+
+        assert_eq!(map.blocks().len(), 1);
+        assert!((arterial_length(&map) - 1400.).abs() < 1e-6);
+        assert!(count_streets(&map, StreetClass::Alley) > 0);
+        assert!(map
+            .streets()
+            .values()
+            .filter(|street| street.class() == StreetClass::Alley)
+            .all(|street| street.width() == StreetClass::Alley.default_width()));
+        assert!(map.districts().len() > 1);
+
+        action.undo(&mut map);
+        assert!(map.streets().is_empty());
+    }
+
+    #[test]
+    fn existing_arterials_are_not_laid_again() {
+        let mut map = create_map();
+        generate_roads(&mut map);
+        generate_roads(&mut map);
+
+        assert!((arterial_length(&map) - 1400.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn refilled_block_keeps_arterials() {
+        let mut map = create_map();
+        generate_roads(&mut map);
+        let arterials = arterial_length(&map);
+        let locals = count_streets(&map, StreetClass::Alley);
+
+        let block = map.blocks().pop().unwrap();
+        let mut action = FillBlockAction::new(
+            block,
+            RoadClass::new(StreetClass::Footpath, 3.),
+            LocalStreetParameters::default(),
+            2,
+        );
+        action.execute(&mut map);
+
+        assert!((arterial_length(&map) - arterials).abs() < 1e-6);
+        assert_eq!(count_streets(&map, StreetClass::Alley), 0);
+        assert!(count_streets(&map, StreetClass::Footpath) > 0);
+        assert_eq!(map.blocks().len(), 1);
+
+        action.undo(&mut map);
+        assert_eq!(count_streets(&map, StreetClass::Alley), locals);
+        assert_eq!(count_streets(&map, StreetClass::Footpath), 0);
+    }
+
+    #[test]
+    fn refilled_block_does_not_fragment_arterials() {
+        let fill = |map: &mut Map, seed: u64| {
+            let block = map.blocks().pop().unwrap();
+            FillBlockAction::new(
+                block,
+                RoadClass::new(StreetClass::Alley, 0.),
+                LocalStreetParameters::default(),
+                seed,
+            )
+            .execute(map);
+        };
+
+        let mut filled_once = create_map();
+        generate_roads(&mut filled_once);
+        fill(&mut filled_once, 3);
+
+        let mut filled_twice = create_map();
+        generate_roads(&mut filled_twice);
+        fill(&mut filled_twice, 2);
+        fill(&mut filled_twice, 3);
+
+        assert_eq!(
+            count_streets(&filled_twice, StreetClass::MainRoad),
+            count_streets(&filled_once, StreetClass::MainRoad)
+        );
+        assert_eq!(
+            filled_twice.intersections().len(),
+            filled_once.intersections().len()
+        );
+    }
+
+    #[test]
+    fn refilled_block_keeps_junctions_of_arterials() {
+        let mut map = Map::new(1000, 1000);
+        CreateStreets::new(vec![
+            (
+                LineString::from(vec![(100., 100.), (300., 100.)]),
+                StreetClass::MainRoad,
+            ),
+            (
+                LineString::from(vec![(300., 100.), (500., 100.)]),
+                StreetClass::MainRoad,
+            ),
+            (
+                LineString::from(vec![(500., 100.), (500., 400.), (100., 400.), (100., 100.)]),
+                StreetClass::MainRoad,
+            ),
+            (
+                LineString::from(vec![(300., 100.), (300., 250.)]),
+                StreetClass::Alley,
+            ),
+        ])
+        .execute(&mut map);
+
+        let junction = map
+            .get_intersection_at_position(&Coordinate { x: 300., y: 100. }, 1., &vec![])
+            .unwrap();
+
+        let block = Polygon::new(
+            LineString::from(vec![(100., 100.), (500., 100.), (500., 400.), (100., 400.)]),
+            vec![],
+        );
+        let mut action = FillBlockAction::new(
+            block,
+            RoadClass::new(StreetClass::Alley, 0.),
+            LocalStreetParameters::default(),
+            2,
+        );
+        action.execute(&mut map);
+
+        assert!(map.intersection(&junction).is_some());
+        assert!(!map.block_junctions().contains(&junction));
+        assert!(!map.block_junctions().is_empty());
+
+        action.undo(&mut map);
+        assert!(map.block_junctions().is_empty());
+        assert_eq!(count_streets(&map, StreetClass::MainRoad), 3);
+    }
+}
@@ -0,0 +1,454 @@
+use std::fmt;
+
+use futures::executor::block_on;
+use geo::{algorithm::concave_hull::ConcaveHull, Coordinate};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::{Action, Redo, Undo},
+    input::mouse,
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+
+use crate::{
+    algorithm::{
+        fortification::{fortification_layout, FortificationParameters},
+        settlement::{settlement_layout, SettlementParameters, SettlementPreset},
+    },
+    map::{
+        actions::street::create_streets::CreateStreets,
+        map::Map,
+        street_class::StreetClass,
+        wall::Wall,
+        water::{Water, WaterKind},
+    },
+};
+
+/// Concavity of the boundary around which the wall of a walled settlement is built.
+const WALL_CONCAVITY: f64 = 2.;
+
+/// Thickness of the wall of a walled settlement.
+const WALL_WIDTH: f64 = 8.;
+
+/// Generates a whole settlement from a preset. A click on the map replaces the map by a settlement around the
+/// clicked position, which consists of main roads, streets and their districts, water and walls depending on the
+/// preset. The terrain of the map is kept and the same seed always results in the same settlement.
+///
+/// Unless "Use Preset" is switched on, the radius, the main roads, the water, the walls and the growth of the
+/// streets are taken from the options instead of the preset.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GenerateSettlement {
+    #[option(
+        label = "Preset",
+        description = "One of hamlet, village, town, walled_city or port_city",
+        validator = r"^(hamlet|village|town|walled_city|port_city)$"
+    )]
+    preset: String,
+
+    #[option(
+        default = 1,
+        min = 0,
+        max = 999999,
+        label = "Seed",
+        description = "The same seed always generates the same settlement"
+    )]
+    seed: u64,
+
+    #[option(
+        default = true,
+        label = "Use Preset",
+        description = "Generates the settlement with the parameters of the preset and ignores the options below"
+    )]
+    use_preset: bool,
+
+    #[option(
+        default = 300.,
+        min = 0.,
+        max = 1000.,
+        label = "Radius",
+        description = "Distance between the center and the outermost houses"
+    )]
+    radius: f64,
+
+    #[option(
+        default = 2,
+        min = 0,
+        max = 8,
+        label = "Arterials",
+        description = "Number of main roads leading out of the settlement"
+    )]
+    arterials: usize,
+
+    #[option(
+        default = false,
+        label = "River",
+        description = "A river flows past the center"
+    )]
+    river: bool,
+
+    #[option(
+        default = false,
+        label = "Sea",
+        description = "The settlement lies at the coast"
+    )]
+    sea: bool,
+
+    #[option(
+        default = false,
+        label = "Walls",
+        description = "The settlement is enclosed by a wall"
+    )]
+    walls: bool,
+
+    #[option(
+        default = 0.3,
+        min = 0.,
+        max = 1.,
+        label = "Growth Density",
+        description = "Probability that a street branches off"
+    )]
+    density: f64,
+
+    #[option(
+        default = 80.,
+        min = 10.,
+        max = 200.,
+        label = "Segment Length",
+        description = "Average length of a street segment"
+    )]
+    segment_length: f64,
+}
+
+impl GenerateSettlement {
+    fn preset(&self) -> SettlementPreset {
+        self.preset.parse().unwrap_or_default()
+    }
+
+    /// The parameters of the selected preset, with the options applied unless the preset is used as is.
+    fn parameters(&self) -> SettlementParameters {
+        let mut parameters = self.preset().parameters();
+        if self.use_preset {
+            return parameters;
+        }
+
+        // The streets grow as far as the settlement reaches
+        parameters.radius = self.radius;
+        parameters.growth.radius = self.radius;
+
+        parameters.arterials = self.arterials;
+        parameters.river = self.river;
+        parameters.sea = self.sea;
+        parameters.walls = self.walls;
+        parameters.growth.density = self.density;
+        parameters.growth.segment_length = self.segment_length;
+
+        parameters
+    }
+}
+
+/// Generates a settlement around `center` on a new map of the same size and terrain as `map`. The streets are
+/// created by a [`CreateStreets`] so that they are connected and the districts between them are created by
+/// the map. The wall, if any, is built last so that each street that leaves the settlement gets a gate.
+pub fn generate_settlement(
+    map: &Map,
+    center: Coordinate<f64>,
+    parameters: &SettlementParameters,
+    seed: u64,
+) -> Map {
+    let mut settlement = Map::new(map.width(), map.height());
+    settlement.set_seed(seed);
+    settlement.set_terrain(map.terrain().cloned());
+
+    let layout = settlement_layout(
+        center,
+        parameters,
+        |position| settlement.elevation_at(position),
+        seed,
+    );
+
+    if let Some(sea) = layout.sea {
+        settlement.add_water(Water::new(WaterKind::Sea, sea, vec![]));
+    }
+
+    if let Some((river, widths)) = layout.river {
+        settlement.add_water(Water::new(WaterKind::River, river, widths));
+    }
+
+    let streets = layout
+        .arterials
+        .into_iter()
+        .map(|street| (street, StreetClass::MainRoad))
+        .chain(
+            layout
+                .streets
+                .into_iter()
+                .map(|street| (street, StreetClass::Street)),
+        )
+        .collect();
+    CreateStreets::new(streets).execute(&mut settlement);
+
+    if parameters.walls && settlement.intersections().len() >= 3 {
+        let fortification = FortificationParameters {
+            offset: 40.,
+            tower_spacing: 80.,
+            corner_angle: 30_f64.to_radians(),
+            gate_width: 24.,
+        };
+
+        if let Some(layout) = fortification_layout(
+            &settlement.concave_hull(WALL_CONCAVITY),
+            settlement.streets().values().map(|street| &street.lines),
+            &fortification,
+        ) {
+            settlement.add_wall(Wall::new(
+                layout.ring,
+                layout.towers,
+                layout.gates,
+                WALL_WIDTH,
+                fortification.gate_width,
+            ));
+        }
+    }
+
+    settlement
+}
+
+/// Replaces the whole map by a generated settlement. The replaced map is kept so that undo restores it.
+pub struct GenerateSettlementAction {
+    preset: SettlementPreset,
+    seed: u64,
+    settlement: Map,
+    replaced: Option<Map>,
+}
+
+impl GenerateSettlementAction {
+    pub fn new(preset: SettlementPreset, seed: u64, settlement: Map) -> Self {
+        GenerateSettlementAction {
+            preset,
+            seed,
+            settlement,
+            replaced: None,
+        }
+    }
+}
+
+impl Undo<Map> for GenerateSettlementAction {
+    fn undo(&mut self, map: &mut Map) {
+        if let Some(replaced) = self.replaced.take() {
+            *map = replaced;
+        }
+    }
+}
+
+impl Redo<Map> for GenerateSettlementAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.replaced = Some(std::mem::replace(map, self.settlement.clone()));
+    }
+}
+
+impl Action<Map> for GenerateSettlementAction {}
+
+impl fmt::Display for GenerateSettlementAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[generate_settlement] preset={}, seed={}, streets={}",
+            self.preset,
+            self.seed,
+            self.settlement.streets().len()
+        )
+    }
+}
+
+impl Plugin<Map> for GenerateSettlement {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.district", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "location_city",
+                        "generate_settlement",
+                        "Generate Settlement".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GenerateSettlement::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let preset = self.preset();
+        let settlement = generate_settlement(app.data(), mouse_pos, &self.parameters(), self.seed);
+
+        let action = Rc::new(RefCell::new(GenerateSettlementAction::new(
+            preset, self.seed, settlement,
+        )));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::{
+        algorithm::{
+            growth::GrowthParameters,
+            settlement::{SettlementParameters, SettlementPreset},
+        },
+        map::{map::Map, street::Street, water::WaterKind},
+    };
+
+    use super::{generate_settlement, GenerateSettlementAction};
+
+    const CENTER: Coordinate<f64> = Coordinate { x: 500., y: 500. };
+
+    fn parameters() -> SettlementParameters {
+        SettlementParameters {
+            radius: 300.,
+            growth: GrowthParameters {
+                radius: 300.,
+                max_segments: 30,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn settlement_has_streets_and_districts() {
+        let map = Map::new(1000, 1000);
+        let settlement = generate_settlement(&map, CENTER, &parameters(), 7);
+
+        assert!(settlement.streets().len() > 2);
+        assert!(!settlement.districts().is_empty());
+        assert_eq!(settlement.seed(), 7);
+    }
+
+    #[test]
+    fn walled_settlement_has_wall_and_port_has_sea() {
+        let map = Map::new(1000, 1000);
+
+        let walled = SettlementParameters {
+            walls: true,
+            ..parameters()
+        };
+        assert_eq!(
+            generate_settlement(&map, CENTER, &walled, 7).walls().len(),
+            1
+        );
+
+        let port = SettlementParameters {
+            sea: true,
+            ..parameters()
+        };
+        assert!(generate_settlement(&map, CENTER, &port, 7)
+            .waters()
+            .values()
+            .any(|water| water.kind() == WaterKind::Sea));
+    }
+
+    #[test]
+    fn undo_restores_replaced_map() {
+        let mut map = Map::new(1000, 1000);
+        map.add_street(&Street::new(LineString::from(vec![(0., 0.), (100., 0.)])));
+
+        let settlement = generate_settlement(&map, CENTER, &parameters(), 7);
+        let streets = settlement.streets().len();
+
+        let mut action = GenerateSettlementAction::new(SettlementPreset::Village, 7, settlement);
+        action.execute(&mut map);
+        assert_eq!(map.streets().len(), streets);
+
+        action.undo(&mut map);
+        assert_eq!(map.streets().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::settlement::SettlementPreset;
+
+    use super::GenerateSettlement;
+
+    #[test]
+    fn unit_preset_ignores_options() {
+        let plugin = GenerateSettlement {
+            preset: "walled_city".to_string(),
+            radius: 200.,
+            river: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            plugin.parameters(),
+            SettlementPreset::WalledCity.parameters()
+        );
+    }
+
+    #[test]
+    fn unit_options_replace_preset_parameters() {
+        let plugin = GenerateSettlement {
+            preset: "walled_city".to_string(),
+            use_preset: false,
+            radius: 200.,
+            arterials: 0,
+            river: true,
+            walls: false,
+            density: 0.,
+            segment_length: 50.,
+            ..Default::default()
+        };
+        let parameters = plugin.parameters();
+
+        assert_eq!(parameters.radius, 200.);
+        assert_eq!(parameters.growth.radius, 200.);
+        assert_eq!(parameters.arterials, 0);
+        assert!(parameters.river);
+        assert!(!parameters.sea);
+        assert!(!parameters.walls);
+        assert_eq!(parameters.growth.density, 0.);
+        assert_eq!(parameters.growth.segment_length, 50.);
+        assert_eq!(
+            parameters.growth.max_segments,
+            SettlementPreset::WalledCity
+                .parameters()
+                .growth
+                .max_segments
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use futures::executor::block_on;
+use geo::Coordinate;
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    log,
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{CanvasRenderingContext2d, DragEvent, HtmlCanvasElement, ImageBitmap};
+
+use crate::map::{
+    actions::terrain::set::SetTerrain,
+    map::Map,
+    terrain::{Terrain, TerrainShading, TerrainSource},
+};
+
+/// Imported images are scaled down so that their longer side has at most this number of samples.
+const MAXIMUM_IMAGE_SAMPLES: u32 = 256;
+
+/// Adds a terrain below the streets. A left click generates hills from noise, a right click removes the terrain.
+/// A grayscale image that is dropped on the map while the tool is active is imported as heightmap, black is the
+/// lowest point.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GenerateTerrain {
+    #[option(
+        default = 100.,
+        min = 1.,
+        max = 1000.,
+        label = "Relief",
+        description = "Elevation of the highest point"
+    )]
+    relief: f64,
+
+    #[option(
+        default = 400.,
+        min = 20.,
+        max = 5000.,
+        label = "Hill Size",
+        description = "Size of the largest hills of generated terrain"
+    )]
+    scale: f64,
+
+    #[option(
+        default = 4,
+        min = 1,
+        max = 8,
+        label = "Detail",
+        description = "Number of noise layers of generated terrain"
+    )]
+    octaves: usize,
+
+    #[option(
+        label = "Shading",
+        description = "One of hillshade or contours",
+        validator = r"^(hillshade|contours)$"
+    )]
+    shading: String,
+
+    /// Heightmap of an image that was dropped on the map. It is loaded asynchronously and added to the map on
+    /// the next mouse event.
+    #[option(skip)]
+    imported: Rc<RefCell<Option<TerrainSource>>>,
+}
+
+impl GenerateTerrain {
+    fn shading(&self) -> TerrainShading {
+        self.shading.parse().unwrap_or_default()
+    }
+
+    fn set_terrain(&self, terrain: Option<Terrain>, app: &mut App<Map>) {
+        let action = Rc::new(RefCell::new(SetTerrain::new(terrain)));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+    }
+
+    fn add_imported_terrain(&self, app: &mut App<Map>) {
+        let source = self.imported.borrow_mut().take();
+
+        if let Some(source) = source {
+            let map = app.data();
+            let terrain = Terrain::new(
+                source,
+                self.relief,
+                self.shading(),
+                map.width(),
+                map.height(),
+            );
+
+            self.set_terrain(Some(terrain), app);
+        }
+    }
+}
+
+/// Draws `bitmap` scaled down to at most [`MAXIMUM_IMAGE_SAMPLES`] on a canvas and returns the brightness of each
+/// pixel in [0, 1].
+fn heightmap_from_bitmap(bitmap: &ImageBitmap) -> Result<TerrainSource, JsValue> {
+    let factor =
+        (MAXIMUM_IMAGE_SAMPLES as f64 / bitmap.width().max(bitmap.height()) as f64).min(1.);
+    let columns = ((bitmap.width() as f64 * factor) as u32).max(2);
+    let rows = ((bitmap.height() as f64 * factor) as u32).max(2);
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from("no document"))?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+    canvas.set_width(columns);
+    canvas.set_height(rows);
+
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from("no 2d context"))?
+        .dyn_into()?;
+    context.draw_image_with_image_bitmap_and_dw_and_dh(
+        bitmap,
+        0.,
+        0.,
+        columns as f64,
+        rows as f64,
+    )?;
+
+    let pixels = context
+        .get_image_data(0., 0., columns as f64, rows as f64)?
+        .data();
+    let samples = pixels
+        .chunks(4)
+        .map(|pixel| {
+            (0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64) / 255.
+        })
+        .collect();
+
+    Ok(TerrainSource::Image {
+        columns: columns as usize,
+        rows: rows as usize,
+        samples,
+    })
+}
+
+impl Plugin<Map> for GenerateTerrain {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.terrain", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "terrain",
+                        "generate_terrain",
+                        "Generate Terrain".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GenerateTerrain::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn drop(&mut self, event: DragEvent) {
+        let imported = Rc::clone(&self.imported);
+
+        spawn_local(async move {
+            let file = event
+                .data_transfer()
+                .and_then(|transfer| transfer.files())
+                .and_then(|files| files.get(0));
+
+            if let Some(file) = file {
+                let window = web_sys::window().expect("no global `window` exists");
+                let bitmap = match window.create_image_bitmap_with_blob(&file) {
+                    Ok(promise) => JsFuture::from(promise).await,
+                    Err(e) => Err(e),
+                };
+
+                match bitmap
+                    .and_then(|bitmap| bitmap.dyn_into::<ImageBitmap>())
+                    .and_then(|bitmap| heightmap_from_bitmap(&bitmap))
+                {
+                    Ok(source) => *imported.borrow_mut() = Some(source),
+                    Err(e) => log!("the heightmap could not be imported: {:?}", e),
+                }
+            }
+        });
+    }
+
+    fn mouse_move(
+        &mut self,
+        _: Coordinate<f64>,
+        _: Coordinate<f64>,
+        _: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        self.add_imported_terrain(app);
+
+        false
+    }
+
+    fn mouse_up(&mut self, _: Coordinate<f64>, button: mouse::Button, app: &mut App<Map>) -> bool {
+        match button {
+            mouse::Button::Left => {
+                let map = app.data();
+                let terrain = Terrain::new(
+                    TerrainSource::Noise {
+                        seed: rand::random(),
+                        scale: self.scale,
+                        octaves: self.octaves,
+                    },
+                    self.relief,
+                    self.shading(),
+                    map.width(),
+                    map.height(),
+                );
+
+                self.set_terrain(Some(terrain), app);
+            }
+            mouse::Button::Right if app.data().terrain().is_some() => {
+                self.set_terrain(None, app);
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Applies relief and shading to the existing terrain right away.
+    fn property_updated(&mut self, _: &str, editor: &mut App<Map>) {
+        let map = editor.data_mut();
+
+        if let Some(terrain) = map.terrain() {
+            let source = match terrain.source() {
+                TerrainSource::Noise { seed, .. } => TerrainSource::Noise {
+                    seed: *seed,
+                    scale: self.scale,
+                    octaves: self.octaves,
+                },
+                source => source.clone(),
+            };
+            let terrain = Terrain::new(
+                source,
+                self.relief,
+                self.shading(),
+                map.width(),
+                map.height(),
+            );
+
+            map.set_terrain(Some(terrain));
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+
+use futures::executor::block_on;
+use geo::{algorithm::concave_hull::ConcaveHull, Coordinate, Polygon};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::{Action, MultiAction, Redo, Undo},
+    gizmo::Id,
+    input::mouse,
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::{
+    algorithm::fortification::{fortification_layout, FortificationParameters},
+    map::{
+        actions::wall::{create::CreateWall, delete::DeleteWall},
+        map::Map,
+        wall::Wall,
+    },
+};
+
+/// Builds a fortification ring around the settlement. The wall follows the concave hull of all intersections
+/// at a distance, towers are placed at its corners and at regular intervals and each street that crosses the
+/// wall gets a gate. Clicking on an existing wall rebuilds it with the current settings, which adds gates for
+/// streets that were drawn after the wall.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GenerateWalls {
+    #[option(
+        default = 2.,
+        min = 1.,
+        max = 10.,
+        label = "Concavity",
+        description = "Higher values result in a smoother wall"
+    )]
+    concavity: f64,
+
+    #[option(
+        default = 40.,
+        min = 0.,
+        max = 500.,
+        label = "Offset",
+        description = "Distance between the outermost intersections and the wall"
+    )]
+    offset: f64,
+
+    #[option(
+        default = 80.,
+        min = 10.,
+        max = 1000.,
+        label = "Tower Spacing",
+        description = "Maximum distance between two towers"
+    )]
+    tower_spacing: f64,
+
+    #[option(
+        default = 30.,
+        min = 0.,
+        max = 180.,
+        label = "Corner Angle",
+        description = "Minimum turn of the wall in degrees at which a tower is placed"
+    )]
+    corner_angle: f64,
+
+    #[option(
+        default = 8.,
+        min = 1.,
+        max = 50.,
+        label = "Wall Width",
+        description = "Thickness of the wall"
+    )]
+    wall_width: f64,
+
+    #[option(
+        default = 24.,
+        min = 5.,
+        max = 100.,
+        label = "Gate Width",
+        description = "Width of the opening of each gate"
+    )]
+    gate_width: f64,
+
+    #[option(skip)]
+    hovered_wall: Option<Uuid>,
+}
+
+impl GenerateWalls {
+    /// Returns the boundary of the settlement or `None` if the map has too few intersections to enclose an area.
+    fn boundary(&self, map: &Map) -> Option<Polygon<f64>> {
+        if map.intersections().len() < 3 {
+            return None;
+        }
+
+        Some(map.concave_hull(self.concavity))
+    }
+
+    fn wall(&self, map: &Map) -> Option<Wall> {
+        let parameters = FortificationParameters {
+            offset: self.offset,
+            tower_spacing: self.tower_spacing,
+            corner_angle: self.corner_angle.to_radians(),
+            gate_width: self.gate_width,
+        };
+
+        let layout = fortification_layout(
+            &self.boundary(map)?,
+            map.streets().values().map(|street| &street.lines),
+            &parameters,
+        )?;
+
+        Some(Wall::new(
+            layout.ring,
+            layout.towers,
+            layout.gates,
+            self.wall_width,
+            self.gate_width,
+        ))
+    }
+}
+
+/// Adds a wall to the map. If the wall replaces another one, the other wall is removed first.
+pub struct GenerateWallAction {
+    wall: Wall,
+    replaced: Option<Uuid>,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl GenerateWallAction {
+    pub fn new(wall: Wall, replaced: Option<Uuid>) -> Self {
+        GenerateWallAction {
+            wall,
+            replaced,
+            action_stack: MultiAction::new(),
+        }
+    }
+}
+
+impl Undo<Map> for GenerateWallAction {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for GenerateWallAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        if let Some(replaced) = self.replaced {
+            let mut action = DeleteWall::new(replaced);
+            action.execute(map);
+            self.action_stack.push(action);
+        }
+
+        let mut action = CreateWall::new(self.wall.clone());
+        action.execute(map);
+        self.action_stack.push(action);
+    }
+}
+
+impl Action<Map> for GenerateWallAction {}
+
+impl fmt::Display for GenerateWallAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[generate_wall] wall={}\n\u{251C}  {}",
+            self.wall.id(),
+            self.action_stack
+        )
+    }
+}
+
+impl Plugin<Map> for GenerateWalls {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.district", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "fort",
+                        "generate_walls",
+                        "Generate Walls".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GenerateWalls::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        let map = editor.data_mut();
+        if let Some(old_hovered_wall) = self.hovered_wall.take() {
+            if let Some(wall) = map.wall_mut(&old_hovered_wall) {
+                wall.set_state(InteractiveElementState::Normal);
+            }
+        }
+
+        if let Some(hovered_wall) = map.get_wall_at_position(&mouse_pos) {
+            map.wall_mut(&hovered_wall)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_wall = Some(hovered_wall);
+        }
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let wall = match self.wall(app.data()) {
+            Some(wall) => wall,
+            None => return false,
+        };
+
+        let replaced = app.data().get_wall_at_position(&mouse_pos);
+        self.hovered_wall = None;
+
+        let action = Rc::new(RefCell::new(GenerateWallAction::new(wall, replaced)));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::{Coordinate, LineString};
+    use rust_editor::{
+        actions::{Action, Undo},
+        gizmo::Id,
+    };
+
+    use crate::map::{map::Map, wall::Wall};
+
+    use super::GenerateWallAction;
+
+    fn create_wall(offset: f64) -> Wall {
+        Wall::new(
+            LineString::from(vec![
+                (-offset, -offset),
+                (100. + offset, -offset),
+                (100. + offset, 100. + offset),
+                (-offset, -offset),
+            ]),
+            vec![],
+            vec![],
+            8.,
+            20.,
+        )
+    }
+
+    #[test]
+    fn generated_wall_replaces_old_wall() {
+        let mut map = Map::new(1000, 1000);
+        let old = map.add_wall(create_wall(10.));
+        let new = create_wall(20.);
+
+        let mut action = GenerateWallAction::new(new.clone(), Some(old));
+        action.execute(&mut map);
+
+        assert!(map.wall(&old).is_none());
+        assert!(map.wall(&new.id()).is_some());
+        assert_eq!(
+            map.get_wall_at_position(&Coordinate { x: 50., y: -20. }),
+            Some(new.id())
+        );
+    }
+
+    #[test]
+    fn undo_restores_old_wall() {
+        let mut map = Map::new(1000, 1000);
+        let old = map.add_wall(create_wall(10.));
+
+        let mut action = GenerateWallAction::new(create_wall(20.), Some(old));
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert_eq!(map.walls().len(), 1);
+        assert!(map.wall(&old).is_some());
+    }
+}
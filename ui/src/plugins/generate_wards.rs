@@ -0,0 +1,302 @@
+use std::{f64::consts::PI, fmt};
+
+use futures::executor::block_on;
+use geo::{
+    algorithm::concave_hull::ConcaveHull,
+    prelude::{Area, Centroid, Contains},
+    Coordinate, LineString, Polygon,
+};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rust_editor::{
+    actions::{Action, MultiAction, Redo, Undo},
+    input::mouse,
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+
+use crate::{
+    algorithm::voronoi::voronoi_layout,
+    map::{
+        actions::{district::set_ward::SetWard, street::create_streets::CreateStreets},
+        map::Map,
+        street_class::StreetClass,
+        ward::{assign_wards, Ward},
+    },
+};
+
+/// Number of vertices of the circular boundary that is used if the map has no settlement yet.
+const CIRCLE_VERTICES: usize = 32;
+
+/// Generates wards like classic medieval city generators. Sites are scattered within the city boundary, the
+/// edges of their Voronoi cells become streets and the cells become districts with a ward each. A click within
+/// the concave hull of the existing intersections fills the hull, otherwise a circular city is generated around
+/// the clicked position.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GenerateWards {
+    #[option(
+        default = 150.,
+        min = 50.,
+        max = 1000.,
+        label = "Ward Size",
+        description = "Minimum distance between the centers of two wards"
+    )]
+    spacing: f64,
+
+    #[option(
+        default = 2.,
+        min = 1.,
+        max = 10.,
+        label = "Concavity",
+        description = "Higher values result in a smoother city boundary"
+    )]
+    concavity: f64,
+
+    #[option(
+        default = 500.,
+        min = 100.,
+        max = 5000.,
+        label = "Radius",
+        description = "Radius of a new city on an empty part of the map"
+    )]
+    radius: f64,
+
+    #[option(
+        label = "Street Class",
+        description = "One of highway, main_road, street, alley or footpath. Empty for street",
+        validator = StreetClass::VALIDATOR
+    )]
+    street_class: String,
+}
+
+impl GenerateWards {
+    /// Returns the concave hull of the map if it contains `position`, otherwise a circle around `position`.
+    fn boundary(&self, position: &Coordinate<f64>, map: &Map) -> Polygon<f64> {
+        if map.intersections().len() >= 3 {
+            let hull = map.concave_hull(self.concavity);
+            if hull.unsigned_area() > 0. && hull.contains(position) {
+                return hull;
+            }
+        }
+
+        let circle = (0..CIRCLE_VERTICES)
+            .map(|i| {
+                let angle = 2. * PI * i as f64 / CIRCLE_VERTICES as f64;
+
+                *position
+                    + Coordinate {
+                        x: angle.cos(),
+                        y: angle.sin(),
+                    } * self.radius
+            })
+            .collect();
+
+        Polygon::new(LineString(circle), vec![])
+    }
+}
+
+/// Creates the streets between the wards and assigns a ward to the district around each ward center. The
+/// streets are created by a [`CreateStreets`] so that the districts are created by the map.
+pub struct GenerateWardsAction {
+    streets: Vec<LineString<f64>>,
+    class: StreetClass,
+
+    /// The center of each ward and its ward.
+    wards: Vec<(Coordinate<f64>, Ward)>,
+
+    action_stack: MultiAction<Map>,
+}
+
+impl GenerateWardsAction {
+    pub fn new(
+        streets: Vec<LineString<f64>>,
+        class: StreetClass,
+        wards: Vec<(Coordinate<f64>, Ward)>,
+    ) -> Self {
+        GenerateWardsAction {
+            streets,
+            class,
+            wards,
+            action_stack: MultiAction::new(),
+        }
+    }
+
+    fn execute_and_push<A>(&mut self, mut action: A, map: &mut Map)
+    where
+        A: Action<Map> + 'static,
+    {
+        action.execute(map);
+        self.action_stack.push(action);
+    }
+}
+
+impl Undo<Map> for GenerateWardsAction {
+    fn undo(&mut self, map: &mut Map) {
+        self.action_stack.undo(map);
+    }
+}
+
+impl Redo<Map> for GenerateWardsAction {
+    fn redo(&mut self, map: &mut Map) {
+        self.action_stack.clear();
+
+        let streets = self
+            .streets
+            .iter()
+            .map(|street| (street.clone(), self.class))
+            .collect();
+        self.execute_and_push(CreateStreets::new(streets), map);
+
+        for (center, ward) in self.wards.clone() {
+            if let Some(district_id) = map.get_district_at_position(&center) {
+                self.execute_and_push(SetWard::new(district_id, Some(ward)), map);
+            }
+        }
+    }
+}
+
+impl Action<Map> for GenerateWardsAction {}
+
+impl fmt::Display for GenerateWardsAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[generate_wards] class={}, streets={}, wards={}\n\u{251C}  {}",
+            self.class,
+            self.streets.len(),
+            self.wards.len(),
+            self.action_stack
+        )
+    }
+}
+
+impl Plugin<Map> for GenerateWards {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.district", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "hexagon",
+                        "generate_wards",
+                        "Generate Wards".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GenerateWards::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let boundary = self.boundary(&mouse_pos, app.data());
+        let seed: u64 = rand::random();
+        let layout = voronoi_layout(&boundary, self.spacing, seed);
+
+        let centers: Vec<(Coordinate<f64>, bool)> = layout
+            .cells
+            .iter()
+            .map(|cell| (cell.site, cell.at_boundary))
+            .collect();
+        let center = boundary
+            .centroid()
+            .map_or(mouse_pos, |centroid| centroid.into());
+        let wards = assign_wards(&centers, center, &mut ChaCha8Rng::seed_from_u64(seed));
+
+        let action = Rc::new(RefCell::new(GenerateWardsAction::new(
+            layout.edges,
+            self.street_class.parse().unwrap_or_default(),
+            centers
+                .into_iter()
+                .map(|(center, _)| center)
+                .zip(wards)
+                .collect(),
+        )));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::{Coordinate, LineString, Polygon};
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::{
+        algorithm::voronoi::voronoi_layout,
+        map::{map::Map, street_class::StreetClass, ward::Ward},
+    };
+
+    use super::GenerateWardsAction;
+
+    fn create_action() -> GenerateWardsAction {
+        let boundary = Polygon::new(
+            LineString::from(vec![(0., 0.), (600., 0.), (600., 600.), (0., 600.)]),
+            vec![],
+        );
+        let layout = voronoi_layout(&boundary, 200., 3);
+        let wards = layout
+            .cells
+            .iter()
+            .map(|cell| (cell.site, Ward::Craftsmen))
+            .collect();
+
+        GenerateWardsAction::new(layout.edges, StreetClass::Street, wards)
+    }
+
+    #[test]
+    fn wards_become_districts() {
+        let mut map = Map::new(1000, 1000);
+        create_action().execute(&mut map);
+
+        let district_id = map
+            .get_district_at_position(&Coordinate { x: 300., y: 300. })
+            .unwrap();
+        assert_eq!(
+            map.district(&district_id).unwrap().ward(),
+            Some(Ward::Craftsmen)
+        );
+    }
+
+    #[test]
+    fn undo_removes_wards() {
+        let mut map = Map::new(1000, 1000);
+        let mut action = create_action();
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map.districts().is_empty());
+    }
+}
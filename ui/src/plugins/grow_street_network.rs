@@ -0,0 +1,213 @@
+use futures::executor::block_on;
+use geo::Coordinate;
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+
+use crate::{
+    algorithm::growth::{grow_street_network, GrowthParameters},
+    map::{actions::street::create_streets::CreateStreets, map::Map, street_class::StreetClass},
+};
+
+/// Grows a street network from the clicked position. Each click uses a new random seed.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct GrowStreetNetwork {
+    #[option(
+        default = 80.,
+        min = 10.,
+        max = 500.,
+        label = "Segment Length",
+        description = "Average length of a street segment"
+    )]
+    segment_length: f64,
+
+    #[option(
+        default = 90.,
+        min = 0.,
+        max = 180.,
+        label = "Branching Angle",
+        description = "Angle in degrees between a street and its branches"
+    )]
+    branching_angle: f64,
+
+    #[option(
+        default = 0.3,
+        min = 0.,
+        max = 1.,
+        label = "Density",
+        description = "Probability that a street branches off at the end of each segment"
+    )]
+    density: f64,
+
+    #[option(
+        default = 10.,
+        min = 0.,
+        max = 45.,
+        label = "Deviation",
+        description = "Maximum change of direction in degrees between two segments"
+    )]
+    deviation: f64,
+
+    #[option(
+        default = 600.,
+        min = 50.,
+        max = 5000.,
+        label = "Radius",
+        description = "Maximum distance of the streets from the clicked position"
+    )]
+    radius: f64,
+
+    #[option(default = 200, min = 1, max = 2000, label = "Maximum Segments")]
+    max_segments: usize,
+
+    #[option(
+        default = 0.3,
+        min = 0.01,
+        max = 10.,
+        label = "Maximum Slope",
+        description = "Maximum rise of the terrain per unit of street length, streets turn away from steeper slopes"
+    )]
+    max_slope: f64,
+
+    #[option(
+        label = "Street Class",
+        description = "One of highway, main_road, street, alley or footpath. Empty for street",
+        validator = StreetClass::VALIDATOR
+    )]
+    street_class: String,
+}
+
+impl GrowStreetNetwork {
+    fn parameters(&self) -> GrowthParameters {
+        GrowthParameters {
+            segment_length: self.segment_length,
+            branching_angle: self.branching_angle,
+            density: self.density,
+            deviation: self.deviation,
+            radius: self.radius,
+            max_segments: self.max_segments,
+            max_slope: self.max_slope,
+        }
+    }
+}
+
+impl Plugin<Map> for GrowStreetNetwork {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "account_tree",
+                        "grow_street_network",
+                        "Grow Street Network".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(GrowStreetNetwork::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let map = app.data();
+        let streets = grow_street_network(
+            mouse_pos,
+            &self.parameters(),
+            |position| map.elevation_at(position),
+            rand::random(),
+        );
+        let class = self.street_class.parse().unwrap_or_default();
+        let streets = streets.into_iter().map(|street| (street, class)).collect();
+
+        let action = Rc::new(RefCell::new(CreateStreets::new(streets)));
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::Coordinate;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::{
+        algorithm::growth::{grow_street_network, GrowthParameters},
+        map::{
+            actions::street::create_streets::CreateStreets, map::Map, street_class::StreetClass,
+        },
+    };
+
+    fn grow(map: &mut Map) -> CreateStreets {
+        let parameters = GrowthParameters {
+            max_segments: 30,
+            radius: 400.,
+            ..Default::default()
+        };
+        let streets = grow_street_network(Coordinate { x: 500., y: 500. }, &parameters, |_| 0., 42);
+
+        let mut action = CreateStreets::new(
+            streets
+                .into_iter()
+                .map(|street| (street, StreetClass::Street))
+                .collect(),
+        );
+        action.execute(map);
+
+        action
+    }
+
+    #[test]
+    fn grown_network_is_added_to_map() {
+        let mut map = Map::new(1000, 1000);
+        grow(&mut map);
+
+        assert!(map.streets().len() > 2);
+        assert!(!map.intersections().is_empty());
+    }
+
+    #[test]
+    fn undo_removes_grown_network() {
+        let mut map = Map::new(1000, 1000);
+        let mut action = grow(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map.intersections().is_empty());
+        assert!(map.districts().is_empty());
+    }
+}
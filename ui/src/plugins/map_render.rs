@@ -1,25 +1,61 @@
-use rust_editor::plugin::Plugin;
+use rust_editor::{plugin::Plugin, renderer::Canvas};
 use rust_macro::editor_plugin;
-use web_sys::CanvasRenderingContext2d;
 
-use crate::map::map::Map;
+use crate::map::{map::Map, street::Street};
 
 #[editor_plugin(skip, specific_to=Map)]
 pub struct MapRender {}
 
 impl Plugin<Map> for MapRender {
-    fn render(&self, context: &CanvasRenderingContext2d, editor: &App<Map>) {
+    fn render(&self, context: &mut dyn Canvas, editor: &App<Map>) {
         let data = editor.data();
+
+        // The terrain is the ground everything else stands on
+        if let Some(terrain) = data.terrain() {
+            terrain.render(context);
+        }
+
+        for (_, water) in data.waters() {
+            water.render(context).unwrap();
+        }
+
         for (_, district) in data.districts() {
             district.render(context).unwrap();
         }
 
-        for (_, street) in data.streets() {
+        // Bridges are rendered below the streets so that only their parapets are visible
+        for bridge in data.bridges() {
+            bridge.render(context).unwrap();
+        }
+
+        // Narrow streets are rendered first so that wider ones are drawn on top of them
+        let mut streets: Vec<&Street> = data.streets().values().collect();
+        streets.sort_by_key(|street| street.class().render_order());
+
+        for street in streets {
             street.render(context).unwrap();
         }
 
         for (_, intersection) in data.intersections() {
-            intersection.render(&context).unwrap();
+            intersection.render(context).unwrap();
+        }
+
+        for (_, wall) in data.walls() {
+            wall.render(context).unwrap();
+        }
+
+        // Labels are rendered last so that no street or district covers them
+        for (_, district) in data.districts() {
+            district.render_label(context).unwrap();
+        }
+
+        for (_, street) in data.streets() {
+            street.render_label(context).unwrap();
+        }
+
+        // Landmarks carry their own names and are rendered on top of all other labels
+        for (_, landmark) in data.landmarks() {
+            landmark.render(context).unwrap();
         }
 
         /*
@@ -1,8 +1,23 @@
 pub mod create_freeform_street;
 pub mod delete_street;
+pub mod edit_street_curve;
+pub mod edit_street_vertices;
+pub mod grow_street_network;
+pub mod tensor_field_roads;
 pub mod create_district;
 pub mod delete_district;
+pub mod reroll_district;
+pub mod inspect_district;
+pub mod generate_roads;
+pub mod generate_settlement;
+pub mod generate_terrain;
+pub mod generate_wards;
+pub mod generate_walls;
+pub mod draw_water;
+pub mod fit_view;
 pub mod map_render;
+pub mod name_elements;
+pub mod place_landmarks;
 pub mod new;
 pub mod save;
 pub mod load;
@@ -10,4 +25,5 @@ pub mod settings;
 pub mod debug;
 pub mod reference_image;
 pub mod test_data;
-pub mod sync;
\ No newline at end of file
+pub mod sync;
+pub mod validation;
\ No newline at end of file
@@ -0,0 +1,147 @@
+use geo::Coordinate;
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::map::{
+    actions::{district::rename::RenameDistrict, street::rename::RenameStreet},
+    map::Map,
+};
+
+/// Names streets and districts. A click on a street or a district gives it the name from the options,
+/// streets take precedence over the district below them.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct NameElements {
+    #[option(
+        label = "Name",
+        description = "Name of the clicked street or district, an empty name removes it"
+    )]
+    name: String,
+
+    #[option(skip)]
+    hovered_street: Option<Uuid>,
+
+    #[option(skip)]
+    hovered_district: Option<Uuid>,
+}
+
+impl NameElements {
+    fn name(&self) -> Option<String> {
+        let name = self.name.trim();
+
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    fn reset_hover(&mut self, map: &mut Map) {
+        if let Some(street) = self
+            .hovered_street
+            .take()
+            .and_then(|id| map.street_mut(&id))
+        {
+            street.set_state(InteractiveElementState::Normal);
+        }
+
+        if let Some(district) = self
+            .hovered_district
+            .take()
+            .and_then(|id| map.district_mut(&id))
+        {
+            district.set_state(InteractiveElementState::Normal);
+        }
+    }
+}
+
+impl Plugin<Map> for NameElements {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                for (toolbar, id) in [
+                    ("primary.edit.modes.street", "name_streets"),
+                    ("primary.edit.modes.district", "name_districts"),
+                ] {
+                    let toolbar = toolbar_plugin
+                        .get_or_add_toolbar(toolbar, ToolbarPosition::Left)
+                        .unwrap();
+
+                    let enabled = Rc::clone(&self.__enabled);
+                    toolbar
+                        .add_toggle_button(
+                            "label",
+                            id,
+                            "Name Streets and Districts".to_string(),
+                            move || *enabled.as_ref().borrow(),
+                            move || EditorMessages::ActivatePlugin(NameElements::identifier()),
+                        )
+                        .unwrap();
+                }
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        let map = editor.data_mut();
+        self.reset_hover(map);
+
+        if let Some(street) = map.get_street_at_position(&mouse_pos, &vec![]) {
+            map.street_mut(&street)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_street = Some(street);
+        } else if let Some(district) = map.get_district_at_position(&mouse_pos) {
+            map.district_mut(&district)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_district = Some(district);
+        }
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        let map = app.data();
+        let action: Rc<RefCell<dyn Action<Map>>> =
+            if let Some(street) = map.get_street_at_position(&mouse_pos, &vec![]) {
+                Rc::new(RefCell::new(RenameStreet::new(street, self.name())))
+            } else if let Some(district) = map.get_district_at_position(&mouse_pos) {
+                Rc::new(RefCell::new(RenameDistrict::new(district, self.name())))
+            } else {
+                return false;
+            };
+
+        action.borrow_mut().execute(app.data_mut());
+
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push_generic(Rc::clone(&action));
+        });
+
+        false
+    }
+}
@@ -37,12 +37,13 @@ impl Plugin<Map> for New {
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, ctx: &Context<App<Map>>, editor: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::N] {
             let mut dialog_visible = self.dialog_visible.borrow_mut();
             if *dialog_visible {
                 let map = editor.data_mut();
                 map.clear();
+                map.set_seed(rand::random());
 
                 editor.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
                     redo.clear();
@@ -54,7 +55,7 @@ impl Plugin<Map> for New {
             }
 
             *dialog_visible = !*dialog_visible;
-            ctx.link().send_message(EditorMessages::UpdateElements());
+            editor.send_message(EditorMessages::UpdateElements());
         }
     }
 
@@ -84,40 +85,54 @@ impl Plugin<Map> for New {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use rust_editor::input::keyboard::Key;
-    use rust_editor::plugins::plugin::Plugin;
-    use rust_editor::ui::app::App;
-    use rust_editor::ui::toolbar::ToolbarPosition;
+    use geo::LineString;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{input::keyboard::Key, ui::headless::HeadlessApp};
 
-    use crate::map::map::Map;
-    use crate::plugins::new::New;
+    use crate::map::{map::Map, street::Street};
 
-    #[test]
-    fn integration_startup_adds_shortcut() {
-        let mut app = App::<Map>::default();
+    use super::New;
 
-        let mut plugin = New::default();
-        plugin.startup(&mut app).unwrap();
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(New::default()).unwrap();
 
-        assert!(app.has_shortkey(vec![Key::Ctrl, Key::N]))
+        app
     }
 
     #[test]
+    fn integration_startup_adds_shortcut() {
+        let app = app();
+
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::N]));
+    }
 
+    #[test]
     fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+        let app = app();
+
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.actions", "new");
+        });
 
-        let mut plugin = New::default();
-        plugin.startup(&mut app).unwrap();
+        assert!(has_button);
+    }
+
+    #[test]
+    fn integration_second_shortcut_clears_map() {
+        let mut app = app();
+        let street = Street::new(LineString::from(vec![(100., 100.), (300., 100.)]));
+        app.app_mut().data_mut().add_street(&street);
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
-            .unwrap();
+        // The first press only opens the dialog, the second one discards the map
+        app.press_keys(&[Key::Ctrl, Key::N]);
+        assert_eq!(app.data().streets().len(), 1);
 
-        assert!(toolbar.has_button("new"));
+        app.press_keys(&[Key::Ctrl, Key::N]);
+        assert!(app.data().streets().is_empty());
     }
 }
-*/
\ No newline at end of file
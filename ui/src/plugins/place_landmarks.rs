@@ -0,0 +1,401 @@
+use futures::executor::block_on;
+use geo::Coordinate;
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    gizmo::{GetPosition, Gizmo, Id, MoveGizmo, SetPosition},
+    input::{keyboard::Key, mouse},
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    renderer::Canvas,
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::map::{
+    actions::landmark::{
+        create::CreateLandmark, delete::DeleteLandmark, edit::EditLandmark,
+        move_landmark::MoveLandmark,
+    },
+    landmark::{Landmark, LandmarkKind},
+    map::Map,
+};
+
+/// The selected landmark, moved by the gizmo.
+struct Handle {
+    landmark_id: Uuid,
+    position: Coordinate<f64>,
+}
+
+impl Id for Handle {
+    fn id(&self) -> Uuid {
+        self.landmark_id
+    }
+}
+
+impl GetPosition for Handle {
+    fn position(&self) -> Coordinate<f64> {
+        self.position
+    }
+}
+
+impl SetPosition for Handle {
+    fn set_position(&mut self, position: Coordinate<f64>) {
+        self.position = position;
+    }
+}
+
+/// Places temples, taverns, castles, wells and gates on the map. A click on an empty spot places a landmark
+/// with the options, a click on a landmark selects it so that it can be dragged with the gizmo, a click on the
+/// selected landmark applies the options to it and delete removes the selected landmark.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct PlaceLandmarks {
+    #[option(
+        label = "Kind",
+        description = "One of temple, tavern, castle, well or gate",
+        validator = r"^(temple|tavern|castle|well|gate)$"
+    )]
+    kind: String,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 360.,
+        label = "Rotation",
+        description = "Clockwise rotation of the icon in degrees"
+    )]
+    rotation: f64,
+
+    #[option(
+        label = "Name",
+        description = "Name shown below the landmark, an empty name removes it"
+    )]
+    name: String,
+
+    #[option(label = "Notes", description = "Free text notes for the game master")]
+    notes: String,
+
+    #[option(skip)]
+    hovered_landmark: Option<Uuid>,
+
+    #[option(skip)]
+    selected_landmark: Option<Handle>,
+
+    /// Position of the selected landmark when the current drag started.
+    #[option(skip)]
+    drag_start: Option<Coordinate<f64>>,
+
+    #[option(skip)]
+    gizmo: MoveGizmo,
+}
+
+impl PlaceLandmarks {
+    fn kind(&self) -> LandmarkKind {
+        self.kind.parse().unwrap_or_default()
+    }
+
+    fn name(&self) -> Option<String> {
+        let name = self.name.trim();
+
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    fn set_state(map: &mut Map, id: Option<Uuid>, state: InteractiveElementState) {
+        if let Some(id) = id {
+            map.update_landmark(&id, |landmark| landmark.set_state(state));
+        }
+    }
+
+    fn select(&mut self, landmark_id: Option<Uuid>, map: &mut Map) {
+        let old_selected = self
+            .selected_landmark
+            .take()
+            .map(|handle| handle.landmark_id);
+        PlaceLandmarks::set_state(map, old_selected, InteractiveElementState::Normal);
+        PlaceLandmarks::set_state(map, landmark_id, InteractiveElementState::Selected);
+
+        self.selected_landmark = landmark_id
+            .and_then(|id| map.landmark(&id))
+            .map(|landmark| Handle {
+                landmark_id: landmark.id(),
+                position: landmark.position(),
+            });
+
+        if let Some(handle) = &self.selected_landmark {
+            self.gizmo.set_position(handle.position);
+        }
+    }
+
+    fn execute<A: Action<Map> + 'static>(&self, action: A, app: &mut App<Map>) {
+        let action = Rc::new(RefCell::new(action));
+        action.borrow_mut().execute(app.data_mut());
+
+        PlaceLandmarks::record(action, app);
+    }
+
+    /// Records an action whose changes are already applied to the map.
+    fn record<A: Action<Map> + 'static>(action: Rc<RefCell<A>>, app: &mut App<Map>) {
+        app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+            redo.clear();
+        });
+
+        app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+            undo.push(Rc::clone(&action));
+        });
+
+        let cloned_data = app.data().clone();
+        app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+            block_on(sync.send(cloned_data.clone()));
+        });
+    }
+}
+
+impl Plugin<Map> for PlaceLandmarks {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.landmark", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "place",
+                        "place_landmarks",
+                        "Place Landmarks".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(PlaceLandmarks::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn deactivated(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        let map = editor.data_mut();
+        PlaceLandmarks::set_state(
+            map,
+            self.hovered_landmark.take(),
+            InteractiveElementState::Normal,
+        );
+        self.select(None, map);
+        self.drag_start = None;
+
+        Ok(())
+    }
+
+    fn mouse_down(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        _: &App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(handle) = &self.selected_landmark {
+            let start = handle.position;
+
+            self.gizmo
+                .mouse_down(mouse_pos, button as u32, self.selected_landmark.iter_mut());
+            if self.gizmo.is_active() {
+                self.drag_start = Some(start);
+            }
+        }
+
+        false
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        let map = editor.data_mut();
+
+        if self.drag_start.is_some() {
+            self.gizmo
+                .mouse_move(mouse_pos, self.selected_landmark.iter_mut());
+
+            // The landmark follows the gizmo while it is dragged, the move is recorded as a single action when
+            // the drag ends
+            if let Some(handle) = &self.selected_landmark {
+                let position = handle.position;
+                map.update_landmark(&handle.landmark_id, |landmark| {
+                    landmark.set_position(position)
+                });
+            }
+
+            return false;
+        }
+
+        let selected = self
+            .selected_landmark
+            .as_ref()
+            .map(|handle| handle.landmark_id);
+        let hovered = map
+            .get_landmark_at_position(&mouse_pos)
+            .filter(|id| Some(*id) != selected);
+
+        PlaceLandmarks::set_state(
+            map,
+            self.hovered_landmark.take(),
+            InteractiveElementState::Normal,
+        );
+        PlaceLandmarks::set_state(map, hovered, InteractiveElementState::Hover);
+        self.hovered_landmark = hovered;
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(from) = self.drag_start.take() {
+            self.gizmo
+                .mouse_up(mouse_pos, button as u32, self.selected_landmark.iter_mut());
+
+            // The map already contains the moved landmark, the action only has to be recorded
+            if let Some(handle) = self
+                .selected_landmark
+                .as_ref()
+                .filter(|handle| handle.position != from)
+            {
+                let action = MoveLandmark::new(handle.landmark_id, from, handle.position);
+                PlaceLandmarks::record(Rc::new(RefCell::new(action)), app);
+            }
+
+            return false;
+        }
+
+        let selected = self
+            .selected_landmark
+            .as_ref()
+            .map(|handle| handle.landmark_id);
+        match (app.data().get_landmark_at_position(&mouse_pos), selected) {
+            (Some(landmark), Some(selected)) if landmark == selected => {
+                let action = EditLandmark::new(
+                    landmark,
+                    self.kind(),
+                    self.rotation,
+                    self.name(),
+                    self.notes.clone(),
+                );
+                self.execute(action, app);
+            }
+            (Some(landmark), _) => {
+                self.hovered_landmark = None;
+                self.select(Some(landmark), app.data_mut());
+            }
+            (None, Some(_)) => self.select(None, app.data_mut()),
+            (None, None) => {
+                let mut landmark = Landmark::new(self.kind(), mouse_pos);
+                landmark.set_rotation(self.rotation);
+                landmark.set_name(self.name());
+                landmark.set_notes(self.notes.clone());
+
+                self.execute(CreateLandmark::new(landmark), app);
+            }
+        }
+
+        false
+    }
+
+    fn key_down(&mut self, key: Key, app: &mut App<Map>) {
+        if key != Key::Delete && key != Key::Backspace {
+            return;
+        }
+
+        // Deselected first so that undo restores the landmark in its normal state
+        if let Some(landmark) = self
+            .selected_landmark
+            .as_ref()
+            .map(|handle| handle.landmark_id)
+        {
+            self.select(None, app.data_mut());
+            self.drag_start = None;
+            self.execute(DeleteLandmark::new(landmark), app);
+        }
+    }
+
+    fn render(&self, context: &mut dyn Canvas, _: &App<Map>) {
+        self.gizmo
+            .render(context, self.selected_landmark.iter())
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use plugin_undo_redo::{Redo, Undo};
+    use rust_editor::{
+        gizmo::GetPosition,
+        input::{keyboard::Key, mouse},
+        ui::headless::HeadlessApp,
+    };
+
+    use crate::{
+        map::{landmark::LandmarkKind, map::Map},
+        plugins::map_render::MapRender,
+    };
+
+    use super::PlaceLandmarks;
+
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(Undo::<Map>::default()).unwrap();
+        app.add_plugin(Redo::<Map>::default()).unwrap();
+        app.add_plugin(MapRender::default()).unwrap();
+        app.add_plugin(PlaceLandmarks::default()).unwrap();
+
+        app
+    }
+
+    #[test]
+    fn integration_click_places_landmark_and_undo_removes_it() {
+        let mut app = app();
+        let position = Coordinate { x: 100., y: 100. };
+
+        app.click(position, mouse::Button::Left);
+
+        let landmarks: Vec<_> = app.data().landmarks().values().collect();
+        assert_eq!(landmarks.len(), 1);
+        assert_eq!(landmarks[0].position(), position);
+        assert!(app
+            .render()
+            .texts()
+            .contains(&LandmarkKind::default().icon()));
+
+        app.press_keys(&[Key::Ctrl, Key::Z]);
+        assert!(app.data().landmarks().is_empty());
+    }
+
+    #[test]
+    fn integration_selected_landmark_is_deleted() {
+        let mut app = app();
+        let position = Coordinate { x: 100., y: 100. };
+
+        // The first click places the landmark and the second one selects it
+        app.click(position, mouse::Button::Left);
+        app.click(position, mouse::Button::Left);
+        assert_eq!(app.data().landmarks().len(), 1);
+
+        app.press_keys(&[Key::Delete]);
+        assert!(app.data().landmarks().is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use geo::Coordinate;
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::mouse,
+    interactive_element::{InteractiveElement, InteractiveElementState},
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+use uuid::Uuid;
+
+use crate::map::{actions::district::reroll::RerollDistrict as ActionRerollDistrict, map::Map};
+
+#[editor_plugin(skip, specific_to=Map, execution=Exclusive)]
+pub struct RerollDistrict {
+    #[option(skip)]
+    hovered_district: Option<Uuid>,
+}
+
+impl Plugin<Map> for RerollDistrict {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.district", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "casino",
+                        "reroll_district",
+                        "Reroll District".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(RerollDistrict::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_move(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        _mouse_movement: Coordinate<f64>,
+        _: mouse::Button,
+        editor: &mut App<Map>,
+    ) -> bool {
+        let map = editor.data_mut();
+        if let Some(old_hovered_district) = self.hovered_district.take() {
+            if let Some(district) = map.district_mut(&old_hovered_district) {
+                district.set_state(InteractiveElementState::Normal);
+            }
+        }
+
+        if let Some(hovered_district) = map.get_district_at_position(&mouse_pos) {
+            map.district_mut(&hovered_district)
+                .unwrap()
+                .set_state(InteractiveElementState::Hover);
+            self.hovered_district = Some(hovered_district);
+        }
+
+        false
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(district) = app.data().get_district_at_position(&mouse_pos) {
+            let action = Rc::new(RefCell::new(ActionRerollDistrict::new(district)));
+            action.as_ref().borrow_mut().execute(app.data_mut());
+
+            app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+                redo.clear();
+            });
+
+            app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+                undo.push(Rc::clone(&action));
+            });
+        }
+
+        false
+    }
+}
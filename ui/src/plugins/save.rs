@@ -37,7 +37,7 @@ impl Plugin<Map> for Save {
         Ok(())
     }
 
-    fn shortkey_pressed(&mut self, key: &Shortkey, _: &Context<App<Map>>, editor: &mut App<Map>) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::S] {
             if let Some(store) = Store::new("map_editor") {
                 store.sync_local_storage(editor.data()).unwrap();
@@ -46,40 +46,39 @@ impl Plugin<Map> for Save {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use rust_editor::input::keyboard::Key;
-    use rust_editor::plugins::plugin::Plugin;
-    use rust_editor::ui::app::App;
-    use rust_editor::ui::toolbar::ToolbarPosition;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{input::keyboard::Key, ui::headless::HeadlessApp};
 
     use crate::map::map::Map;
-    use crate::plugins::save::Save;
 
-    #[test]
-    fn integration_startup_adds_shortcut() {
-        let mut app = App::<Map>::default();
+    use super::Save;
 
-        let mut plugin = Save::default();
-        plugin.startup(&mut app).unwrap();
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(Save::default()).unwrap();
 
-        assert!(app.has_shortkey(vec![Key::Ctrl, Key::S]))
+        app
     }
 
     #[test]
+    fn integration_startup_adds_shortcut() {
+        let app = app();
 
-    fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::S]));
+    }
 
-        let mut plugin = Save::default();
-        plugin.startup(&mut app).unwrap();
+    #[test]
+    fn integration_startup_adds_toolbar_button() {
+        let app = app();
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
-            .unwrap();
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.actions", "save");
+        });
 
-        assert!(toolbar.has_button("save"));
+        assert!(has_button);
     }
 }
-*/
\ No newline at end of file
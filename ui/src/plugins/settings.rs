@@ -44,12 +44,7 @@ impl Plugin<Map> for Settings {
         Ok(())
     }
 
-    fn shortkey_pressed(
-        &mut self,
-        key: &Shortkey,
-        _ctx: &Context<App<Map>>,
-        _editor: &mut App<Map>,
-    ) {
+    fn shortkey_pressed(&mut self, key: &Shortkey, _editor: &mut App<Map>) {
         if *key == vec![Key::Ctrl, Key::M] {
             let mut visible = self.visible.borrow_mut();
             *visible = !(*visible);
@@ -85,28 +80,39 @@ impl Plugin<Map> for Settings {
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use rust_editor::plugins::plugin::Plugin;
-    use rust_editor::ui::app::App;
-    use rust_editor::ui::toolbar::ToolbarPosition;
+    use plugin_toolbar::ToolbarPlugin;
+    use rust_editor::{input::keyboard::Key, ui::headless::HeadlessApp};
 
     use crate::map::map::Map;
-    use crate::plugins::settings::Settings;
+
+    use super::Settings;
+
+    fn app() -> HeadlessApp<Map> {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(ToolbarPlugin::<Map>::default()).unwrap();
+        app.add_plugin(Settings::default()).unwrap();
+
+        app
+    }
 
     #[test]
-    fn integration_startup_adds_toolbar_button() {
-        let mut app = App::<Map>::default();
+    fn integration_startup_adds_shortcut() {
+        let app = app();
 
-        let mut plugin = Settings::default();
-        plugin.startup(&mut app).unwrap();
+        assert!(app.app().has_shortkey(vec![Key::Ctrl, Key::M]));
+    }
 
-        let toolbar = app
-            .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
-            .unwrap();
+    #[test]
+    fn integration_startup_adds_toolbar_button() {
+        let app = app();
+
+        let mut has_button = false;
+        app.app().plugin(|toolbar: &ToolbarPlugin<Map>| {
+            has_button = toolbar.has_button("primary.actions", "settings");
+        });
 
-        assert!(toolbar.has_button("settings"));
+        assert!(has_button);
     }
 }
-*/
\ No newline at end of file
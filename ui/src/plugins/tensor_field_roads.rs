@@ -0,0 +1,337 @@
+use futures::executor::block_on;
+use geo::{simplify::Simplify, Coordinate, Line, LineString};
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    actions::Action,
+    input::{keyboard::Key, mouse},
+    plugin::{Plugin, PluginWithOptions},
+    renderer::{Canvas, PrimitiveRenderer},
+    style::Style,
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+
+use crate::{
+    algorithm::tensor_field::{
+        trace_hyperstreamlines, BasisField, BasisFieldKind, Direction, StreamlineParameters,
+        TensorField,
+    },
+    map::{actions::street::create_streets::CreateStreets, map::Map, street_class::StreetClass},
+};
+
+/// Distance between two points of a traced streamline.
+const STREAMLINE_STEP: f64 = 5.;
+
+/// Traced streamlines are simplified by this factor before they are turned into streets.
+const STREAMLINE_SIMPLIFICATION: f64 = 1.;
+
+/// Distance between the glyphs that preview the directions of the tensor field.
+const PREVIEW_SPACING: f64 = 40.;
+
+/// Lays out roads along the hyperstreamlines of a tensor field. A click places a basis field of the selected
+/// kind, boundary fields are placed on an existing street and follow it. Space traces the major streamlines
+/// into main roads and the minor streamlines into streets, delete removes the last placed field and escape
+/// removes all of them.
+#[editor_plugin(specific_to=Map, execution=Exclusive)]
+pub struct TensorFieldRoads {
+    #[option(
+        label = "Basis Field",
+        description = "One of grid, radial or boundary",
+        validator = r"^(grid|radial|boundary)$"
+    )]
+    basis_field: String,
+
+    #[option(
+        default = 400.,
+        min = 50.,
+        max = 5000.,
+        label = "Size",
+        description = "Distance at which the influence of the field has dropped by the decay"
+    )]
+    size: f64,
+
+    #[option(default = 1., min = 0., max = 10., label = "Decay")]
+    decay: f64,
+
+    #[option(default = 1., min = 0., max = 10., label = "Weight")]
+    weight: f64,
+
+    #[option(
+        default = 0.,
+        min = 0.,
+        max = 180.,
+        label = "Grid Angle",
+        description = "Direction of the main roads of a grid field in degrees"
+    )]
+    grid_angle: f64,
+
+    #[option(default = 150., min = 20., max = 1000., label = "Main Road Separation")]
+    major_separation: f64,
+
+    #[option(default = 100., min = 20., max = 1000., label = "Street Separation")]
+    minor_separation: f64,
+
+    #[option(skip)]
+    field: TensorField,
+}
+
+impl TensorFieldRoads {
+    fn basis_field_kind(&self, position: &Coordinate<f64>, map: &Map) -> Option<BasisFieldKind> {
+        match self.basis_field.as_str() {
+            "radial" => Some(BasisFieldKind::Radial),
+            "boundary" => {
+                let street_id = map.get_street_at_position(position, &vec![])?;
+                let line_string = map.street(&street_id)?.lines.clone();
+
+                Some(BasisFieldKind::Boundary { line_string })
+            }
+            _ => Some(BasisFieldKind::Grid {
+                angle: self.grid_angle.to_radians(),
+            }),
+        }
+    }
+
+    /// Traces the streamlines of the current field and returns them as streets.
+    fn streets(&self) -> Vec<(LineString<f64>, StreetClass)> {
+        let bounds = match self.field.bounds() {
+            Some(bounds) => bounds,
+            None => return vec![],
+        };
+
+        let parameters = StreamlineParameters {
+            major_separation: self.major_separation,
+            minor_separation: self.minor_separation,
+            step: STREAMLINE_STEP,
+            minimum_length: self.minor_separation.min(self.major_separation) / 2.,
+            bounds,
+        };
+
+        trace_hyperstreamlines(&self.field, &parameters, rand::random())
+            .into_iter()
+            .flat_map(|streamline| {
+                let class = match streamline.direction {
+                    Direction::Major => StreetClass::MainRoad,
+                    Direction::Minor => StreetClass::Street,
+                };
+
+                split_closed(streamline.line_string.simplify(&STREAMLINE_SIMPLIFICATION))
+                    .into_iter()
+                    .map(move |line_string| (line_string, class))
+            })
+            .collect()
+    }
+}
+
+/// Splits a closed line string into two halves, as a street must connect two different intersections.
+fn split_closed(line_string: LineString<f64>) -> Vec<LineString<f64>> {
+    if !line_string.is_closed() || line_string.0.len() < 4 {
+        return vec![line_string];
+    }
+
+    let middle = line_string.0.len() / 2;
+
+    vec![
+        LineString(line_string.0[..=middle].to_vec()),
+        LineString(line_string.0[middle..].to_vec()),
+    ]
+}
+
+impl Plugin<Map> for TensorFieldRoads {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.edit.modes.street", ToolbarPosition::Left)
+                    .unwrap();
+
+                let enabled = Rc::clone(&self.__enabled);
+                toolbar
+                    .add_toggle_button(
+                        "grid_4x4",
+                        "tensor_field_roads",
+                        "Tensor Field Roads".to_string(),
+                        move || *enabled.as_ref().borrow(),
+                        move || EditorMessages::ActivatePlugin(TensorFieldRoads::identifier()),
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn mouse_up(
+        &mut self,
+        mouse_pos: Coordinate<f64>,
+        button: mouse::Button,
+        app: &mut App<Map>,
+    ) -> bool {
+        if button != mouse::Button::Left {
+            return false;
+        }
+
+        if let Some(kind) = self.basis_field_kind(&mouse_pos, app.data()) {
+            let mut field = BasisField::new(kind, mouse_pos, self.size);
+            field.decay = self.decay;
+            field.weight = self.weight;
+
+            self.field.add_field(field);
+        }
+
+        false
+    }
+
+    fn key_down(&mut self, key: Key, app: &mut App<Map>) {
+        match key {
+            Key::Delete | Key::Backspace => {
+                let last = self.field.fields().len().saturating_sub(1);
+                self.field.remove_field(last);
+            }
+            Key::Escape => self.field = TensorField::new(),
+            Key::Space => {
+                let streets = self.streets();
+                if streets.is_empty() {
+                    return;
+                }
+
+                let action = Rc::new(RefCell::new(CreateStreets::new(streets)));
+                action.borrow_mut().execute(app.data_mut());
+
+                app.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+                    redo.clear();
+                });
+
+                app.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+                    undo.push(Rc::clone(&action));
+                });
+
+                let cloned_data = app.data().clone();
+                app.plugin_mut(move |sync: &mut crate::plugins::sync::Sync| {
+                    block_on(sync.send(cloned_data.clone()));
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, context: &mut dyn Canvas, _: &App<Map>) {
+        let bounds = match self.field.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let major_style = Style {
+            border_width: 2,
+            border_color: "#FFFFFF".to_string(),
+            background_color: "#C45D53".to_string(),
+        };
+        let minor_style = Style {
+            border_width: 1,
+            border_color: "#FFFFFF".to_string(),
+            background_color: "#9E9E9E".to_string(),
+        };
+
+        // Preview the field by a cross of the major and the minor direction at each grid point
+        let columns = (bounds.width() / PREVIEW_SPACING).ceil() as usize;
+        let rows = (bounds.height() / PREVIEW_SPACING).ceil() as usize;
+        for column in 0..=columns {
+            for row in 0..=rows {
+                let position = bounds.min()
+                    + Coordinate {
+                        x: column as f64 * PREVIEW_SPACING,
+                        y: row as f64 * PREVIEW_SPACING,
+                    };
+                let tensor = self.field.tensor(&position);
+
+                for (direction, style, length) in [
+                    (Direction::Major, &major_style, PREVIEW_SPACING * 0.4),
+                    (Direction::Minor, &minor_style, PREVIEW_SPACING * 0.2),
+                ] {
+                    if let Some(vector) = tensor.direction(direction) {
+                        Line::new(position - vector * length, position + vector * length)
+                            .render(style, context)
+                            .unwrap();
+                    }
+                }
+            }
+        }
+
+        for field in self.field.fields() {
+            if let BasisFieldKind::Boundary { line_string } = &field.kind {
+                for line in line_string.lines() {
+                    line.render(&major_style, context).unwrap();
+                }
+            }
+
+            field.center.render(&major_style, context).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod action_tests {
+    use geo::LineString;
+    use rust_editor::actions::{Action, Undo};
+
+    use crate::map::{
+        actions::street::create_streets::CreateStreets, map::Map, street_class::StreetClass,
+    };
+
+    use super::split_closed;
+
+    fn create_action() -> CreateStreets {
+        CreateStreets::new(vec![
+            (
+                LineString::from(vec![(0., 100.), (200., 100.)]),
+                StreetClass::MainRoad,
+            ),
+            (
+                LineString::from(vec![(100., 0.), (100., 200.)]),
+                StreetClass::Street,
+            ),
+        ])
+    }
+
+    #[test]
+    fn streamlines_are_connected_at_crossings() {
+        let mut map = Map::new(1000, 1000);
+        create_action().execute(&mut map);
+
+        assert_eq!(map.streets().len(), 4);
+        assert!(map
+            .streets()
+            .values()
+            .any(|street| street.class() == StreetClass::MainRoad));
+        assert!(map
+            .intersections()
+            .values()
+            .any(|intersection| intersection.get_connected_streets().len() == 4));
+    }
+
+    #[test]
+    fn undo_removes_streamlines() {
+        let mut map = Map::new(1000, 1000);
+        let mut action = create_action();
+        action.execute(&mut map);
+        action.undo(&mut map);
+
+        assert!(map.streets().is_empty());
+        assert!(map.intersections().is_empty());
+    }
+
+    #[test]
+    fn closed_streamlines_are_split() {
+        let ring = LineString::from(vec![
+            (0., 0.),
+            (100., 0.),
+            (100., 100.),
+            (0., 100.),
+            (0., 0.),
+        ]);
+        let halves = split_closed(ring);
+
+        assert_eq!(halves.len(), 2);
+        assert_eq!(halves[0].0.last(), halves[1].0.first());
+        assert!(halves.iter().all(|half| !half.is_closed()));
+    }
+}
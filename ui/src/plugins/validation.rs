@@ -0,0 +1,192 @@
+use std::any::Any;
+
+use plugin_toolbar::toolbar::ToolbarPosition;
+use rust_editor::{
+    plugin::{Plugin, PluginWithOptions},
+    ui::app::EditorError,
+};
+use rust_macro::editor_plugin;
+
+use crate::map::{map::Map, validation::Problem};
+
+/// Messages sent by the buttons of the panel. They are handled in [`Plugin::on_message`] as only there the map
+/// can be changed.
+enum ValidationMessage {
+    ToggleVisibility,
+    Fix(Problem),
+}
+
+/// Shows the problems reported by [`Map::validate`] in a panel. Problems that can be fixed automatically
+/// get a button that applies the fix as an undoable action.
+#[editor_plugin(skip, specific_to=Map)]
+pub struct Validation {
+    #[option(skip)]
+    visible: Rc<RefCell<bool>>,
+
+    /// Problems of the map and whether they can be fixed. The map is only validated again once its revision
+    /// differs from `revision`.
+    #[option(skip)]
+    problems: Vec<(Problem, bool)>,
+
+    #[option(skip)]
+    revision: Option<u64>,
+}
+
+impl Validation {
+    fn update_problems(&mut self, map: &Map) {
+        if self.revision == Some(map.revision()) {
+            return;
+        }
+
+        self.problems = map
+            .validate()
+            .into_iter()
+            .map(|problem| {
+                let fixable = problem.fix(map).is_some();
+                (problem, fixable)
+            })
+            .collect();
+        self.revision = Some(map.revision());
+    }
+}
+
+impl Plugin<Map> for Validation {
+    fn startup(&mut self, editor: &mut App<Map>) -> Result<(), EditorError> {
+        editor.plugin_mut(
+            move |toolbar_plugin: &mut plugin_toolbar::ToolbarPlugin<Map>| {
+                let toolbar = toolbar_plugin
+                    .get_or_add_toolbar("primary.actions", ToolbarPosition::Left)
+                    .unwrap();
+
+                let visible = Rc::clone(&self.visible);
+                toolbar
+                    .add_toggle_button(
+                        "rule",
+                        "validation",
+                        "Show/Hide map problems".to_string(),
+                        move || *visible.as_ref().borrow(),
+                        || {
+                            EditorMessages::PluginMessage(
+                                Validation::identifier(),
+                                Box::new(ValidationMessage::ToggleVisibility),
+                            )
+                        },
+                    )
+                    .unwrap();
+            },
+        );
+
+        Ok(())
+    }
+
+    fn on_message(&mut self, message: Box<dyn Any>, editor: &mut App<Map>) {
+        let message = match message.downcast::<ValidationMessage>() {
+            Ok(message) => *message,
+            Err(_) => return,
+        };
+
+        match message {
+            ValidationMessage::ToggleVisibility => {
+                let mut visible = self.visible.borrow_mut();
+                *visible = !*visible;
+            }
+            ValidationMessage::Fix(problem) => {
+                if let Some(action) = problem.fix(editor.data()) {
+                    action.borrow_mut().execute(editor.data_mut());
+
+                    editor.plugin_mut(move |redo: &mut plugin_undo_redo::Redo<Map>| {
+                        redo.clear();
+                    });
+
+                    editor.plugin_mut(move |undo: &mut plugin_undo_redo::Undo<Map>| {
+                        undo.push_generic(Rc::clone(&action));
+                    });
+                }
+            }
+        }
+    }
+
+    fn editor_elements(&mut self, ctx: &Context<App<Map>>, editor: &App<Map>) -> Vec<Html> {
+        let mut elements: Vec<Html> = Vec::new();
+
+        if !*self.visible.as_ref().borrow() {
+            return elements;
+        }
+
+        self.update_problems(editor.data());
+
+        elements.push(html! {
+        <div class="validation">
+            <h6>{"Map Problems"}</h6>
+
+            if self.problems.is_empty() {
+                <p>{"No problems found"}</p>
+            }
+
+            <ul>
+            {
+                for self.problems.iter().map(|(problem, fixable)| {
+                    let message = problem.to_string();
+
+                    let problem = problem.clone();
+                    let fix = ctx.link().callback(move |_| {
+                        EditorMessages::PluginMessage(
+                            Validation::identifier(),
+                            Box::new(ValidationMessage::Fix(problem.clone())),
+                        )
+                    });
+
+                    html! {
+                    <li>
+                        <span>{message}</span>
+                        if *fixable {
+                            <button onclick={fix}>{"Fix"}</button>
+                        }
+                    </li>
+                    }
+                })
+            }
+            </ul>
+        </div>
+        });
+
+        elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Coordinate;
+    use plugin_undo_redo::{Redo, Undo};
+    use rust_editor::{
+        input::keyboard::Key,
+        plugin::PluginWithOptions,
+        ui::{app::EditorMessages, headless::HeadlessApp},
+    };
+
+    use crate::map::{intersection::Intersection, map::Map};
+
+    use super::{Validation, ValidationMessage};
+
+    #[test]
+    fn integration_fix_message_applies_undoable_fix() {
+        let mut app = HeadlessApp::<Map>::new(800, 600);
+        app.add_plugin(Undo::<Map>::default()).unwrap();
+        app.add_plugin(Redo::<Map>::default()).unwrap();
+        app.add_plugin(Validation::default()).unwrap();
+
+        app.app_mut()
+            .data_mut()
+            .add_intersection(Intersection::new(Coordinate { x: 10., y: 10. }));
+        let problem = app.data().validate().pop().unwrap();
+
+        app.send_message(EditorMessages::PluginMessage(
+            Validation::identifier(),
+            Box::new(ValidationMessage::Fix(problem)),
+        ));
+        assert!(app.data().intersections().is_empty());
+
+        app.press_keys(&[Key::Ctrl, Key::Z]);
+        assert_eq!(app.data().intersections().len(), 1);
+    }
+}